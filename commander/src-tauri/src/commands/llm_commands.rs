@@ -191,84 +191,6 @@ pub async fn fetch_claude_models() -> Result<Vec<String>, String> {
     Ok(models)
 }
 
-#[tauri::command]
-pub async fn fetch_codex_models() -> Result<Vec<String>, String> {
-    // Check if codex/gh CLI is available
-    if !check_command_available("codex").await && !check_command_available("gh").await {
-        return Err("Codex/GitHub CLI is not installed or not available in PATH".to_string());
-    }
-
-    let mut models = Vec::new();
-
-    // Try codex command first
-    if check_command_available("codex").await {
-        let output = Command::new("codex")
-            .arg("--help")
-            .output()
-            .await
-            .map_err(|e| format!("Failed to execute codex --help: {}", e))?;
-
-        if output.status.success() {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            // Parse output for model information
-            for line in stdout.lines() {
-                let line = line.trim().to_lowercase();
-                if line.contains("model") && (line.contains("gpt") || line.contains("codex")) {
-                    if line.contains("gpt-4") {
-                        models.push("gpt-4".to_string());
-                    }
-                    if line.contains("gpt-3.5") {
-                        models.push("gpt-3.5-turbo".to_string());
-                    }
-                    if line.contains("codex") {
-                        models.push("code-davinci-002".to_string());
-                    }
-                }
-            }
-        }
-    }
-
-    // Try GitHub CLI copilot extension
-    if check_command_available("gh").await && models.is_empty() {
-        let output = Command::new("gh")
-            .args(&["copilot", "--help"])
-            .output()
-            .await;
-
-        if let Ok(output) = output {
-            if output.status.success() {
-                let stdout = String::from_utf8_lossy(&output.stdout);
-                for line in stdout.lines() {
-                    let line = line.trim().to_lowercase();
-                    if line.contains("model") && line.contains("gpt") {
-                        if line.contains("gpt-4") {
-                            models.push("gpt-4".to_string());
-                        }
-                        if line.contains("gpt-3.5") {
-                            models.push("gpt-3.5-turbo".to_string());
-                        }
-                    }
-                }
-            }
-        }
-    }
-
-    // Fallback to common Codex/GitHub Copilot models
-    if models.is_empty() {
-        models = vec![
-            "gpt-4".to_string(),
-            "gpt-3.5-turbo".to_string(),
-            "code-davinci-002".to_string(),
-        ];
-    }
-
-    // Remove duplicates
-    models.sort();
-    models.dedup();
-
-    Ok(models)
-}
-
 #[tauri::command]
 pub async fn fetch_gemini_models() -> Result<Vec<String>, String> {
     // Check if Gemini CLI is available
@@ -329,7 +251,8 @@ pub async fn fetch_gemini_models() -> Result<Vec<String>, String> {
 pub async fn fetch_agent_models(agent: String) -> Result<Vec<String>, String> {
     match agent.as_str() {
         "claude" => fetch_claude_models().await,
-        "codex" => fetch_codex_models().await,
+        // Codex has no command that lists models; the setting takes any model id
+        "codex" => Err("Codex models can't be listed, enter a model id instead".to_string()),
         "gemini" => fetch_gemini_models().await,
         _ => Err(format!("Unknown agent: {}", agent)),
     }
@@ -3,7 +3,8 @@ use std::path::Path;
 use tauri_plugin_store::StoreExt;
 
 use crate::models::*;
-use crate::services::project_service;
+use crate::services::repo_scan_service::{self, RepoKind, ScanOptions};
+use crate::services::{git_service, project_service};
 
 async fn scan_projects_folder(projects_folder: &str) -> Result<Vec<RecentProject>, String> {
     let path = Path::new(projects_folder);
@@ -12,98 +13,68 @@ async fn scan_projects_folder(projects_folder: &str) -> Result<Vec<RecentProject
         return Ok(Vec::new());
     }
 
-    let mut projects = Vec::new();
-
-    match fs::read_dir(path) {
-        Ok(entries) => {
-            for entry in entries {
-                if let Ok(entry) = entry {
-                    let entry_path = entry.path();
-
-                    // Only consider directories
-                    if entry_path.is_dir() {
-                        if let Some(name) = entry_path.file_name() {
-                            if let Some(name_str) = name.to_str() {
-                                // Skip hidden directories
-                                if name_str.starts_with('.') {
-                                    continue;
-                                }
-
-                                let path_str = entry_path.to_string_lossy().to_string();
-
-                                // Check if it's a git repository
-                                let git_dir = entry_path.join(".git");
-                                let is_git_repo = git_dir.exists();
-
-                                let mut git_branch = None;
-                                let mut git_status = None;
-
-                                if is_git_repo {
-                                    // Get current git branch
-                                    if let Ok(output) = std::process::Command::new("git")
-                                        .args(&["-C", &path_str, "branch", "--show-current"])
-                                        .output()
-                                    {
-                                        if output.status.success() {
-                                            if let Ok(branch) = String::from_utf8(output.stdout) {
-                                                let branch = branch.trim();
-                                                if !branch.is_empty() {
-                                                    git_branch = Some(branch.to_string());
-                                                }
-                                            }
-                                        }
-                                    }
-
-                                    // Get git status (clean/dirty)
-                                    if let Ok(output) = std::process::Command::new("git")
-                                        .args(&["-C", &path_str, "status", "--porcelain"])
-                                        .output()
-                                    {
-                                        if output.status.success() {
-                                            let status_output =
-                                                String::from_utf8_lossy(&output.stdout);
-                                            git_status = Some(if status_output.trim().is_empty() {
-                                                "clean".to_string()
-                                            } else {
-                                                "dirty".to_string()
-                                            });
-                                        }
-                                    }
-                                }
-
-                                // Use file modification time as last accessed
-                                let last_accessed = entry_path
-                                    .metadata()
-                                    .and_then(|m| m.modified())
-                                    .map(|t| {
-                                        t.duration_since(std::time::UNIX_EPOCH)
-                                            .unwrap_or_default()
-                                            .as_secs()
-                                            as i64
-                                    })
-                                    .unwrap_or(0);
-
-                                projects.push(RecentProject {
-                                    name: name_str.to_string(),
-                                    path: path_str,
-                                    last_accessed,
-                                    is_git_repo,
-                                    git_branch,
-                                    git_status,
-                                });
-                            }
+    let root = path.to_path_buf();
+    let repos = tokio::task::spawn_blocking(move || {
+        repo_scan_service::scan_repositories_cached(&root, &ScanOptions::default())
+    })
+    .await
+    .map_err(|e| format!("Failed to scan projects directory: {}", e))?;
+
+    let mut projects: Vec<RecentProject> = repos
+        .iter()
+        .map(|repo| {
+            let path_str = repo.path.to_string_lossy().to_string();
+            // Bare repositories have no working tree to report on
+            let has_worktree = repo.kind != RepoKind::Bare;
+            RecentProject {
+                name: repo.name.clone(),
+                last_accessed: repo
+                    .last_commit_at
+                    .unwrap_or_else(|| modified_secs(&repo.path)),
+                is_git_repo: true,
+                git_branch: git_service::get_git_branch(&path_str)
+                    .filter(|b| has_worktree && !b.is_empty()),
+                git_status: git_service::get_git_status(&path_str)
+                    .filter(|_| has_worktree)
+                    .map(|s| {
+                        if s.is_empty() {
+                            "clean".to_string()
+                        } else {
+                            "dirty".to_string()
                         }
-                    }
-                }
+                    }),
+                remote_url: repo.remote_url.clone(),
+                path: path_str,
             }
+        })
+        .collect();
+
+    // Plain folders directly inside the projects folder are still projects,
+    // as long as no repository was found inside them
+    let entries =
+        fs::read_dir(path).map_err(|e| format!("Failed to read projects directory: {}", e))?;
+    for entry in entries.flatten() {
+        let entry_path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !entry_path.is_dir() || name.starts_with('.') {
+            continue;
         }
-        Err(e) => {
-            return Err(format!("Failed to read projects directory: {}", e));
+        if repos.iter().any(|r| r.path.starts_with(&entry_path)) {
+            continue;
         }
+        projects.push(RecentProject {
+            name,
+            path: entry_path.to_string_lossy().to_string(),
+            last_accessed: modified_secs(&entry_path),
+            is_git_repo: false,
+            git_branch: None,
+            git_status: None,
+            remote_url: None,
+        });
     }
 
     // Sort by last accessed time (most recent first)
-    projects.sort_by(|a, b| b.last_accessed.cmp(&a.last_accessed));
+    projects.sort_by_key(|p| std::cmp::Reverse(p.last_accessed));
 
     // Limit to most recent 10 projects
     projects.truncate(10);
@@ -111,6 +82,17 @@ async fn scan_projects_folder(projects_folder: &str) -> Result<Vec<RecentProject
     Ok(projects)
 }
 
+fn modified_secs(path: &Path) -> i64 {
+    path.metadata()
+        .and_then(|m| m.modified())
+        .map(|t| {
+            t.duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs() as i64
+        })
+        .unwrap_or(0)
+}
+
 #[tauri::command]
 pub async fn get_user_home_directory() -> Result<String, String> {
     match dirs::home_dir() {
@@ -254,6 +236,7 @@ pub async fn list_recent_projects(app: tauri::AppHandle) -> Result<Vec<RecentPro
                         is_git_repo,
                         git_branch,
                         git_status,
+                        remote_url: project.remote_url,
                     });
                 }
             }
@@ -297,7 +280,8 @@ pub async fn add_project_to_recent(
 
 #[tauri::command]
 pub async fn refresh_recent_projects(app: tauri::AppHandle) -> Result<Vec<RecentProject>, String> {
-    // This is the same as list_recent_projects - we always scan fresh
+    // Same as list_recent_projects, but rescan the projects folder from disk
+    repo_scan_service::invalidate_scan_cache();
     list_recent_projects(app).await
 }
 
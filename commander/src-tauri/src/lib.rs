@@ -147,7 +147,6 @@ pub fn run() {
             load_llm_settings,
             get_default_llm_settings,
             fetch_claude_models,
-            fetch_codex_models,
             fetch_gemini_models,
             fetch_agent_models,
             check_ai_agents,
@@ -11,6 +11,9 @@ pub struct RecentProject {
     pub is_git_repo: bool,
     pub git_branch: Option<String>,
     pub git_status: Option<String>,
+    /// URL of the repository's `origin` (or first) remote, when known
+    #[serde(default)]
+    pub remote_url: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -9,4 +9,5 @@ pub mod git_service;
 pub mod llm_service;
 pub mod project_service;
 pub mod prompt_service;
+pub mod repo_scan_service;
 pub mod sub_agent_service;
@@ -70,6 +70,7 @@ pub async fn add_project_to_recent_projects(
         is_git_repo,
         git_branch,
         git_status,
+        remote_url: None,
     };
 
     // Dedup, MRU insert, and cap at 20
@@ -110,6 +111,7 @@ pub fn open_existing_project_core(
         is_git_repo: true,
         git_branch: get_git_branch(project_path),
         git_status: get_git_status(project_path),
+        remote_url: None,
     };
 
     Ok(upsert_recent_projects(existing, new_item, 20))
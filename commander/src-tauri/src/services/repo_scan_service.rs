@@ -0,0 +1,233 @@
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Mutex};
+
+/// Default number of directory levels below the projects folder to search
+pub const DEFAULT_MAX_DEPTH: usize = 3;
+
+/// Directory names that are never descended into
+pub const DEFAULT_IGNORES: &[&str] = &[
+    "node_modules",
+    "target",
+    "vendor",
+    "dist",
+    "build",
+    "out",
+    "Pods",
+    "DerivedData",
+    "__pycache__",
+    "venv",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RepoKind {
+    /// Regular checkout with a `.git` directory
+    Repository,
+    /// Linked worktree (`.git` file pointing into `.git/worktrees/`)
+    Worktree,
+    /// Submodule checkout (`.git` file pointing into `.git/modules/`)
+    Submodule,
+    /// Bare repository
+    Bare,
+}
+
+#[derive(Debug, Clone)]
+pub struct DiscoveredRepo {
+    pub name: String,
+    pub path: PathBuf,
+    pub kind: RepoKind,
+    /// Unix timestamp (seconds) of the last commit on HEAD
+    pub last_commit_at: Option<i64>,
+    /// URL of `origin`, or of the first remote if there is no origin
+    pub remote_url: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ScanOptions {
+    pub max_depth: usize,
+    /// Extra directory names to skip; `*suffix` and `prefix*` patterns are supported
+    pub ignore: Vec<String>,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self {
+            max_depth: DEFAULT_MAX_DEPTH,
+            ignore: Vec::new(),
+        }
+    }
+}
+
+static SCAN_CACHE: Lazy<Mutex<HashMap<PathBuf, Vec<DiscoveredRepo>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn matches_ignore(name: &str, pattern: &str) -> bool {
+    if let Some(suffix) = pattern.strip_prefix('*') {
+        name.ends_with(suffix)
+    } else if let Some(prefix) = pattern.strip_suffix('*') {
+        name.starts_with(prefix)
+    } else {
+        name == pattern
+    }
+}
+
+/// Detect whether a directory is the root of a repository, worktree, submodule or bare repo
+pub fn detect_repo(dir: &Path) -> Option<RepoKind> {
+    let dot_git = dir.join(".git");
+
+    if let Ok(meta) = std::fs::symlink_metadata(&dot_git) {
+        if meta.is_dir() {
+            return Some(RepoKind::Repository);
+        }
+        if meta.is_file() {
+            let content = std::fs::read_to_string(&dot_git).ok()?;
+            let gitdir = content
+                .lines()
+                .find_map(|l| l.strip_prefix("gitdir:"))?
+                .trim()
+                .replace('\\', "/");
+            return Some(if gitdir.contains("/modules/") {
+                RepoKind::Submodule
+            } else {
+                RepoKind::Worktree
+            });
+        }
+    }
+
+    if dir.join("HEAD").is_file() && dir.join("objects").is_dir() && dir.join("refs").is_dir() {
+        return Some(RepoKind::Bare);
+    }
+
+    None
+}
+
+fn git_output(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if text.is_empty() {
+        None
+    } else {
+        Some(text)
+    }
+}
+
+fn describe_repo(dir: &Path, kind: RepoKind) -> DiscoveredRepo {
+    let last_commit_at =
+        git_output(dir, &["log", "-1", "--format=%ct"]).and_then(|t| t.parse().ok());
+
+    let remote_url = git_output(dir, &["config", "--get", "remote.origin.url"]).or_else(|| {
+        let first_remote = git_output(dir, &["remote"])?.lines().next()?.to_string();
+        git_output(dir, &["config", "--get", &format!("remote.{}.url", first_remote)])
+    });
+
+    DiscoveredRepo {
+        name: dir
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        path: dir.to_path_buf(),
+        kind,
+        last_commit_at,
+        remote_url,
+    }
+}
+
+/// Recursively find repositories below `root` using a pool of worker threads.
+/// Hidden directories and symlinks are skipped; repositories are descended into
+/// so nested layouts are found too (bare repositories are not).
+pub fn scan_repositories(root: &Path, options: &ScanOptions) -> Vec<DiscoveredRepo> {
+    let ignores: Vec<String> = DEFAULT_IGNORES
+        .iter()
+        .map(|s| s.to_string())
+        .chain(options.ignore.iter().cloned())
+        .collect();
+
+    // Directories to visit; `None` tells a worker to stop
+    let (queue, jobs) = mpsc::channel::<Option<(PathBuf, usize)>>();
+    let jobs = Mutex::new(jobs);
+    let found: Mutex<Vec<DiscoveredRepo>> = Mutex::new(Vec::new());
+    // Directories queued or being processed; the scan is done when it reaches zero
+    let pending = AtomicUsize::new(1);
+    let _ = queue.send(Some((root.to_path_buf(), 0)));
+
+    let workers = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4)
+        .min(8);
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let next = jobs.lock().unwrap().recv();
+                let Ok(Some((dir, depth))) = next else {
+                    break;
+                };
+
+                if depth < options.max_depth {
+                    for entry in std::fs::read_dir(&dir).into_iter().flatten().flatten() {
+                        if !entry.file_type().map(|t| t.is_dir()).unwrap_or(false) {
+                            continue;
+                        }
+                        let name = entry.file_name().to_string_lossy().to_string();
+                        if name.starts_with('.') || ignores.iter().any(|p| matches_ignore(&name, p))
+                        {
+                            continue;
+                        }
+
+                        let path = entry.path();
+                        if let Some(kind) = detect_repo(&path) {
+                            let is_bare = kind == RepoKind::Bare;
+                            found.lock().unwrap().push(describe_repo(&path, kind));
+                            if is_bare {
+                                continue;
+                            }
+                        }
+
+                        pending.fetch_add(1, Ordering::SeqCst);
+                        let _ = queue.send(Some((path, depth + 1)));
+                    }
+                }
+
+                // The last directory is done: wake every worker so it can stop
+                if pending.fetch_sub(1, Ordering::SeqCst) == 1 {
+                    for _ in 0..workers {
+                        let _ = queue.send(None);
+                    }
+                }
+            });
+        }
+    });
+
+    let mut repos = found.into_inner().unwrap_or_default();
+    repos.sort_by(|a, b| a.path.cmp(&b.path));
+    repos
+}
+
+/// Cached variant of [`scan_repositories`] keyed by root path
+pub fn scan_repositories_cached(root: &Path, options: &ScanOptions) -> Vec<DiscoveredRepo> {
+    if let Some(cached) = SCAN_CACHE.lock().unwrap().get(root) {
+        return cached.clone();
+    }
+    let repos = scan_repositories(root, options);
+    SCAN_CACHE
+        .lock()
+        .unwrap()
+        .insert(root.to_path_buf(), repos.clone());
+    repos
+}
+
+/// Drop cached scan results so the next scan walks the filesystem again
+pub fn invalidate_scan_cache() {
+    SCAN_CACHE.lock().unwrap().clear();
+}
@@ -9,3 +9,4 @@ pub mod file_service;
 pub mod git_service_enhanced;
 pub mod prompt_service;
 pub mod recent_projects;
+pub mod repo_scan_service;
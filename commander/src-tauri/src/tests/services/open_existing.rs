@@ -15,8 +15,8 @@ mod tests {
 
         // existing list contains the same path (older) and another project
         let existing = vec![
-            RecentProject { name: "X".into(), path: git_path_str.clone(), last_accessed: 10, is_git_repo: true, git_branch: None, git_status: None },
-            RecentProject { name: "Y".into(), path: "/other".into(), last_accessed: 20, is_git_repo: false, git_branch: None, git_status: None },
+            RecentProject { name: "X".into(), path: git_path_str.clone(), last_accessed: 10, is_git_repo: true, git_branch: None, git_status: None, remote_url: None },
+            RecentProject { name: "Y".into(), path: "/other".into(), last_accessed: 20, is_git_repo: false, git_branch: None, git_status: None, remote_url: None },
         ];
 
         let updated = project_service::open_existing_project_core(existing, &git_path_str, 999)
@@ -11,6 +11,7 @@ mod tests {
             is_git_repo: true,
             git_branch: Some("main".to_string()),
             git_status: Some("clean".to_string()),
+            remote_url: None,
         }
     }
 
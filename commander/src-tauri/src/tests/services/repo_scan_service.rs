@@ -0,0 +1,108 @@
+#[cfg(test)]
+mod tests {
+    use crate::services::repo_scan_service::{self, RepoKind, ScanOptions};
+    use std::fs;
+    use std::path::Path;
+    use std::process::Command as StdCommand;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = StdCommand::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .expect("Failed to run git");
+        assert!(
+            status.status.success(),
+            "git {:?} failed: {:?}",
+            args,
+            status
+        );
+    }
+
+    fn init_repo_with_commit(dir: &Path) {
+        fs::create_dir_all(dir).unwrap();
+        git(dir, &["init", "-q"]);
+        git(dir, &["config", "user.email", "test@example.com"]);
+        git(dir, &["config", "user.name", "Test"]);
+        fs::write(dir.join("README.md"), "hello").unwrap();
+        git(dir, &["add", "."]);
+        git(dir, &["commit", "-q", "-m", "init"]);
+    }
+
+    fn kind_of(repos: &[repo_scan_service::DiscoveredRepo], name: &str) -> Option<RepoKind> {
+        repos
+            .iter()
+            .find(|r| r.name == name)
+            .map(|r| r.kind.clone())
+    }
+
+    #[test]
+    fn test_scan_finds_nested_worktrees_and_bare_repos() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+
+        let main = root.join("group").join("app");
+        init_repo_with_commit(&main);
+        git(
+            &main,
+            &[
+                "worktree",
+                "add",
+                "-q",
+                "-b",
+                "feature",
+                root.join("app-feature").to_str().unwrap(),
+            ],
+        );
+        git(root, &["init", "-q", "--bare", "mirror.git"]);
+        git(&main, &["remote", "add", "upstream", "https://example.com/group/app.git"]);
+
+        let repos = repo_scan_service::scan_repositories(root, &ScanOptions::default());
+
+        assert_eq!(kind_of(&repos, "app"), Some(RepoKind::Repository));
+        assert_eq!(kind_of(&repos, "app-feature"), Some(RepoKind::Worktree));
+        assert_eq!(kind_of(&repos, "mirror.git"), Some(RepoKind::Bare));
+
+        let app = repos.iter().find(|r| r.name == "app").unwrap();
+        assert!(
+            app.last_commit_at.is_some(),
+            "Should report last commit time"
+        );
+        assert_eq!(
+            app.remote_url.as_deref(),
+            Some("https://example.com/group/app.git"),
+            "Falls back to the first remote without an origin"
+        );
+    }
+
+    #[test]
+    fn test_scan_respects_depth_and_ignores() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+
+        init_repo_with_commit(&root.join("a").join("b").join("deep"));
+        init_repo_with_commit(&root.join("node_modules").join("dep"));
+        init_repo_with_commit(&root.join("skipme").join("repo"));
+
+        let shallow = ScanOptions {
+            max_depth: 2,
+            ignore: vec!["skip*".to_string()],
+        };
+        let repos = repo_scan_service::scan_repositories(root, &shallow);
+        assert!(repos.is_empty(), "Expected no repos, got {:?}", repos);
+
+        let deep = ScanOptions {
+            max_depth: 3,
+            ignore: Vec::new(),
+        };
+        let repos = repo_scan_service::scan_repositories(root, &deep);
+        let names: Vec<&str> = repos.iter().map(|r| r.name.as_str()).collect();
+        assert!(names.contains(&"deep"));
+        assert!(names.contains(&"repo"));
+        assert!(
+            !names.contains(&"dep"),
+            "node_modules is ignored by default"
+        );
+    }
+}
@@ -8,6 +8,7 @@ export interface RecentProject {
   is_git_repo: boolean
   git_branch: string | null
   git_status: string | null
+  remote_url?: string | null
 }
 
 export function useRecentProjects() {
@@ -0,0 +1,22 @@
+#!/bin/bash
+
+# Bindings Check Script
+# Regenerates the ts-rs bindings and fails if they differ from the committed ones
+# Usage: ./scripts/check-bindings.sh
+
+set -e
+
+cd "$(dirname "$0")/.."
+
+npm run bindings
+
+# Untracked files count too: a new exported type must be committed
+CHANGES=$(git status --porcelain -- src/bindings)
+if [ -n "$CHANGES" ]; then
+    echo "src/bindings is out of date. Run 'npm run bindings' and commit the result:" >&2
+    echo "$CHANGES" >&2
+    git --no-pager diff -- src/bindings >&2
+    exit 1
+fi
+
+echo "Bindings are up to date"
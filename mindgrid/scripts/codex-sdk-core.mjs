@@ -12,14 +12,7 @@ export async function runCodex(options = {}, io = defaultIO) {
     skipGitRepoCheck,
   } = options
 
-  let CodexModule
-  const distPath = process.env.CODEX_SDK_DIST_PATH
-  if (distPath && fs.existsSync(distPath)) {
-    CodexModule = await import(pathToFileURL(distPath).href)
-  } else {
-    CodexModule = await import('@openai/codex-sdk')
-  }
-  const { Codex } = CodexModule
+  const { Codex } = await loadCodex()
 
   const codexOptions = workingDirectory ? { workingDirectory } : {}
   const codex = new Codex(codexOptions)
@@ -70,6 +63,95 @@ export async function runCodex(options = {}, io = defaultIO) {
   }
 }
 
+async function loadCodex() {
+  const distPath = process.env.CODEX_SDK_DIST_PATH
+  if (distPath && fs.existsSync(distPath)) {
+    return import(pathToFileURL(distPath).href)
+  }
+  return import('@openai/codex-sdk')
+}
+
+/**
+ * Long-lived runner for one Codex thread (see src-tauri/src/codex_thread.rs).
+ * Reads JSON commands, one per line:
+ *   { type: 'start', threadId?, workingDirectory?, sandboxMode?, model?, systemPrompt?, skipGitRepoCheck? }
+ *   { type: 'send', turnId, prompt }
+ *   { type: 'cancel' }
+ * and writes the same event envelopes as `runCodex` (with `turnId`), plus
+ * control messages: { control: 'thread', threadId } once the thread id is known
+ * and { control: 'turn_finished', turnId, status, error? } after every turn.
+ */
+export async function serveThread(lines, io = defaultIO) {
+  let thread = null
+  let current = null
+
+  const control = (message) => io.write(JSON.stringify(message))
+
+  const runTurn = (turnId, prompt) => {
+    const controller = new AbortController()
+    const promise = (async () => {
+      try {
+        const { events } = await thread.runStreamed(prompt, { signal: controller.signal })
+        for await (const event of events) {
+          if (controller.signal.aborted) break
+          if (event.type === 'thread.started' && event.thread_id) {
+            await control({ control: 'thread', threadId: event.thread_id })
+          }
+          await io.write(JSON.stringify({ sessionId: turnId, turnId, content: JSON.stringify(event), finished: false }))
+        }
+        const status = controller.signal.aborted ? 'cancelled' : 'completed'
+        await control({ control: 'turn_finished', turnId, status })
+      } catch (error) {
+        const message = error instanceof Error ? error.message : String(error)
+        const status = controller.signal.aborted ? 'cancelled' : 'failed'
+        await control({ control: 'turn_finished', turnId, status, error: message })
+      } finally {
+        current = null
+      }
+    })()
+    return { turnId, controller, promise }
+  }
+
+  for await (const line of lines) {
+    if (!line.trim()) continue
+    let command
+    try {
+      command = JSON.parse(line)
+    } catch {
+      await io.writeError(JSON.stringify({ error: `Invalid command: ${line}` }))
+      continue
+    }
+
+    if (command.type === 'start') {
+      const { Codex } = await loadCodex()
+      const { threadId, workingDirectory, sandboxMode, model, systemPrompt, skipGitRepoCheck } = command
+      const codex = new Codex(workingDirectory ? { workingDirectory } : {})
+      const threadOptions = {
+        ...(model ? { model } : {}),
+        ...(sandboxMode ? { sandboxMode } : {}),
+        ...(workingDirectory ? { workingDirectory } : {}),
+        ...(systemPrompt ? { system: systemPrompt } : {}),
+        skipGitRepoCheck: skipGitRepoCheck !== false,
+      }
+      thread = threadId ? codex.resumeThread(threadId, threadOptions) : codex.startThread(threadOptions)
+      if (threadId) await control({ control: 'thread', threadId })
+    } else if (command.type === 'send') {
+      if (!thread || current) {
+        const error = thread ? 'A turn is already running' : 'Thread not started'
+        await control({ control: 'turn_finished', turnId: command.turnId, status: 'failed', error })
+        continue
+      }
+      current = runTurn(command.turnId, command.prompt || '')
+    } else if (command.type === 'cancel') {
+      current?.controller.abort()
+    }
+  }
+
+  // Input closed: the app dropped the thread
+  current?.controller.abort()
+  await current?.promise
+}
+
 export async function readStdin() {
   const chunks = []
   for await (const chunk of process.stdin) {
@@ -0,0 +1,9 @@
+import readline from 'readline'
+import { serveThread } from './codex-sdk-core.mjs'
+
+const lines = readline.createInterface({ input: process.stdin, crlfDelay: Infinity })
+
+serveThread(lines).catch((err) => {
+  console.error(JSON.stringify({ error: err?.message || String(err), finished: true }))
+  process.exit(1)
+})
@@ -0,0 +1,223 @@
+use parking_lot::Mutex;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::Value;
+use std::sync::Arc;
+use tauri::{AppHandle, Wry};
+use tauri_plugin_store::{Store, StoreExt};
+
+use crate::error::{MindgridError, MindgridResult};
+
+/// Serializes read-modify-write cycles of the backend on the session list
+static SESSIONS_WRITE: Mutex<()> = Mutex::new(());
+
+/// A project as the frontend saves it (`saveProject` in src/lib/database.ts)
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredProject {
+    pub id: String,
+    pub name: String,
+    pub path: String,
+}
+
+/// A session as the frontend saves it (`saveSession` in src/lib/database.ts).
+/// Only the fields the backend reads; the rest of the entry is left untouched.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredSession {
+    pub id: String,
+    pub project_id: String,
+    pub name: String,
+    pub cwd: String,
+    #[serde(default)]
+    pub model: Option<String>,
+    /// "active", "paused" or "closed"; missing for sessions saved by old versions
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(default)]
+    pub pr_url: Option<String>,
+    #[serde(default)]
+    pub permission_mode: Option<String>,
+    #[serde(default)]
+    pub created_at: i64,
+    #[serde(default)]
+    pub updated_at: i64,
+}
+
+impl StoredSession {
+    pub fn is_closed(&self) -> bool {
+        self.status.as_deref() == Some("closed")
+    }
+}
+
+/// The store the frontend loads (`getStoreFilename` in src/lib/dev-mode.ts).
+/// The plugin hands out one shared instance per file, so both sides see the same data.
+fn open(app: &AppHandle) -> MindgridResult<Arc<Store<Wry>>> {
+    app.store(crate::get_store_name())
+        .map_err(|e| MindgridError::db("store_unavailable", "Failed to open the app data store").with_technical(e))
+}
+
+fn save(store: &Store<Wry>) -> MindgridResult<()> {
+    store
+        .save()
+        .map_err(|e| MindgridError::db("store_save_failed", "Failed to save the app data store").with_technical(e))
+}
+
+/// Entries of a stored list; entries that don't parse are skipped
+fn parse_list<T: DeserializeOwned>(value: Option<Value>) -> Vec<T> {
+    match value {
+        Some(Value::Array(items)) => items
+            .into_iter()
+            .filter_map(|item| serde_json::from_value(item).ok())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn entry_id(entry: &Value) -> Option<&str> {
+    entry.get("id").and_then(Value::as_str)
+}
+
+/// Add an entry, replacing the one with the same id
+fn upsert(list: &mut Vec<Value>, entry: Value) {
+    match list.iter_mut().find(|e| entry_id(e).is_some() && entry_id(e) == entry_id(&entry)) {
+        Some(existing) => *existing = entry,
+        None => list.push(entry),
+    }
+}
+
+/// Overwrite the given fields of the entry with `id`, keeping its other fields.
+/// Returns false if there is no such entry.
+fn merge(list: &mut [Value], id: &str, fields: &Value) -> bool {
+    let Some(Value::Object(entry)) = list.iter_mut().find(|e| entry_id(e) == Some(id)) else {
+        return false;
+    };
+    if let Value::Object(fields) = fields {
+        for (key, value) in fields {
+            entry.insert(key.clone(), value.clone());
+        }
+    }
+    true
+}
+
+/// Update the session list under the write lock and save it if `change` says so
+fn edit_sessions(app: &AppHandle, change: impl FnOnce(&mut Vec<Value>) -> bool) -> MindgridResult<bool> {
+    let _write = SESSIONS_WRITE.lock();
+    let store = open(app)?;
+    let mut list = match store.get("sessions") {
+        Some(Value::Array(items)) => items,
+        _ => Vec::new(),
+    };
+    if !change(&mut list) {
+        return Ok(false);
+    }
+    store.set("sessions", Value::Array(list));
+    save(&store)?;
+    Ok(true)
+}
+
+pub fn projects(app: &AppHandle) -> MindgridResult<Vec<StoredProject>> {
+    Ok(parse_list(open(app)?.get("projects")))
+}
+
+pub fn project(app: &AppHandle, project_id: &str) -> MindgridResult<StoredProject> {
+    projects(app)?
+        .into_iter()
+        .find(|p| p.id == project_id)
+        .ok_or_else(|| MindgridError::validation("project_not_found", "Project not found").with_technical(project_id))
+}
+
+pub fn sessions(app: &AppHandle) -> MindgridResult<Vec<StoredSession>> {
+    Ok(parse_list(open(app)?.get("sessions")))
+}
+
+pub fn session(app: &AppHandle, session_id: &str) -> MindgridResult<StoredSession> {
+    sessions(app)?
+        .into_iter()
+        .find(|s| s.id == session_id)
+        .ok_or_else(|| MindgridError::validation("session_not_found", "Session not found").with_technical(session_id))
+}
+
+/// Save a new session entry (or replace the one with the same id)
+pub fn insert_session(app: &AppHandle, entry: Value) -> MindgridResult<()> {
+    edit_sessions(app, |list| {
+        upsert(list, entry);
+        true
+    })
+    .map(|_| ())
+}
+
+/// Overwrite some fields of a session. Returns false if the session doesn't exist.
+pub fn update_session(app: &AppHandle, session_id: &str, fields: Value) -> MindgridResult<bool> {
+    edit_sessions(app, |list| merge(list, session_id, &fields))
+}
+
+/// Remove a session entry. Returns false if it didn't exist.
+pub fn remove_session(app: &AppHandle, session_id: &str) -> MindgridResult<bool> {
+    edit_sessions(app, |list| {
+        let before = list.len();
+        list.retain(|e| entry_id(e) != Some(session_id));
+        list.len() != before
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reads_entries_saved_by_the_frontend() {
+        let sessions: Vec<StoredSession> = parse_list(Some(json!([
+            {
+                "id": "s1", "name": "Fix login", "projectId": "p1", "cwd": "/repo/.mindgrid/worktrees/fix-login-s1",
+                "claudeSessionId": null, "ptyId": null, "messages": [], "chatWindows": [], "isRunning": false,
+                "totalCost": 0.5, "model": "sonnet", "createdAt": 1700000000000i64, "updatedAt": 1700000000001i64,
+                "permissionMode": "bypassPermissions", "commitMode": "checkpoint", "status": "closed",
+                "prUrl": "https://github.com/octo/app/pull/1"
+            },
+            // Saved before sessions had a status or PR
+            { "id": "s2", "name": "Old", "projectId": "p1", "cwd": "/repo", "model": null, "createdAt": 1, "updatedAt": 2 },
+            { "id": "broken" },
+        ])));
+
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].project_id, "p1");
+        assert_eq!(sessions[0].pr_url.as_deref(), Some("https://github.com/octo/app/pull/1"));
+        assert_eq!(sessions[0].permission_mode.as_deref(), Some("bypassPermissions"));
+        assert_eq!(sessions[0].created_at, 1700000000000);
+        assert!(sessions[0].is_closed());
+        assert_eq!(sessions[1].status, None);
+        assert_eq!(sessions[1].model, None);
+        assert!(!sessions[1].is_closed());
+
+        let projects: Vec<StoredProject> = parse_list(Some(json!([
+            { "id": "p1", "name": "app", "path": "/repo", "sessions": ["s1", "s2"], "defaultModel": "sonnet" },
+        ])));
+        assert_eq!(
+            projects,
+            vec![StoredProject { id: "p1".into(), name: "app".into(), path: "/repo".into() }]
+        );
+
+        assert!(parse_list::<StoredProject>(None).is_empty());
+        assert!(parse_list::<StoredSession>(Some(json!({ "s1": {} }))).is_empty());
+    }
+
+    #[test]
+    fn edits_keep_fields_the_backend_doesnt_know() {
+        let mut list = vec![
+            json!({ "id": "s1", "status": "active", "messages": [], "totalCost": 1.5 }),
+            json!({ "id": "s2", "status": "active" }),
+        ];
+
+        assert!(merge(&mut list, "s1", &json!({ "status": "closed", "updatedAt": 5 })));
+        assert_eq!(list[0], json!({ "id": "s1", "status": "closed", "messages": [], "totalCost": 1.5, "updatedAt": 5 }));
+        assert!(!merge(&mut list, "missing", &json!({ "status": "closed" })));
+
+        upsert(&mut list, json!({ "id": "s2", "status": "paused" }));
+        upsert(&mut list, json!({ "id": "s3", "status": "active" }));
+        assert_eq!(list.len(), 3);
+        assert_eq!(list[1], json!({ "id": "s2", "status": "paused" }));
+        assert_eq!(entry_id(&list[2]), Some("s3"));
+    }
+}
@@ -0,0 +1,743 @@
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::diff::{DiffLimits, DiffScope, DiffSummary};
+use crate::error::{MindgridError, MindgridResult};
+use crate::multi_model::{QueryAgent, QueryContext, QueryIsolation, QueryTarget, TargetRun};
+use crate::operations::{Operation, OperationRegistry};
+
+const MAX_CANDIDATES: usize = 8;
+
+/// Keep the end of the test output, where failures are summarized
+const MAX_TEST_OUTPUT: usize = 16 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum CandidateStatus {
+    Running,
+    Succeeded,
+    Failed,
+    /// Picked and merged into the main branch
+    Merged,
+    /// Not picked; work kept on a `mindgrid/archive/*` branch
+    Archived,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct TestRun {
+    pub command: String,
+    pub passed: bool,
+    pub exit_code: Option<i32>,
+    /// Combined stdout/stderr, truncated from the front if very long
+    pub output: String,
+    #[ts(type = "number")]
+    pub duration_ms: i64,
+}
+
+/// Comparison report for one candidate
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct Candidate {
+    pub id: String,
+    pub agent: QueryAgent,
+    pub model: Option<String>,
+    pub worktree_path: String,
+    pub branch: String,
+    pub status: CandidateStatus,
+    /// Final reply of the agent
+    pub response: Option<String>,
+    pub error: Option<String>,
+    /// Changes against the main branch, committed or not
+    pub diff: Option<DiffSummary>,
+    pub test: Option<TestRun>,
+    pub cost_usd: Option<f64>,
+    #[ts(type = "number | null")]
+    pub duration_ms: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct BestOfNRun {
+    pub id: String,
+    pub project_path: String,
+    pub task: String,
+    pub test_command: Option<String>,
+    pub candidates: Vec<Candidate>,
+    /// Id of the merged candidate
+    pub winner: Option<String>,
+    #[ts(type = "number")]
+    pub created_at: i64,
+}
+
+impl BestOfNRun {
+    fn candidate(&mut self, id: &str) -> Option<&mut Candidate> {
+        self.candidates.iter_mut().find(|c| c.id == id)
+    }
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export, optional_fields = nullable)]
+pub struct BestOfNArgs {
+    /// Lets the caller subscribe to the candidate channels before the run starts
+    pub run_id: Option<String>,
+    pub project_path: String,
+    pub task: String,
+    pub candidates: Vec<QueryTarget>,
+    /// Shell command run in each worktree once its agent finishes
+    pub test_command: Option<String>,
+    pub system_prompt: Option<String>,
+    /// Permission mode for Claude candidates, as for a session
+    pub permission_mode: Option<String>,
+}
+
+pub struct BestOfNState {
+    runs: Mutex<HashMap<String, BestOfNRun>>,
+}
+
+impl BestOfNState {
+    pub fn new() -> Self {
+        Self {
+            runs: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn get(&self, run_id: &str) -> MindgridResult<BestOfNRun> {
+        self.runs
+            .lock()
+            .get(run_id)
+            .cloned()
+            .ok_or_else(|| MindgridError::validation("run_not_found", "Best-of-N run not found").with_technical(run_id))
+    }
+
+    /// Apply a change to a run and broadcast the new state
+    fn update(&self, app: &AppHandle, run_id: &str, change: impl FnOnce(&mut BestOfNRun)) {
+        let run = {
+            let mut runs = self.runs.lock();
+            let Some(run) = runs.get_mut(run_id) else {
+                return;
+            };
+            change(run);
+            run.clone()
+        };
+        let _ = app.emit("best-of-n-updated", run);
+    }
+}
+
+/// Operation of a candidate's agent run and then its tests; cancelling it with
+/// `cancel_operation` stops whichever is running
+pub fn operation_id(run_id: &str, candidate_id: &str) -> String {
+    format!("best_of_n:{}:{}", run_id, candidate_id)
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+async fn run_tests(op: &Operation, dir: &Path, command: &str) -> TestRun {
+    let started = Instant::now();
+    let mut cmd = if cfg!(windows) {
+        let mut cmd = tokio::process::Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    } else {
+        let mut cmd = tokio::process::Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    };
+    let output = op.output(cmd.current_dir(dir), "Test command").await;
+
+    let (passed, exit_code, mut output) = match output {
+        Ok(output) => {
+            let text = format!(
+                "{}{}",
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            );
+            (output.status.success(), output.status.code(), text)
+        }
+        Err(e) => (false, None, e.user_message()),
+    };
+    if output.len() > MAX_TEST_OUTPUT {
+        let mut start = output.len() - MAX_TEST_OUTPUT;
+        while !output.is_char_boundary(start) {
+            start += 1;
+        }
+        output = output.split_off(start);
+    }
+    TestRun {
+        command: command.to_string(),
+        passed,
+        exit_code,
+        output,
+        duration_ms: started.elapsed().as_millis() as i64,
+    }
+}
+
+/// Run one candidate's agent, then its tests, and fill in its report
+async fn run_candidate(
+    app: AppHandle,
+    state: Arc<BestOfNState>,
+    run_id: String,
+    target: TargetRun,
+    worktree: String,
+    test_command: Option<String>,
+) {
+    let started = Instant::now();
+    let dir = PathBuf::from(&worktree);
+    let operations = app.state::<Arc<OperationRegistry>>().inner().clone();
+    let operation_id = operation_id(&run_id, &target.target_id);
+
+    let reply = match operations.begin(Some(operation_id.clone()), "best_of_n_agent") {
+        Ok(op) => {
+            let tracker = op.tracker();
+            op.run("Candidate agent", target.agent(&dir, Some(&tracker))).await.and_then(|reply| reply)
+        }
+        Err(e) => Err(e),
+    };
+    let test = match (&reply, &test_command) {
+        (Ok(_), Some(command)) => match operations.begin(Some(operation_id), "best_of_n_tests") {
+            Ok(op) => Some(run_tests(&op, &dir, command).await),
+            Err(e) => Some(TestRun {
+                command: command.clone(),
+                passed: false,
+                exit_code: None,
+                output: e.user_message(),
+                duration_ms: 0,
+            }),
+        },
+        _ => None,
+    };
+    let diff = crate::diff::list_changes(&worktree, DiffScope::VsMain, DiffLimits::default())
+        .await
+        .map_err(|e| eprintln!("[MindGrid] Failed to diff candidate {}: {}", target.target_id, e.technical_message()))
+        .ok();
+
+    state.update(&app, &run_id, |run| {
+        let Some(candidate) = run.candidate(&target.target_id) else {
+            return;
+        };
+        match reply {
+            Ok(reply) => {
+                candidate.status = CandidateStatus::Succeeded;
+                candidate.response = Some(reply.text);
+                candidate.cost_usd = reply.cost_usd;
+            }
+            Err(e) => {
+                eprintln!("[MindGrid] Candidate {} failed: {}", target.target_id, e.technical_message());
+                candidate.status = CandidateStatus::Failed;
+                candidate.error = Some(e.user_message());
+            }
+        }
+        candidate.diff = diff;
+        candidate.test = test;
+        candidate.duration_ms = Some(started.elapsed().as_millis() as i64);
+    });
+}
+
+/// Commit whatever the candidate left uncommitted so it can be merged or archived
+async fn commit_candidate(app: &AppHandle, candidate: &Candidate, task: &str) -> MindgridResult<()> {
+    let message = format!("{} ({})", task.lines().next().unwrap_or("Best-of-N candidate"), candidate.id);
+    let result = crate::git::git_checkpoint_commit(app.clone(), candidate.worktree_path.clone(), message, None, None, None).await?;
+    match result.error {
+        Some(error) if !result.success && error != "No changes to commit" => {
+            Err(MindgridError::git("commit_failed", "Failed to commit the candidate's changes").with_technical(error))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Remove a candidate's worktree and keep its branch as `mindgrid/archive/<name>`
+async fn archive_candidate(app: &AppHandle, project_path: &str, candidate: &Candidate, task: &str) -> MindgridResult<()> {
+    if Path::new(&candidate.worktree_path).exists() {
+        commit_candidate(app, candidate, task).await?;
+    }
+    archive_worktree(project_path, &candidate.worktree_path, &candidate.branch).await
+}
+
+async fn archive_worktree(project_path: &str, worktree_path: &str, branch: &str) -> MindgridResult<()> {
+    let remove_args = ["worktree", "remove", "--force", worktree_path];
+    let output = tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(project_path)
+        .args(remove_args)
+        .output()
+        .await
+        .map_err(|e| MindgridError::spawn_failed("git worktree remove", e))?;
+    if !output.status.success() && Path::new(worktree_path).exists() {
+        return Err(MindgridError::git_failed(
+            "worktree_remove_failed",
+            "Failed to remove the candidate worktree",
+            &remove_args,
+            &output,
+        ));
+    }
+
+    let archived = branch.replacen("mindgrid/", "mindgrid/archive/", 1);
+    let rename_args = ["branch", "-M", branch, archived.as_str()];
+    let output = tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(project_path)
+        .args(rename_args)
+        .output()
+        .await
+        .map_err(|e| MindgridError::spawn_failed("git branch", e))?;
+    if !output.status.success() {
+        return Err(MindgridError::git_failed(
+            "branch_rename_failed",
+            "Failed to archive the candidate branch",
+            &rename_args,
+            &output,
+        ));
+    }
+    Ok(())
+}
+
+/// Squash-merge a committed candidate into the main branch and remove its
+/// worktree and branch
+async fn merge_winner(project_path: &str, winner: &Candidate, message: String) -> MindgridResult<()> {
+    let merged = crate::git::git_merge_to_main(winner.worktree_path.clone(), project_path.to_string(), message, Some(true), None).await?;
+    if !merged.success {
+        return Err(MindgridError::git("merge_failed", merged.error.unwrap_or_else(|| "Merge failed".to_string())));
+    }
+    crate::git::remove_workspace_worktree(project_path.to_string(), winner.worktree_path.clone()).await
+}
+
+async fn git_in(dir: &str, args: &[&str], code: &str, message: &str) -> MindgridResult<std::process::Output> {
+    let output = tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| MindgridError::spawn_failed("git", e))?;
+    if !output.status.success() {
+        return Err(MindgridError::git_failed(code, message, args, &output));
+    }
+    Ok(output)
+}
+
+/// Archive candidate worktrees created before `before`. Runs only live in
+/// memory, so after a restart nothing can pick or discard them anymore; their
+/// work is committed and kept on `mindgrid/archive/*` like a discarded run.
+pub async fn sweep_orphaned_worktrees(project_path: &str, before: SystemTime) -> MindgridResult<usize> {
+    let mut swept = 0;
+    for (path, branch, is_main) in crate::resources::list_worktrees(project_path).await? {
+        let Some(branch) = branch.filter(|b| !is_main && b.starts_with("mindgrid/best-of-")) else {
+            continue;
+        };
+        // The `.git` file is written when the worktree is created
+        let created = std::fs::metadata(Path::new(&path).join(".git")).and_then(|m| m.modified());
+        if created.map(|at| at >= before).unwrap_or(false) {
+            continue;
+        }
+
+        if Path::new(&path).exists() {
+            git_in(&path, &["add", "-A"], "add_failed", "Failed to stage the candidate's changes").await?;
+            let staged = tokio::process::Command::new("git")
+                .arg("-C")
+                .arg(&path)
+                .args(["diff", "--cached", "--quiet"])
+                .status()
+                .await
+                .map(|s| !s.success())
+                .unwrap_or(false);
+            if staged {
+                git_in(
+                    &path,
+                    &["commit", "--no-verify", "--no-gpg-sign", "-m", "Interrupted Best-of-N candidate"],
+                    "commit_failed",
+                    "Failed to commit the candidate's changes",
+                )
+                .await?;
+            }
+        }
+        archive_worktree(project_path, &path, &branch).await?;
+        swept += 1;
+    }
+    Ok(swept)
+}
+
+/// Sweep candidate worktrees a previous app run left behind in every known project
+pub fn start_sweep(app: AppHandle) {
+    let started = SystemTime::now();
+    tauri::async_runtime::spawn(async move {
+        let projects = match crate::app_store::projects(&app) {
+            Ok(projects) => projects,
+            Err(e) => {
+                eprintln!("[MindGrid] Best-of-N sweep failed to list projects: {}", e.technical_message());
+                return;
+            }
+        };
+        for path in projects.into_iter().map(|p| p.path) {
+            if !Path::new(&path).is_dir() {
+                continue;
+            }
+            match sweep_orphaned_worktrees(&path, started).await {
+                Ok(0) => {}
+                Ok(swept) => eprintln!("[MindGrid] Archived {} interrupted Best-of-N candidate(s) in {}", swept, path),
+                Err(e) => eprintln!("[MindGrid] Best-of-N sweep failed in {}: {}", path, e.technical_message()),
+            }
+        }
+    });
+}
+
+/// Give the same task to several agents/models, each in its own worktree.
+/// Worktrees are created up front; agents then run concurrently and the run
+/// is broadcast as `best-of-n-updated` whenever a candidate finishes. Agent
+/// output streams on `best-of-n:<run_id>:<candidate_id>`.
+#[tauri::command]
+pub async fn best_of_n_start(
+    app: AppHandle,
+    state: tauri::State<'_, Arc<BestOfNState>>,
+    args: BestOfNArgs,
+) -> MindgridResult<BestOfNRun> {
+    if args.task.trim().is_empty() {
+        return Err(MindgridError::validation("empty_task", "Describe the task for the agents"));
+    }
+    if args.candidates.is_empty() || args.candidates.len() > MAX_CANDIDATES {
+        return Err(MindgridError::validation(
+            "candidate_count",
+            format!("Pick between 1 and {} candidates", MAX_CANDIDATES),
+        ));
+    }
+
+    let ids = crate::multi_model::target_ids(&args.candidates, 1)?;
+
+    let run_id = args.run_id.unwrap_or_else(|| Uuid::new_v4().to_string());
+    let short_id: String = run_id.chars().filter(|c| c.is_ascii_alphanumeric()).take(6).collect();
+    let ctx = Arc::new(QueryContext {
+        app: app.clone(),
+        query_id: run_id.clone(),
+        channel_prefix: "best-of-n",
+        prompt: args.task.clone(),
+        system_prompt: args.system_prompt,
+        cwd: PathBuf::from(&args.project_path),
+        isolation: QueryIsolation::Worktree,
+        permission_mode: args.permission_mode,
+    });
+
+    // Sequentially, since concurrent `git worktree add` calls contend for the same locks
+    let mut jobs = Vec::with_capacity(args.candidates.len());
+    let mut candidates: Vec<Candidate> = Vec::with_capacity(args.candidates.len());
+    for (target, id) in args.candidates.into_iter().zip(ids) {
+        let name = format!("best-of-{}-{}", short_id, id).to_lowercase();
+        let created = crate::git::create_workspace_worktree(
+            app.clone(),
+            args.project_path.clone(),
+            name.clone(),
+            None,
+            None,
+            None,
+        )
+        .await;
+        let worktree = match created {
+            Ok(worktree) => worktree,
+            Err(e) => {
+                for candidate in &candidates {
+                    let _ = crate::git::remove_workspace_worktree(args.project_path.clone(), candidate.worktree_path.clone()).await;
+                }
+                return Err(e);
+            }
+        };
+        candidates.push(Candidate {
+            id: id.clone(),
+            agent: target.agent,
+            model: target.model.clone(),
+            worktree_path: worktree.clone(),
+            branch: format!("mindgrid/{}", name),
+            status: CandidateStatus::Running,
+            response: None,
+            error: None,
+            diff: None,
+            test: None,
+            cost_usd: None,
+            duration_ms: None,
+        });
+        jobs.push((
+            TargetRun {
+                ctx: ctx.clone(),
+                target_id: id,
+                target,
+            },
+            worktree,
+        ));
+    }
+
+    let run = BestOfNRun {
+        id: run_id.clone(),
+        project_path: args.project_path,
+        task: args.task,
+        test_command: args.test_command.filter(|c| !c.trim().is_empty()),
+        candidates,
+        winner: None,
+        created_at: now_ms(),
+    };
+    state.runs.lock().insert(run_id.clone(), run.clone());
+
+    let state = state.inner().clone();
+    for (target, worktree) in jobs {
+        tauri::async_runtime::spawn(run_candidate(
+            app.clone(),
+            state.clone(),
+            run_id.clone(),
+            target,
+            worktree,
+            run.test_command.clone(),
+        ));
+    }
+    Ok(run)
+}
+
+#[tauri::command]
+pub async fn best_of_n_status(state: tauri::State<'_, Arc<BestOfNState>>, run_id: String) -> MindgridResult<BestOfNRun> {
+    state.get(&run_id)
+}
+
+/// Merge the winning candidate into the main branch and archive the others
+#[tauri::command]
+pub async fn best_of_n_pick(
+    app: AppHandle,
+    state: tauri::State<'_, Arc<BestOfNState>>,
+    run_id: String,
+    candidate_id: String,
+    commit_message: Option<String>,
+) -> MindgridResult<BestOfNRun> {
+    let run = state.get(&run_id)?;
+    if run.winner.is_some() {
+        return Err(MindgridError::validation("already_picked", "A winner was already picked for this run"));
+    }
+    if run.candidates.iter().any(|c| c.status == CandidateStatus::Running) {
+        return Err(MindgridError::validation("candidates_running", "Wait for all candidates to finish"));
+    }
+    let winner = run
+        .candidates
+        .iter()
+        .find(|c| c.id == candidate_id)
+        .ok_or_else(|| MindgridError::validation("candidate_not_found", "Candidate not found").with_technical(&candidate_id))?;
+
+    commit_candidate(&app, winner, &run.task).await?;
+    let message = commit_message.unwrap_or_else(|| run.task.lines().next().unwrap_or("Best-of-N result").to_string());
+    merge_winner(&run.project_path, winner, message).await?;
+    state.update(&app, &run_id, |run| {
+        run.winner = Some(candidate_id.clone());
+        if let Some(candidate) = run.candidate(&candidate_id) {
+            candidate.status = CandidateStatus::Merged;
+        }
+    });
+
+    for candidate in run.candidates.iter().filter(|c| c.id != candidate_id) {
+        match archive_candidate(&app, &run.project_path, candidate, &run.task).await {
+            Ok(()) => state.update(&app, &run_id, |run| {
+                if let Some(archived) = run.candidate(&candidate.id) {
+                    archived.status = CandidateStatus::Archived;
+                }
+            }),
+            Err(e) => eprintln!("[MindGrid] Failed to archive candidate {}: {}", candidate.id, e.technical_message()),
+        }
+    }
+    state.get(&run_id)
+}
+
+/// Archive every candidate without merging and forget the run
+#[tauri::command]
+pub async fn best_of_n_discard(
+    app: AppHandle,
+    state: tauri::State<'_, Arc<BestOfNState>>,
+    run_id: String,
+) -> MindgridResult<()> {
+    let run = state.get(&run_id)?;
+    if run.candidates.iter().any(|c| c.status == CandidateStatus::Running) {
+        return Err(MindgridError::validation("candidates_running", "Wait for all candidates to finish"));
+    }
+    for candidate in run
+        .candidates
+        .iter()
+        .filter(|c| !matches!(c.status, CandidateStatus::Merged | CandidateStatus::Archived))
+    {
+        archive_candidate(&app, &run.project_path, candidate, &run.task).await?;
+    }
+    state.runs.lock().remove(&run_id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::multi_model::{target_ids, QueryAgent, QueryTarget};
+    use crate::tests::fixtures::{git_in, TempRepo};
+    use std::time::Duration;
+
+    fn target(id: Option<&str>) -> QueryTarget {
+        QueryTarget {
+            id: id.map(str::to_string),
+            agent: QueryAgent::Claude,
+            model: None,
+        }
+    }
+
+    /// A candidate worktree on `mindgrid/best-of-run1-<id>` with `files` committed
+    fn finished_candidate(project: &TempRepo, id: &str, files: &[(&str, &str)]) -> Candidate {
+        let name = format!("best-of-run1-{}", id);
+        let path = project.path.join(".mindgrid").join("worktrees").join(&name);
+        let branch = format!("mindgrid/{}", name);
+        project.git(&["worktree", "add", "-q", "-b", &branch, &path.to_string_lossy()]);
+        for (file, content) in files {
+            std::fs::write(path.join(file), content).unwrap();
+        }
+        git_in(&path, &["add", "-A"]);
+        git_in(&path, &["commit", "-q", "-m", &format!("Candidate {}", id)]);
+        Candidate {
+            id: id.to_string(),
+            agent: QueryAgent::Claude,
+            model: None,
+            worktree_path: path.to_string_lossy().to_string(),
+            branch,
+            status: CandidateStatus::Succeeded,
+            response: None,
+            error: None,
+            diff: None,
+            test: None,
+            cost_usd: None,
+            duration_ms: None,
+        }
+    }
+
+    fn branches(project: &TempRepo) -> Vec<String> {
+        project.git(&["branch", "--format=%(refname:short)"]).lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn best_of_n_args_and_statuses() {
+        let args: BestOfNArgs = serde_json::from_value(serde_json::json!({
+            "project_path": "/tmp/demo",
+            "task": "Add a --verbose flag",
+            "candidates": [{ "agent": "claude", "model": "opus" }, { "agent": "codex" }],
+            "test_command": "cargo test",
+        }))
+        .unwrap();
+        assert_eq!(args.candidates.len(), 2);
+        assert!(args.run_id.is_none() && args.system_prompt.is_none());
+        assert_eq!(serde_json::to_value(CandidateStatus::Archived).unwrap(), serde_json::json!("archived"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_commands_run_as_cancellable_operations() {
+        let project = TempRepo::new();
+        let operations = Arc::new(OperationRegistry::new());
+        let id = operation_id("run1", "1");
+
+        let op = operations.begin(Some(id.clone()), "best_of_n_tests").unwrap();
+        let test = run_tests(&op, &project.path, "echo ok; exit 3").await;
+        assert!(!test.passed);
+        assert_eq!(test.exit_code, Some(3));
+        assert_eq!(test.output, "ok\n");
+        drop(op);
+
+        let op = operations.begin(Some(id.clone()), "best_of_n_tests").unwrap();
+        let cancel = {
+            let operations = operations.clone();
+            tokio::spawn(async move {
+                tokio::time::sleep(Duration::from_millis(300)).await;
+                operations.cancel(&id)
+            })
+        };
+        let started = Instant::now();
+        let test = run_tests(&op, &project.path, "sleep 30").await;
+        assert!(cancel.await.unwrap());
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert!(!test.passed && test.exit_code.is_none());
+        assert_eq!(test.output, "Test command was cancelled");
+    }
+
+    #[test]
+    fn candidate_ids_are_validated() {
+        assert_eq!(target_ids(&[target(None), target(Some("fast-1"))], 1).unwrap(), ["1", "fast-1"]);
+
+        for bad in ["../x", "Fast", "a b", ""] {
+            let err = target_ids(&[target(Some(bad))], 1).unwrap_err();
+            assert_eq!(err.code, "validation.invalid_target_id", "{:?}", bad);
+        }
+        let err = target_ids(&[target(Some("2")), target(None)], 1).unwrap_err();
+        assert_eq!(err.code, "validation.duplicate_target_id");
+    }
+
+    #[tokio::test]
+    async fn sweep_archives_orphaned_candidates() {
+        let project = TempRepo::with_files(&[("README.md", "hello\n")]);
+        let worktrees = project.path.join(".mindgrid").join("worktrees");
+        let candidate = worktrees.join("best-of-abc123-1");
+        let session = worktrees.join("feature");
+        project.git(&["worktree", "add", "-q", "-b", "mindgrid/best-of-abc123-1", &candidate.to_string_lossy()]);
+        project.git(&["worktree", "add", "-q", "-b", "mindgrid/feature", &session.to_string_lossy()]);
+        std::fs::write(candidate.join("answer.txt"), "42\n").unwrap();
+
+        // Worktrees created after the app started may belong to a live run
+        let started = SystemTime::now() - Duration::from_secs(60);
+        assert_eq!(sweep_orphaned_worktrees(&project.path_str(), started).await.unwrap(), 0);
+        assert!(candidate.exists());
+
+        let started = SystemTime::now() + Duration::from_secs(60);
+        assert_eq!(sweep_orphaned_worktrees(&project.path_str(), started).await.unwrap(), 1);
+        assert!(!candidate.exists());
+        assert!(session.exists());
+
+        let branches = project.git(&["branch", "--format=%(refname:short)"]);
+        assert!(branches.lines().any(|b| b == "mindgrid/archive/best-of-abc123-1"), "{}", branches);
+        assert!(branches.lines().any(|b| b == "mindgrid/feature"), "{}", branches);
+        let archived = git_in(&project.path, &["show", "mindgrid/archive/best-of-abc123-1:answer.txt"]);
+        assert_eq!(archived, "42\n");
+    }
+
+    #[tokio::test]
+    async fn winner_is_merged_and_the_rest_archived() {
+        let project = TempRepo::with_files(&[("README.md", "hello\n")]);
+        let winner = finished_candidate(&project, "1", &[("flag.rs", "pub const VERBOSE: bool = true;\n")]);
+        let other = finished_candidate(&project, "2", &[("flag.rs", "pub const VERBOSE: bool = false;\n")]);
+
+        merge_winner(&project.path_str(), &winner, "Add a --verbose flag".to_string()).await.unwrap();
+        archive_worktree(&project.path_str(), &other.worktree_path, &other.branch).await.unwrap();
+
+        // One squashed commit on main with the winner's changes
+        assert_eq!(project.git(&["log", "-1", "--format=%s"]).trim(), "Add a --verbose flag");
+        assert_eq!(project.git(&["rev-list", "--count", "main"]).trim(), "2");
+        assert_eq!(project.read("flag.rs"), "pub const VERBOSE: bool = true;\n");
+        assert_eq!(project.git(&["status", "--porcelain"]), "");
+
+        assert!(!Path::new(&winner.worktree_path).exists());
+        assert!(!Path::new(&other.worktree_path).exists());
+        assert_eq!(branches(&project), ["main", "mindgrid/archive/best-of-run1-2"]);
+        let archived = project.git(&["show", "mindgrid/archive/best-of-run1-2:flag.rs"]);
+        assert_eq!(archived, "pub const VERBOSE: bool = false;\n");
+    }
+
+    #[tokio::test]
+    async fn conflicting_winner_is_left_in_place() {
+        let project = TempRepo::with_files(&[("flag.rs", "pub const VERBOSE: bool = false;\n")]);
+        let winner = finished_candidate(&project, "1", &[("flag.rs", "pub const VERBOSE: bool = true;\n")]);
+        // Main moved on while the candidate ran
+        project.write("flag.rs", "pub const VERBOSE: u8 = 0;\n");
+        project.commit_all("Make verbosity a level");
+
+        let err = merge_winner(&project.path_str(), &winner, "Add a --verbose flag".to_string())
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, "git.merge_failed");
+        assert!(Path::new(&winner.worktree_path).exists());
+        assert!(branches(&project).contains(&winner.branch));
+        assert_eq!(project.git(&["log", "-1", "--format=%s"]).trim(), "Make verbosity a level");
+        assert_eq!(project.git(&["status", "--porcelain"]), "");
+    }
+}
@@ -1,53 +1,208 @@
 use serde::Serialize;
 use serde::Deserialize;
-use std::collections::HashSet;
 use std::path::PathBuf;
 use std::process::Command;
+use std::sync::{Arc, OnceLock};
+use tauri::{AppHandle, Manager};
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::process::Command as TokioCommand;
+use ts_rs::TS;
 use uuid::Uuid;
 
-#[derive(Debug, Serialize)]
-pub struct CodexModel {
-    pub id: String,
-    pub name: String,
+use crate::error::{MindgridError, MindgridResult};
+use crate::operations::{OperationRegistry, ProcessTracker};
+
+/// Oldest Node.js major version the Codex SDK supports
+const MIN_NODE_MAJOR: u32 = 18;
+
+/// Bundled resources directory, set at startup. The runner scripts ship in
+/// `scripts/` (see tauri.conf.json); an SDK placed in `node_modules/` there is preferred.
+static RESOURCE_DIR: OnceLock<PathBuf> = OnceLock::new();
+/// Node binary and version, cached once found to be usable
+static NODE: OnceLock<(String, String)> = OnceLock::new();
+/// `node_modules` directory containing @openai/codex-sdk, cached once found
+static SDK_NODE_MODULES: OnceLock<PathBuf> = OnceLock::new();
+
+pub fn init_runner_resources(app: &AppHandle) {
+    match app.path().resource_dir() {
+        Ok(dir) => {
+            let _ = RESOURCE_DIR.set(dir);
+        }
+        Err(e) => eprintln!("[MindGrid] Failed to resolve resource dir for the Codex runner: {}", e),
+    }
 }
 
-/// Run the Codex CLI `/model` command and extract available model identifiers.
-#[tauri::command]
-pub fn codex_list_models() -> Result<Vec<CodexModel>, String> {
-    let output = Command::new("codex")
-        .arg("/model")
-        .env("NO_COLOR", "1")
-        .env("TERM", "dumb")
-        .env("CI", "true")
-        .output()
-        .map_err(|e| format!("Failed to run Codex CLI: {}", e))?;
-
-    let combined = format!(
-        "{}\n{}",
-        String::from_utf8_lossy(&output.stdout),
-        String::from_utf8_lossy(&output.stderr)
-    );
-
-    let parsed = parse_models(&combined);
-
-    if parsed.is_empty() {
-        return Err("No models returned by Codex CLI".to_string());
+/// Dev builds run from the source tree, where resources aren't copied next to the binary
+fn source_tree_dir(relative: &str) -> Option<PathBuf> {
+    if cfg!(debug_assertions) {
+        Some(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("..").join(relative))
+    } else {
+        None
     }
+}
+
+fn resolve_codex_runner_path(script: &str) -> MindgridResult<PathBuf> {
+    let candidates: Vec<PathBuf> = RESOURCE_DIR
+        .get()
+        .map(|dir| dir.join("scripts"))
+        .into_iter()
+        .chain(source_tree_dir("scripts"))
+        .map(|dir| dir.join(script))
+        .collect();
+    candidates.iter().find(|path| path.exists()).cloned().ok_or_else(|| {
+        let searched: Vec<String> = candidates.iter().map(|p| p.display().to_string()).collect();
+        MindgridError::agent("runner_missing", "The Codex SDK runner is missing from this installation")
+            .with_technical(format!("searched: {}", searched.join(", ")))
+    })
+}
 
-    Ok(parsed)
+/// Find `node` the way `find_gh_path` finds gh: GUI launches often miss the shell PATH
+fn find_node_path() -> String {
+    let paths = [
+        "/opt/homebrew/bin/node",     // Apple Silicon Homebrew
+        "/usr/local/bin/node",        // Intel Homebrew / official installer
+        "/usr/bin/node",              // System install
+    ];
+    let found = paths.iter().find(|path| std::path::Path::new(path).exists());
+    found.map_or("node", |path| path).to_string()
 }
 
-fn resolve_codex_runner_path() -> Result<PathBuf, String> {
-    let base = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../scripts/codex-sdk-runner.mjs");
-    if base.exists() {
-        Ok(base)
-    } else {
-        Err(format!("Codex SDK runner not found at {}", base.display()))
+/// Parse `node --version` output ("v20.11.1") into its major version
+pub(crate) fn node_major_version(version: &str) -> Option<u32> {
+    version.trim().trim_start_matches('v').split('.').next()?.parse().ok()
+}
+
+/// Locate a usable Node.js, returning its path and version
+fn check_node() -> MindgridResult<(String, String)> {
+    if let Some(node) = NODE.get() {
+        return Ok(node.clone());
+    }
+    let node = find_node_path();
+    let output = Command::new(&node).arg("--version").output().map_err(|e| {
+        MindgridError::agent("node_missing", "Codex needs Node.js, but it was not found. Install Node.js 18 or newer.")
+            .with_command(format!("{} --version", node))
+            .with_technical(e)
+    })?;
+    let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    match node_major_version(&version) {
+        Some(major) if output.status.success() && major >= MIN_NODE_MAJOR => {
+            let _ = NODE.set((node.clone(), version.clone()));
+            Ok((node, version))
+        }
+        Some(_) => Err(MindgridError::agent(
+            "node_outdated",
+            format!("Codex needs Node.js {} or newer, but {} is installed", MIN_NODE_MAJOR, version),
+        )
+        .with_command(format!("{} --version", node))),
+        None => Err(MindgridError::agent("node_missing", "Codex needs Node.js, but it could not be run")
+            .with_command(format!("{} --version", node))
+            .with_output(&output)),
+    }
+}
+
+/// Find the `node_modules` holding @openai/codex-sdk: bundled, source tree, then global
+fn find_codex_sdk(node: &str) -> MindgridResult<PathBuf> {
+    if let Some(dir) = SDK_NODE_MODULES.get() {
+        return Ok(dir.clone());
+    }
+    let has_sdk = |dir: &PathBuf| dir.join("@openai/codex-sdk/package.json").exists();
+    let mut candidates: Vec<PathBuf> = RESOURCE_DIR
+        .get()
+        .map(|dir| dir.join("node_modules"))
+        .into_iter()
+        .chain(source_tree_dir("node_modules"))
+        .collect();
+    if !candidates.iter().any(has_sdk) {
+        // npm sits next to node in every standard install
+        let npm = PathBuf::from(node).with_file_name("npm");
+        let npm = if npm.exists() { npm } else { PathBuf::from("npm") };
+        if let Ok(output) = Command::new(npm).args(["root", "-g"]).output() {
+            if output.status.success() {
+                candidates.push(PathBuf::from(String::from_utf8_lossy(&output.stdout).trim()));
+            }
+        }
+    }
+
+    let found = candidates.iter().find(|dir| has_sdk(dir)).and_then(|dir| std::fs::canonicalize(dir).ok());
+    match found {
+        Some(dir) => {
+            let _ = SDK_NODE_MODULES.set(dir.clone());
+            Ok(dir)
+        }
+        None => {
+            let searched: Vec<String> = candidates.iter().map(|p| p.display().to_string()).collect();
+            Err(MindgridError::agent(
+                "sdk_missing",
+                "The Codex SDK is not installed. Install it with: npm install -g @openai/codex-sdk",
+            )
+            .with_technical(format!("searched: {}", searched.join(", "))))
+        }
     }
 }
 
+/// `node <script>` with piped stdio and the SDK made discoverable.
+/// Returns the command and its display form for errors.
+pub(crate) fn codex_runner_command(script: &str) -> MindgridResult<(TokioCommand, String)> {
+    let script_path = resolve_codex_runner_path(script)?;
+    let (node, _) = check_node()?;
+    let node_modules = find_codex_sdk(&node)?;
+
+    let mut cmd = TokioCommand::new(&node);
+    cmd.arg(script_path.to_string_lossy().to_string());
+    // Own process group, so cancelling takes down the codex processes the runner starts
+    #[cfg(unix)]
+    cmd.process_group(0);
+    cmd.stdin(std::process::Stdio::piped());
+    cmd.stdout(std::process::Stdio::piped());
+    cmd.stderr(std::process::Stdio::piped());
+
+    // The bundled scripts aren't under node_modules, so point node at the SDK
+    cmd.env("NODE_PATH", &node_modules);
+    let sdk_dist_path = node_modules.join("@openai/codex-sdk/dist/index.js");
+    if sdk_dist_path.exists() {
+        cmd.env("CODEX_SDK_DIST_PATH", sdk_dist_path);
+    }
+
+    Ok((cmd, format!("{} {}", node, script_path.display())))
+}
+
+/// What the Codex runner needs, so the UI can explain a broken setup
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
+pub struct CodexRunnerDiagnostics {
+    pub ready: bool,
+    pub node_path: Option<String>,
+    pub node_version: Option<String>,
+    pub runner_path: Option<String>,
+    pub sdk_path: Option<String>,
+    pub problems: Vec<MindgridError>,
+}
+
+/// Check Node.js, the bundled runner and the Codex SDK without running anything
+#[tauri::command]
+pub async fn codex_runner_diagnostics() -> MindgridResult<CodexRunnerDiagnostics> {
+    tokio::task::spawn_blocking(|| {
+        let mut problems = Vec::new();
+        let runner_path = resolve_codex_runner_path("codex-sdk-runner.mjs")
+            .map_err(|e| problems.push(e))
+            .ok();
+        let node = check_node().map_err(|e| problems.push(e)).ok();
+        let sdk_path = node
+            .as_ref()
+            .and_then(|(path, _)| find_codex_sdk(path).map_err(|e| problems.push(e)).ok());
+        CodexRunnerDiagnostics {
+            ready: problems.is_empty(),
+            node_path: node.as_ref().map(|(path, _)| path.clone()),
+            node_version: node.map(|(_, version)| version),
+            runner_path: runner_path.map(|p| p.display().to_string()),
+            sdk_path: sdk_path.map(|p| p.join("@openai/codex-sdk").display().to_string()),
+            problems,
+        }
+    })
+    .await
+    .map_err(|e| MindgridError::agent("diagnostics_failed", "Failed to check the Codex setup").with_technical(e))
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 struct CodexSdkInvocation {
     #[serde(rename = "sessionId")]
@@ -71,30 +226,49 @@ fn default_skip_git_repo_check() -> bool {
 
 /// Run a Codex prompt through the SDK runner (single-turn) and return concatenated output.
 #[tauri::command]
-pub async fn run_codex(prompt: String, model: Option<String>, cwd: Option<String>, system_prompt: Option<String>) -> Result<String, String> {
-    let script_path = resolve_codex_runner_path()?;
-
-    let mut cmd = TokioCommand::new("node");
-    cmd.arg(script_path.to_string_lossy().to_string());
-    cmd.stdin(std::process::Stdio::piped());
-    cmd.stdout(std::process::Stdio::piped());
-    cmd.stderr(std::process::Stdio::piped());
+pub async fn run_codex(
+    operations: tauri::State<'_, Arc<OperationRegistry>>,
+    prompt: String,
+    model: Option<String>,
+    cwd: Option<String>,
+    system_prompt: Option<String>,
+    operation_id: Option<String>,
+) -> MindgridResult<String> {
+    let op = operations.begin(operation_id, "run_codex")?;
+    let tracker = op.tracker();
+    op.run(
+        "codex",
+        run_codex_streamed(prompt, model, cwd, system_prompt, "workspace-write", Some(&tracker), |_| {}),
+    )
+    .await?
+}
 
-    // Ensure node_modules is discoverable
-    let node_modules_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../node_modules");
-    if let Ok(canonical) = std::fs::canonicalize(&node_modules_dir) {
-        cmd.env("NODE_PATH", &canonical);
-        let sdk_dist_path = canonical.join("@openai/codex-sdk/dist/index.js");
-        if sdk_dist_path.exists() {
-            cmd.env("CODEX_SDK_DIST_PATH", sdk_dist_path);
-        }
-    }
+/// Run a Codex prompt, passing each runner output line to `on_line` as it arrives.
+/// `sandbox_mode` is the SDK sandbox ("read-only", "workspace-write", ...).
+/// The runner is registered with `tracker` so a cancelled operation can kill it.
+pub(crate) async fn run_codex_streamed(
+    prompt: String,
+    model: Option<String>,
+    cwd: Option<String>,
+    system_prompt: Option<String>,
+    sandbox_mode: &str,
+    tracker: Option<&ProcessTracker>,
+    mut on_line: impl FnMut(&str) + Send,
+) -> MindgridResult<String> {
+    let (mut cmd, command_line) = codex_runner_command("codex-sdk-runner.mjs")?;
 
     if let Some(dir) = &cwd {
         cmd.current_dir(dir);
     }
 
-    let mut child = cmd.spawn().map_err(|e| format!("Failed to spawn Codex SDK runner: {}", e))?;
+    let mut child = cmd.spawn().map_err(|e| {
+        MindgridError::agent("spawn_failed", "Failed to start the Codex SDK runner")
+            .with_command(command_line.clone())
+            .with_technical(e)
+    })?;
+    if let (Some(tracker), Some(pid)) = (tracker, child.id()) {
+        tracker.track(pid);
+    }
 
     // Write payload
     if let Some(mut stdin) = child.stdin.take() {
@@ -102,12 +276,13 @@ pub async fn run_codex(prompt: String, model: Option<String>, cwd: Option<String
             session_id: format!("session-{}", Uuid::new_v4().to_string()),
             prompt,
             working_directory: cwd.clone(),
-            sandbox_mode: Some("workspace-write".to_string()),
+            sandbox_mode: Some(sandbox_mode.to_string()),
             model,
             system_prompt,
             skip_git_repo_check: true,
         };
-        let serialized = serde_json::to_string(&payload).map_err(|e| format!("Failed to serialize Codex payload: {}", e))?;
+        let serialized = serde_json::to_string(&payload)
+            .map_err(|e| MindgridError::agent("invalid_payload", "Failed to serialize Codex payload").with_technical(e))?;
         tokio::spawn(async move {
             let _ = stdin.write_all(serialized.as_bytes()).await;
             let _ = stdin.shutdown().await;
@@ -115,6 +290,7 @@ pub async fn run_codex(prompt: String, model: Option<String>, cwd: Option<String
     }
 
     // Collect stdout/stderr
+    let stderr = crate::operations::collect_stderr(&mut child);
     let mut combined = String::new();
 
     if let Some(stdout) = child.stdout.take() {
@@ -123,124 +299,48 @@ pub async fn run_codex(prompt: String, model: Option<String>, cwd: Option<String
             if line.trim().is_empty() {
                 continue;
             }
+            on_line(&line);
             combined.push_str(&line);
             combined.push('\n');
         }
     }
 
-    if let Some(stderr) = child.stderr.take() {
-        let mut lines = BufReader::new(stderr).lines();
-        while let Ok(Some(line)) = lines.next_line().await {
-            if line.trim().is_empty() {
-                continue;
-            }
-            combined.push_str(&line);
-            combined.push('\n');
+    let stderr = stderr.await.unwrap_or_default();
+    for line in String::from_utf8_lossy(&stderr).lines() {
+        if line.trim().is_empty() {
+            continue;
         }
+        combined.push_str(line);
+        combined.push('\n');
     }
 
-    let status = child.wait().await.map_err(|e| format!("Failed to wait for Codex runner: {}", e))?;
+    let status = child.wait().await.map_err(|e| {
+        MindgridError::agent("run_failed", "Failed to wait for the Codex runner")
+            .with_command(command_line.clone())
+            .with_technical(e)
+    })?;
     if !status.success() {
-        // Return the actual error output instead of just the status code
+        // Attach the actual error output instead of just the status code
+        let mut error = MindgridError::agent("run_failed", "Codex run failed").with_command(command_line);
+        error.details.exit_code = status.code();
         if !combined.trim().is_empty() {
-            return Err(combined);
-        } else {
-            return Err(format!("Codex runner exited with status {:?}", status.code()));
+            error.details.stderr = Some(combined.trim().to_string());
         }
+        return Err(error);
     }
 
     Ok(combined)
 }
 
-fn parse_models(raw: &str) -> Vec<CodexModel> {
-    // Try to parse JSON first (either an array of ids, or an object with `models`)
-    if let Ok(value) = serde_json::from_str::<serde_json::Value>(raw) {
-        if let Some(arr) = value.as_array() {
-            return normalize_models(arr.iter().filter_map(|v| v.as_str()));
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        if let Some(models) = value
-            .get("models")
-            .and_then(|v| v.as_array())
-            .map(|arr| normalize_models(arr.iter().filter_map(|v| v.as_str())))
-        {
-            return models;
-        }
+    #[test]
+    fn node_version_parsing() {
+        assert_eq!(node_major_version("v20.11.1\n"), Some(20));
+        assert_eq!(node_major_version("v8.17.0"), Some(8));
+        assert_eq!(node_major_version(""), None);
+        assert_eq!(node_major_version("command not found"), None);
     }
-
-    // Fallback: scan text for tokens that look like Codex/GPT model ids
-    let mut found: Vec<String> = Vec::new();
-    let mut seen = HashSet::new();
-
-    for word in raw.split_whitespace() {
-        let cleaned = word
-            .trim_matches(|c: char| !c.is_alphanumeric() && c != '-' && c != '_' && c != '.')
-            .trim_matches(['\'', '"']);
-
-        if cleaned.is_empty() {
-            continue;
-        }
-
-        let lower = cleaned.to_lowercase();
-        let looks_like_model = lower.contains("gpt") || lower.contains("codex") || lower == "auto";
-
-        if looks_like_model && seen.insert(lower.clone()) {
-            found.push(cleaned.to_string());
-        }
-    }
-
-    found
-        .into_iter()
-        .map(|id| CodexModel {
-            name: prettify_model_id(&id),
-            id,
-        })
-        .collect()
-}
-
-fn normalize_models<'a>(models: impl Iterator<Item = &'a str>) -> Vec<CodexModel> {
-    let mut seen = HashSet::new();
-    let mut out = Vec::new();
-
-    for id in models {
-        if id.is_empty() {
-            continue;
-        }
-        if seen.insert(id.to_string()) {
-            out.push(CodexModel {
-                id: id.to_string(),
-                name: prettify_model_id(id),
-            });
-        }
-    }
-
-    out
-}
-
-fn prettify_model_id(id: &str) -> String {
-    let mut parts: Vec<String> = id
-        .replace('_', "-")
-        .split('-')
-        .filter(|s| !s.is_empty())
-        .map(|s| {
-            let mut chars = s.chars();
-            match chars.next() {
-                Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
-                None => String::new(),
-            }
-        })
-        .collect();
-
-    if parts.is_empty() {
-        return id.to_string();
-    }
-
-    if parts[0].eq_ignore_ascii_case("gpt") && parts.len() > 1 {
-        parts[1] = format!("GPT {}", parts[1]);
-        parts.remove(0);
-    }
-
-    parts.join(" ")
 }
-
-
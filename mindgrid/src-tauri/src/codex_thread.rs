@@ -0,0 +1,468 @@
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Sqlite};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::ChildStdin;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::error::{MindgridError, MindgridResult};
+
+const RUNNER_SCRIPT: &str = "codex-sdk-thread.mjs";
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export, optional_fields = nullable)]
+pub struct CodexThreadArgs {
+    /// Reopen a stored thread
+    pub thread_id: Option<String>,
+    /// Without `thread_id`, continue the session's most recent thread
+    pub session_id: Option<String>,
+    /// Codex conversation to continue when the stored thread has none, e.g. the
+    /// one a thread closed for a model change had already started
+    pub codex_thread_id: Option<String>,
+    pub cwd: String,
+    pub model: Option<String>,
+    /// Codex SDK sandbox; defaults to "workspace-write"
+    pub sandbox_mode: Option<String>,
+    pub system_prompt: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct CodexThreadInfo {
+    pub thread_id: String,
+    /// Assigned by Codex on the first turn
+    pub codex_thread_id: Option<String>,
+    pub session_id: Option<String>,
+    pub cwd: String,
+    pub model: Option<String>,
+    /// The thread continues an earlier Codex conversation
+    pub resumed: bool,
+}
+
+/// Payload of `codex-thread-event`: one runner envelope, as `run_codex` returns them
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct CodexThreadEvent {
+    pub thread_id: String,
+    pub turn_id: String,
+    pub line: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum CodexTurnStatus {
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+/// Payload of `codex-turn-finished`
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct CodexTurnFinished {
+    pub thread_id: String,
+    pub turn_id: String,
+    pub status: CodexTurnStatus,
+    pub error: Option<String>,
+}
+
+/// Control messages written by the runner next to the event envelopes
+#[derive(Deserialize)]
+#[serde(tag = "control", rename_all = "snake_case")]
+pub(crate) enum RunnerControl {
+    Thread {
+        #[serde(rename = "threadId")]
+        thread_id: String,
+    },
+    TurnFinished {
+        #[serde(rename = "turnId")]
+        turn_id: String,
+        status: CodexTurnStatus,
+        error: Option<String>,
+    },
+}
+
+struct ThreadProcess {
+    info: Mutex<CodexThreadInfo>,
+    /// Closing the runner's stdin ends it
+    stdin: tokio::sync::Mutex<Option<ChildStdin>>,
+    /// Turn in progress
+    turn: Mutex<Option<String>>,
+}
+
+impl ThreadProcess {
+    async fn write(&self, command: serde_json::Value) -> MindgridResult<()> {
+        let mut line = command.to_string();
+        line.push('\n');
+        let closed = || MindgridError::agent("runner_closed", "The Codex thread is no longer running");
+        let mut stdin = self.stdin.lock().await;
+        let stdin = stdin.as_mut().ok_or_else(closed)?;
+        stdin
+            .write_all(line.as_bytes())
+            .await
+            .map_err(|e| closed().with_technical(e))?;
+        stdin.flush().await.map_err(MindgridError::from)
+    }
+}
+
+pub struct CodexThreadState {
+    threads: Mutex<HashMap<String, Arc<ThreadProcess>>>,
+}
+
+impl CodexThreadState {
+    pub fn new() -> Self {
+        Self {
+            threads: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn get(&self, thread_id: &str) -> MindgridResult<Arc<ThreadProcess>> {
+        self.threads.lock().get(thread_id).cloned().ok_or_else(|| {
+            MindgridError::validation("thread_not_running", "Codex thread is not running; start it again")
+                .with_technical(thread_id)
+        })
+    }
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+fn db_error(code: &'static str, message: &'static str) -> impl Fn(sqlx::Error) -> MindgridError {
+    move |e| MindgridError::db(code, message).with_technical(e)
+}
+
+async fn pool(app: &AppHandle) -> MindgridResult<Pool<Sqlite>> {
+    crate::db::get_pool(app)
+        .await
+        .map_err(|e| MindgridError::db("unavailable", "The thread database is not loaded").with_technical(e))
+}
+
+/// (thread id, Codex thread id) of a stored thread
+async fn find_thread(pool: &Pool<Sqlite>, args: &CodexThreadArgs) -> MindgridResult<Option<(String, Option<String>)>> {
+    let row = match (&args.thread_id, &args.session_id) {
+        (Some(thread_id), _) => sqlx::query_as("SELECT id, codex_thread_id FROM codex_threads WHERE id = ?")
+            .bind(thread_id)
+            .fetch_optional(pool)
+            .await,
+        (None, Some(session_id)) => sqlx::query_as(
+            "SELECT id, codex_thread_id FROM codex_threads WHERE session_id = ? ORDER BY updated_at DESC LIMIT 1",
+        )
+        .bind(session_id)
+        .fetch_optional(pool)
+        .await,
+        (None, None) => Ok(None),
+    };
+    row.map_err(db_error("query_failed", "Failed to load the Codex thread"))
+}
+
+async fn save_thread(pool: &Pool<Sqlite>, info: &CodexThreadInfo, sandbox_mode: &str) -> MindgridResult<()> {
+    let now = now_ms();
+    sqlx::query(
+        "INSERT INTO codex_threads (id, session_id, codex_thread_id, cwd, model, sandbox_mode, created_at, updated_at) \
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?) \
+         ON CONFLICT(id) DO UPDATE SET cwd = excluded.cwd, model = excluded.model, \
+         sandbox_mode = excluded.sandbox_mode, updated_at = excluded.updated_at, \
+         codex_thread_id = COALESCE(excluded.codex_thread_id, codex_threads.codex_thread_id)",
+    )
+    .bind(&info.thread_id)
+    .bind(&info.session_id)
+    .bind(&info.codex_thread_id)
+    .bind(&info.cwd)
+    .bind(&info.model)
+    .bind(sandbox_mode)
+    .bind(now)
+    .bind(now)
+    .execute(pool)
+    .await
+    .map_err(db_error("insert_failed", "Failed to save the Codex thread"))?;
+    Ok(())
+}
+
+async fn record_codex_thread_id(pool: &Pool<Sqlite>, thread_id: &str, codex_thread_id: &str) -> MindgridResult<()> {
+    sqlx::query("UPDATE codex_threads SET codex_thread_id = ?, updated_at = ? WHERE id = ?")
+        .bind(codex_thread_id)
+        .bind(now_ms())
+        .bind(thread_id)
+        .execute(pool)
+        .await
+        .map_err(db_error("update_failed", "Failed to save the Codex thread id"))?;
+    Ok(())
+}
+
+/// Forward runner output until it exits
+async fn read_runner(app: AppHandle, thread_id: String, process: Arc<ThreadProcess>, stdout: tokio::process::ChildStdout) {
+    let mut lines = BufReader::new(stdout).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        if let Ok(control) = serde_json::from_str::<RunnerControl>(&line) {
+            match control {
+                RunnerControl::Thread { thread_id: codex_thread_id } => {
+                    process.info.lock().codex_thread_id = Some(codex_thread_id.clone());
+                    let recorded = async { record_codex_thread_id(&pool(&app).await?, &thread_id, &codex_thread_id).await };
+                    if let Err(e) = recorded.await {
+                        eprintln!("[MindGrid] {}", e.technical_message());
+                    }
+                }
+                RunnerControl::TurnFinished { turn_id, status, error } => {
+                    process.turn.lock().take();
+                    let _ = app.emit(
+                        "codex-turn-finished",
+                        CodexTurnFinished {
+                            thread_id: thread_id.clone(),
+                            turn_id,
+                            status,
+                            error,
+                        },
+                    );
+                }
+            }
+            continue;
+        }
+
+        let turn_id = process.turn.lock().clone().unwrap_or_default();
+        let _ = app.emit(
+            "codex-thread-event",
+            CodexThreadEvent {
+                thread_id: thread_id.clone(),
+                turn_id,
+                line,
+            },
+        );
+    }
+
+    // The runner exited; fail a turn it didn't finish
+    if let Some(turn_id) = process.turn.lock().take() {
+        let _ = app.emit(
+            "codex-turn-finished",
+            CodexTurnFinished {
+                thread_id: thread_id.clone(),
+                turn_id,
+                status: CodexTurnStatus::Failed,
+                error: Some("The Codex runner exited".to_string()),
+            },
+        );
+    }
+    let state = app.state::<Arc<CodexThreadState>>();
+    let mut threads = state.threads.lock();
+    if threads.get(&thread_id).is_some_and(|current| Arc::ptr_eq(current, &process)) {
+        threads.remove(&thread_id);
+    }
+    drop(threads);
+    let _ = app.emit("codex-thread-closed", thread_id);
+}
+
+/// Start (or reopen) a Codex thread backed by a long-lived SDK runner.
+/// Turn output streams as `codex-thread-event`, completion as `codex-turn-finished`.
+#[tauri::command]
+pub async fn codex_start_thread(
+    app: AppHandle,
+    state: tauri::State<'_, Arc<CodexThreadState>>,
+    args: CodexThreadArgs,
+) -> MindgridResult<CodexThreadInfo> {
+    if let Some(thread_id) = &args.thread_id {
+        if let Ok(process) = state.get(thread_id) {
+            return Ok(process.info.lock().clone());
+        }
+    }
+    if !std::path::Path::new(&args.cwd).is_dir() {
+        return Err(MindgridError::validation("directory_missing", "Working directory does not exist")
+            .with_technical(&args.cwd));
+    }
+
+    // Threads still work without the database; they just can't be resumed later
+    let db = pool(&app).await;
+    let stored = match &db {
+        Ok(pool) => find_thread(pool, &args).await?,
+        Err(e) => {
+            eprintln!("[MindGrid] Codex thread won't be persisted: {}", e.technical_message());
+            None
+        }
+    };
+    let (thread_id, codex_thread_id) = match stored {
+        Some((thread_id, _)) if state.get(&thread_id).is_ok() => {
+            return Ok(state.get(&thread_id)?.info.lock().clone());
+        }
+        Some((thread_id, codex_thread_id)) => (thread_id, codex_thread_id.or(args.codex_thread_id.clone())),
+        None => (
+            args.thread_id.clone().unwrap_or_else(|| Uuid::new_v4().to_string()),
+            args.codex_thread_id.clone(),
+        ),
+    };
+    let sandbox_mode = args.sandbox_mode.clone().unwrap_or_else(|| "workspace-write".to_string());
+    let info = CodexThreadInfo {
+        thread_id: thread_id.clone(),
+        resumed: codex_thread_id.is_some(),
+        codex_thread_id,
+        session_id: args.session_id.clone(),
+        cwd: args.cwd.clone(),
+        model: args.model.clone(),
+    };
+    if let Ok(pool) = &db {
+        save_thread(pool, &info, &sandbox_mode).await?;
+    }
+
+    let (mut cmd, command_line) = crate::codex::codex_runner_command(RUNNER_SCRIPT)?;
+    let mut child = cmd.current_dir(&args.cwd).kill_on_drop(true).spawn().map_err(|e| {
+        MindgridError::agent("spawn_failed", "Failed to start the Codex SDK runner")
+            .with_command(command_line)
+            .with_technical(e)
+    })?;
+    let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+        return Err(MindgridError::agent("spawn_failed", "Codex SDK runner has no stdio"));
+    };
+    if let Some(stderr) = child.stderr.take() {
+        let thread_id = thread_id.clone();
+        tauri::async_runtime::spawn(async move {
+            let mut lines = BufReader::new(stderr).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                eprintln!("[MindGrid] Codex thread {}: {}", thread_id, line);
+            }
+        });
+    }
+
+    let process = Arc::new(ThreadProcess {
+        info: Mutex::new(info.clone()),
+        stdin: tokio::sync::Mutex::new(Some(stdin)),
+        turn: Mutex::new(None),
+    });
+    process
+        .write(serde_json::json!({
+            "type": "start",
+            "threadId": info.codex_thread_id,
+            "workingDirectory": args.cwd,
+            "sandboxMode": sandbox_mode,
+            "model": args.model,
+            "systemPrompt": args.system_prompt,
+            "skipGitRepoCheck": true,
+        }))
+        .await?;
+
+    state.threads.lock().insert(thread_id.clone(), process.clone());
+    tauri::async_runtime::spawn(async move {
+        read_runner(app, thread_id, process, stdout).await;
+        let _ = child.wait().await;
+    });
+    Ok(info)
+}
+
+/// Send a prompt to a running thread. Returns the turn id its events carry.
+#[tauri::command]
+pub async fn codex_send(
+    state: tauri::State<'_, Arc<CodexThreadState>>,
+    thread_id: String,
+    prompt: String,
+) -> MindgridResult<String> {
+    let process = state.get(&thread_id)?;
+    let turn_id = Uuid::new_v4().to_string();
+    {
+        let mut turn = process.turn.lock();
+        if turn.is_some() {
+            return Err(MindgridError::validation("turn_running", "Wait for the current Codex turn to finish"));
+        }
+        *turn = Some(turn_id.clone());
+    }
+    let sent = process
+        .write(serde_json::json!({ "type": "send", "turnId": turn_id, "prompt": prompt }))
+        .await;
+    if let Err(e) = sent {
+        process.turn.lock().take();
+        return Err(e);
+    }
+    Ok(turn_id)
+}
+
+/// Cancel the running turn; the thread stays open
+#[tauri::command]
+pub async fn codex_cancel(state: tauri::State<'_, Arc<CodexThreadState>>, thread_id: String) -> MindgridResult<()> {
+    let process = state.get(&thread_id)?;
+    if process.turn.lock().is_none() {
+        return Ok(());
+    }
+    process.write(serde_json::json!({ "type": "cancel" })).await
+}
+
+/// Stop a thread's runner. The thread stays stored and can be reopened.
+/// Returns its last state (with the Codex thread id assigned meanwhile), or
+/// nothing if it wasn't running.
+#[tauri::command]
+pub async fn codex_close_thread(
+    state: tauri::State<'_, Arc<CodexThreadState>>,
+    thread_id: String,
+) -> MindgridResult<Option<CodexThreadInfo>> {
+    let process = state.threads.lock().remove(&thread_id);
+    let Some(process) = process else {
+        return Ok(None);
+    };
+    process.stdin.lock().await.take();
+    let info = process.info.lock().clone();
+    Ok(Some(info))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codex_runner_control_lines() {
+        let finished = r#"{"control":"turn_finished","turnId":"t1","status":"cancelled"}"#;
+        match serde_json::from_str::<RunnerControl>(finished).unwrap() {
+            RunnerControl::TurnFinished { turn_id, status, error } => {
+                assert_eq!(turn_id, "t1");
+                assert_eq!(status, CodexTurnStatus::Cancelled);
+                assert!(error.is_none());
+            }
+            RunnerControl::Thread { .. } => panic!("expected turn_finished"),
+        }
+        // Event envelopes are forwarded, not treated as control messages
+        let envelope = r#"{"sessionId":"t1","turnId":"t1","content":"{}","finished":false}"#;
+        assert!(serde_json::from_str::<RunnerControl>(envelope).is_err());
+    }
+
+    #[tokio::test]
+    async fn closed_threads_resume_their_conversation() {
+        let pool = crate::tests::fixtures::memory_pool().await;
+        let args = CodexThreadArgs {
+            thread_id: None,
+            session_id: Some("s1".into()),
+            codex_thread_id: None,
+            cwd: "/tmp/demo".into(),
+            model: None,
+            sandbox_mode: None,
+            system_prompt: None,
+        };
+        let mut info = CodexThreadInfo {
+            thread_id: "t1".into(),
+            codex_thread_id: None,
+            session_id: Some("s1".into()),
+            cwd: "/tmp/demo".into(),
+            model: None,
+            resumed: false,
+        };
+        save_thread(&pool, &info, "workspace-write").await.unwrap();
+        // The first turn assigns the Codex thread id
+        record_codex_thread_id(&pool, "t1", "codex-1").await.unwrap();
+
+        // Reopened after a close, by id or as the session's latest thread
+        let by_id = CodexThreadArgs { thread_id: Some("t1".into()), ..args };
+        assert_eq!(find_thread(&pool, &by_id).await.unwrap(), Some(("t1".into(), Some("codex-1".into()))));
+        let by_session = CodexThreadArgs { thread_id: None, ..by_id };
+        assert_eq!(find_thread(&pool, &by_session).await.unwrap(), Some(("t1".into(), Some("codex-1".into()))));
+
+        // Saving the reopened thread with another model keeps the conversation
+        info.model = Some("gpt-5-codex".into());
+        save_thread(&pool, &info, "workspace-write").await.unwrap();
+        assert_eq!(find_thread(&pool, &by_session).await.unwrap(), Some(("t1".into(), Some("codex-1".into()))));
+        info.codex_thread_id = Some("codex-2".into());
+        save_thread(&pool, &info, "workspace-write").await.unwrap();
+        assert_eq!(find_thread(&pool, &by_session).await.unwrap(), Some(("t1".into(), Some("codex-2".into()))));
+    }
+}
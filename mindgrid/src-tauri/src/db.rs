@@ -0,0 +1,21 @@
+use sqlx::{Pool, Sqlite};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_sql::{DbInstances, DbPool};
+
+/// Get the SQLite pool opened by the SQL plugin for the current database.
+///
+/// The plugin preloads the database at startup (see `app_context`), so this
+/// only fails if the database couldn't be opened.
+pub async fn get_pool(app: &AppHandle) -> Result<Pool<Sqlite>, String> {
+    let instances = app
+        .try_state::<DbInstances>()
+        .ok_or_else(|| "SQL plugin is not initialized".to_string())?;
+
+    let db_uri = format!("sqlite:{}", crate::get_db_name());
+    let instances = instances.0.read().await;
+
+    match instances.get(&db_uri) {
+        Some(DbPool::Sqlite(pool)) => Ok(pool.clone()),
+        None => Err(format!("Database not loaded yet: {}", db_uri)),
+    }
+}
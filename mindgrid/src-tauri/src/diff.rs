@@ -0,0 +1,968 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+use std::process::Stdio;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use ts_rs::TS;
+
+use crate::error::{MindgridError, MindgridResult};
+use crate::lfs::LfsChange;
+
+/// git's well-known empty tree, used as the base in repositories without commits
+const EMPTY_TREE: &str = "4b825dc642cb6eb9a060e54bf8d69288fbee4904";
+
+/// Tree entry mode of a submodule (gitlink)
+const GITLINK_MODE: &str = "160000";
+
+/// How many leading bytes to inspect when sniffing for binary content (same as git)
+const BINARY_SNIFF_BYTES: usize = 8000;
+
+/// Which changes a diff covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum DiffScope {
+    /// Everything not yet committed: HEAD vs working tree, plus untracked files
+    #[default]
+    Working,
+    /// Index vs HEAD
+    Staged,
+    /// Working tree vs index
+    Unstaged,
+    /// Untracked, non-ignored files
+    Untracked,
+    /// Working tree (committed or not) vs merge-base with the main branch, plus untracked files
+    VsMain,
+}
+
+/// Safeguards for large diffs
+#[derive(Debug, Clone, Copy)]
+pub struct DiffLimits {
+    /// Files larger than this are not loaded into `old_value`/`new_value`
+    pub max_file_bytes: u64,
+    /// Patches are cut after this many lines
+    pub max_patch_lines: usize,
+    /// Patches are cut after this many bytes
+    pub max_patch_bytes: usize,
+    /// Summaries list at most this many files
+    pub max_files: usize,
+}
+
+impl Default for DiffLimits {
+    fn default() -> Self {
+        Self {
+            max_file_bytes: 1024 * 1024,
+            max_patch_lines: 5000,
+            max_patch_bytes: 2 * 1024 * 1024,
+            max_files: 2000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct DiffFileEntry {
+    pub path: String,
+    /// Previous path for renames and copies
+    pub old_path: Option<String>,
+    /// "added", "modified", "deleted", "renamed", "copied", "typechange" or "untracked"
+    pub status: String,
+    pub additions: i32,
+    pub deletions: i32,
+    pub is_binary: bool,
+    /// Rename/copy similarity in percent
+    pub similarity: Option<u8>,
+    /// Untracked file too large to count lines in
+    pub too_large: bool,
+    /// Gitlink entry; the change is to the commit the submodule points at
+    pub submodule: bool,
+    /// Tracked by Git LFS; line counts refer to the pointer file
+    pub lfs: bool,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct DiffSummary {
+    pub scope: DiffScope,
+    pub files: Vec<DiffFileEntry>,
+    pub total_additions: i32,
+    pub total_deletions: i32,
+    /// More files changed than `max_files`
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct WordSegment {
+    /// "context", "added" or "removed"
+    pub kind: String,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct WordDiffHunk {
+    pub header: String,
+    /// One entry per line of the new file, each split into segments
+    pub lines: Vec<Vec<WordSegment>>,
+}
+
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
+pub struct FileDiff {
+    pub path: String,
+    pub old_path: Option<String>,
+    pub status: String,
+    pub patch: String,
+    pub old_value: String,
+    pub new_value: String,
+    pub is_binary: bool,
+    /// The patch was cut at the line or byte limit
+    pub truncated: bool,
+    /// One side was too large to load into `old_value`/`new_value`
+    pub too_large: bool,
+    pub word_diff: Option<Vec<WordDiffHunk>>,
+    /// Submodule change; `patch` holds the submodule's commit log instead of a diff
+    pub submodule: bool,
+    /// Pointer change of an LFS-tracked file; `old_value`/`new_value` are left empty
+    pub lfs: Option<LfsChange>,
+}
+
+async fn git_stdout(working_directory: &str, args: &[&str]) -> Option<String> {
+    let output = tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(working_directory)
+        .args(args)
+        .output()
+        .await
+        .ok()?;
+    if output.status.success() {
+        Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        None
+    }
+}
+
+/// Commit where the session's branch forked from the main branch
+pub async fn merge_base_with_main(working_directory: &str) -> MindgridResult<String> {
+    let main_branch = main_branch(working_directory).await?;
+    git_stdout(working_directory, &["merge-base", &main_branch, "HEAD"])
+        .await
+        .filter(|s| !s.is_empty())
+        .ok_or_else(|| {
+            MindgridError::git("no_merge_base", format!("No common ancestor between HEAD and {}", main_branch))
+        })
+}
+
+async fn main_branch(working_directory: &str) -> MindgridResult<String> {
+    crate::git::detect_main_branch(working_directory)
+        .await
+        .ok_or_else(|| MindgridError::git("no_main_branch", "Could not detect the main branch"))
+}
+
+fn require_dir(working_directory: &str) -> MindgridResult<()> {
+    let path = Path::new(working_directory);
+    if !path.exists() || !path.is_dir() {
+        return Err(MindgridError::validation("directory_missing", "Directory does not exist")
+            .with_technical(working_directory));
+    }
+    Ok(())
+}
+
+async fn head_or_empty_tree(working_directory: &str) -> String {
+    crate::stack::resolve_ref(working_directory, "HEAD")
+        .await
+        .unwrap_or_else(|| EMPTY_TREE.to_string())
+}
+
+/// Arguments selecting the two sides of `git diff` for a scope
+async fn scope_args(working_directory: &str, scope: DiffScope) -> MindgridResult<Vec<String>> {
+    Ok(match scope {
+        DiffScope::Working => vec![head_or_empty_tree(working_directory).await],
+        DiffScope::Staged => vec!["--cached".to_string(), head_or_empty_tree(working_directory).await],
+        DiffScope::Unstaged | DiffScope::Untracked => Vec::new(),
+        DiffScope::VsMain => vec![merge_base_with_main(working_directory).await?],
+    })
+}
+
+fn includes_untracked(scope: DiffScope) -> bool {
+    matches!(scope, DiffScope::Working | DiffScope::Untracked | DiffScope::VsMain)
+}
+
+fn describe_status(code: char) -> &'static str {
+    match code {
+        'A' => "added",
+        'D' => "deleted",
+        'R' => "renamed",
+        'C' => "copied",
+        'T' => "typechange",
+        _ => "modified",
+    }
+}
+
+/// Parse `git diff --raw --numstat -z` output. Both sections list files in the
+/// same order; raw records start with ':' and carry status and similarity, numstat
+/// records carry line counts ("-" for binary files).
+fn parse_raw_numstat(text: &str) -> Vec<DiffFileEntry> {
+    let mut tokens = text.split('\0').filter(|t| !t.is_empty()).peekable();
+    let mut entries: Vec<DiffFileEntry> = Vec::new();
+    let mut index_by_path: HashMap<String, usize> = HashMap::new();
+
+    while let Some(token) = tokens.next() {
+        if let Some(raw) = token.strip_prefix(':') {
+            let fields: Vec<&str> = raw.split_whitespace().collect();
+            let status_field = fields.last().copied().unwrap_or("M");
+            // Mode 160000 on either side marks a submodule
+            let submodule = fields.iter().take(2).any(|mode| *mode == GITLINK_MODE);
+            let code = status_field.chars().next().unwrap_or('M');
+            let similarity = status_field[code.len_utf8()..].parse::<u8>().ok();
+
+            let first = tokens.next().unwrap_or_default().to_string();
+            let (path, old_path) = if code == 'R' || code == 'C' {
+                (tokens.next().unwrap_or_default().to_string(), Some(first))
+            } else {
+                (first, None)
+            };
+
+            index_by_path.insert(path.clone(), entries.len());
+            entries.push(DiffFileEntry {
+                path,
+                old_path,
+                status: describe_status(code).to_string(),
+                additions: 0,
+                deletions: 0,
+                is_binary: false,
+                similarity,
+                too_large: false,
+                submodule,
+                lfs: false,
+            });
+        } else {
+            let mut parts = token.splitn(3, '\t');
+            let adds = parts.next().unwrap_or("");
+            let dels = parts.next().unwrap_or("");
+            let mut path = parts.next().unwrap_or("").to_string();
+            if path.is_empty() {
+                // Renames: "adds\tdels\t\0old\0new"
+                tokens.next();
+                path = tokens.next().unwrap_or_default().to_string();
+            }
+
+            if let Some(&i) = index_by_path.get(&path) {
+                let entry = &mut entries[i];
+                entry.is_binary = adds == "-" && dels == "-";
+                entry.additions = adds.parse().unwrap_or(0);
+                entry.deletions = dels.parse().unwrap_or(0);
+            }
+        }
+    }
+
+    entries
+}
+
+/// Line count and binary flag of an untracked file, read in-process
+fn inspect_untracked(path: &Path, limits: &DiffLimits) -> (i32, bool, bool) {
+    let size = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    let Ok(mut file) = std::fs::File::open(path) else {
+        return (0, false, false);
+    };
+
+    if size > limits.max_file_bytes {
+        let mut head = vec![0u8; BINARY_SNIFF_BYTES];
+        let n = file.read(&mut head).unwrap_or(0);
+        return (0, head[..n].contains(&0), true);
+    }
+
+    let mut content = Vec::with_capacity(size as usize);
+    if file.read_to_end(&mut content).is_err() {
+        return (0, false, false);
+    }
+    if content[..content.len().min(BINARY_SNIFF_BYTES)].contains(&0) {
+        return (0, true, false);
+    }
+
+    let mut lines = content.iter().filter(|&&b| b == b'\n').count();
+    if !content.is_empty() && !content.ends_with(b"\n") {
+        lines += 1;
+    }
+    (lines as i32, false, false)
+}
+
+async fn list_untracked(working_directory: &str, limits: DiffLimits) -> MindgridResult<Vec<DiffFileEntry>> {
+    let args = ["ls-files", "--others", "--exclude-standard", "-z"];
+    let output = tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(working_directory)
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| MindgridError::spawn_failed("git ls-files", e))?;
+    if !output.status.success() {
+        return Err(MindgridError::git_failed("ls_files_failed", "Failed to list untracked files", &args, &output));
+    }
+
+    let root = Path::new(working_directory).to_path_buf();
+    let paths: Vec<String> = String::from_utf8_lossy(&output.stdout)
+        .split('\0')
+        .filter(|p| !p.is_empty())
+        .take(limits.max_files + 1)
+        .map(|p| p.to_string())
+        .collect();
+
+    tokio::task::spawn_blocking(move || {
+        paths
+            .into_iter()
+            .map(|path| {
+                let (additions, is_binary, too_large) = inspect_untracked(&root.join(&path), &limits);
+                DiffFileEntry {
+                    path,
+                    old_path: None,
+                    status: "untracked".to_string(),
+                    additions,
+                    deletions: 0,
+                    is_binary,
+                    similarity: None,
+                    too_large,
+                    submodule: false,
+                    lfs: false,
+                }
+            })
+            .collect()
+    })
+    .await
+    .map_err(|e| MindgridError::io("inspect_failed", "Failed to inspect untracked files").with_technical(e))
+}
+
+/// List changed files for a scope with rename/copy detection and line counts
+pub async fn list_changes(
+    working_directory: &str,
+    scope: DiffScope,
+    limits: DiffLimits,
+) -> MindgridResult<DiffSummary> {
+    require_dir(working_directory)?;
+
+    let mut files = Vec::new();
+
+    if scope != DiffScope::Untracked {
+        let base = scope_args(working_directory, scope).await?;
+        let mut args = vec!["diff", "--raw", "--numstat", "-z", "-M", "-C", "--no-color", "--no-ext-diff"];
+        args.extend(base.iter().map(|b| b.as_str()));
+        let output = tokio::process::Command::new("git")
+            .arg("-C")
+            .arg(working_directory)
+            .args(&args)
+            .output()
+            .await
+            .map_err(|e| MindgridError::spawn_failed("git diff", e))?;
+
+        if !output.status.success() {
+            return Err(MindgridError::git_failed("diff_failed", "Failed to get diff", &args, &output));
+        }
+        files.extend(parse_raw_numstat(&String::from_utf8_lossy(&output.stdout)));
+    }
+
+    if includes_untracked(scope) {
+        files.extend(list_untracked(working_directory, limits).await?);
+    }
+
+    let truncated = files.len() > limits.max_files;
+    files.truncate(limits.max_files);
+    crate::lfs::mark_lfs(working_directory, &mut files).await;
+
+    let total_additions = files.iter().map(|f| f.additions).sum();
+    let total_deletions = files.iter().map(|f| f.deletions).sum();
+
+    Ok(DiffSummary {
+        scope,
+        files,
+        total_additions,
+        total_deletions,
+        truncated,
+    })
+}
+
+/// Run a command and collect its stdout up to the line/byte limits, killing it
+/// once the limit is reached. Appends a truncation marker when cut.
+async fn read_limited(mut cmd: tokio::process::Command, limits: &DiffLimits) -> MindgridResult<(String, bool)> {
+    let mut child = cmd
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| MindgridError::spawn_failed("git diff", e))?;
+
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| MindgridError::io("no_stdout", "Failed to read git diff output"))?;
+    let mut lines = BufReader::new(stdout).lines();
+
+    let mut text = String::new();
+    let mut line_count = 0usize;
+    let mut truncated = false;
+
+    while let Ok(Some(line)) = lines.next_line().await {
+        if line_count >= limits.max_patch_lines || text.len() + line.len() > limits.max_patch_bytes {
+            truncated = true;
+            break;
+        }
+        text.push_str(&line);
+        text.push('\n');
+        line_count += 1;
+    }
+
+    if truncated {
+        let _ = child.kill().await;
+        text.push_str(&format!(
+            "\\ Diff truncated after {} lines; open the file to see the rest\n",
+            line_count
+        ));
+    } else {
+        let _ = child.wait().await;
+    }
+
+    Ok((text, truncated))
+}
+
+fn diff_command(working_directory: &str, base: &[String], paths: &[&str], untracked: bool, extra: &[&str]) -> tokio::process::Command {
+    let mut cmd = tokio::process::Command::new("git");
+    cmd.arg("-C").arg(working_directory);
+    if untracked {
+        cmd.args(["diff", "--no-index", "--no-color", "--no-ext-diff"])
+            .args(extra)
+            .args(["--", "/dev/null"])
+            .args(paths);
+    } else {
+        cmd.args(["diff", "--no-color", "--no-ext-diff", "--submodule=log", "-M", "-C"])
+            .args(extra)
+            .args(base)
+            .arg("--")
+            .args(paths);
+    }
+    cmd
+}
+
+/// Status and previous path from a patch's extended header lines
+fn status_from_patch(patch: &str) -> (Option<&'static str>, Option<String>) {
+    let mut status = None;
+    let mut old_path = None;
+    for line in patch.lines() {
+        if line.starts_with("@@") {
+            break;
+        }
+        if line.starts_with("new file mode") {
+            status = Some("added");
+        } else if line.starts_with("deleted file mode") {
+            status = Some("deleted");
+        } else if let Some(from) = line.strip_prefix("rename from ") {
+            status = Some("renamed");
+            old_path = Some(from.to_string());
+        } else if let Some(from) = line.strip_prefix("copy from ") {
+            status = Some("copied");
+            old_path = Some(from.to_string());
+        } else if line.starts_with("Submodule ") {
+            // --submodule=log headers: "Submodule <path> <old>..<new> (new submodule)"
+            if line.ends_with("(new submodule)") {
+                status = Some("added");
+            } else if line.ends_with("(submodule deleted)") {
+                status = Some("deleted");
+            }
+        }
+    }
+    (status, old_path)
+}
+
+fn patch_is_binary(patch: &str) -> bool {
+    patch
+        .lines()
+        .any(|l| (l.starts_with("Binary files ") && l.ends_with(" differ")) || l == "GIT binary patch")
+}
+
+/// Parse `git diff --word-diff=porcelain` output into hunks of segmented lines
+pub fn parse_word_diff(text: &str) -> Vec<WordDiffHunk> {
+    let mut hunks: Vec<WordDiffHunk> = Vec::new();
+    let mut line: Vec<WordSegment> = Vec::new();
+
+    for raw in text.lines() {
+        if raw.starts_with("@@") {
+            if let Some(hunk) = hunks.last_mut() {
+                if !line.is_empty() {
+                    hunk.lines.push(std::mem::take(&mut line));
+                }
+            }
+            hunks.push(WordDiffHunk {
+                header: raw.to_string(),
+                lines: Vec::new(),
+            });
+            continue;
+        }
+        let Some(hunk) = hunks.last_mut() else {
+            continue;
+        };
+
+        let (kind, body) = match raw.chars().next() {
+            Some(' ') => ("context", &raw[1..]),
+            Some('+') => ("added", &raw[1..]),
+            Some('-') => ("removed", &raw[1..]),
+            Some('~') => {
+                hunk.lines.push(std::mem::take(&mut line));
+                continue;
+            }
+            _ => continue,
+        };
+        line.push(WordSegment {
+            kind: kind.to_string(),
+            text: body.to_string(),
+        });
+    }
+
+    if let Some(hunk) = hunks.last_mut() {
+        if !line.is_empty() {
+            hunk.lines.push(line);
+        }
+    }
+    hunks
+}
+
+/// Read a blob (`<rev>:<path>` or `:<path>` for the index) if it is within the size limit
+async fn read_blob(working_directory: &str, spec: &str, max_bytes: u64) -> (String, bool) {
+    let Some(size) = git_stdout(working_directory, &["cat-file", "-s", spec])
+        .await
+        .and_then(|s| s.parse::<u64>().ok())
+    else {
+        return (String::new(), false);
+    };
+    if size > max_bytes {
+        return (String::new(), true);
+    }
+    let content = tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(working_directory)
+        .args(["cat-file", "blob", spec])
+        .output()
+        .await
+        .map(|o| String::from_utf8_lossy(&o.stdout).to_string())
+        .unwrap_or_default();
+    (content, false)
+}
+
+fn read_worktree_file(path: &Path, max_bytes: u64) -> (String, bool) {
+    match std::fs::metadata(path) {
+        Ok(meta) if meta.len() > max_bytes => (String::new(), true),
+        Ok(_) => (std::fs::read_to_string(path).unwrap_or_default(), false),
+        Err(_) => (String::new(), false),
+    }
+}
+
+/// Diff a single file for a scope, with optional word-level diff
+pub async fn file_diff(
+    working_directory: &str,
+    file_path: &str,
+    old_path: Option<&str>,
+    scope: DiffScope,
+    untracked: bool,
+    word_diff: bool,
+    limits: DiffLimits,
+) -> MindgridResult<FileDiff> {
+    require_dir(working_directory)?;
+    let root = Path::new(working_directory);
+
+    let untracked = untracked || scope == DiffScope::Untracked;
+    let base = if untracked {
+        Vec::new()
+    } else {
+        scope_args(working_directory, scope).await?
+    };
+
+    let mut paths = vec![file_path];
+    if let Some(old) = old_path.filter(|o| *o != file_path) {
+        paths.push(old);
+    }
+
+    let (patch, truncated) = read_limited(
+        diff_command(working_directory, &base, &paths, untracked, &[]),
+        &limits,
+    )
+    .await?;
+
+    let (patch_status, patch_old_path) = status_from_patch(&patch);
+    let status = if untracked {
+        "untracked".to_string()
+    } else {
+        patch_status.unwrap_or("modified").to_string()
+    };
+    let old_path = patch_old_path.or_else(|| old_path.map(|s| s.to_string()));
+
+    let submodule = !untracked && patch.lines().any(|l| l.starts_with("Submodule "));
+    let lfs = if untracked || submodule {
+        None
+    } else if crate::lfs::lfs_paths(working_directory, &[file_path]).await.contains(file_path) {
+        Some(crate::lfs::change_from_patch(&patch))
+    } else {
+        None
+    };
+
+    let full_path = root.join(file_path);
+    let is_binary = if untracked {
+        let path = full_path.clone();
+        tokio::task::spawn_blocking(move || inspect_untracked(&path, &limits).1)
+            .await
+            .unwrap_or(false)
+    } else {
+        patch_is_binary(&patch)
+    };
+
+    let word_diff = if word_diff && !is_binary && !submodule && lfs.is_none() {
+        let (text, _) = read_limited(
+            diff_command(working_directory, &base, &paths, untracked, &["--word-diff=porcelain"]),
+            &limits,
+        )
+        .await?;
+        Some(parse_word_diff(&text))
+    } else {
+        None
+    };
+
+    let mut too_large = false;
+    let (old_value, new_value) = if is_binary || submodule || lfs.is_some() {
+        (String::new(), String::new())
+    } else {
+        let old_spec_path = old_path.as_deref().unwrap_or(file_path);
+        let old_rev = match scope {
+            _ if untracked || status == "added" => None,
+            DiffScope::Unstaged => Some(String::new()),
+            _ => base.last().cloned(),
+        };
+        let (old_value, old_too_large) = match old_rev {
+            Some(rev) => read_blob(working_directory, &format!("{}:{}", rev, old_spec_path), limits.max_file_bytes).await,
+            None => (String::new(), false),
+        };
+
+        let (new_value, new_too_large) = if status == "deleted" {
+            (String::new(), false)
+        } else if scope == DiffScope::Staged {
+            read_blob(working_directory, &format!(":{}", file_path), limits.max_file_bytes).await
+        } else {
+            read_worktree_file(&full_path, limits.max_file_bytes)
+        };
+
+        too_large = old_too_large || new_too_large;
+        (old_value, new_value)
+    };
+
+    Ok(FileDiff {
+        path: file_path.to_string(),
+        old_path,
+        status,
+        patch,
+        old_value,
+        new_value,
+        is_binary,
+        truncated,
+        too_large,
+        word_diff,
+        submodule,
+        lfs,
+    })
+}
+
+/// Commits listed in a session diff are capped to keep the payload small
+const MAX_SESSION_COMMITS: usize = 200;
+
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
+pub struct SessionCommit {
+    pub hash: String,
+    pub short_hash: String,
+    pub subject: String,
+    pub author: String,
+    /// Unix timestamp (seconds)
+    #[ts(type = "number")]
+    pub timestamp: i64,
+    pub files: Vec<DiffFileEntry>,
+    pub additions: i32,
+    pub deletions: i32,
+}
+
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
+pub struct SessionDiff {
+    pub main_branch: String,
+    /// merge-base(main, HEAD) the session is compared against
+    pub base: String,
+    /// Commits made in the session, oldest first
+    pub commits: Vec<SessionCommit>,
+    /// More commits than listed
+    pub commits_truncated: bool,
+    /// Everything the session changed, committed or not, against the base
+    pub aggregate: DiffSummary,
+    /// The part of `aggregate` that is not committed yet
+    pub uncommitted: DiffSummary,
+}
+
+/// Per-commit file lists for `base..HEAD`, oldest first
+async fn session_commits(working_directory: &str, base: &str) -> MindgridResult<(Vec<SessionCommit>, bool)> {
+    let range = format!("{}..HEAD", base);
+    let args = [
+        "log",
+        "-z",
+        "--reverse",
+        "--format=%x1e%H%x1f%h%x1f%s%x1f%an%x1f%ct",
+        "--raw",
+        "--numstat",
+        "-M",
+        "-C",
+        "--no-color",
+        range.as_str(),
+    ];
+    let output = tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(working_directory)
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| MindgridError::spawn_failed("git log", e))?;
+
+    if !output.status.success() {
+        return Err(MindgridError::git_failed("log_failed", "Failed to read session commits", &args, &output));
+    }
+
+    let text = String::from_utf8_lossy(&output.stdout);
+    let mut commits: Vec<SessionCommit> = text
+        .split('\u{1e}')
+        .filter(|chunk| !chunk.trim().is_empty())
+        .map(|chunk| {
+            let (header, changes) = chunk.split_once('\0').unwrap_or((chunk, ""));
+            let fields: Vec<&str> = header.split('\u{1f}').collect();
+            let field = |i: usize| fields.get(i).copied().unwrap_or("").to_string();
+
+            let files = parse_raw_numstat(changes.trim_start_matches('\n'));
+            SessionCommit {
+                hash: field(0),
+                short_hash: field(1),
+                subject: field(2),
+                author: field(3),
+                timestamp: field(4).parse().unwrap_or(0),
+                additions: files.iter().map(|f| f.additions).sum(),
+                deletions: files.iter().map(|f| f.deletions).sum(),
+                files,
+            }
+        })
+        .collect();
+
+    // Keep the most recent commits when the session has a very long history
+    let truncated = commits.len() > MAX_SESSION_COMMITS;
+    if truncated {
+        commits.drain(..commits.len() - MAX_SESSION_COMMITS);
+    }
+    Ok((commits, truncated))
+}
+
+/// Everything a session changed relative to where it forked from main
+pub async fn session_diff(working_directory: &str, limits: DiffLimits) -> MindgridResult<SessionDiff> {
+    let main_branch = main_branch(working_directory).await?;
+    let base = merge_base_with_main(working_directory).await?;
+
+    let (commits, aggregate, uncommitted) = tokio::try_join!(
+        session_commits(working_directory, &base),
+        list_changes(working_directory, DiffScope::VsMain, limits),
+        list_changes(working_directory, DiffScope::Working, limits),
+    )?;
+    let (commits, commits_truncated) = commits;
+
+    Ok(SessionDiff {
+        main_branch,
+        base,
+        commits,
+        commits_truncated,
+        aggregate,
+        uncommitted,
+    })
+}
+
+/// Diff a session worktree (committed and uncommitted work) against merge-base(main, HEAD)
+#[tauri::command]
+pub async fn get_session_diff(working_directory: String) -> MindgridResult<SessionDiff> {
+    session_diff(&working_directory, DiffLimits::default()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::fixtures::TempRepo;
+
+    // Captured from `git diff --cached --raw --numstat -z -M -C HEAD`, plus a gitlink record
+    const RAW_NUMSTAT: &str = concat!(
+        ":000000 100644 0000000 d5f7fc3 A\0added.txt\0",
+        ":100644 000000 286c5f5 0000000 D\0gone.txt\0",
+        ":100644 100644 b566061 f832141 M\0keep.txt\0",
+        ":100644 100644 88768ef f68ed80 M\0logo.png\0",
+        ":100644 100644 600d48a a3fb829 R086\0old name.txt\0new name.txt\0",
+        ":160000 160000 1f2e3d4 5a6b7c8 M\0vendor/lib\0",
+        "1\t0\tadded.txt\0",
+        "0\t1\tgone.txt\0",
+        "1\t1\tkeep.txt\0",
+        "-\t-\tlogo.png\0",
+        "1\t0\t\0old name.txt\0new name.txt\0",
+        "1\t1\tvendor/lib\0",
+    );
+
+    // Captured from `git diff --word-diff=porcelain` after editing the third line
+    const WORD_DIFF: &str = "diff --git a/keep.txt b/keep.txt\n\
+                             index b566061..f832141 100644\n\
+                             --- a/keep.txt\n\
+                             +++ b/keep.txt\n\
+                             @@ -1,3 +1,3 @@\n \
+                             one\n~\n \
+                             two\n~\n \
+                             three \n-old\n+changed here\n~\n";
+
+    #[test]
+    fn parses_raw_numstat() {
+        let entries = parse_raw_numstat(RAW_NUMSTAT);
+        let summary: Vec<(&str, &str, i32, i32)> = entries
+            .iter()
+            .map(|e| (e.path.as_str(), e.status.as_str(), e.additions, e.deletions))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("added.txt", "added", 1, 0),
+                ("gone.txt", "deleted", 0, 1),
+                ("keep.txt", "modified", 1, 1),
+                ("logo.png", "modified", 0, 0),
+                ("new name.txt", "renamed", 1, 0),
+                ("vendor/lib", "modified", 1, 1),
+            ]
+        );
+
+        assert!(entries[3].is_binary);
+        assert!(!entries[2].is_binary);
+        assert_eq!(entries[4].old_path.as_deref(), Some("old name.txt"));
+        assert_eq!(entries[4].similarity, Some(86));
+        assert_eq!(entries[2].similarity, None);
+        assert!(entries[5].submodule);
+        assert!(!entries[0].submodule);
+    }
+
+    #[test]
+    fn parses_word_diff() {
+        let hunks = parse_word_diff(WORD_DIFF);
+        assert_eq!(hunks.len(), 1);
+        assert_eq!(hunks[0].header, "@@ -1,3 +1,3 @@");
+
+        let lines: Vec<Vec<(&str, &str)>> = hunks[0]
+            .lines
+            .iter()
+            .map(|line| line.iter().map(|s| (s.kind.as_str(), s.text.as_str())).collect())
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                vec![("context", "one")],
+                vec![("context", "two")],
+                vec![("context", "three "), ("removed", "old"), ("added", "changed here")],
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_status_from_patch_headers() {
+        let renamed = "diff --git a/a.txt b/b.txt\nsimilarity index 90%\nrename from a.txt\nrename to b.txt\n@@ -1 +1 @@\n";
+        assert_eq!(status_from_patch(renamed), (Some("renamed"), Some("a.txt".to_string())));
+        assert_eq!(status_from_patch("Submodule vendor/lib 0000000...1f2e3d4 (new submodule)\n"), (Some("added"), None));
+        assert!(patch_is_binary("diff --git a/logo.png b/logo.png\nBinary files a/logo.png and b/logo.png differ\n"));
+        assert!(!patch_is_binary("+Binary files are fine in text\n"));
+    }
+
+    #[tokio::test]
+    async fn large_and_binary_files_stay_out_of_memory() {
+        let repo = TempRepo::with_files(&[("big.txt", &"line\n".repeat(400))]);
+        repo.write("big.txt", &"line\n".repeat(800));
+        repo.write("huge-untracked.txt", &"x\n".repeat(600));
+        std::fs::write(repo.path.join("blob.bin"), [0u8, 159, 146, 150]).unwrap();
+        let limits = DiffLimits {
+            max_file_bytes: 1000,
+            max_patch_lines: 50,
+            max_patch_bytes: 1 << 20,
+            max_files: 10,
+        };
+        let path = repo.path_str();
+
+        let summary = list_changes(&path, DiffScope::Working, limits).await.unwrap();
+        let find = |name: &str| summary.files.iter().find(|f| f.path == name).unwrap();
+        assert_eq!(find("big.txt").additions, 400);
+        assert!(find("huge-untracked.txt").too_large);
+        assert!(find("blob.bin").is_binary);
+
+        let diff = file_diff(&path, "big.txt", None, DiffScope::Working, false, true, limits).await.unwrap();
+        assert!(diff.truncated);
+        assert!(diff.too_large);
+        assert!(diff.old_value.is_empty() && diff.new_value.is_empty());
+        assert!(diff.patch.ends_with("open the file to see the rest\n"), "{}", diff.patch);
+
+        let limits = DiffLimits { max_files: 2, ..limits };
+        assert!(list_changes(&path, DiffScope::Working, limits).await.unwrap().truncated);
+    }
+
+    /// Paths listed for a scope, in order
+    async fn scope_paths(path: &str, scope: DiffScope) -> Vec<String> {
+        let summary = list_changes(path, scope, DiffLimits::default()).await.unwrap();
+        summary.files.into_iter().map(|f| format!("{} {}", f.status, f.path)).collect()
+    }
+
+    #[tokio::test]
+    async fn scopes_and_session_diff_against_merge_base() {
+        let repo = TempRepo::with_files(&[("base.txt", "base\n"), ("shared.txt", "one\n")]);
+        repo.git(&["checkout", "-q", "-b", "mindgrid/session"]);
+        repo.write("feature.txt", "feature\n");
+        repo.commit_all("Add feature");
+        repo.write("shared.txt", "one\ntwo\n");
+        repo.commit_all("Extend shared");
+
+        // main moves on after the fork; none of it belongs to the session
+        repo.git(&["checkout", "-q", "main"]);
+        repo.write("upstream.txt", "upstream\n");
+        repo.commit_all("Upstream work");
+        repo.git(&["checkout", "-q", "mindgrid/session"]);
+
+        repo.write("staged.txt", "staged\n");
+        repo.git(&["add", "staged.txt"]);
+        repo.write("base.txt", "base\nedited\n");
+        repo.write("scratch.txt", "scratch\n");
+        let path = repo.path_str();
+
+        assert_eq!(scope_paths(&path, DiffScope::Staged).await, ["added staged.txt"]);
+        assert_eq!(scope_paths(&path, DiffScope::Unstaged).await, ["modified base.txt"]);
+        assert_eq!(scope_paths(&path, DiffScope::Untracked).await, ["untracked scratch.txt"]);
+        assert_eq!(
+            scope_paths(&path, DiffScope::Working).await,
+            ["modified base.txt", "added staged.txt", "untracked scratch.txt"]
+        );
+        assert_eq!(
+            scope_paths(&path, DiffScope::VsMain).await,
+            [
+                "modified base.txt",
+                "added feature.txt",
+                "modified shared.txt",
+                "added staged.txt",
+                "untracked scratch.txt"
+            ]
+        );
+
+        let diff = session_diff(&path, DiffLimits::default()).await.unwrap();
+        assert_eq!(diff.main_branch, "main");
+        assert_eq!(diff.base, repo.git(&["merge-base", "main", "HEAD"]).trim());
+        let subjects: Vec<&str> = diff.commits.iter().map(|c| c.subject.as_str()).collect();
+        assert_eq!(subjects, ["Add feature", "Extend shared"]);
+        assert_eq!(diff.commits[1].files[0].path, "shared.txt");
+        assert_eq!((diff.commits[1].additions, diff.commits[1].deletions), (1, 0));
+        assert_eq!(diff.aggregate.files.len(), 5);
+        assert_eq!(diff.uncommitted.files.len(), 3);
+
+        let file = file_diff(&path, "shared.txt", None, DiffScope::VsMain, false, false, DiffLimits::default())
+            .await
+            .unwrap();
+        assert_eq!((file.old_value.as_str(), file.new_value.as_str()), ("one\n", "one\ntwo\n"));
+    }
+}
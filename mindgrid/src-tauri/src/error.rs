@@ -0,0 +1,176 @@
+use serde::Serialize;
+use std::fmt;
+use std::process::Output;
+use ts_rs::TS;
+
+/// Area an error comes from, so the UI can react without matching on messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum ErrorKind {
+    Git,
+    Pty,
+    Io,
+    Db,
+    Agent,
+    Validation,
+    /// Cancelled or timed out (see operations.rs)
+    Operation,
+}
+
+/// Error returned by mindgrid commands
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct MindgridError {
+    pub kind: ErrorKind,
+    /// Stable identifier such as "git.worktree_add_failed" or "pty.not_found"
+    pub code: String,
+    /// Short message suitable for showing to the user
+    pub message: String,
+    /// Boxed so results carrying a `MindgridError` stay small
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub details: Box<ErrorDetails>,
+}
+
+/// Technical details of an error, serialized inline with it
+#[derive(Debug, Clone, Default, Serialize, TS)]
+pub struct ErrorDetails {
+    /// Underlying error for logs and "show details" views
+    pub technical: Option<String>,
+    /// The external command that failed, e.g. "git worktree add -B ..."
+    pub command: Option<String>,
+    pub stderr: Option<String>,
+    pub exit_code: Option<i32>,
+}
+
+impl MindgridError {
+    pub fn new(kind: ErrorKind, code: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            code: code.into(),
+            message: message.into(),
+            details: Box::default(),
+        }
+    }
+
+    /// Create a Git error
+    pub fn git(code: &str, message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Git, format!("git.{}", code), message)
+    }
+
+    /// Create a PTY error
+    pub fn pty(code: &str, message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Pty, format!("pty.{}", code), message)
+    }
+
+    /// Create a file system / process spawning error
+    pub fn io(code: &str, message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Io, format!("io.{}", code), message)
+    }
+
+    /// Create a database error
+    pub fn db(code: &str, message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Db, format!("db.{}", code), message)
+    }
+
+    /// Create an agent (Claude, Codex, ...) error
+    pub fn agent(code: &str, message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Agent, format!("agent.{}", code), message)
+    }
+
+    /// Create a validation error for bad input
+    pub fn validation(code: &str, message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Validation, format!("validation.{}", code), message)
+    }
+
+    /// Create an error for a cancelled or timed out operation
+    pub fn operation(code: &str, message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Operation, format!("operation.{}", code), message)
+    }
+
+    pub fn with_technical(mut self, technical: impl fmt::Display) -> Self {
+        self.details.technical = Some(technical.to_string());
+        self
+    }
+
+    pub fn with_command(mut self, command: impl Into<String>) -> Self {
+        self.details.command = Some(command.into());
+        self
+    }
+
+    /// Attach the exit code and stderr of a finished process
+    pub fn with_output(mut self, output: &Output) -> Self {
+        self.details.exit_code = output.status.code();
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        if !stderr.is_empty() {
+            self.details.stderr = Some(stderr);
+        }
+        self
+    }
+
+    /// A git invocation exited unsuccessfully
+    pub fn git_failed(code: &str, message: impl Into<String>, args: &[&str], output: &Output) -> Self {
+        Self::git(code, message)
+            .with_command(format!("git {}", args.join(" ")))
+            .with_output(output)
+    }
+
+    /// A process could not be started at all
+    pub fn spawn_failed(program: &str, error: impl fmt::Display) -> Self {
+        Self::io("spawn_failed", format!("Failed to run {}", program))
+            .with_command(program.to_string())
+            .with_technical(error)
+    }
+
+    /// User-facing message with stderr appended, for places that only show a string
+    pub fn user_message(&self) -> String {
+        match &self.details.stderr {
+            Some(stderr) => format!("{}: {}", self.message, stderr),
+            None => self.message.clone(),
+        }
+    }
+
+    /// Full description for logging
+    pub fn technical_message(&self) -> String {
+        let mut text = format!("[{}] {}", self.code, self.message);
+        if let Some(command) = &self.details.command {
+            text.push_str(&format!(" (command: {}", command));
+            if let Some(code) = self.details.exit_code {
+                text.push_str(&format!(", exit code {}", code));
+            }
+            text.push(')');
+        }
+        if let Some(technical) = &self.details.technical {
+            text.push_str(&format!(": {}", technical));
+        }
+        if let Some(stderr) = &self.details.stderr {
+            text.push_str(&format!("\n{}", stderr));
+        }
+        text
+    }
+}
+
+impl fmt::Display for MindgridError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.user_message())
+    }
+}
+
+impl std::error::Error for MindgridError {}
+
+impl From<std::io::Error> for MindgridError {
+    fn from(error: std::io::Error) -> Self {
+        MindgridError::io("error", error.to_string()).with_technical(format!("{:?}", error.kind()))
+    }
+}
+
+/// Convert MindgridError to String for code paths that still report plain strings
+impl From<MindgridError> for String {
+    fn from(error: MindgridError) -> Self {
+        error.user_message()
+    }
+}
+
+/// Result type alias for mindgrid commands
+pub type MindgridResult<T> = Result<T, MindgridError>;
@@ -0,0 +1,738 @@
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::error::{MindgridError, MindgridResult};
+
+/// Shared markdown memory kept in the project root and mirrored into every
+/// session worktree
+pub const FOUNDATION_FILES: &[&str] = &["PLAN.md", "CONTEXT.md", "DECISIONS.md", "PROGRESS.md"];
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct FoundationFile {
+    pub name: String,
+    pub path: String,
+    pub exists: bool,
+    pub content: String,
+    /// Worktrees whose copy conflicts with the project copy
+    pub conflicts: Vec<String>,
+}
+
+/// Where a change came from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+#[ts(export)]
+pub enum ChangeSource {
+    /// Saved from the Foundations panel
+    User,
+    /// Edited in the project root outside mindgrid (e.g. an editor)
+    Project,
+    /// Written in a session worktree (usually by the agent)
+    Worktree { path: String },
+}
+
+/// Payload of the `foundation-changed` event
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct FoundationChange {
+    pub project_path: String,
+    pub file: String,
+    pub sources: Vec<ChangeSource>,
+    /// Unified diff from the previous synced content
+    pub diff: String,
+    pub content: String,
+    /// The file was deleted and removed from every copy
+    pub deleted: bool,
+}
+
+/// Payload of the `foundation-conflict` event. The worktree copy is left
+/// untouched until the conflict is resolved with `foundations_write`.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct FoundationConflict {
+    pub project_path: String,
+    pub file: String,
+    pub worktree_path: String,
+    pub base: String,
+    pub ours: String,
+    pub theirs: String,
+    /// Merge result with conflict markers
+    pub merged: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum WriteStatus {
+    Written,
+    /// The file changed since the editor loaded it and was merged cleanly
+    Merged,
+    /// Nothing was written; `content` holds the merge with conflict markers
+    Conflict,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct FoundationWriteResult {
+    pub status: WriteStatus,
+    pub content: String,
+}
+
+enum Merge {
+    Clean(String),
+    Conflict(String),
+}
+
+/// Event produced by a sync, emitted by the caller
+pub(crate) enum SyncEvent {
+    Changed(FoundationChange),
+    Conflict(FoundationConflict),
+}
+
+fn emit_events(app: &AppHandle, events: Vec<SyncEvent>) {
+    for event in events {
+        let _ = match event {
+            SyncEvent::Changed(change) => app.emit("foundation-changed", change),
+            SyncEvent::Conflict(conflict) => app.emit("foundation-conflict", conflict),
+        };
+    }
+}
+
+#[derive(Default)]
+struct SyncState {
+    /// Content at the last sync per file, the common ancestor for merges
+    base: HashMap<String, Option<String>>,
+    /// Last content seen at each copy, to tell which copies changed
+    seen: HashMap<PathBuf, Option<String>>,
+    /// Worktree copies with an unresolved conflict; not overwritten by mirroring
+    conflicts: HashSet<PathBuf>,
+    /// Session worktrees found at the last tick or write
+    worktrees: Vec<PathBuf>,
+    /// Content mirroring last wrote to each worktree copy (`None` if it removed
+    /// the copy), and when
+    mirrored: HashMap<PathBuf, (Option<String>, Instant)>,
+}
+
+pub(crate) struct ProjectSync {
+    project_path: String,
+    state: tokio::sync::Mutex<SyncState>,
+    watching: AtomicBool,
+}
+
+pub struct FoundationsState {
+    projects: parking_lot::Mutex<HashMap<String, Arc<ProjectSync>>>,
+}
+
+impl FoundationsState {
+    pub fn new() -> Self {
+        Self {
+            projects: parking_lot::Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub(crate) fn project(&self, project_path: &str) -> Arc<ProjectSync> {
+        self.projects
+            .lock()
+            .entry(project_path.to_string())
+            .or_insert_with(|| {
+                Arc::new(ProjectSync {
+                    project_path: project_path.to_string(),
+                    state: tokio::sync::Mutex::new(SyncState::default()),
+                    watching: AtomicBool::new(false),
+                })
+            })
+            .clone()
+    }
+
+    /// Content mirrored from the project into a worktree's copy of `name` since
+    /// `since`, `Some(None)` for a deletion. The orchestrator uses it to tell
+    /// these writes from agent edits.
+    pub async fn mirrored_since(&self, worktree: &Path, name: &str, since: Instant) -> Option<Option<String>> {
+        let projects: Vec<Arc<ProjectSync>> = self.projects.lock().values().cloned().collect();
+        // Worktree paths come from `git worktree list`, which resolves symlinks
+        let copy = std::fs::canonicalize(worktree).unwrap_or_else(|_| worktree.to_path_buf()).join(name);
+        for sync in projects {
+            let state = sync.state.lock().await;
+            if let Some((content, at)) = state.mirrored.get(&copy) {
+                return (*at >= since).then(|| content.clone());
+            }
+        }
+        None
+    }
+}
+
+fn validate_name(name: &str) -> MindgridResult<()> {
+    if FOUNDATION_FILES.contains(&name) {
+        Ok(())
+    } else {
+        Err(MindgridError::validation("unknown_foundation", format!("{} is not a Foundations file", name)))
+    }
+}
+
+fn read(path: &Path) -> Option<String> {
+    std::fs::read_to_string(path).ok()
+}
+
+fn write(path: &Path, content: &str) -> MindgridResult<()> {
+    std::fs::write(path, content).map_err(|e| {
+        MindgridError::io("write_failed", format!("Failed to write {}", path.display())).with_technical(e)
+    })
+}
+
+/// Write `content` to `path`, or remove the file when there is none
+fn write_or_remove(path: &Path, content: Option<&str>) -> MindgridResult<()> {
+    match content {
+        Some(content) => write(path, content),
+        None => match std::fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(MindgridError::io("remove_failed", format!("Failed to remove {}", path.display()))
+                    .with_technical(e))
+            }
+            _ => Ok(()),
+        },
+    }
+}
+
+/// Session worktrees of a project
+async fn session_worktrees(project_path: &str) -> Vec<PathBuf> {
+    crate::resources::list_worktrees(project_path)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|(path, _, is_main)| !is_main && path.contains(".mindgrid/worktrees/"))
+        .map(|(path, _, _)| PathBuf::from(path))
+        .collect()
+}
+
+/// Scratch directory for files handed to git, removed on drop
+struct Scratch(PathBuf);
+
+impl Scratch {
+    fn new() -> MindgridResult<Self> {
+        let dir = std::env::temp_dir().join(format!("mindgrid-foundations-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| MindgridError::io("create_dir_failed", "Failed to create a scratch directory").with_technical(e))?;
+        Ok(Self(dir))
+    }
+
+    fn file(&self, name: &str, content: &str) -> MindgridResult<PathBuf> {
+        let path = self.0.join(name);
+        write(&path, content)?;
+        Ok(path)
+    }
+}
+
+impl Drop for Scratch {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Three-way merge with `git merge-file`
+async fn merge(base: &str, ours: &str, theirs: &str, theirs_label: &str) -> MindgridResult<Merge> {
+    let scratch = Scratch::new()?;
+    let ours_path = scratch.file("ours", ours)?;
+    let base_path = scratch.file("base", base)?;
+    let theirs_path = scratch.file("theirs", theirs)?;
+
+    let output = tokio::process::Command::new("git")
+        .args(["merge-file", "-p", "-L", "project", "-L", "base", "-L", theirs_label])
+        .arg(&ours_path)
+        .arg(&base_path)
+        .arg(&theirs_path)
+        .output()
+        .await
+        .map_err(|e| MindgridError::spawn_failed("git merge-file", e))?;
+
+    // The exit code is the number of conflicts, negative on error
+    let merged = String::from_utf8_lossy(&output.stdout).to_string();
+    match output.status.code() {
+        Some(0) => Ok(Merge::Clean(merged)),
+        Some(code) if (1..128).contains(&code) => Ok(Merge::Conflict(merged)),
+        _ => Err(MindgridError::git("merge_failed", "Failed to merge Foundations file")
+            .with_command("git merge-file")
+            .with_output(&output)),
+    }
+}
+
+/// Unified diff between two versions of a file
+async fn diff(name: &str, old: &str, new: &str) -> String {
+    let Ok(scratch) = Scratch::new() else {
+        return String::new();
+    };
+    let (Ok(old_path), Ok(new_path)) = (scratch.file("old", old), scratch.file("new", new)) else {
+        return String::new();
+    };
+
+    let output = tokio::process::Command::new("git")
+        .args(["diff", "--no-index", "--no-color", "-U3", "--"])
+        .arg(&old_path)
+        .arg(&new_path)
+        .output()
+        .await;
+
+    match output {
+        Ok(o) => {
+            let text = String::from_utf8_lossy(&o.stdout);
+            // Replace the scratch file headers with the file name
+            match text.find("\n@@") {
+                Some(start) => format!("--- a/{}\n+++ b/{}{}", name, name, &text[start..]),
+                None => String::new(),
+            }
+        }
+        Err(_) => String::new(),
+    }
+}
+
+impl ProjectSync {
+    fn canonical(&self, name: &str) -> PathBuf {
+        Path::new(&self.project_path).join(name)
+    }
+
+    /// Sync one file across the project root and worktrees after external edits
+    async fn sync_file(&self, state: &mut SyncState, name: &str, events: &mut Vec<SyncEvent>) {
+        let canonical = self.canonical(name);
+        let current = read(&canonical);
+        let base = state
+            .base
+            .entry(name.to_string())
+            .or_insert_with(|| current.clone())
+            .clone();
+
+        let mut merged = current.clone();
+        let mut sources = Vec::new();
+        if current != base {
+            sources.push(ChangeSource::Project);
+        }
+
+        for worktree in state.worktrees.clone() {
+            let copy = worktree.join(name);
+            let content = read(&copy);
+            let Some(last_seen) = state.seen.get(&copy).cloned() else {
+                // New worktree: its copy comes from a checkout and is brought up to date below
+                state.seen.insert(copy, content);
+                continue;
+            };
+            if content == last_seen || content == merged || content == base {
+                continue;
+            }
+            state.seen.insert(copy.clone(), content.clone());
+
+            if merged == base {
+                merged = content;
+            } else {
+                let label = worktree.file_name().and_then(|n| n.to_str()).unwrap_or("worktree");
+                let result = merge(
+                    base.as_deref().unwrap_or_default(),
+                    merged.as_deref().unwrap_or_default(),
+                    content.as_deref().unwrap_or_default(),
+                    label,
+                )
+                .await;
+                match result {
+                    Ok(Merge::Clean(text)) => merged = Some(text),
+                    Ok(Merge::Conflict(text)) => {
+                        state.conflicts.insert(copy.clone());
+                        events.push(SyncEvent::Conflict(FoundationConflict {
+                            project_path: self.project_path.clone(),
+                            file: name.to_string(),
+                            worktree_path: worktree.to_string_lossy().to_string(),
+                            base: base.clone().unwrap_or_default(),
+                            ours: merged.clone().unwrap_or_default(),
+                            theirs: content.unwrap_or_default(),
+                            merged: text,
+                        }));
+                        continue;
+                    }
+                    Err(e) => {
+                        eprintln!("[MindGrid] Failed to merge {}: {}", name, e.technical_message());
+                        continue;
+                    }
+                }
+            }
+            sources.push(ChangeSource::Worktree {
+                path: worktree.to_string_lossy().to_string(),
+            });
+        }
+
+        // A deleted copy leaves `merged` empty and the deletion is applied everywhere
+        if merged != base || merged != current {
+            match self.apply(state, name, merged.as_deref(), sources, base.as_deref()).await {
+                Ok(change) => events.push(change),
+                Err(e) => eprintln!("[MindGrid] Failed to sync {}: {}", name, e.technical_message()),
+            }
+        } else if let Some(content) = merged {
+            // Bring new or stale copies up to date
+            self.mirror(state, name, Some(&content));
+        }
+        // Otherwise the file isn't set up in this project
+    }
+
+    /// Write the merged content (or the deletion) to the project root and all worktrees
+    async fn apply(
+        &self,
+        state: &mut SyncState,
+        name: &str,
+        content: Option<&str>,
+        sources: Vec<ChangeSource>,
+        previous: Option<&str>,
+    ) -> MindgridResult<SyncEvent> {
+        let canonical = self.canonical(name);
+        if read(&canonical).as_deref() != content {
+            write_or_remove(&canonical, content)?;
+        }
+        state.base.insert(name.to_string(), content.map(str::to_string));
+        self.mirror(state, name, content);
+
+        let diff = diff(name, previous.unwrap_or_default(), content.unwrap_or_default()).await;
+        Ok(SyncEvent::Changed(FoundationChange {
+            project_path: self.project_path.clone(),
+            file: name.to_string(),
+            sources,
+            diff,
+            content: content.unwrap_or_default().to_string(),
+            deleted: content.is_none(),
+        }))
+    }
+
+    fn mirror(&self, state: &mut SyncState, name: &str, content: Option<&str>) {
+        for worktree in &state.worktrees {
+            let copy = worktree.join(name);
+            if state.conflicts.contains(&copy) {
+                continue;
+            }
+            if read(&copy).as_deref() != content {
+                if let Err(e) = write_or_remove(&copy, content) {
+                    eprintln!("[MindGrid] {}", e.technical_message());
+                    continue;
+                }
+                state.mirrored.insert(copy.clone(), (content.map(str::to_string), Instant::now()));
+            }
+            state.seen.insert(copy, content.map(str::to_string));
+        }
+    }
+
+    pub(crate) async fn tick(&self) -> Vec<SyncEvent> {
+        let worktrees = session_worktrees(&self.project_path).await;
+        let mut state = self.state.lock().await;
+        state.worktrees = worktrees;
+        let mut events = Vec::new();
+        for name in FOUNDATION_FILES {
+            self.sync_file(&mut state, name, &mut events).await;
+        }
+        events
+    }
+
+    /// Save a file from the Foundations panel; see `foundations_write`
+    pub(crate) async fn write(
+        &self,
+        name: &str,
+        content: String,
+        base: Option<String>,
+    ) -> MindgridResult<(FoundationWriteResult, Option<SyncEvent>)> {
+        validate_name(name)?;
+        let worktrees = session_worktrees(&self.project_path).await;
+        let mut state = self.state.lock().await;
+        state.worktrees = worktrees;
+
+        let current = read(&self.canonical(name));
+        let (status, content) = match base {
+            Some(base) if current.as_deref() != Some(base.as_str()) => {
+                match merge(&base, current.as_deref().unwrap_or_default(), &content, "edit").await? {
+                    Merge::Clean(merged) => (WriteStatus::Merged, merged),
+                    Merge::Conflict(merged) => {
+                        let result = FoundationWriteResult {
+                            status: WriteStatus::Conflict,
+                            content: merged,
+                        };
+                        return Ok((result, None));
+                    }
+                }
+            }
+            _ => (WriteStatus::Written, content),
+        };
+
+        // An explicit save from the user wins over conflicting worktree copies
+        state
+            .conflicts
+            .retain(|copy| copy.file_name().and_then(|n| n.to_str()) != Some(name));
+        let previous = state.base.get(name).cloned().flatten().or(current);
+        let change = self
+            .apply(&mut state, name, Some(&content), vec![ChangeSource::User], previous.as_deref())
+            .await?;
+
+        Ok((FoundationWriteResult { status, content }, Some(change)))
+    }
+}
+
+/// List the Foundations files of a project
+#[tauri::command]
+pub async fn foundations_list(
+    state: tauri::State<'_, Arc<FoundationsState>>,
+    project_path: String,
+) -> MindgridResult<Vec<FoundationFile>> {
+    let sync = state.project(&project_path);
+    let sync_state = sync.state.lock().await;
+
+    Ok(FOUNDATION_FILES
+        .iter()
+        .map(|name| {
+            let path = sync.canonical(name);
+            let content = read(&path);
+            let conflicts = sync_state
+                .conflicts
+                .iter()
+                .filter(|copy| copy.file_name().and_then(|n| n.to_str()) == Some(*name))
+                .filter_map(|copy| copy.parent().map(|p| p.to_string_lossy().to_string()))
+                .collect();
+            FoundationFile {
+                name: name.to_string(),
+                path: path.to_string_lossy().to_string(),
+                exists: content.is_some(),
+                content: content.unwrap_or_default(),
+                conflicts,
+            }
+        })
+        .collect())
+}
+
+/// Save a Foundations file and mirror it into every session worktree.
+///
+/// `base` is the content the editor started from; if the file changed on disk
+/// since then, the edit is merged. Without `base` the content is written as is,
+/// which also resolves outstanding conflicts for the file.
+#[tauri::command]
+pub async fn foundations_write(
+    app: AppHandle,
+    state: tauri::State<'_, Arc<FoundationsState>>,
+    project_path: String,
+    name: String,
+    content: String,
+    base: Option<String>,
+) -> MindgridResult<FoundationWriteResult> {
+    let (result, change) = state.project(&project_path).write(&name, content, base).await?;
+    emit_events(&app, change.into_iter().collect());
+    Ok(result)
+}
+
+/// Start watching a project's Foundations files for external edits
+#[tauri::command]
+pub async fn foundations_watch(
+    app: AppHandle,
+    state: tauri::State<'_, Arc<FoundationsState>>,
+    project_path: String,
+) -> MindgridResult<()> {
+    if !Path::new(&project_path).is_dir() {
+        return Err(MindgridError::validation("directory_missing", "Project directory does not exist")
+            .with_technical(&project_path));
+    }
+
+    let sync = state.project(&project_path);
+    if sync.watching.swap(true, Ordering::SeqCst) {
+        return Ok(());
+    }
+
+    tauri::async_runtime::spawn(async move {
+        while sync.watching.load(Ordering::SeqCst) {
+            emit_events(&app, sync.tick().await);
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    });
+    Ok(())
+}
+
+/// Stop watching a project's Foundations files
+#[tauri::command]
+pub async fn foundations_unwatch(
+    state: tauri::State<'_, Arc<FoundationsState>>,
+    project_path: String,
+) -> MindgridResult<()> {
+    if let Some(sync) = state.projects.lock().remove(&project_path) {
+        sync.watching.store(false, Ordering::SeqCst);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::fixtures::TempRepo;
+
+    fn session_worktree(project: &TempRepo, name: &str) -> PathBuf {
+        let path = project.path.join(".mindgrid").join("worktrees").join(name);
+        let branch = format!("mindgrid/{}", name);
+        project.git(&["worktree", "add", "-q", "-b", &branch, &path.to_string_lossy()]);
+        path
+    }
+
+    #[test]
+    fn foundation_change_sources_are_tagged() {
+        assert_eq!(serde_json::to_value(ChangeSource::User).unwrap(), serde_json::json!({ "kind": "user" }));
+        assert_eq!(
+            serde_json::to_value(ChangeSource::Worktree { path: "/w".into() }).unwrap(),
+            serde_json::json!({ "kind": "worktree", "path": "/w" })
+        );
+    }
+
+    #[tokio::test]
+    async fn merges_cleanly_or_reports_conflicts() {
+        let base = "# Plan\n\n1. First\n\n2. Second\n";
+        let ours = "# Plan\n\n1. First, done\n\n2. Second\n";
+        let theirs = "# Plan\n\n1. First\n\n2. Second, done\n";
+        match merge(base, ours, theirs, "coder").await.unwrap() {
+            Merge::Clean(text) => assert_eq!(text, "# Plan\n\n1. First, done\n\n2. Second, done\n"),
+            Merge::Conflict(text) => panic!("unexpected conflict:\n{}", text),
+        }
+
+        let theirs = "# Plan\n\n1. First, skipped\n\n2. Second\n";
+        match merge(base, ours, theirs, "coder").await.unwrap() {
+            Merge::Conflict(text) => {
+                assert!(text.contains("<<<<<<< project\n1. First, done\n=======\n1. First, skipped\n>>>>>>> coder"));
+            }
+            Merge::Clean(text) => panic!("expected a conflict:\n{}", text),
+        }
+    }
+
+    #[tokio::test]
+    async fn worktree_edits_are_mirrored_everywhere() {
+        let project = TempRepo::with_files(&[("PLAN.md", "# Plan\n")]);
+        let planner = session_worktree(&project, "planner");
+        let coder = session_worktree(&project, "coder");
+        let state = FoundationsState::new();
+        let sync = state.project(&project.path_str());
+        assert!(sync.tick().await.is_empty());
+
+        let edited = "# Plan\n\n1. Add a flag\n";
+        std::fs::write(planner.join("PLAN.md"), edited).unwrap();
+        let events = sync.tick().await;
+        assert_eq!(project.read("PLAN.md"), edited);
+        assert_eq!(read(&coder.join("PLAN.md")).as_deref(), Some(edited));
+        match events.as_slice() {
+            [SyncEvent::Changed(change)] => {
+                assert_eq!(change.file, "PLAN.md");
+                assert!(matches!(&change.sources[..], [ChangeSource::Worktree { path }] if path.ends_with("planner")));
+                assert!(change.diff.contains("+1. Add a flag"));
+            }
+            _ => panic!("expected one change"),
+        }
+        // Mirroring isn't reported as a new edit
+        assert!(sync.tick().await.is_empty());
+        let since = Instant::now() - std::time::Duration::from_secs(60);
+        assert_eq!(state.mirrored_since(&coder, "PLAN.md", since).await, Some(Some(edited.to_string())));
+
+        // Files a project doesn't have are left alone
+        assert!(!project.path.join("DECISIONS.md").exists());
+        assert!(!coder.join("DECISIONS.md").exists());
+    }
+
+    #[tokio::test]
+    async fn deletions_are_mirrored_everywhere() {
+        let project = TempRepo::with_files(&[("PLAN.md", "# Plan\n"), ("PROGRESS.md", "# Progress\n")]);
+        let planner = session_worktree(&project, "planner");
+        let coder = session_worktree(&project, "coder");
+        let sync = FoundationsState::new().project(&project.path_str());
+        sync.tick().await;
+
+        // Deleted in a worktree
+        std::fs::remove_file(planner.join("PLAN.md")).unwrap();
+        let events = sync.tick().await;
+        assert!(!project.path.join("PLAN.md").exists());
+        assert!(!coder.join("PLAN.md").exists());
+        match events.as_slice() {
+            [SyncEvent::Changed(change)] => {
+                assert!(change.deleted);
+                assert!(change.diff.contains("-# Plan"));
+            }
+            _ => panic!("expected one change"),
+        }
+
+        // Deleted in the project root
+        std::fs::remove_file(project.path.join("PROGRESS.md")).unwrap();
+        assert_eq!(sync.tick().await.len(), 1);
+        assert!(!planner.join("PROGRESS.md").exists());
+        assert!(!coder.join("PROGRESS.md").exists());
+        assert!(sync.tick().await.is_empty());
+
+        // Recreating a deleted file brings it back everywhere
+        std::fs::write(coder.join("PLAN.md"), "# Plan, again\n").unwrap();
+        sync.tick().await;
+        assert_eq!(project.read("PLAN.md"), "# Plan, again\n");
+        assert_eq!(read(&planner.join("PLAN.md")).as_deref(), Some("# Plan, again\n"));
+    }
+
+    #[tokio::test]
+    async fn conflicting_worktree_edits_are_kept_apart() {
+        let project = TempRepo::with_files(&[("PLAN.md", "# Plan\n\n1. First\n")]);
+        let planner = session_worktree(&project, "planner");
+        let coder = session_worktree(&project, "coder");
+        let state = FoundationsState::new();
+        let sync = state.project(&project.path_str());
+        sync.tick().await;
+
+        std::fs::write(planner.join("PLAN.md"), "# Plan\n\n1. First, planned\n").unwrap();
+        std::fs::write(coder.join("PLAN.md"), "# Plan\n\n1. First, coded\n").unwrap();
+        let events = sync.tick().await;
+
+        // One copy wins; the other gets a conflict and keeps its content
+        let (winner, loser) = if project.read("PLAN.md").contains("planned") {
+            (&planner, &coder)
+        } else {
+            (&coder, &planner)
+        };
+        assert_eq!(read(&winner.join("PLAN.md")), Some(project.read("PLAN.md")));
+        assert_ne!(read(&loser.join("PLAN.md")), Some(project.read("PLAN.md")));
+        let conflict = events
+            .iter()
+            .find_map(|event| match event {
+                SyncEvent::Conflict(conflict) => Some(conflict),
+                SyncEvent::Changed(_) => None,
+            })
+            .expect("expected a conflict");
+        assert_eq!(Path::new(&conflict.worktree_path), std::fs::canonicalize(loser).unwrap());
+        assert!(conflict.merged.contains("<<<<<<< project"));
+
+        // Saving from the panel resolves it everywhere
+        let saved = "# Plan\n\n1. First, resolved\n";
+        sync.write("PLAN.md", saved.to_string(), None).await.unwrap();
+        assert_eq!(read(&coder.join("PLAN.md")).as_deref(), Some(saved));
+        assert_eq!(read(&planner.join("PLAN.md")).as_deref(), Some(saved));
+    }
+
+    #[tokio::test]
+    async fn stale_panel_saves_are_merged() {
+        let base = "# Decisions\n\nA\n\nB\n";
+        let project = TempRepo::with_files(&[("DECISIONS.md", base)]);
+        let sync = FoundationsState::new().project(&project.path_str());
+        sync.tick().await;
+        project.write("DECISIONS.md", "# Decisions\n\nA, agreed\n\nB\n");
+
+        let (result, _) = sync
+            .write("DECISIONS.md", "# Decisions\n\nA\n\nB, agreed\n".to_string(), Some(base.to_string()))
+            .await
+            .unwrap();
+        assert_eq!(result.status, WriteStatus::Merged);
+        assert_eq!(project.read("DECISIONS.md"), "# Decisions\n\nA, agreed\n\nB, agreed\n");
+
+        let (result, change) = sync
+            .write("DECISIONS.md", "# Decisions\n\nA, rejected\n\nB\n".to_string(), Some(base.to_string()))
+            .await
+            .unwrap();
+        assert_eq!(result.status, WriteStatus::Conflict);
+        assert!(change.is_none());
+        assert_eq!(project.read("DECISIONS.md"), "# Decisions\n\nA, agreed\n\nB, agreed\n");
+
+        assert_eq!(
+            sync.write("NOTES.md", String::new(), None).await.err().unwrap().code,
+            "validation.unknown_foundation"
+        );
+    }
+}
@@ -1,7 +1,18 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::io;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::Emitter;
+use ts_rs::TS;
+
+use crate::error::{MindgridError, MindgridResult};
+use crate::operations::OperationRegistry;
+use crate::diff::{DiffLimits, DiffScope, DiffSummary, FileDiff};
+use crate::provision::{ProvisionOptions, ProvisionReport};
+use crate::repo_scan::{GitRepoInfo, RepoKind, RepoScanCache, ScanOptions};
+use crate::submodule::SubmoduleStatus;
+use crate::trailers::CommitProvenance;
 
 #[cfg(unix)]
 fn create_dir_symlink(src: &Path, dst: &Path) -> io::Result<()> {
@@ -71,74 +82,33 @@ fn link_node_modules_to_external(worktree_name: &str, worktree_path: &Path) {
     }
 }
 
-// Helper function to validate if a directory is a git repository
+// Helper function to validate if a directory is a git checkout sessions can
+// run in: a regular checkout, linked worktree or submodule. Bare repositories
+// are detected too (see repo_scan::detect_repo) but have no working tree.
 pub fn is_valid_git_repository(path: &Path) -> bool {
-    let git_dir = path.join(".git");
-    git_dir.exists() && git_dir.is_dir()
-}
-
-#[derive(Debug, Serialize)]
-pub struct GitRepoInfo {
-    pub name: String,
-    pub path: String,
+    matches!(crate::repo_scan::detect_repo(path), Some((kind, _)) if kind != RepoKind::Bare)
 }
 
-/// List all git repositories in a directory (non-recursive, just immediate children)
+/// List git repositories below a directory, descending up to `max_depth` levels.
+/// Results are cached per directory; use `refresh_git_repos` to rescan.
 #[tauri::command]
-pub async fn list_git_repos(parent_directory: String) -> Result<Vec<GitRepoInfo>, String> {
-    let parent = Path::new(&parent_directory);
-
-    if !parent.exists() || !parent.is_dir() {
-        return Err(format!("Directory does not exist: {}", parent_directory));
-    }
-
-    let mut repos = Vec::new();
-
-    let entries = std::fs::read_dir(parent)
-        .map_err(|e| format!("Failed to read directory: {}", e))?;
-
-    for entry in entries {
-        let entry = match entry {
-            Ok(e) => e,
-            Err(_) => continue,
-        };
-
-        let path = entry.path();
-
-        // Skip hidden directories (except we still check them for .git)
-        if let Some(name) = path.file_name() {
-            if name.to_string_lossy().starts_with('.') {
-                continue;
-            }
-        }
-
-        // Only check directories
-        if !path.is_dir() {
-            continue;
-        }
-
-        // Check if it's a git repository
-        if is_valid_git_repository(&path) {
-            let name = path
-                .file_name()
-                .map(|n| n.to_string_lossy().to_string())
-                .unwrap_or_else(|| "unknown".to_string());
-
-            repos.push(GitRepoInfo {
-                name,
-                path: path.to_string_lossy().to_string(),
-            });
-        }
-    }
-
-    // Sort by name (case-insensitive)
-    repos.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
-
-    Ok(repos)
+pub async fn list_git_repos(
+    scan_cache: tauri::State<'_, Arc<RepoScanCache>>,
+    parent_directory: String,
+    max_depth: Option<usize>,
+    ignore: Option<Vec<String>>,
+    include_hidden: Option<bool>,
+) -> MindgridResult<Vec<GitRepoInfo>> {
+    let options = ScanOptions {
+        max_depth,
+        ignore,
+        include_hidden,
+    };
+    crate::repo_scan::scan_cached(&scan_cache, &parent_directory, options).await
 }
 
 #[tauri::command]
-pub async fn validate_git_repository(project_path: String) -> Result<bool, String> {
+pub async fn validate_git_repository(project_path: String) -> MindgridResult<bool> {
     let path = Path::new(&project_path);
 
     if !path.exists() || !path.is_dir() {
@@ -149,16 +119,16 @@ pub async fn validate_git_repository(project_path: String) -> Result<bool, Strin
 }
 
 #[tauri::command]
-pub async fn get_git_worktrees() -> Result<Vec<HashMap<String, String>>, String> {
+pub async fn get_git_worktrees() -> MindgridResult<Vec<HashMap<String, String>>> {
     get_git_worktrees_for_path(None).await
 }
 
 #[tauri::command]
-pub async fn get_project_worktrees(project_path: String) -> Result<Vec<HashMap<String, String>>, String> {
+pub async fn get_project_worktrees(project_path: String) -> MindgridResult<Vec<HashMap<String, String>>> {
     get_git_worktrees_for_path(Some(&project_path)).await
 }
 
-async fn get_git_worktrees_for_path(path: Option<&str>) -> Result<Vec<HashMap<String, String>>, String> {
+async fn get_git_worktrees_for_path(path: Option<&str>) -> MindgridResult<Vec<HashMap<String, String>>> {
     let mut cmd = tokio::process::Command::new("git");
     cmd.args(&["worktree", "list", "--porcelain"]);
 
@@ -169,7 +139,7 @@ async fn get_git_worktrees_for_path(path: Option<&str>) -> Result<Vec<HashMap<St
     let output = cmd
         .output()
         .await
-        .map_err(|e| format!("Failed to execute git worktree list: {}", e))?;
+        .map_err(|e| MindgridError::spawn_failed("git worktree list", e))?;
 
     if !output.status.success() {
         // Not in a git repository or worktree not supported
@@ -217,14 +187,38 @@ async fn get_git_worktrees_for_path(path: Option<&str>) -> Result<Vec<HashMap<St
 
 #[tauri::command]
 pub async fn create_workspace_worktree(
-    _app: tauri::AppHandle,
+    app: tauri::AppHandle,
+    project_path: String,
+    name: String,
+    parent: Option<String>,
+    session_id: Option<String>,
+    provision: Option<ProvisionOptions>,
+) -> MindgridResult<String> {
+    let (path, report) = add_workspace_worktree(project_path, name, parent, session_id, provision).await?;
+    if let Some(report) = report {
+        let _ = app.emit("worktree-provisioned", report);
+    }
+    Ok(path)
+}
+
+/// Create a session worktree on a new `mindgrid/<name>` branch. Returns its
+/// path and, when `provision` is set, the provisioning report.
+async fn add_workspace_worktree(
     project_path: String,
     name: String,
-) -> Result<String, String> {
+    parent: Option<String>,
+    session_id: Option<String>,
+    provision: Option<ProvisionOptions>,
+) -> MindgridResult<(String, Option<ProvisionReport>)> {
     // Ensure valid repo
     let repo = PathBuf::from(&project_path);
+    if matches!(crate::repo_scan::detect_repo(&repo), Some((RepoKind::Bare, _))) {
+        return Err(MindgridError::validation("bare_repository", "Bare repositories have no working tree")
+            .with_technical(&project_path));
+    }
     if !is_valid_git_repository(&repo) {
-        return Err("Not a valid git repository".to_string());
+        return Err(MindgridError::validation("not_a_repository", "Not a valid git repository")
+            .with_technical(&project_path));
     }
 
     // Check if repo has any commits (worktree requires at least one commit)
@@ -274,12 +268,14 @@ pub async fn create_workspace_worktree(
             .args(["commit", "-m", "Initial commit", "--allow-empty"])
             .output()
             .await
-            .map_err(|e| format!("Failed to create initial commit: {}", e))?;
+            .map_err(|e| MindgridError::spawn_failed("git commit", e))?;
 
         if !commit_result.status.success() {
-            return Err(format!(
-                "Failed to create initial commit: {}",
-                String::from_utf8_lossy(&commit_result.stderr)
+            return Err(MindgridError::git_failed(
+                "initial_commit_failed",
+                "Failed to create initial commit",
+                &["commit", "-m", "Initial commit", "--allow-empty"],
+                &commit_result,
             ));
         }
     }
@@ -287,46 +283,182 @@ pub async fn create_workspace_worktree(
     // Ensure .mindgrid/worktrees directory
     let worktrees_dir = repo.join(".mindgrid").join("worktrees");
     std::fs::create_dir_all(&worktrees_dir)
-        .map_err(|e| format!("Failed to create worktrees directory: {}", e))?;
+        .map_err(|e| MindgridError::io("create_dir_failed", "Failed to create worktrees directory").with_technical(e))?;
 
     // Generate branch name
     let branch = format!("mindgrid/{}", name);
     let target_path = worktrees_dir.join(&name);
 
+    // Stacked sessions branch from the parent session's branch instead of HEAD
+    let parent_branch = parent.as_ref().map(|p| format!("mindgrid/{}", p));
+    let parent_sha = match &parent_branch {
+        Some(parent_branch) => Some(
+            crate::stack::resolve_ref(&project_path, parent_branch)
+                .await
+                .ok_or_else(|| {
+                    MindgridError::validation("parent_missing", format!("Parent branch {} does not exist", parent_branch))
+                })?,
+        ),
+        None => None,
+    };
+
     // Create worktree on new branch
-    // git worktree add -B <branch> <path>
-    let status = tokio::process::Command::new("git")
-        .arg("-C")
-        .arg(&project_path)
-        .args([
-            "worktree",
-            "add",
-            "-B",
-            &branch,
-            target_path.to_string_lossy().as_ref(),
-        ])
+    // git worktree add -B <branch> <path> [<parent>]
+    let target_path_str = target_path.to_string_lossy().to_string();
+    let sparse = provision
+        .as_ref()
+        .and_then(|p| p.sparse_patterns.as_ref())
+        .is_some_and(|patterns| !patterns.is_empty());
+    let mut worktree_args = vec!["worktree", "add"];
+    if sparse {
+        // Populated by populate_worktree below
+        worktree_args.push("--no-checkout");
+    }
+    worktree_args.extend(["-B", &branch, &target_path_str]);
+    if let Some(parent_branch) = &parent_branch {
+        worktree_args.push(parent_branch);
+    }
+
+    // `-B` reuses an existing branch; only a branch created here is deleted on failure
+    let created_branch = crate::stack::resolve_ref(&project_path, &branch).await.is_none();
+
+    // With git-lfs installed, skip smudging during checkout and fetch all LFS
+    // objects in one batch afterwards instead of one file at a time
+    let uses_lfs = crate::lfs::uses_lfs(&repo).await;
+    let lfs_available = uses_lfs && crate::lfs::lfs_available().await;
+
+    let mut worktree_cmd = tokio::process::Command::new("git");
+    worktree_cmd.arg("-C").arg(&project_path).args(&worktree_args);
+    if lfs_available {
+        worktree_cmd.env("GIT_LFS_SKIP_SMUDGE", "1");
+    }
+    let status = worktree_cmd
         .output()
         .await
-        .map_err(|e| format!("git worktree add failed: {}", e))?;
+        .map_err(|e| MindgridError::spawn_failed("git worktree add", e))?;
 
     if !status.status.success() {
-        return Err(format!(
-            "Failed to add worktree: {}",
-            String::from_utf8_lossy(&status.stderr)
+        return Err(MindgridError::git_failed(
+            "worktree_add_failed",
+            "Failed to add worktree",
+            &worktree_args,
+            &status,
         ));
     }
 
-    // Optionally symlink node_modules to external storage to save local disk space
-    link_node_modules_to_external(&name, &target_path);
+    let discard_branch = created_branch.then_some(branch.as_str());
+    if let (Some(parent_branch), Some(parent_sha)) = (&parent_branch, &parent_sha) {
+        if let Err(e) = crate::stack::record_parent(&project_path, &branch, parent_branch, parent_sha).await {
+            discard_worktree(&project_path, &target_path_str, discard_branch).await;
+            return Err(e);
+        }
+    }
 
-    Ok(target_path.to_string_lossy().to_string())
+    // Commits made in the worktree are tagged with the session id (see trailers.rs)
+    if let Some(session_id) = &session_id {
+        if let Err(e) = crate::trailers::record_session(&project_path, &branch, session_id).await {
+            discard_worktree(&project_path, &target_path_str, discard_branch).await;
+            return Err(e);
+        }
+    }
+
+    let report = match populate_worktree(&repo, &target_path, provision.as_ref(), uses_lfs, lfs_available).await {
+        Ok(report) => report,
+        Err(e) => {
+            discard_worktree(&project_path, &target_path_str, discard_branch).await;
+            return Err(e);
+        }
+    };
+    if report.is_none() {
+        // Optionally symlink node_modules to external storage to save local disk space
+        link_node_modules_to_external(&name, &target_path);
+    }
+
+    Ok((target_path_str, report))
+}
+
+/// Undo a worktree whose setup failed: remove it and, if it was created for
+/// the worktree, its branch (deleting a branch also drops its config section)
+async fn discard_worktree(project_path: &str, target_path: &str, branch: Option<&str>) {
+    let _ = tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(project_path)
+        .args(["worktree", "remove", "--force", target_path])
+        .output()
+        .await;
+    if let Some(branch) = branch {
+        let _ = tokio::process::Command::new("git")
+            .arg("-C")
+            .arg(project_path)
+            .args(["branch", "-D", branch])
+            .output()
+            .await;
+    }
+}
+
+/// Fill a worktree created by `git worktree add`. Sparse checkout comes first:
+/// a worktree added with `--no-checkout` is empty until then, so `.gitmodules`
+/// and LFS-tracked files only exist afterwards. Returns the provisioning report
+/// when `provision` is set.
+pub(crate) async fn populate_worktree(
+    repo: &Path,
+    target_path: &Path,
+    provision: Option<&ProvisionOptions>,
+    uses_lfs: bool,
+    lfs_available: bool,
+) -> MindgridResult<Option<ProvisionReport>> {
+    let sparse_patterns = provision
+        .and_then(|p| p.sparse_patterns.as_ref())
+        .filter(|patterns| !patterns.is_empty());
+
+    let mut cloned = Vec::new();
+    let mut errors = Vec::new();
+    if let Some(options) = provision {
+        if let Some(patterns) = sparse_patterns {
+            let cone = options.cone.unwrap_or(true);
+            crate::provision::apply_sparse_checkout(target_path, patterns, cone, lfs_available).await?;
+        }
+
+        let clone_dirs = options.clone_dirs.clone().unwrap_or_else(|| {
+            crate::provision::DEFAULT_CLONE_DIRS
+                .iter()
+                .map(|d| d.to_string())
+                .collect()
+        });
+        (cloned, errors) = crate::provision::clone_heavy_dirs(repo, target_path, &clone_dirs).await;
+        if !errors.is_empty() {
+            eprintln!("[MindGrid] Errors provisioning worktree: {:?}", errors);
+        }
+    }
+
+    if crate::submodule::has_submodules(target_path) {
+        let errors = crate::submodule::init_submodules(repo, target_path).await;
+        if !errors.is_empty() {
+            eprintln!("[MindGrid] Failed to initialize submodules: {:?}", errors);
+        }
+    }
+
+    if lfs_available {
+        if let Err(e) = crate::lfs::pull(target_path).await {
+            eprintln!("[MindGrid] {}", e.technical_message());
+        }
+    } else if uses_lfs {
+        eprintln!("[MindGrid] Repository uses Git LFS but git-lfs is not installed; LFS files stay as pointers");
+    }
+
+    Ok(provision.map(|_| ProvisionReport {
+        worktree_path: target_path.to_string_lossy().to_string(),
+        sparse: sparse_patterns.is_some(),
+        cloned,
+        errors,
+    }))
 }
 
 #[tauri::command]
 pub async fn remove_workspace_worktree(
     project_path: String,
     worktree_path: String,
-) -> Result<(), String> {
+) -> MindgridResult<()> {
     // Extract the worktree name to determine the branch name
     let worktree_name = Path::new(&worktree_path)
         .file_name()
@@ -340,7 +472,7 @@ pub async fn remove_workspace_worktree(
         .args(["worktree", "remove", "--force", &worktree_path])
         .output()
         .await
-        .map_err(|e| format!("git worktree remove failed: {}", e))?;
+        .map_err(|e| MindgridError::spawn_failed("git worktree remove", e))?;
 
     if !status.status.success() {
         let stderr = String::from_utf8_lossy(&status.stderr).to_string();
@@ -351,7 +483,12 @@ pub async fn remove_workspace_worktree(
            stderr.contains("No such file or directory") {
             eprintln!("[MindGrid] Worktree already removed or doesn't exist, continuing with cleanup");
         } else {
-            return Err(format!("Failed to remove worktree: {}", stderr));
+            return Err(MindgridError::git_failed(
+                "worktree_remove_failed",
+                "Failed to remove worktree",
+                &["worktree", "remove", "--force", &worktree_path],
+                &status,
+            ));
         }
     }
 
@@ -395,9 +532,79 @@ pub async fn remove_workspace_worktree(
     Ok(())
 }
 
-#[derive(Debug, Serialize, Clone)]
+/// Overall state of a working tree, most significant first: an operation in
+/// progress wins over a detached HEAD, which wins over local changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum GitState {
+    Rebasing,
+    /// `git am` is applying patches
+    Applying,
+    Merging,
+    CherryPicking,
+    Reverting,
+    Bisecting,
+    Detached,
+    /// Unmerged paths without an operation in progress (e.g. after `git stash pop`)
+    Conflict,
+    Diverged,
+    Modified,
+    Untracked,
+    Ahead,
+    Behind,
+    Clean,
+    Unknown,
+}
+
+impl GitState {
+    /// Rebase, merge, cherry-pick, revert, am or bisect in progress
+    pub fn is_operation(self) -> bool {
+        matches!(
+            self,
+            GitState::Rebasing
+                | GitState::Applying
+                | GitState::Merging
+                | GitState::CherryPicking
+                | GitState::Reverting
+                | GitState::Bisecting
+        )
+    }
+}
+
+/// Detect an operation in progress from the state files git leaves in the
+/// (per-worktree) git directory
+pub(crate) fn operation_in_progress(git_dir: &Path) -> Option<GitState> {
+    if git_dir.join("rebase-merge").is_dir() {
+        return Some(GitState::Rebasing);
+    }
+    let rebase_apply = git_dir.join("rebase-apply");
+    if rebase_apply.is_dir() {
+        return Some(if rebase_apply.join("applying").exists() {
+            GitState::Applying
+        } else {
+            GitState::Rebasing
+        });
+    }
+    if git_dir.join("MERGE_HEAD").is_file() {
+        return Some(GitState::Merging);
+    }
+    if git_dir.join("CHERRY_PICK_HEAD").is_file() {
+        return Some(GitState::CherryPicking);
+    }
+    if git_dir.join("REVERT_HEAD").is_file() {
+        return Some(GitState::Reverting);
+    }
+    if git_dir.join("BISECT_LOG").is_file() {
+        return Some(GitState::Bisecting);
+    }
+    None
+}
+
+#[derive(Debug, Serialize, Clone, TS)]
+#[ts(export)]
 pub struct GitStatus {
-    pub state: String,
+    pub state: GitState,
     pub ahead: Option<i32>,
     pub behind: Option<i32>,
     pub additions: Option<i32>,
@@ -409,20 +616,28 @@ pub struct GitStatus {
     pub current_branch: Option<String>,
     pub main_branch: Option<String>,
     pub is_detached: Option<bool>,
+    /// Unmerged paths exist (also during a merge, rebase, cherry-pick or revert)
+    pub has_conflicts: Option<bool>,
+    /// Submodule states; `None` when the repository has no submodules
+    pub submodules: Option<Vec<SubmoduleStatus>>,
+    /// LFS files checked out as pointers; `None` without LFS or git-lfs
+    pub lfs_pointers: Option<i32>,
 }
 
 #[tauri::command]
-pub async fn get_git_status(working_directory: String) -> Result<GitStatus, String> {
+pub async fn get_git_status(working_directory: String) -> MindgridResult<GitStatus> {
     let path = Path::new(&working_directory);
 
     if !path.exists() || !path.is_dir() {
-        return Err("Directory does not exist".to_string());
+        return Err(MindgridError::validation("directory_missing", "Directory does not exist")
+            .with_technical(&working_directory));
     }
 
     // Check if it's a git repository (either direct or worktree)
     let git_dir = path.join(".git");
     if !git_dir.exists() {
-        return Err("Not a git repository".to_string());
+        return Err(MindgridError::validation("not_a_repository", "Not a git repository")
+            .with_technical(&working_directory));
     }
 
     // Get current branch using git branch --show-current (like Crystal)
@@ -433,7 +648,7 @@ pub async fn get_git_status(working_directory: String) -> Result<GitStatus, Stri
         .args(["branch", "--show-current"])
         .output()
         .await
-        .map_err(|e| format!("Failed to get branch: {}", e))?;
+        .map_err(|e| MindgridError::spawn_failed("git branch", e))?;
 
     let current_branch = if branch_output.status.success() {
         let branch = String::from_utf8_lossy(&branch_output.stdout).trim().to_string();
@@ -480,7 +695,7 @@ pub async fn get_git_status(working_directory: String) -> Result<GitStatus, Stri
         .args(["status", "--porcelain"])
         .output()
         .await
-        .map_err(|e| format!("Failed to get status: {}", e))?;
+        .map_err(|e| MindgridError::spawn_failed("git status", e))?;
 
     let status_text = String::from_utf8_lossy(&status_output.stdout);
     let lines: Vec<&str> = status_text.lines().collect();
@@ -492,27 +707,31 @@ pub async fn get_git_status(working_directory: String) -> Result<GitStatus, Stri
     // Get diff stats for uncommitted changes
     let (additions, deletions) = get_diff_stats(&working_directory).await;
 
+    let has_conflicts = has_unmerged_paths(&working_directory).await;
+    let operation = git_dir_path(&working_directory)
+        .await
+        .and_then(|dir| operation_in_progress(&dir));
+
     // Determine state
-    let state = determine_state(
-        &lines,
-        ahead,
-        behind,
-        has_uncommitted_changes,
-        has_untracked_files,
-        &working_directory,
-    ).await;
+    let state = match operation {
+        Some(operation) => operation,
+        None if is_detached => GitState::Detached,
+        None if !status_output.status.success() => GitState::Unknown,
+        None => determine_state(ahead, behind, has_conflicts, has_uncommitted_changes, has_untracked_files),
+    };
 
     // Determine if ready to merge
     let is_ready_to_merge = ahead.map(|a| a > 0).unwrap_or(false)
         && !has_uncommitted_changes
         && !has_untracked_files
-        && behind.map(|b| b == 0).unwrap_or(true);
+        && behind.map(|b| b == 0).unwrap_or(true)
+        && !state.is_operation();
 
-    // If detached HEAD, set state to "detached"
-    let final_state = if is_detached { "detached".to_string() } else { state };
+    let submodules = crate::submodule::submodule_status(&working_directory).await;
+    let lfs_pointers = crate::lfs::pointer_count(&working_directory).await;
 
     Ok(GitStatus {
-        state: final_state,
+        state,
         ahead,
         behind,
         additions: if additions > 0 { Some(additions) } else { None },
@@ -524,10 +743,13 @@ pub async fn get_git_status(working_directory: String) -> Result<GitStatus, Stri
         current_branch,
         main_branch,
         is_detached: Some(is_detached),
+        has_conflicts: Some(has_conflicts),
+        submodules: Some(submodules).filter(|s| !s.is_empty()),
+        lfs_pointers,
     })
 }
 
-async fn detect_main_branch(working_directory: &str) -> Option<String> {
+pub(crate) async fn detect_main_branch(working_directory: &str) -> Option<String> {
     // Try to find main or master branch
     for branch in &["main", "master"] {
         let output = tokio::process::Command::new("git")
@@ -630,276 +852,142 @@ async fn get_diff_stats(working_directory: &str) -> (i32, i32) {
     (0, 0)
 }
 
-async fn determine_state(
-    _status_lines: &[&str],
-    ahead: Option<i32>,
-    behind: Option<i32>,
-    has_uncommitted: bool,
-    has_untracked: bool,
-    working_directory: &str,
-) -> String {
-    // Check for merge conflicts
-    let in_conflict = tokio::process::Command::new("git")
+/// Absolute path of the working tree's git directory (`.git/worktrees/<name>` for linked worktrees)
+async fn git_dir_path(working_directory: &str) -> Option<PathBuf> {
+    let output = tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(working_directory)
+        .args(["rev-parse", "--absolute-git-dir"])
+        .output()
+        .await
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(PathBuf::from(String::from_utf8_lossy(&output.stdout).trim()))
+}
+
+async fn has_unmerged_paths(working_directory: &str) -> bool {
+    tokio::process::Command::new("git")
         .arg("-C")
         .arg(working_directory)
         .args(["ls-files", "--unmerged"])
         .output()
         .await
         .map(|o| o.status.success() && !o.stdout.is_empty())
-        .unwrap_or(false);
+        .unwrap_or(false)
+}
 
-    if in_conflict {
-        return "conflict".to_string();
+/// State of a working tree with no operation in progress and HEAD on a branch
+pub(crate) fn determine_state(
+    ahead: Option<i32>,
+    behind: Option<i32>,
+    has_conflicts: bool,
+    has_uncommitted: bool,
+    has_untracked: bool,
+) -> GitState {
+    if has_conflicts {
+        return GitState::Conflict;
     }
 
     // Check diverged state
     if ahead.map(|a| a > 0).unwrap_or(false) && behind.map(|b| b > 0).unwrap_or(false) {
-        return "diverged".to_string();
+        return GitState::Diverged;
     }
 
     // Check modified/untracked
     if has_uncommitted {
-        return "modified".to_string();
+        return GitState::Modified;
     }
 
     if has_untracked {
-        return "untracked".to_string();
+        return GitState::Untracked;
     }
 
     // Check ahead/behind
     if ahead.map(|a| a > 0).unwrap_or(false) {
-        return "ahead".to_string();
+        return GitState::Ahead;
     }
 
     if behind.map(|b| b > 0).unwrap_or(false) {
-        return "behind".to_string();
+        return GitState::Behind;
     }
 
-    "clean".to_string()
-}
-
-#[derive(Debug, Serialize)]
-pub struct GitDiffResult {
-    pub files: Vec<GitDiffFile>,
-    pub total_additions: i32,
-    pub total_deletions: i32,
-}
-
-#[derive(Debug, Serialize)]
-pub struct GitDiffFile {
-    pub path: String,
-    pub status: String,
-    pub additions: i32,
-    pub deletions: i32,
-}
-
-#[derive(Debug, Serialize)]
-pub struct GitFileDiff {
-    pub path: String,
-    pub status: String,
-    pub patch: String,
-    pub old_value: String,
-    pub new_value: String,
-    pub is_binary: bool,
+    GitState::Clean
 }
 
+/// List changed files for a diff scope (defaults to all uncommitted changes)
 #[tauri::command]
-pub async fn get_git_diff(working_directory: String) -> Result<GitDiffResult, String> {
-    let path = Path::new(&working_directory);
-
-    if !path.exists() || !path.is_dir() {
-        return Err("Directory does not exist".to_string());
-    }
-
-    // Get diff with numstat for counts
-    let output = tokio::process::Command::new("git")
-        .arg("-C")
-        .arg(&working_directory)
-        .args(["diff", "--numstat"])
-        .output()
-        .await
-        .map_err(|e| format!("Failed to get diff: {}", e))?;
-
-    let text = String::from_utf8_lossy(&output.stdout);
-    let mut files = Vec::new();
-    let mut total_additions = 0;
-    let mut total_deletions = 0;
-
-    for line in text.lines() {
-        let parts: Vec<&str> = line.split('\t').collect();
-        if parts.len() >= 3 {
-            let adds = parts[0].parse::<i32>().unwrap_or(0);
-            let dels = parts[1].parse::<i32>().unwrap_or(0);
-            let path = parts[2].to_string();
-
-            total_additions += adds;
-            total_deletions += dels;
-
-            files.push(GitDiffFile {
-                path,
-                status: "modified".to_string(),
-                additions: adds,
-                deletions: dels,
-            });
-        }
-    }
-
-    // Also get untracked files
-    let untracked_output = tokio::process::Command::new("git")
-        .arg("-C")
-        .arg(&working_directory)
-        .args(["ls-files", "--others", "--exclude-standard"])
-        .output()
-        .await
-        .map_err(|e| format!("Failed to get untracked: {}", e))?;
-
-    let untracked_text = String::from_utf8_lossy(&untracked_output.stdout);
-    for line in untracked_text.lines() {
-        if !line.is_empty() {
-            files.push(GitDiffFile {
-                path: line.to_string(),
-                status: "untracked".to_string(),
-                additions: 0,
-                deletions: 0,
-            });
-        }
-    }
-
-    Ok(GitDiffResult {
-        files,
-        total_additions,
-        total_deletions,
-    })
+pub async fn get_git_diff(
+    working_directory: String,
+    scope: Option<DiffScope>,
+) -> MindgridResult<DiffSummary> {
+    let summary = crate::diff::list_changes(&working_directory, scope.unwrap_or_default(), DiffLimits::default()).await?;
+    Ok(summary)
 }
 
+/// Diff a single file. `status` is the entry's status from `get_git_diff`
+/// ("untracked" files are diffed against /dev/null).
 #[tauri::command]
 pub async fn get_git_file_diff(
     working_directory: String,
     file_path: String,
     status: Option<String>,
-) -> Result<GitFileDiff, String> {
-    let path = Path::new(&working_directory);
-
-    if !path.exists() || !path.is_dir() {
-        return Err("Directory does not exist".to_string());
-    }
-
-    let status_value = status.unwrap_or_else(|| "modified".to_string());
-    let is_untracked = status_value == "untracked";
-    let is_deleted = status_value == "deleted";
-
-    // Check if file is binary
-    let is_binary = check_if_binary(&working_directory, &file_path).await;
-
-    // Get the patch/diff
-    let mut cmd = tokio::process::Command::new("git");
-    cmd.arg("-C").arg(&working_directory);
-
-    if is_untracked {
-        cmd.args([
-            "diff",
-            "--no-index",
-            "--no-color",
-            "--",
-            "/dev/null",
-            &file_path,
-        ]);
-    } else {
-        cmd.args(["diff", "--no-color", "--", &file_path]);
-    }
-
-    let output = cmd
-        .output()
-        .await
-        .map_err(|e| format!("Failed to get file diff: {}", e))?;
-
-    let patch = String::from_utf8_lossy(&output.stdout).to_string();
-
-    // Get old value (from HEAD)
-    let old_value = if is_untracked || is_binary {
-        String::new()
-    } else {
-        get_file_from_head(&working_directory, &file_path).await.unwrap_or_default()
-    };
-
-    // Get new value (current working directory content)
-    let new_value = if is_deleted || is_binary {
-        String::new()
-    } else {
-        let full_path = path.join(&file_path);
-        std::fs::read_to_string(&full_path).unwrap_or_default()
-    };
-
-    Ok(GitFileDiff {
-        path: file_path,
-        status: status_value,
-        patch,
-        old_value,
-        new_value,
-        is_binary,
-    })
-}
-
-/// Check if a file is binary
-async fn check_if_binary(working_directory: &str, file_path: &str) -> bool {
-    // Use git's diff to check - binary files will have "Binary files" in the output
-    let output = tokio::process::Command::new("git")
-        .arg("-C")
-        .arg(working_directory)
-        .args(["diff", "--numstat", "--", file_path])
-        .output()
-        .await
-        .ok();
-
-    if let Some(output) = output {
-        let text = String::from_utf8_lossy(&output.stdout);
-        // Binary files show as "-\t-\t" in numstat output
-        text.starts_with("-\t-\t")
-    } else {
-        false
-    }
-}
-
-/// Get file content from HEAD
-async fn get_file_from_head(working_directory: &str, file_path: &str) -> Option<String> {
-    let output = tokio::process::Command::new("git")
-        .arg("-C")
-        .arg(working_directory)
-        .args(["show", &format!("HEAD:{}", file_path)])
-        .output()
-        .await
-        .ok()?;
-
-    if output.status.success() {
-        Some(String::from_utf8_lossy(&output.stdout).to_string())
-    } else {
-        None
-    }
+    old_path: Option<String>,
+    scope: Option<DiffScope>,
+    word_diff: Option<bool>,
+) -> MindgridResult<FileDiff> {
+    let untracked = status.as_deref() == Some("untracked");
+    let diff = crate::diff::file_diff(
+        &working_directory,
+        &file_path,
+        old_path.as_deref(),
+        scope.unwrap_or_default(),
+        untracked,
+        word_diff.unwrap_or(false),
+        DiffLimits::default(),
+    )
+    .await?;
+    Ok(diff)
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
 pub struct CommitResult {
     pub success: bool,
     pub commit_hash: Option<String>,
     pub error: Option<String>,
+    /// Hooks that ran before the commit, in order
+    pub hooks: Vec<crate::hooks::HookRun>,
+    /// Name of the hook that blocked the commit, if any
+    pub failed_hook: Option<String>,
+}
+
+impl CommitResult {
+    fn failure(error: String) -> Self {
+        CommitResult {
+            success: false,
+            commit_hash: None,
+            error: Some(error),
+            hooks: Vec::new(),
+            failed_hook: None,
+        }
+    }
 }
 
 /// Stage all changes (git add -A)
 #[tauri::command]
-pub async fn git_add_all(working_directory: String) -> Result<(), String> {
+pub async fn git_add_all(working_directory: String) -> MindgridResult<()> {
     let output = tokio::process::Command::new("git")
         .arg("-C")
         .arg(&working_directory)
         .args(["add", "-A"])
         .output()
         .await
-        .map_err(|e| format!("git add failed: {}", e))?;
+        .map_err(|e| MindgridError::spawn_failed("git add", e))?;
 
     if !output.status.success() {
-        return Err(format!(
-            "git add failed: {}",
-            String::from_utf8_lossy(&output.stderr)
-        ));
+        return Err(MindgridError::git_failed("add_failed", "Failed to stage changes", &["add", "-A"], &output));
     }
 
     Ok(())
@@ -908,76 +996,138 @@ pub async fn git_add_all(working_directory: String) -> Result<(), String> {
 /// Create a commit with the given message
 #[tauri::command]
 pub async fn git_commit(
+    app: tauri::AppHandle,
     working_directory: String,
     message: String,
     no_verify: bool,
-) -> Result<CommitResult, String> {
-    git_commit_with_signature(working_directory, message, no_verify, None).await
+    agent_pty_id: Option<String>,
+    provenance: Option<CommitProvenance>,
+) -> MindgridResult<CommitResult> {
+    git_commit_with_signature(app, working_directory, message, no_verify, None, agent_pty_id, provenance).await
 }
 
-/// Create a commit with an optional signature/footer
+/// Create a commit with an optional signature/footer.
+///
+/// Unless `no_verify` is set, pre-commit and commit-msg hooks are run by mindgrid
+/// itself so their output can be streamed (`git-hook-output`) and a failure can be
+/// reported per hook (`git-hook-failed`) and optionally sent to the agent's PTY.
 #[tauri::command]
 pub async fn git_commit_with_signature(
+    app: tauri::AppHandle,
     working_directory: String,
     message: String,
     no_verify: bool,
     signature: Option<String>,
-) -> Result<CommitResult, String> {
+    agent_pty_id: Option<String>,
+    provenance: Option<CommitProvenance>,
+) -> MindgridResult<CommitResult> {
+    // Build full commit message with optional signature
+    let full_message = if let Some(sig) = signature {
+        format!("{}\n\n{}", message, sig)
+    } else {
+        message
+    };
+    let provenance = provenance.unwrap_or_default();
+    create_commit(&app, &working_directory, full_message, no_verify, agent_pty_id, &provenance, false).await
+}
+
+async fn create_commit(
+    app: &tauri::AppHandle,
+    working_directory: &str,
+    message: String,
+    no_verify: bool,
+    agent_pty_id: Option<String>,
+    provenance: &CommitProvenance,
+    checkpoint: bool,
+) -> MindgridResult<CommitResult> {
     // First check if there are staged changes
     let status_output = tokio::process::Command::new("git")
         .arg("-C")
-        .arg(&working_directory)
+        .arg(working_directory)
         .args(["diff", "--cached", "--quiet"])
         .output()
         .await
-        .map_err(|e| format!("git diff failed: {}", e))?;
+        .map_err(|e| MindgridError::spawn_failed("git diff", e))?;
 
     // Exit code 0 means no changes, 1 means changes exist
     if status_output.status.success() {
-        return Ok(CommitResult {
-            success: false,
-            commit_hash: None,
-            error: Some("No staged changes to commit".to_string()),
-        });
+        return Ok(CommitResult::failure("No staged changes to commit".to_string()));
     }
 
-    // Build full commit message with optional signature
-    let full_message = if let Some(sig) = signature {
-        format!("{}\n\n{}", message, sig)
-    } else {
-        message
+    let trailers = crate::trailers::commit_trailers(working_directory, provenance, checkpoint).await;
+    let full_message = crate::trailers::append_trailers(working_directory, &message, &trailers).await;
+
+    // Apply the project's signing policy
+    let sign_arg = match crate::signing::commit_sign_arg(working_directory).await {
+        Ok(arg) => arg,
+        Err(e) => return Ok(CommitResult::failure(e.user_message())),
     };
 
-    // Build commit command
-    let mut args = vec!["commit", "-m", &full_message];
-    if no_verify {
-        args.push("--no-verify");
+    let mut hooks = Vec::new();
+
+    // The message goes through a file so commit-msg hooks can inspect and edit it
+    let message_file = commit_message_path(working_directory).await?;
+    std::fs::write(&message_file, &full_message)
+        .map_err(|e| MindgridError::io("write_failed", "Failed to write commit message").with_technical(e))?;
+    let message_file_str = message_file.to_string_lossy().to_string();
+
+    if !no_verify {
+        let hook_runs = [
+            ("pre-commit", Vec::new()),
+            ("commit-msg", vec![message_file_str.as_str()]),
+        ];
+        for (hook, args) in hook_runs {
+            if let Some(run) = crate::hooks::run_hook(app, working_directory, hook, &args).await? {
+                let failed = !run.success;
+                hooks.push(run);
+                if failed {
+                    let run = hooks.last().expect("hook run was just pushed");
+                    crate::hooks::report_failure(app, working_directory, run, agent_pty_id.as_deref());
+                    let _ = std::fs::remove_file(&message_file);
+                    return Ok(CommitResult {
+                        success: false,
+                        commit_hash: None,
+                        error: Some(format!("{} hook failed:\n{}", hook, run.output.trim_end())),
+                        failed_hook: Some(hook.to_string()),
+                        hooks,
+                    });
+                }
+            }
+        }
+    }
+
+    // Hooks already ran above, so git itself skips them
+    let mut args = vec!["commit", "--no-verify", "-F", &message_file_str];
+    if let Some(arg) = sign_arg {
+        args.push(arg);
     }
 
     let output = tokio::process::Command::new("git")
         .arg("-C")
-        .arg(&working_directory)
+        .arg(working_directory)
         .args(&args)
         .output()
         .await
-        .map_err(|e| format!("git commit failed: {}", e))?;
+        .map_err(|e| MindgridError::spawn_failed("git commit", e));
+    let _ = std::fs::remove_file(&message_file);
+    let output = output?;
 
     if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
         return Ok(CommitResult {
-            success: false,
-            commit_hash: None,
-            error: Some(String::from_utf8_lossy(&output.stderr).to_string()),
+            hooks,
+            ..CommitResult::failure(crate::signing::explain_signing_failure(&stderr).unwrap_or(stderr))
         });
     }
 
     // Get the commit hash
     let hash_output = tokio::process::Command::new("git")
         .arg("-C")
-        .arg(&working_directory)
+        .arg(working_directory)
         .args(["rev-parse", "HEAD"])
         .output()
         .await
-        .map_err(|e| format!("git rev-parse failed: {}", e))?;
+        .map_err(|e| MindgridError::spawn_failed("git rev-parse", e))?;
 
     let commit_hash = if hash_output.status.success() {
         Some(String::from_utf8_lossy(&hash_output.stdout).trim().to_string())
@@ -989,15 +1139,49 @@ pub async fn git_commit_with_signature(
         success: true,
         commit_hash,
         error: None,
+        hooks,
+        failed_hook: None,
+    })
+}
+
+/// Location of the temporary commit message file inside the (worktree's) git dir
+async fn commit_message_path(working_directory: &str) -> MindgridResult<PathBuf> {
+    let output = tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(working_directory)
+        .args(["rev-parse", "--git-path", "MINDGRID_COMMIT_MSG"])
+        .output()
+        .await
+        .map_err(|e| MindgridError::spawn_failed("git rev-parse", e))?;
+
+    if !output.status.success() {
+        return Err(MindgridError::git_failed(
+            "not_a_repository",
+            "Not a git repository",
+            &["rev-parse", "--git-path", "MINDGRID_COMMIT_MSG"],
+            &output,
+        ));
+    }
+
+    let path = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+    Ok(if path.is_absolute() {
+        path
+    } else {
+        Path::new(working_directory).join(path)
     })
 }
 
-/// Create a checkpoint commit (stages all and commits with --no-verify)
+/// Create a checkpoint commit (stages all and commits).
+/// Hooks are skipped unless `run_hooks` is set, since checkpoints are automatic.
 #[tauri::command]
 pub async fn git_checkpoint_commit(
+    app: tauri::AppHandle,
     working_directory: String,
     message: String,
-) -> Result<CommitResult, String> {
+    run_hooks: Option<bool>,
+    agent_pty_id: Option<String>,
+    provenance: Option<CommitProvenance>,
+) -> MindgridResult<CommitResult> {
     // First check if there are any changes at all
     let status_output = tokio::process::Command::new("git")
         .arg("-C")
@@ -1005,34 +1189,31 @@ pub async fn git_checkpoint_commit(
         .args(["status", "--porcelain"])
         .output()
         .await
-        .map_err(|e| format!("git status failed: {}", e))?;
+        .map_err(|e| MindgridError::spawn_failed("git status", e))?;
 
     let status_text = String::from_utf8_lossy(&status_output.stdout);
     if status_text.trim().is_empty() {
-        return Ok(CommitResult {
-            success: false,
-            commit_hash: None,
-            error: Some("No changes to commit".to_string()),
-        });
+        return Ok(CommitResult::failure("No changes to commit".to_string()));
     }
 
     // Stage all changes
     git_add_all(working_directory.clone()).await?;
 
-    // Commit with --no-verify to bypass hooks
-    git_commit(working_directory, message, true).await
+    let no_verify = !run_hooks.unwrap_or(false);
+    let provenance = provenance.unwrap_or_default();
+    create_commit(&app, &working_directory, message, no_verify, agent_pty_id, &provenance, true).await
 }
 
 /// Check if there are uncommitted changes
 #[tauri::command]
-pub async fn git_has_changes(working_directory: String) -> Result<bool, String> {
+pub async fn git_has_changes(working_directory: String) -> MindgridResult<bool> {
     let output = tokio::process::Command::new("git")
         .arg("-C")
         .arg(&working_directory)
         .args(["status", "--porcelain"])
         .output()
         .await
-        .map_err(|e| format!("git status failed: {}", e))?;
+        .map_err(|e| MindgridError::spawn_failed("git status", e))?;
 
     let status_text = String::from_utf8_lossy(&output.stdout);
     Ok(!status_text.trim().is_empty())
@@ -1040,14 +1221,14 @@ pub async fn git_has_changes(working_directory: String) -> Result<bool, String>
 
 /// Get the last commit message and hash
 #[tauri::command]
-pub async fn git_get_last_commit(working_directory: String) -> Result<Option<(String, String)>, String> {
+pub async fn git_get_last_commit(working_directory: String) -> MindgridResult<Option<(String, String)>> {
     let output = tokio::process::Command::new("git")
         .arg("-C")
         .arg(&working_directory)
         .args(["log", "-1", "--format=%H|%s"])
         .output()
         .await
-        .map_err(|e| format!("git log failed: {}", e))?;
+        .map_err(|e| MindgridError::spawn_failed("git log", e))?;
 
     if !output.status.success() {
         return Ok(None);
@@ -1067,13 +1248,15 @@ pub async fn git_get_last_commit(working_directory: String) -> Result<Option<(St
     }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
 pub struct PushResult {
     pub success: bool,
     pub error: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
 pub struct PullRequestInfo {
     pub number: i32,
     pub title: String,
@@ -1081,14 +1264,16 @@ pub struct PullRequestInfo {
     pub url: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
 pub struct CreatePrResult {
     pub success: bool,
     pub url: Option<String>,
     pub error: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
 pub struct MergeResult {
     pub success: bool,
     pub message: Option<String>,
@@ -1098,9 +1283,13 @@ pub struct MergeResult {
 /// Push the current branch to remote
 #[tauri::command]
 pub async fn git_push(
+    operations: tauri::State<'_, Arc<OperationRegistry>>,
     working_directory: String,
     set_upstream: bool,
-) -> Result<PushResult, String> {
+    operation_id: Option<String>,
+) -> MindgridResult<PushResult> {
+    let op = operations.begin(operation_id, "git_push")?;
+
     // Get current branch name
     let branch_output = tokio::process::Command::new("git")
         .arg("-C")
@@ -1108,7 +1297,7 @@ pub async fn git_push(
         .args(["rev-parse", "--abbrev-ref", "HEAD"])
         .output()
         .await
-        .map_err(|e| format!("Failed to get branch: {}", e))?;
+        .map_err(|e| MindgridError::spawn_failed("git rev-parse", e))?;
 
     if !branch_output.status.success() {
         return Ok(PushResult {
@@ -1127,13 +1316,15 @@ pub async fn git_push(
         args.push(&branch);
     }
 
-    let output = tokio::process::Command::new("git")
-        .arg("-C")
-        .arg(&working_directory)
-        .args(&args)
-        .output()
-        .await
-        .map_err(|e| format!("git push failed: {}", e))?;
+    let output = op
+        .output(
+            tokio::process::Command::new("git")
+                .arg("-C")
+                .arg(&working_directory)
+                .args(&args),
+            "git push",
+        )
+        .await?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
@@ -1141,13 +1332,15 @@ pub async fn git_push(
         // Check if we need to set upstream
         if stderr.contains("has no upstream branch") {
             // Retry with --set-upstream
-            let retry_output = tokio::process::Command::new("git")
-                .arg("-C")
-                .arg(&working_directory)
-                .args(["push", "-u", "origin", &branch])
-                .output()
-                .await
-                .map_err(|e| format!("git push retry failed: {}", e))?;
+            let retry_output = op
+                .output(
+                    tokio::process::Command::new("git")
+                        .arg("-C")
+                        .arg(&working_directory)
+                        .args(["push", "-u", "origin", &branch]),
+                    "git push",
+                )
+                .await?;
 
             if retry_output.status.success() {
                 return Ok(PushResult {
@@ -1175,7 +1368,7 @@ pub async fn git_push(
 }
 
 /// Find gh CLI path (checks common Homebrew locations)
-fn find_gh_path() -> Option<String> {
+pub(crate) fn find_gh_path() -> Option<String> {
     // Common paths where gh might be installed
     let paths = [
         "/opt/homebrew/bin/gh",      // Apple Silicon Homebrew
@@ -1202,7 +1395,8 @@ fn find_gh_path() -> Option<String> {
     None
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
 pub struct GhCliStatus {
     pub available: bool,
     pub authenticated: bool,
@@ -1212,7 +1406,7 @@ pub struct GhCliStatus {
 
 /// Check if gh CLI is available and authenticated
 #[tauri::command]
-pub async fn git_check_gh_cli() -> Result<GhCliStatus, String> {
+pub async fn git_check_gh_cli() -> MindgridResult<GhCliStatus> {
     let gh_path = match find_gh_path() {
         Some(path) => path,
         None => return Ok(GhCliStatus {
@@ -1258,11 +1452,16 @@ pub async fn git_check_gh_cli() -> Result<GhCliStatus, String> {
 
 /// Get PR info for the current branch using gh CLI
 #[tauri::command]
-pub async fn git_get_pr_info(working_directory: String) -> Result<Option<PullRequestInfo>, String> {
+pub async fn git_get_pr_info(
+    operations: tauri::State<'_, Arc<OperationRegistry>>,
+    working_directory: String,
+    operation_id: Option<String>,
+) -> MindgridResult<Option<PullRequestInfo>> {
     let gh_path = match find_gh_path() {
         Some(path) => path,
         None => return Ok(None),
     };
+    let op = operations.begin(operation_id, "git_get_pr_info")?;
 
     // Get current branch name
     let branch_output = tokio::process::Command::new("git")
@@ -1271,7 +1470,7 @@ pub async fn git_get_pr_info(working_directory: String) -> Result<Option<PullReq
         .args(["rev-parse", "--abbrev-ref", "HEAD"])
         .output()
         .await
-        .map_err(|e| format!("Failed to get branch: {}", e))?;
+        .map_err(|e| MindgridError::spawn_failed("git rev-parse", e))?;
 
     if !branch_output.status.success() {
         return Ok(None);
@@ -1280,19 +1479,21 @@ pub async fn git_get_pr_info(working_directory: String) -> Result<Option<PullReq
     let branch = String::from_utf8_lossy(&branch_output.stdout).trim().to_string();
 
     // Use gh CLI to get PR info
-    let output = tokio::process::Command::new(&gh_path)
-        .arg("pr")
-        .arg("list")
-        .arg("--head")
-        .arg(&branch)
-        .arg("--json")
-        .arg("number,title,state,url")
-        .arg("--limit")
-        .arg("1")
-        .current_dir(&working_directory)
-        .output()
-        .await
-        .map_err(|e| format!("gh pr list failed: {}", e))?;
+    let output = op
+        .output(
+            tokio::process::Command::new(&gh_path)
+                .arg("pr")
+                .arg("list")
+                .arg("--head")
+                .arg(&branch)
+                .arg("--json")
+                .arg("number,title,state,url")
+                .arg("--limit")
+                .arg("1")
+                .current_dir(&working_directory),
+            "gh pr list",
+        )
+        .await?;
 
     if !output.status.success() {
         // gh CLI might not be installed or authenticated
@@ -1308,7 +1509,7 @@ pub async fn git_get_pr_info(working_directory: String) -> Result<Option<PullReq
 
     // Parse JSON array
     let prs: Vec<serde_json::Value> = serde_json::from_str(trimmed)
-        .map_err(|e| format!("Failed to parse PR JSON: {}", e))?;
+        .map_err(|e| MindgridError::git("invalid_pr_json", "Failed to parse PR JSON").with_technical(e))?;
 
     if let Some(pr) = prs.first() {
         Ok(Some(PullRequestInfo {
@@ -1325,10 +1526,13 @@ pub async fn git_get_pr_info(working_directory: String) -> Result<Option<PullReq
 /// Create a PR for the current branch using gh CLI
 #[tauri::command]
 pub async fn git_create_pr(
+    operations: tauri::State<'_, Arc<OperationRegistry>>,
     working_directory: String,
     title: String,
     body: String,
-) -> Result<CreatePrResult, String> {
+    operation_id: Option<String>,
+) -> MindgridResult<CreatePrResult> {
+    let op = operations.begin(operation_id, "git_create_pr")?;
     let gh_path = match find_gh_path() {
         Some(path) => path,
         None => return Ok(CreatePrResult {
@@ -1345,7 +1549,7 @@ pub async fn git_create_pr(
         .args(["rev-parse", "--abbrev-ref", "HEAD"])
         .output()
         .await
-        .map_err(|e| format!("Failed to get branch: {}", e))?;
+        .map_err(|e| MindgridError::spawn_failed("git rev-parse", e))?;
 
     if !branch_output.status.success() {
         return Ok(CreatePrResult {
@@ -1355,18 +1559,25 @@ pub async fn git_create_pr(
         });
     }
 
+    let branch = String::from_utf8_lossy(&branch_output.stdout).trim().to_string();
+
+    // Stacked sessions target their parent's branch instead of the default branch
+    let base_branch = crate::stack::pr_base_branch(&working_directory, &branch).await;
+
     // Create PR using gh CLI
-    let output = tokio::process::Command::new(&gh_path)
-        .arg("pr")
+    let mut cmd = tokio::process::Command::new(&gh_path);
+    cmd.arg("pr")
         .arg("create")
         .arg("--title")
         .arg(&title)
         .arg("--body")
-        .arg(&body)
-        .current_dir(&working_directory)
-        .output()
-        .await
-        .map_err(|e| format!("gh pr create failed: {}", e))?;
+        .arg(&body);
+    if let Some(base) = &base_branch {
+        cmd.arg("--base").arg(base);
+    }
+
+    cmd.current_dir(&working_directory);
+    let output = op.output(&mut cmd, "gh pr create").await?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
@@ -1387,14 +1598,16 @@ pub async fn git_create_pr(
     })
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
 pub struct ConflictInfo {
     pub has_conflicts: bool,
     pub conflicting_files: Vec<String>,
     pub conflicting_commits: Option<ConflictCommits>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
 pub struct ConflictCommits {
     pub ours: Vec<String>,
     pub theirs: Vec<String>,
@@ -1405,7 +1618,7 @@ pub struct ConflictCommits {
 pub async fn git_check_merge_conflicts(
     working_directory: String,
     project_path: String,
-) -> Result<ConflictInfo, String> {
+) -> MindgridResult<ConflictInfo> {
     // Get current branch
     let branch_output = tokio::process::Command::new("git")
         .arg("-C")
@@ -1413,10 +1626,15 @@ pub async fn git_check_merge_conflicts(
         .args(["rev-parse", "--abbrev-ref", "HEAD"])
         .output()
         .await
-        .map_err(|e| format!("Failed to get branch: {}", e))?;
+        .map_err(|e| MindgridError::spawn_failed("git rev-parse", e))?;
 
     if !branch_output.status.success() {
-        return Err("Failed to get current branch".to_string());
+        return Err(MindgridError::git_failed(
+            "branch_unknown",
+            "Failed to get current branch",
+            &["rev-parse", "--abbrev-ref", "HEAD"],
+            &branch_output,
+        ));
     }
 
     let branch = String::from_utf8_lossy(&branch_output.stdout).trim().to_string();
@@ -1430,7 +1648,7 @@ pub async fn git_check_merge_conflicts(
         .args(["merge-tree", &main_branch, &branch])
         .output()
         .await
-        .map_err(|e| format!("Failed to check merge conflicts: {}", e))?;
+        .map_err(|e| MindgridError::spawn_failed("git merge-tree", e))?;
 
     let output_text = String::from_utf8_lossy(&merge_check.stdout);
 
@@ -1538,7 +1756,8 @@ pub async fn git_merge_to_main(
     project_path: String,
     commit_message: String,
     squash: Option<bool>,
-) -> Result<MergeResult, String> {
+    provenance: Option<CommitProvenance>,
+) -> MindgridResult<MergeResult> {
     let should_squash = squash.unwrap_or(true); // Default to squash for cleaner history
     // Get current branch name in worktree
     let branch_output = tokio::process::Command::new("git")
@@ -1547,7 +1766,7 @@ pub async fn git_merge_to_main(
         .args(["rev-parse", "--abbrev-ref", "HEAD"])
         .output()
         .await
-        .map_err(|e| format!("Failed to get branch: {}", e))?;
+        .map_err(|e| MindgridError::spawn_failed("git rev-parse", e))?;
 
     if !branch_output.status.success() {
         return Ok(MergeResult {
@@ -1562,6 +1781,40 @@ pub async fn git_merge_to_main(
     // Detect main branch
     let main_branch = detect_main_branch(&project_path).await.unwrap_or("main".to_string());
 
+    // Repos that require signed commits on main get the branch verified first
+    let sign_arg = match crate::signing::commit_sign_arg(&project_path).await {
+        Ok(arg) => arg,
+        Err(e) => {
+            return Ok(MergeResult {
+                success: false,
+                message: None,
+                error: Some(e.user_message()),
+            })
+        }
+    };
+
+    // Every branch is verified: a bad or revoked signature blocks the merge
+    // under any policy, unsigned commits only when the project signs commits
+    let signing_required = crate::signing::signing_required(&project_path).await;
+    let report = crate::signing::verify_branch(&working_directory, &main_branch, &branch).await?;
+    let blocking = crate::signing::blocking_commits(&report, signing_required);
+    if !blocking.is_empty() {
+        let listed: Vec<String> = blocking
+            .iter()
+            .map(|c| format!("{} {} ({})", &c.hash[..c.hash.len().min(8)], c.subject, c.status))
+            .collect();
+        let reason = if signing_required {
+            format!("This project requires signed commits. {} has commits without a valid signature", branch)
+        } else {
+            format!("{} has commits with a bad or revoked signature", branch)
+        };
+        return Ok(MergeResult {
+            success: false,
+            message: None,
+            error: Some(format!("{}:\n{}", reason, listed.join("\n"))),
+        });
+    }
+
     // Switch to main in the project root
     let checkout_output = tokio::process::Command::new("git")
         .arg("-C")
@@ -1569,7 +1822,7 @@ pub async fn git_merge_to_main(
         .args(["checkout", &main_branch])
         .output()
         .await
-        .map_err(|e| format!("git checkout failed: {}", e))?;
+        .map_err(|e| MindgridError::spawn_failed("git checkout", e))?;
 
     if !checkout_output.status.success() {
         return Ok(MergeResult {
@@ -1596,14 +1849,17 @@ pub async fn git_merge_to_main(
         .args(&merge_args)
         .output()
         .await
-        .map_err(|e| format!("git merge failed: {}", e))?;
+        .map_err(|e| MindgridError::spawn_failed("git merge", e))?;
 
     if !merge_output.status.success() {
-        // Abort merge and restore original branch
+        // Abort merge and restore original branch. A squash merge leaves no
+        // MERGE_HEAD for `merge --abort`, but `reset --merge` still undoes it
+        // while keeping unrelated local changes.
+        let abort_args: &[&str] = if should_squash { &["reset", "--merge"] } else { &["merge", "--abort"] };
         let _ = tokio::process::Command::new("git")
             .arg("-C")
             .arg(&project_path)
-            .args(["merge", "--abort"])
+            .args(abort_args)
             .output()
             .await;
 
@@ -1625,14 +1881,25 @@ pub async fn git_merge_to_main(
         });
     }
 
-    // Commit the squashed changes
+    // Commit the squashed changes, keeping the link to the session that produced them
+    let mut provenance = provenance.unwrap_or_default();
+    if provenance.session_id.is_none() {
+        provenance.session_id = crate::trailers::session_for_branch(&project_path, &branch).await;
+    }
+    let trailers = crate::trailers::commit_trailers(&project_path, &provenance, false).await;
+    let commit_message = crate::trailers::append_trailers(&project_path, &commit_message, &trailers).await;
+    let mut commit_args = vec!["commit", "-m", &commit_message];
+    if let Some(arg) = sign_arg {
+        commit_args.push(arg);
+    }
+
     let commit_output = tokio::process::Command::new("git")
         .arg("-C")
         .arg(&project_path)
-        .args(["commit", "-m", &commit_message])
+        .args(&commit_args)
         .output()
         .await
-        .map_err(|e| format!("git commit failed: {}", e))?;
+        .map_err(|e| MindgridError::spawn_failed("git commit", e))?;
 
     if !commit_output.status.success() {
         // Reset the merge and checkout back to original branch
@@ -1650,19 +1917,35 @@ pub async fn git_merge_to_main(
             .output()
             .await;
 
+        let stderr = String::from_utf8_lossy(&commit_output.stderr).to_string();
         return Ok(MergeResult {
             success: false,
             message: None,
             error: Some(format!(
                 "Commit failed: {}",
-                String::from_utf8_lossy(&commit_output.stderr)
+                crate::signing::explain_signing_failure(&stderr).unwrap_or(stderr)
             )),
         });
     }
 
+    // Sessions stacked on the merged branch now move onto main
+    if let Err(e) = crate::stack::mark_merged(&project_path, &branch).await {
+        eprintln!("[MindGrid] {}", e.technical_message());
+    }
+    let restacked = crate::stack::restack_children(&project_path, &branch).await;
+    let mut message = format!("Successfully merged {} to {}", branch, main_branch);
+    if !restacked.is_empty() {
+        let rebased = restacked.iter().filter(|r| r.status == "rebased").count();
+        message.push_str(&format!(
+            " (restacked {} of {} stacked sessions)",
+            rebased,
+            restacked.len()
+        ));
+    }
+
     Ok(MergeResult {
         success: true,
-        message: Some(format!("Successfully merged {} to {}", branch, main_branch)),
+        message: Some(message),
         error: None,
     })
 }
@@ -1670,9 +1953,11 @@ pub async fn git_merge_to_main(
 /// Merge PR using gh CLI (uses GitHub's merge)
 #[tauri::command]
 pub async fn git_merge_pr(
+    operations: tauri::State<'_, Arc<OperationRegistry>>,
     working_directory: String,
     squash: bool,
-) -> Result<MergeResult, String> {
+    operation_id: Option<String>,
+) -> MindgridResult<MergeResult> {
     let gh_path = match find_gh_path() {
         Some(path) => path,
         None => return Ok(MergeResult {
@@ -1682,6 +1967,7 @@ pub async fn git_merge_pr(
         }),
     };
 
+    let op = operations.begin(operation_id, "git_merge_pr")?;
     let mut args = vec!["pr", "merge", "--delete-branch"];
 
     if squash {
@@ -1690,12 +1976,14 @@ pub async fn git_merge_pr(
         args.push("--merge");
     }
 
-    let output = tokio::process::Command::new(&gh_path)
-        .args(&args)
-        .current_dir(&working_directory)
-        .output()
-        .await
-        .map_err(|e| format!("gh pr merge failed: {}", e))?;
+    let output = op
+        .output(
+            tokio::process::Command::new(&gh_path)
+                .args(&args)
+                .current_dir(&working_directory),
+            "gh pr merge",
+        )
+        .await?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
@@ -1706,6 +1994,11 @@ pub async fn git_merge_pr(
         });
     }
 
+    // Stacked sessions read this to retarget onto main
+    if let Err(e) = crate::stack::mark_worktree_merged(&working_directory).await {
+        eprintln!("[MindGrid] {}", e.technical_message());
+    }
+
     Ok(MergeResult {
         success: true,
         message: Some("PR merged successfully".to_string()),
@@ -1714,12 +2007,12 @@ pub async fn git_merge_pr(
 }
 
 #[tauri::command]
-pub async fn open_in_editor(path: String) -> Result<(), String> {
+pub async fn open_in_editor(path: String) -> MindgridResult<()> {
     // Open VS Code with the given path
     let status = tokio::process::Command::new("code")
         .arg(&path)
         .spawn()
-        .map_err(|e| format!("Failed to open VS Code: {}", e))?;
+        .map_err(|e| MindgridError::spawn_failed("code", e))?;
 
     // Don't wait for VS Code to close, just check it started
     drop(status);
@@ -1729,20 +2022,20 @@ pub async fn open_in_editor(path: String) -> Result<(), String> {
 
 /// Save session data to the worktree root (so it can be committed with the worktree branch)
 #[tauri::command]
-pub async fn save_session_to_worktree(worktree_path: String, session_data: String) -> Result<(), String> {
+pub async fn save_session_to_worktree(worktree_path: String, session_data: String) -> MindgridResult<()> {
     let path = Path::new(&worktree_path);
 
     // Save session data directly in worktree root
     let session_file = path.join(".mindgrid-session.json");
     std::fs::write(&session_file, &session_data)
-        .map_err(|e| format!("Failed to write session data: {}", e))?;
+        .map_err(|e| MindgridError::io("write_failed", "Failed to write session data").with_technical(e))?;
 
     Ok(())
 }
 
 /// Load session data from the worktree root
 #[tauri::command]
-pub async fn load_session_from_worktree(worktree_path: String) -> Result<Option<String>, String> {
+pub async fn load_session_from_worktree(worktree_path: String) -> MindgridResult<Option<String>> {
     let path = Path::new(&worktree_path);
     let session_file = path.join(".mindgrid-session.json");
 
@@ -1751,35 +2044,38 @@ pub async fn load_session_from_worktree(worktree_path: String) -> Result<Option<
 
     if session_file.exists() {
         let data = std::fs::read_to_string(&session_file)
-            .map_err(|e| format!("Failed to read session data: {}", e))?;
+            .map_err(|e| MindgridError::io("read_failed", "Failed to read session data").with_technical(e))?;
         return Ok(Some(data));
     }
 
     // Fallback to old location
     if old_session_file.exists() {
         let data = std::fs::read_to_string(&old_session_file)
-            .map_err(|e| format!("Failed to read session data: {}", e))?;
+            .map_err(|e| MindgridError::io("read_failed", "Failed to read session data").with_technical(e))?;
         return Ok(Some(data));
     }
 
     Ok(None)
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
 pub struct GitIgnoredFile {
     pub path: String,
     pub name: String,
     pub is_directory: bool,
+    #[ts(type = "number | null")]
     pub size: Option<u64>,
 }
 
 /// List files that are gitignored in the project root (useful for .env files, etc.)
 #[tauri::command]
-pub async fn list_gitignored_files(project_path: String) -> Result<Vec<GitIgnoredFile>, String> {
+pub async fn list_gitignored_files(project_path: String) -> MindgridResult<Vec<GitIgnoredFile>> {
     let path = Path::new(&project_path);
 
     if !path.exists() || !path.is_dir() {
-        return Err("Directory does not exist".to_string());
+        return Err(MindgridError::validation("directory_missing", "Directory does not exist")
+            .with_technical(&project_path));
     }
 
     // Common patterns for files that should be copied to worktrees
@@ -1906,16 +2202,16 @@ pub async fn copy_files_to_worktree(
     project_path: String,
     worktree_path: String,
     files: Vec<String>,
-) -> Result<Vec<String>, String> {
+) -> MindgridResult<Vec<String>> {
     let source = Path::new(&project_path);
     let dest = Path::new(&worktree_path);
 
     if !source.exists() || !source.is_dir() {
-        return Err("Source directory does not exist".to_string());
+        return Err(MindgridError::validation("directory_missing", "Source directory does not exist"));
     }
 
     if !dest.exists() || !dest.is_dir() {
-        return Err("Destination directory does not exist".to_string());
+        return Err(MindgridError::validation("directory_missing", "Destination directory does not exist"));
     }
 
     let mut copied = Vec::new();
@@ -1977,3 +2273,87 @@ fn copy_dir_recursive(src: &Path, dst: &Path) -> std::io::Result<()> {
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::fixtures::{git_in, TempRepo};
+
+    #[test]
+    fn bare_repositories_are_not_checkouts() {
+        let project = TempRepo::with_files(&[("README.md", "hello\n")]);
+        let bare = project.path.with_extension("git");
+        project.git(&["clone", "-q", "--bare", &project.path_str(), &bare.to_string_lossy()]);
+
+        assert!(is_valid_git_repository(&project.path));
+        assert!(!is_valid_git_repository(&bare));
+        let _ = std::fs::remove_dir_all(&bare);
+    }
+
+    #[tokio::test]
+    async fn sparse_worktree_initializes_submodules() {
+        let library = TempRepo::with_files(&[("lib.txt", "library\n")]);
+        let project = TempRepo::with_files(&[("app/main.txt", "app\n"), ("docs/guide.txt", "docs\n")]);
+        project.git(&["-c", "protocol.file.allow=always", "submodule", "add", "-q", &library.path_str(), "vendor/lib"]);
+        project.commit_all("Add submodule");
+
+        let target = project.path.join(".mindgrid").join("worktrees").join("sparse");
+        let target_str = target.to_string_lossy().to_string();
+        git_in(&project.path, &["worktree", "add", "-q", "--no-checkout", "-B", "mindgrid/sparse", &target_str]);
+
+        let provision = ProvisionOptions {
+            sparse_patterns: Some(vec!["app".to_string(), "vendor".to_string()]),
+            cone: None,
+            clone_dirs: Some(Vec::new()),
+        };
+        let report = populate_worktree(&project.path, &target, Some(&provision), false, false)
+            .await
+            .unwrap()
+            .expect("provisioning report");
+
+        assert!(report.sparse);
+        assert!(target.join("app/main.txt").is_file());
+        assert!(!target.join("docs").exists());
+        // Only found once the sparse checkout has populated .gitmodules
+        assert_eq!(std::fs::read_to_string(target.join("vendor/lib/lib.txt")).unwrap(), "library\n");
+    }
+
+    #[tokio::test]
+    async fn failed_stack_record_removes_the_worktree_and_branch() {
+        let project = TempRepo::with_files(&[("README.md", "hello\n")]);
+        project.git(&["branch", "mindgrid/parent"]);
+        // git config can't be written while its lock file exists
+        let lock = project.path.join(".git").join("config.lock");
+        std::fs::write(&lock, "").unwrap();
+
+        let created =
+            add_workspace_worktree(project.path_str(), "child".to_string(), Some("parent".to_string()), None, None)
+                .await;
+        std::fs::remove_file(&lock).unwrap();
+
+        assert_eq!(created.unwrap_err().code, "git.stack_config_failed");
+        assert!(!project.path.join(".mindgrid/worktrees/child").exists());
+        assert!(!project.git(&["worktree", "list"]).contains("child"));
+        assert_eq!(crate::stack::resolve_ref(&project.path_str(), "mindgrid/child").await, None);
+    }
+
+    #[tokio::test]
+    async fn failed_session_record_removes_the_worktree() {
+        let project = TempRepo::with_files(&[("README.md", "hello\n")]);
+        project.git(&["branch", "mindgrid/reused"]);
+        let lock = project.path.join(".git").join("config.lock");
+        std::fs::write(&lock, "").unwrap();
+
+        let session = || Some("session-1".to_string());
+        let fresh = add_workspace_worktree(project.path_str(), "fresh".to_string(), None, session(), None).await;
+        let reused = add_workspace_worktree(project.path_str(), "reused".to_string(), None, session(), None).await;
+        std::fs::remove_file(&lock).unwrap();
+
+        assert_eq!(fresh.unwrap_err().code, "git.config_failed");
+        assert_eq!(reused.unwrap_err().code, "git.config_failed");
+        assert!(!project.git(&["worktree", "list"]).contains(".mindgrid"));
+        assert_eq!(crate::stack::resolve_ref(&project.path_str(), "mindgrid/fresh").await, None);
+        // A branch that existed before is left alone
+        assert!(crate::stack::resolve_ref(&project.path_str(), "mindgrid/reused").await.is_some());
+    }
+}
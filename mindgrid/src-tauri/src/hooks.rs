@@ -0,0 +1,364 @@
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use ts_rs::TS;
+
+use crate::error::{MindgridError, MindgridResult};
+use crate::pty::PtyState;
+
+/// Keep at most this much hook output in the structured result (the full log is streamed)
+const MAX_CAPTURED_OUTPUT: usize = 64 * 1024;
+/// Hooks that run longer than this are killed and fail the commit
+const HOOK_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// Result of running one git hook
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct HookRun {
+    pub hook: String,
+    pub success: bool,
+    pub exit_code: Option<i32>,
+    /// Lines of stdout and stderr in the order they arrived, truncated from the front if very long
+    pub output: String,
+    #[ts(type = "number")]
+    pub duration_ms: u64,
+}
+
+/// One line of hook output streamed to the frontend
+#[derive(Clone, Serialize, TS)]
+#[ts(export)]
+pub struct HookOutput {
+    pub working_directory: String,
+    pub hook: String,
+    /// "stdout" or "stderr"
+    pub stream: String,
+    pub line: String,
+}
+
+/// Event sent when a hook blocks a commit
+#[derive(Clone, Serialize, TS)]
+#[ts(export)]
+pub struct HookFailed {
+    pub working_directory: String,
+    pub run: HookRun,
+    /// Prompt the session's agent can use to fix the failure
+    pub follow_up_prompt: String,
+    /// Whether the prompt was written to the agent's PTY
+    pub sent_to_agent: bool,
+}
+
+async fn git_stdout(working_directory: &str, args: &[&str]) -> Option<String> {
+    let output = tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(working_directory)
+        .args(args)
+        .output()
+        .await
+        .ok()?;
+    if output.status.success() {
+        Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        None
+    }
+}
+
+/// Locate an executable hook, honoring core.hooksPath and worktree git dirs
+async fn find_hook(working_directory: &str, hook: &str) -> Option<PathBuf> {
+    let toplevel = git_stdout(working_directory, &["rev-parse", "--show-toplevel"])
+        .await
+        .unwrap_or_else(|| working_directory.to_string());
+
+    let path = match git_stdout(working_directory, &["config", "--get", "core.hooksPath"]).await {
+        Some(hooks_path) if !hooks_path.is_empty() => {
+            let base = PathBuf::from(&hooks_path);
+            let base = if base.is_absolute() { base } else { Path::new(&toplevel).join(base) };
+            base.join(hook)
+        }
+        _ => {
+            let relative = git_stdout(working_directory, &["rev-parse", "--git-path", &format!("hooks/{}", hook)]).await?;
+            let p = PathBuf::from(relative);
+            if p.is_absolute() {
+                p
+            } else {
+                Path::new(working_directory).join(p)
+            }
+        }
+    };
+
+    if is_executable(&path) {
+        Some(path)
+    } else {
+        None
+    }
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path)
+        .map(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// Run a git hook if it exists, streaming its output as `git-hook-output` events.
+/// Returns `None` when the repository has no such hook.
+pub async fn run_hook(
+    app: &AppHandle,
+    working_directory: &str,
+    hook: &str,
+    args: &[&str],
+) -> MindgridResult<Option<HookRun>> {
+    let emit = |stream: &str, line: &str| {
+        let _ = app.emit(
+            "git-hook-output",
+            HookOutput {
+                working_directory: working_directory.to_string(),
+                hook: hook.to_string(),
+                stream: stream.to_string(),
+                line: line.to_string(),
+            },
+        );
+    };
+    execute_hook(working_directory, hook, args, HOOK_TIMEOUT, &emit).await
+}
+
+/// Absolute path of the worktree's index, which git exports to commit hooks
+async fn index_file(working_directory: &str) -> Option<PathBuf> {
+    let path = PathBuf::from(git_stdout(working_directory, &["rev-parse", "--git-path", "index"]).await?);
+    Some(if path.is_absolute() { path } else { Path::new(working_directory).join(path) })
+}
+
+/// Run a hook the way `git commit` would, passing each output line to `on_line`
+async fn execute_hook(
+    working_directory: &str,
+    hook: &str,
+    args: &[&str],
+    timeout: Duration,
+    on_line: &(dyn Fn(&str, &str) + Sync),
+) -> MindgridResult<Option<HookRun>> {
+    let Some(hook_path) = find_hook(working_directory, hook).await else {
+        return Ok(None);
+    };
+
+    let toplevel = git_stdout(working_directory, &["rev-parse", "--show-toplevel"])
+        .await
+        .unwrap_or_else(|| working_directory.to_string());
+
+    let mut cmd = tokio::process::Command::new(&hook_path);
+    cmd.args(args)
+        .current_dir(&toplevel)
+        // The commit message comes from a file, so no editor will open
+        .env("GIT_EDITOR", ":")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    if let Some(index) = index_file(working_directory).await {
+        cmd.env("GIT_INDEX_FILE", index);
+    }
+    // Own process group, so a timeout also ends whatever the hook started
+    #[cfg(unix)]
+    cmd.process_group(0);
+
+    let started = Instant::now();
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| MindgridError::spawn_failed(&format!("{} hook", hook), e))?;
+
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    let collected = Mutex::new(String::new());
+    let run = async {
+        tokio::join!(
+            stream_lines("stdout", stdout, on_line, &collected),
+            stream_lines("stderr", stderr, on_line, &collected),
+        );
+        child.wait().await
+    };
+    let Ok(status) = tokio::time::timeout(timeout, run).await else {
+        if let Some(pid) = child.id() {
+            crate::operations::kill_tree(pid);
+        }
+        let _ = child.kill().await;
+        return Ok(Some(HookRun {
+            hook: hook.to_string(),
+            success: false,
+            exit_code: None,
+            output: format!("The {} hook did not finish within {} seconds", hook, timeout.as_secs()),
+            duration_ms: started.elapsed().as_millis() as u64,
+        }));
+    };
+    let status = status.map_err(|e| {
+        MindgridError::io("hook_wait_failed", format!("Failed to wait for the {} hook", hook)).with_technical(e)
+    })?;
+
+    let mut output = collected.into_inner();
+    if output.len() > MAX_CAPTURED_OUTPUT {
+        let mut cut = output.len() - MAX_CAPTURED_OUTPUT;
+        while !output.is_char_boundary(cut) {
+            cut += 1;
+        }
+        output = format!("[... output truncated ...]\n{}", &output[cut..]);
+    }
+
+    Ok(Some(HookRun {
+        hook: hook.to_string(),
+        success: status.success(),
+        exit_code: status.code(),
+        output,
+        duration_ms: started.elapsed().as_millis() as u64,
+    }))
+}
+
+/// Pass each line of a hook's output stream to `on_line` and append it to
+/// `collected`, which both streams share so their lines stay interleaved
+async fn stream_lines<R: tokio::io::AsyncRead + Unpin>(
+    stream: &str,
+    reader: Option<R>,
+    on_line: &(dyn Fn(&str, &str) + Sync),
+    collected: &Mutex<String>,
+) {
+    let Some(reader) = reader else {
+        return;
+    };
+
+    let mut lines = BufReader::new(reader).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        on_line(stream, &line);
+        let mut collected = collected.lock();
+        collected.push_str(&line);
+        collected.push('\n');
+    }
+}
+
+/// Prompt asking the agent to fix whatever the hook complained about
+pub fn follow_up_prompt(run: &HookRun) -> String {
+    let exit = run
+        .exit_code
+        .map(|c| format!("exit code {}", c))
+        .unwrap_or_else(|| "a signal".to_string());
+    format!(
+        "The git {} hook rejected the commit ({}). Fix the problems it reports, then commit again.\n\nHook output:\n```\n{}\n```",
+        run.hook,
+        exit,
+        run.output.trim_end()
+    )
+}
+
+/// Emit `git-hook-failed` and optionally hand the failure to the session's agent PTY
+pub fn report_failure(app: &AppHandle, working_directory: &str, run: &HookRun, agent_pty_id: Option<&str>) {
+    let prompt = follow_up_prompt(run);
+
+    let sent_to_agent = match agent_pty_id {
+        Some(id) => {
+            let state = app.state::<Arc<PtyState>>();
+            match crate::pty::write_to_pty(&state, id, &format!("{}\r", prompt)) {
+                Ok(()) => true,
+                Err(e) => {
+                    eprintln!("[MindGrid] Failed to send hook failure to agent: {}", e);
+                    false
+                }
+            }
+        }
+        None => false,
+    };
+
+    let _ = app.emit(
+        "git-hook-failed",
+        HookFailed {
+            working_directory: working_directory.to_string(),
+            run: run.clone(),
+            follow_up_prompt: prompt,
+            sent_to_agent,
+        },
+    );
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::tests::fixtures::TempRepo;
+    use std::os::unix::fs::PermissionsExt;
+
+    fn write_hook(dir: &Path, name: &str, script: &str) {
+        std::fs::create_dir_all(dir).unwrap();
+        let path = dir.join(name);
+        std::fs::write(&path, script).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[tokio::test]
+    async fn finds_hooks_in_hooks_path_only_when_executable() {
+        let repo = TempRepo::with_files(&[("README.md", "hello\n")]);
+        let path = repo.path_str();
+        assert_eq!(find_hook(&path, "pre-commit").await, None);
+
+        write_hook(&repo.path.join(".git/hooks"), "pre-commit", "#!/bin/sh\nexit 0\n");
+        assert_eq!(find_hook(&path, "pre-commit").await, Some(repo.path.join(".git/hooks/pre-commit")));
+
+        repo.git(&["config", "core.hooksPath", "tools/hooks"]);
+        assert_eq!(find_hook(&path, "pre-commit").await, None);
+        repo.write("tools/hooks/pre-commit", "#!/bin/sh\nexit 0\n");
+        assert_eq!(find_hook(&path, "pre-commit").await, None);
+        write_hook(&repo.path.join("tools/hooks"), "pre-commit", "#!/bin/sh\nexit 0\n");
+        assert_eq!(find_hook(&path, "pre-commit").await, Some(repo.path.join("tools/hooks/pre-commit")));
+    }
+
+    #[tokio::test]
+    async fn hooks_see_the_worktree_index() {
+        let repo = TempRepo::with_files(&[("README.md", "hello\n")]);
+        let worktree = repo.path.join(".mindgrid/worktrees/hooked");
+        repo.git(&["worktree", "add", "-q", "-b", "mindgrid/hooked", &worktree.to_string_lossy()]);
+        write_hook(
+            &repo.path.join(".git/hooks"),
+            "pre-commit",
+            "#!/bin/sh\necho \"index=$GIT_INDEX_FILE editor=$GIT_EDITOR\"\ngit diff --cached --name-only\necho rejected >&2\nexit 3\n",
+        );
+        std::fs::write(worktree.join("staged.txt"), "new\n").unwrap();
+        crate::tests::fixtures::git_in(&worktree, &["add", "staged.txt"]);
+
+        let lines = Mutex::new(Vec::new());
+        let on_line = |stream: &str, line: &str| lines.lock().push(format!("{}: {}", stream, line));
+        let worktree_str = worktree.to_string_lossy().to_string();
+        let run = execute_hook(&worktree_str, "pre-commit", &[], HOOK_TIMEOUT, &on_line)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert!(!run.success);
+        assert_eq!(run.exit_code, Some(3));
+        let lines = lines.into_inner();
+        assert!(lines[0].starts_with("stdout: index=/"), "{:?}", lines);
+        assert!(lines[0].contains("worktrees/hooked/index editor=:"), "{:?}", lines);
+        assert!(lines.contains(&"stdout: staged.txt".to_string()), "{:?}", lines);
+        assert!(lines.contains(&"stderr: rejected".to_string()), "{:?}", lines);
+        assert!(run.output.contains("staged.txt\n"));
+        assert!(run.output.contains("rejected\n"));
+        assert!(follow_up_prompt(&run).contains("(exit code 3)"));
+    }
+
+    #[tokio::test]
+    async fn hooks_time_out() {
+        let repo = TempRepo::with_files(&[("README.md", "hello\n")]);
+        write_hook(&repo.path.join(".git/hooks"), "pre-commit", "#!/bin/sh\nsleep 30\n");
+
+        let run = execute_hook(&repo.path_str(), "pre-commit", &[], Duration::from_secs(1), &|_, _| {})
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!run.success);
+        assert_eq!(run.exit_code, None);
+        assert!(run.duration_ms < 5000, "{}", run.duration_ms);
+        assert!(run.output.contains("did not finish within 1 seconds"), "{}", run.output);
+    }
+}
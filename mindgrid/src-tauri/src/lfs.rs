@@ -0,0 +1,273 @@
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::Path;
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+use ts_rs::TS;
+
+use crate::diff::DiffFileEntry;
+use crate::error::{MindgridError, MindgridResult};
+
+/// First line of every Git LFS pointer file
+const POINTER_VERSION: &str = "version https://git-lfs.github.com/spec/v1";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+pub struct LfsPointer {
+    /// "sha256:<hex>"
+    pub oid: String,
+    #[ts(type = "number")]
+    pub size: u64,
+}
+
+/// Both sides of a change to an LFS-tracked file
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct LfsChange {
+    pub old: Option<LfsPointer>,
+    pub new: Option<LfsPointer>,
+}
+
+/// Whether any tracked `.gitattributes` routes files through the LFS filter
+pub async fn uses_lfs(working_directory: &Path) -> bool {
+    let output = tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(working_directory)
+        .args(["ls-files", "-z", "--", ".gitattributes", ":(glob)**/.gitattributes"])
+        .output()
+        .await;
+    let Ok(output) = output else {
+        return false;
+    };
+
+    String::from_utf8_lossy(&output.stdout)
+        .split('\0')
+        .filter(|p| !p.is_empty())
+        .any(|p| {
+            std::fs::read_to_string(working_directory.join(p))
+                .map(|content| content.contains("filter=lfs"))
+                .unwrap_or(false)
+        })
+}
+
+/// Whether the git-lfs extension is installed
+pub async fn lfs_available() -> bool {
+    tokio::process::Command::new("git")
+        .args(["lfs", "version"])
+        .output()
+        .await
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+/// Download (if needed) and check out LFS objects in a worktree. The LFS store
+/// is shared by all worktrees of a repository, so objects the main checkout
+/// already has are not fetched again.
+pub async fn pull(worktree: &Path) -> MindgridResult<()> {
+    let args = ["lfs", "pull"];
+    let output = tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(worktree)
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| MindgridError::spawn_failed("git lfs pull", e))?;
+
+    if !output.status.success() {
+        return Err(MindgridError::git_failed("lfs_pull_failed", "Failed to pull LFS objects", &args, &output));
+    }
+    Ok(())
+}
+
+/// Number of LFS files in a worktree that are still pointer files, or `None`
+/// when the repository doesn't use LFS or git-lfs isn't installed
+pub async fn pointer_count(working_directory: &str) -> Option<i32> {
+    if !uses_lfs(Path::new(working_directory)).await {
+        return None;
+    }
+
+    let output = tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(working_directory)
+        .args(["lfs", "ls-files"])
+        .output()
+        .await
+        .ok()
+        .filter(|o| o.status.success())?;
+
+    // "<oid> * <path>" when the content is checked out, "<oid> - <path>" for a pointer
+    Some(
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter(|l| l.split_whitespace().nth(1) == Some("-"))
+            .count() as i32,
+    )
+}
+
+/// Paths among `paths` that have the LFS filter attribute
+pub async fn lfs_paths(working_directory: &str, paths: &[&str]) -> HashSet<String> {
+    if paths.is_empty() {
+        return HashSet::new();
+    }
+
+    let child = tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(working_directory)
+        .args(["check-attr", "-z", "--stdin", "filter"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn();
+    let Ok(mut child) = child else {
+        return HashSet::new();
+    };
+
+    // Paths are few enough (bounded by DiffLimits::max_files) to write before reading
+    let input: Vec<u8> = paths.iter().flat_map(|p| p.bytes().chain([0])).collect();
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(&input).await;
+    }
+    let Ok(output) = child.wait_with_output().await else {
+        return HashSet::new();
+    };
+
+    // "<path>\0filter\0<value>\0" per path
+    let text = String::from_utf8_lossy(&output.stdout);
+    let fields: Vec<&str> = text.split('\0').collect();
+    fields
+        .chunks(3)
+        .filter(|c| c.len() == 3 && c[2] == "lfs")
+        .map(|c| c[0].to_string())
+        .collect()
+}
+
+/// Flag the LFS-tracked entries of a diff summary
+pub async fn mark_lfs(working_directory: &str, files: &mut [DiffFileEntry]) {
+    if !uses_lfs(Path::new(working_directory)).await {
+        return;
+    }
+    let paths: Vec<&str> = files.iter().map(|f| f.path.as_str()).collect();
+    let tracked = lfs_paths(working_directory, &paths).await;
+    for file in files.iter_mut() {
+        file.lfs = tracked.contains(&file.path);
+    }
+}
+
+pub fn parse_pointer(text: &str) -> Option<LfsPointer> {
+    let mut lines = text.lines();
+    if lines.next()?.trim() != POINTER_VERSION {
+        return None;
+    }
+    let mut oid = None;
+    let mut size = None;
+    for line in lines {
+        if let Some(value) = line.strip_prefix("oid ") {
+            oid = Some(value.trim().to_string());
+        } else if let Some(value) = line.strip_prefix("size ") {
+            size = value.trim().parse().ok();
+        }
+    }
+    Some(LfsPointer { oid: oid?, size: size? })
+}
+
+/// Old and new pointers from a patch of an LFS-tracked file. git diffs the
+/// cleaned (pointer) form, so the hunks hold the pointer text of both sides.
+pub fn change_from_patch(patch: &str) -> LfsChange {
+    let mut old = String::new();
+    let mut new = String::new();
+    let mut in_hunk = false;
+    for line in patch.lines() {
+        if line.starts_with("@@") {
+            in_hunk = true;
+            continue;
+        }
+        if !in_hunk {
+            continue;
+        }
+        let (old_side, new_side) = match line.chars().next() {
+            Some(' ') => (true, true),
+            Some('-') => (true, false),
+            Some('+') => (false, true),
+            _ => continue,
+        };
+        if old_side {
+            old.push_str(&line[1..]);
+            old.push('\n');
+        }
+        if new_side {
+            new.push_str(&line[1..]);
+            new.push('\n');
+        }
+    }
+    LfsChange {
+        old: parse_pointer(&old),
+        new: parse_pointer(&new),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OLD_OID: &str = "sha256:4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393";
+    const NEW_OID: &str = "sha256:a3f5e1c8d2b6f4e07c9a1b3d5f7e9c2a4b6d8f0e1c3a5b7d9f2e4c6a8b0d1f3e";
+
+    fn pointer(oid: &str, size: u64) -> String {
+        format!("{}\noid {}\nsize {}\n", POINTER_VERSION, oid, size)
+    }
+
+    #[test]
+    fn parses_pointer_files() {
+        assert_eq!(
+            parse_pointer(&pointer(OLD_OID, 12345)),
+            Some(LfsPointer { oid: OLD_OID.to_string(), size: 12345 })
+        );
+        // Extension lines and trailing whitespace are tolerated
+        let extended = format!("{} \next-0-foo sha256:00\noid {}\nsize 7\r\n", POINTER_VERSION, NEW_OID);
+        assert_eq!(parse_pointer(&extended).map(|p| p.size), Some(7));
+
+        assert_eq!(parse_pointer("binary content\n"), None);
+        assert_eq!(parse_pointer(&format!("{}\noid {}\n", POINTER_VERSION, OLD_OID)), None);
+        assert_eq!(parse_pointer(&format!("{}\noid {}\nsize many\n", POINTER_VERSION, OLD_OID)), None);
+        assert_eq!(parse_pointer(""), None);
+    }
+
+    #[test]
+    fn reads_both_pointers_from_a_patch() {
+        let modified = format!(
+            "diff --git a/assets/logo.png b/assets/logo.png\n\
+             index 1a2b3c4..5d6e7f8 100644\n\
+             --- a/assets/logo.png\n\
+             +++ b/assets/logo.png\n\
+             @@ -1,3 +1,3 @@\n \
+             {}\n\
+             -oid {}\n\
+             -size 12345\n\
+             +oid {}\n\
+             +size 67890\n",
+            POINTER_VERSION, OLD_OID, NEW_OID
+        );
+        let change = change_from_patch(&modified);
+        assert_eq!(change.old, Some(LfsPointer { oid: OLD_OID.to_string(), size: 12345 }));
+        assert_eq!(change.new, Some(LfsPointer { oid: NEW_OID.to_string(), size: 67890 }));
+
+        let added = format!(
+            "diff --git a/model.bin b/model.bin\n\
+             new file mode 100644\n\
+             --- /dev/null\n\
+             +++ b/model.bin\n\
+             @@ -0,0 +1,3 @@\n\
+             +{}\n\
+             +oid {}\n\
+             +size 42\n",
+            POINTER_VERSION, NEW_OID
+        );
+        let change = change_from_patch(&added);
+        assert_eq!(change.old, None);
+        assert_eq!(change.new.map(|p| p.size), Some(42));
+
+        // Header lines before the first hunk are never read as content
+        let change = change_from_patch("--- a/version https://git-lfs.github.com/spec/v1\n+++ b/oid x\n");
+        assert_eq!((change.old, change.new), (None, None));
+    }
+}
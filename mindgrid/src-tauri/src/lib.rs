@@ -1,6 +1,34 @@
+mod error;
 mod pty;
 mod git;
 mod codex;
+mod db;
+mod app_store;
+mod pr_status;
+mod stack;
+mod signing;
+mod hooks;
+mod repo_scan;
+mod diff;
+mod trailers;
+mod resources;
+mod provision;
+mod submodule;
+mod lfs;
+mod session_manager;
+mod foundations;
+mod orchestrator;
+mod multi_model;
+mod best_of_n;
+mod codex_thread;
+mod operations;
+mod model_catalog;
+mod run_scripts;
+mod preview;
+
+// Test modules (only compiled during testing)
+#[cfg(test)]
+mod tests;
 
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -54,6 +82,33 @@ fn get_db_name() -> &'static str {
     }
 }
 
+/// Get the name of the frontend's JSON store based on dev mode
+/// (must match `getStoreFilename` in src/lib/dev-mode.ts)
+fn get_store_name() -> &'static str {
+    if DEV_MODE.load(Ordering::Relaxed) {
+        "mindgrid-dev-data.json"
+    } else {
+        "mindgrid-data.json"
+    }
+}
+
+/// Make the SQL plugin preload the database the migrations are registered for.
+/// The config files can only name one database, but dev mode is picked at runtime.
+fn preload_database(config: &mut tauri::Config, db_uri: &str) {
+    config
+        .plugins
+        .0
+        .insert("sql".to_string(), serde_json::json!({ "preload": [db_uri] }));
+}
+
+/// Tauri context with the database for the current mode preloaded, so the
+/// backend can use it before (or without) the frontend loading it
+fn app_context() -> tauri::Context {
+    let mut context = tauri::generate_context!();
+    preload_database(context.config_mut(), &format!("sqlite:{}", get_db_name()));
+    context
+}
+
 /// Get the path to the mindgrid zsh config directory
 fn get_zsh_config_dir() -> PathBuf {
     let mut path = std::env::temp_dir();
@@ -125,22 +180,9 @@ alias gb='git branch --color'
     println!("[MindGrid] Created zsh config at {:?}", config_dir);
 }
 
-#[cfg_attr(mobile, tauri::mobile_entry_point)]
-pub fn run() {
-    // Check for dev mode via environment variable (must be "1" or "true")
-    if let Ok(val) = std::env::var("MINDGRID_DEV_MODE") {
-        if val == "1" || val.to_lowercase() == "true" {
-            DEV_MODE.store(true, Ordering::Relaxed);
-            println!("[MindGrid] Running in DEVELOPER MODE - using isolated data storage");
-        }
-    }
-
-    // Set up custom zsh configuration for terminal
-    setup_zsh_config();
-
-    let pty_state = Arc::new(pty::PtyState::new());
-
-    let migrations = vec![
+/// Schema migrations of the app database, applied by the SQL plugin
+pub(crate) fn migrations() -> Vec<Migration> {
+    vec![
         Migration {
             version: 1,
             description: "create_initial_tables",
@@ -201,7 +243,91 @@ pub fn run() {
             "#,
             kind: MigrationKind::Up,
         },
-    ];
+        Migration {
+            version: 4,
+            description: "create_model_queries",
+            sql: r#"
+                CREATE TABLE IF NOT EXISTS model_queries (
+                    id TEXT PRIMARY KEY,
+                    prompt TEXT NOT NULL,
+                    cwd TEXT NOT NULL,
+                    isolation TEXT NOT NULL,
+                    created_at INTEGER NOT NULL
+                );
+
+                CREATE TABLE IF NOT EXISTS model_query_results (
+                    query_id TEXT NOT NULL,
+                    target_id TEXT NOT NULL,
+                    position INTEGER NOT NULL,
+                    agent TEXT NOT NULL,
+                    model TEXT,
+                    status TEXT NOT NULL,
+                    response TEXT,
+                    error TEXT,
+                    diff TEXT,
+                    duration_ms INTEGER NOT NULL,
+                    PRIMARY KEY (query_id, target_id),
+                    FOREIGN KEY (query_id) REFERENCES model_queries(id) ON DELETE CASCADE
+                );
+
+                CREATE INDEX IF NOT EXISTS idx_model_queries_cwd ON model_queries(cwd);
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 5,
+            description: "create_codex_threads",
+            sql: r#"
+                CREATE TABLE IF NOT EXISTS codex_threads (
+                    id TEXT PRIMARY KEY,
+                    session_id TEXT,
+                    codex_thread_id TEXT,
+                    cwd TEXT NOT NULL,
+                    model TEXT,
+                    sandbox_mode TEXT NOT NULL,
+                    created_at INTEGER NOT NULL,
+                    updated_at INTEGER NOT NULL
+                );
+
+                CREATE INDEX IF NOT EXISTS idx_codex_threads_session ON codex_threads(session_id);
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 6,
+            description: "create_run_scripts",
+            sql: r#"
+                CREATE TABLE IF NOT EXISTS run_scripts (
+                    project_id TEXT NOT NULL,
+                    name TEXT NOT NULL,
+                    command TEXT NOT NULL,
+                    restart_on_crash INTEGER NOT NULL DEFAULT 0,
+                    updated_at INTEGER NOT NULL,
+                    PRIMARY KEY (project_id, name)
+                );
+            "#,
+            kind: MigrationKind::Up,
+        },
+    ]
+}
+
+#[cfg_attr(mobile, tauri::mobile_entry_point)]
+pub fn run() {
+    // Check for dev mode via environment variable (must be "1" or "true")
+    if let Ok(val) = std::env::var("MINDGRID_DEV_MODE") {
+        if val == "1" || val.to_lowercase() == "true" {
+            DEV_MODE.store(true, Ordering::Relaxed);
+            println!("[MindGrid] Running in DEVELOPER MODE - using isolated data storage");
+        }
+    }
+
+    // Set up custom zsh configuration for terminal
+    setup_zsh_config();
+
+    let pty_state = Arc::new(pty::PtyState::new());
+    let pr_poller = Arc::new(pr_status::PrPollerState::new(Arc::new(
+        pr_status::GitHubForge::from_env(),
+    )));
 
     // Build database URI based on dev mode
     let db_uri = format!("sqlite:{}", get_db_name());
@@ -214,10 +340,28 @@ pub fn run() {
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .plugin(
             tauri_plugin_sql::Builder::default()
-                .add_migrations(&db_uri, migrations)
+                .add_migrations(&db_uri, migrations())
                 .build(),
         )
         .manage(pty_state)
+        .manage(Arc::new(repo_scan::RepoScanCache::new()))
+        .manage(Arc::new(resources::ResourceState::new()))
+        .manage(pr_poller.clone())
+        .manage(Arc::new(session_manager::SessionManager::new()))
+        .manage(Arc::new(foundations::FoundationsState::new()))
+        .manage(Arc::new(orchestrator::OrchestratorState::new()))
+        .manage(Arc::new(best_of_n::BestOfNState::new()))
+        .manage(Arc::new(codex_thread::CodexThreadState::new()))
+        .manage(Arc::new(operations::OperationRegistry::new()))
+        .manage(Arc::new(model_catalog::ModelCatalogState::new()))
+        .manage(Arc::new(run_scripts::RunScriptState::new()))
+        .manage(Arc::new(preview::PreviewState::new()))
+        .setup(move |app| {
+            pr_status::start_poller(app.handle().clone(), pr_poller);
+            codex::init_runner_resources(app.handle());
+            best_of_n::start_sweep(app.handle().clone());
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             pty::spawn_pty,
             pty::write_pty,
@@ -226,14 +370,23 @@ pub fn run() {
             pty::get_claude_usage,
             pty::get_codex_usage,
             git::list_git_repos,
+            repo_scan::refresh_git_repos,
             git::validate_git_repository,
             git::get_git_worktrees,
             git::get_project_worktrees,
             git::create_workspace_worktree,
             git::remove_workspace_worktree,
+            resources::get_worktree_resources,
+            provision::set_worktree_sparse_patterns,
+            provision::get_worktree_sparse_patterns,
             git::get_git_status,
             git::get_git_diff,
             git::get_git_file_diff,
+            diff::get_session_diff,
+            trailers::git_blame_range,
+            trailers::get_trailer_config,
+            trailers::set_trailer_config,
+            trailers::find_commits_by_session,
             git::git_add_all,
             git::git_commit,
             git::git_commit_with_signature,
@@ -252,12 +405,57 @@ pub fn run() {
             git::load_session_from_worktree,
             git::list_gitignored_files,
             git::copy_files_to_worktree,
-            codex::codex_list_models,
+            session_manager::create_session,
+            session_manager::pause_session,
+            session_manager::resume_session,
+            session_manager::delete_session,
+            foundations::foundations_list,
+            foundations::foundations_write,
+            foundations::foundations_watch,
+            foundations::foundations_unwatch,
+            orchestrator::orchestrator_start,
+            orchestrator::orchestrator_advance,
+            orchestrator::orchestrator_status,
+            orchestrator::orchestrator_stop,
+            multi_model::multi_model_query,
+            multi_model::list_model_queries,
+            multi_model::delete_model_query,
+            best_of_n::best_of_n_start,
+            best_of_n::best_of_n_status,
+            best_of_n::best_of_n_pick,
+            best_of_n::best_of_n_discard,
+            model_catalog::list_models,
+            run_scripts::list_run_scripts,
+            run_scripts::save_run_script,
+            run_scripts::delete_run_script,
+            run_scripts::start_run_script,
+            run_scripts::stop_run_script,
+            run_scripts::list_running_scripts,
+            preview::detect_session_ports,
+            preview::start_preview,
+            preview::get_preview,
+            preview::stop_preview,
             codex::run_codex,
+            codex::codex_runner_diagnostics,
+            codex_thread::codex_start_thread,
+            codex_thread::codex_send,
+            codex_thread::codex_cancel,
+            codex_thread::codex_close_thread,
+            operations::cancel_operation,
+            operations::list_operations,
+            operations::get_operation_timeouts,
+            operations::set_operation_timeout,
+            pr_status::get_pr_statuses,
+            pr_status::refresh_pr_status,
+            stack::git_restack,
+            stack::git_get_stack,
+            signing::get_signing_config,
+            signing::set_signing_policy,
+            signing::git_verify_branch_signatures,
             is_dev_mode,
             path_exists,
             get_worktree_info,
         ])
-        .run(tauri::generate_context!())
+        .run(app_context())
         .expect("error while running tauri application");
 }
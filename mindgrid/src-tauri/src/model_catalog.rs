@@ -0,0 +1,498 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+use ts_rs::TS;
+
+use crate::error::{MindgridError, MindgridResult};
+
+/// Discovered models are reused for a day; `list_models(refresh)` forces a rescan
+const CATALOG_TTL_SECS: u64 = 24 * 60 * 60;
+const CACHE_FILE: &str = "model-catalog.json";
+/// `codex /model` is only scraped when Codex has no cached model list
+const CODEX_SCRAPE_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Built in models per provider: (id, name). The first is the default unless the
+/// agent's own config names one.
+const CLAUDE_MODELS: &[(&str, &str)] = &[
+    ("sonnet", "Claude Sonnet 4.5"),
+    ("opus", "Claude Opus 4.5"),
+    ("haiku", "Claude Haiku 4.5"),
+];
+const CODEX_MODELS: &[(&str, &str)] = &[
+    ("gpt-5.1-codex", "GPT-5.1 Codex"),
+    ("gpt-5.1-codex-max", "GPT-5.1 Codex Max"),
+    ("gpt-5.1-codex-mini", "GPT-5.1 Codex Mini"),
+    ("gpt-5.1", "GPT-5.1"),
+];
+const GEMINI_MODELS: &[(&str, &str)] = &[
+    ("gemini-2.5-pro", "Gemini 2.5 Pro"),
+    ("gemini-2.5-flash", "Gemini 2.5 Flash"),
+    ("gemini-2.5-flash-lite", "Gemini 2.5 Flash Lite"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum ModelProvider {
+    Anthropic,
+    Openai,
+    Google,
+}
+
+/// Where a model entry came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum ModelSource {
+    /// Shipped with mindgrid
+    Builtin,
+    /// Named in the agent's settings (e.g. `model` in ~/.codex/config.toml)
+    AgentConfig,
+    /// The agent's own cached model list (~/.codex/models_cache.json)
+    AgentCache,
+    /// Scraped from `codex /model` output
+    CliScrape,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct CatalogModel {
+    /// Value passed to the agent's `--model` flag
+    pub id: String,
+    pub name: String,
+    pub provider: ModelProvider,
+    pub source: ModelSource,
+    /// The agent's default when no model is chosen
+    pub is_default: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ModelCatalog {
+    pub models: Vec<CatalogModel>,
+    /// Unix timestamp (milliseconds)
+    #[ts(type = "number")]
+    pub fetched_at: i64,
+}
+
+impl ModelCatalog {
+    fn is_fresh(&self) -> bool {
+        now_ms() - self.fetched_at < (CATALOG_TTL_SECS * 1000) as i64
+    }
+}
+
+/// In-memory copy of the catalog; the lock is held while discovering so
+/// concurrent pickers share one scan
+pub struct ModelCatalogState {
+    catalog: tokio::sync::Mutex<Option<ModelCatalog>>,
+}
+
+impl ModelCatalogState {
+    pub fn new() -> Self {
+        Self {
+            catalog: tokio::sync::Mutex::new(None),
+        }
+    }
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// Collects models per provider, skipping ids already listed
+#[derive(Default)]
+struct CatalogBuilder {
+    models: Vec<CatalogModel>,
+    seen: HashSet<(ModelProvider, String)>,
+}
+
+impl CatalogBuilder {
+    fn push(&mut self, provider: ModelProvider, id: &str, name: Option<&str>, source: ModelSource) {
+        let id = id.trim();
+        if id.is_empty() || !self.seen.insert((provider, id.to_lowercase())) {
+            return;
+        }
+        self.models.push(CatalogModel {
+            id: id.to_string(),
+            name: name.map_or_else(|| prettify_model_id(id), str::to_string),
+            provider,
+            source,
+            is_default: false,
+        });
+    }
+
+    fn builtins(&mut self, provider: ModelProvider, models: &[(&str, &str)]) {
+        for (id, name) in models {
+            self.push(provider, id, Some(name), ModelSource::Builtin);
+        }
+    }
+
+    /// Mark `id` as the provider's default, or the first listed model when the
+    /// agent config names none
+    fn set_default(&mut self, provider: ModelProvider, id: Option<&str>) {
+        let index = id
+            .and_then(|id| {
+                self.models
+                    .iter()
+                    .position(|m| m.provider == provider && m.id.eq_ignore_ascii_case(id))
+            })
+            .or_else(|| self.models.iter().position(|m| m.provider == provider));
+        if let Some(index) = index {
+            self.models[index].is_default = true;
+        }
+    }
+}
+
+/// `model = "..."` values from a Codex config.toml, top-level first. Only that
+/// key is needed, so this scans lines instead of parsing TOML.
+pub(crate) fn codex_config_models(config: &str) -> Vec<String> {
+    let mut top_level = Vec::new();
+    let mut profiles = Vec::new();
+    let mut in_table = false;
+    for line in config.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_table = true;
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else { continue };
+        if key.trim() != "model" {
+            continue;
+        }
+        let value = value.split('#').next().unwrap_or("").trim().trim_matches(['"', '\'']);
+        if value.is_empty() {
+            continue;
+        }
+        if in_table {
+            profiles.push(value.to_string());
+        } else {
+            top_level.push(value.to_string());
+        }
+    }
+    top_level.extend(profiles);
+    top_level
+}
+
+/// Model ids and display names from Codex's models cache: an array, or an object
+/// with `models`, whose entries are ids or objects with `slug`/`id` and a name
+pub(crate) fn parse_models_cache(raw: &str) -> Vec<(String, Option<String>)> {
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(raw) else {
+        return Vec::new();
+    };
+    let entries = match value.as_array().or_else(|| value.get("models").and_then(|v| v.as_array())) {
+        Some(entries) => entries,
+        None => return Vec::new(),
+    };
+    entries
+        .iter()
+        .filter_map(|entry| {
+            if let Some(id) = entry.as_str() {
+                return Some((id.to_string(), None));
+            }
+            let id = ["slug", "id", "model"].iter().find_map(|key| entry.get(*key)?.as_str())?;
+            let name = ["display_name", "name"].iter().find_map(|key| entry.get(*key)?.as_str());
+            Some((id.to_string(), name.map(str::to_string)))
+        })
+        .collect()
+}
+
+/// `model` from a Claude or Gemini settings.json (Gemini nests it as `model.name`)
+fn settings_model(path: &Path) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()?;
+    let model = value.get("model")?;
+    model
+        .as_str()
+        .or_else(|| model.get("name")?.as_str())
+        .map(str::to_string)
+}
+
+fn codex_home(home: Option<&Path>) -> Option<PathBuf> {
+    match std::env::var_os("CODEX_HOME") {
+        Some(dir) if !dir.is_empty() => Some(PathBuf::from(dir)),
+        _ => home.map(|home| home.join(".codex")),
+    }
+}
+
+/// Run `codex /model` and scrape model ids from its output
+async fn scrape_codex_models() -> MindgridResult<Vec<String>> {
+    let run = tokio::process::Command::new("codex")
+        .arg("/model")
+        .env("NO_COLOR", "1")
+        .env("TERM", "dumb")
+        .env("CI", "true")
+        .stdin(std::process::Stdio::null())
+        .kill_on_drop(true)
+        .output();
+    let output = tokio::time::timeout(CODEX_SCRAPE_TIMEOUT, run)
+        .await
+        .map_err(|_| MindgridError::agent("timeout", "Codex took too long to list models").with_command("codex /model"))?
+        .map_err(|e| {
+            MindgridError::agent("cli_unavailable", "Failed to run the Codex CLI")
+                .with_command("codex /model")
+                .with_technical(e)
+        })?;
+
+    let combined = format!(
+        "{}\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Ok(parse_models(&combined))
+}
+
+/// Model ids in `codex /model` output: JSON when the CLI prints it, otherwise
+/// any token that looks like a Codex/GPT model id
+pub(crate) fn parse_models(raw: &str) -> Vec<String> {
+    // Try to parse JSON first (either an array of ids, or an object with `models`)
+    if let Ok(value) = serde_json::from_str::<serde_json::Value>(raw) {
+        if let Some(arr) = value.as_array() {
+            return arr.iter().filter_map(|v| v.as_str()).map(str::to_string).collect();
+        }
+
+        if let Some(models) = value.get("models").and_then(|v| v.as_array()) {
+            return models.iter().filter_map(|v| v.as_str()).map(str::to_string).collect();
+        }
+    }
+
+    // Fallback: scan text for tokens that look like Codex/GPT model ids
+    let mut found: Vec<String> = Vec::new();
+    let mut seen = HashSet::new();
+
+    for word in raw.split_whitespace() {
+        let cleaned = word
+            .trim_matches(|c: char| !c.is_alphanumeric() && c != '-' && c != '_' && c != '.')
+            .trim_matches(['\'', '"']);
+
+        if cleaned.is_empty() {
+            continue;
+        }
+
+        let lower = cleaned.to_lowercase();
+        let looks_like_model = lower.contains("gpt") || lower.contains("codex") || lower == "auto";
+
+        if looks_like_model && seen.insert(lower.clone()) {
+            found.push(cleaned.to_string());
+        }
+    }
+
+    found
+}
+
+fn prettify_model_id(id: &str) -> String {
+    let mut parts: Vec<String> = id
+        .replace('_', "-")
+        .split('-')
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            let mut chars = s.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect();
+
+    if parts.is_empty() {
+        return id.to_string();
+    }
+
+    if parts[0].eq_ignore_ascii_case("gpt") && parts.len() > 1 {
+        parts[1] = format!("GPT {}", parts[1]);
+        parts.remove(0);
+    }
+
+    parts.join(" ")
+}
+
+/// Build the catalog from each agent's config and caches, scraping the Codex
+/// CLI only when it has no cached list
+async fn discover(home: Option<&Path>) -> ModelCatalog {
+    let mut catalog = CatalogBuilder::default();
+
+    // Claude: CLI aliases, plus a pinned model from ~/.claude/settings.json
+    let claude_default = home.and_then(|home| settings_model(&home.join(".claude/settings.json")));
+    if let Some(model) = &claude_default {
+        catalog.push(ModelProvider::Anthropic, model, None, ModelSource::AgentConfig);
+    }
+    catalog.builtins(ModelProvider::Anthropic, CLAUDE_MODELS);
+    catalog.set_default(ModelProvider::Anthropic, claude_default.as_deref());
+
+    // Codex: config.toml, then its models cache, then the CLI
+    let codex_home = codex_home(home);
+    let configured = codex_home
+        .as_ref()
+        .and_then(|dir| std::fs::read_to_string(dir.join("config.toml")).ok())
+        .map(|config| codex_config_models(&config))
+        .unwrap_or_default();
+    for model in &configured {
+        catalog.push(ModelProvider::Openai, model, None, ModelSource::AgentConfig);
+    }
+    let cached = codex_home
+        .as_ref()
+        .and_then(|dir| std::fs::read_to_string(dir.join("models_cache.json")).ok())
+        .map(|raw| parse_models_cache(&raw))
+        .unwrap_or_default();
+    for (id, name) in &cached {
+        catalog.push(ModelProvider::Openai, id, name.as_deref(), ModelSource::AgentCache);
+    }
+    if cached.is_empty() {
+        match scrape_codex_models().await {
+            Ok(models) => {
+                for id in &models {
+                    catalog.push(ModelProvider::Openai, id, None, ModelSource::CliScrape);
+                }
+            }
+            Err(e) => eprintln!("[MindGrid] Codex model scrape failed, using built in models: {}", e.technical_message()),
+        }
+    }
+    catalog.builtins(ModelProvider::Openai, CODEX_MODELS);
+    catalog.set_default(ModelProvider::Openai, configured.first().map(String::as_str));
+
+    // Gemini: built in list, plus a pinned model from ~/.gemini/settings.json
+    let gemini_default = home.and_then(|home| settings_model(&home.join(".gemini/settings.json")));
+    if let Some(model) = &gemini_default {
+        catalog.push(ModelProvider::Google, model, None, ModelSource::AgentConfig);
+    }
+    catalog.builtins(ModelProvider::Google, GEMINI_MODELS);
+    catalog.set_default(ModelProvider::Google, gemini_default.as_deref());
+
+    ModelCatalog {
+        models: catalog.models,
+        fetched_at: now_ms(),
+    }
+}
+
+fn cache_path(app: &AppHandle) -> Option<PathBuf> {
+    app.path().app_cache_dir().ok().map(|dir| dir.join(CACHE_FILE))
+}
+
+fn read_disk_cache(app: &AppHandle) -> Option<ModelCatalog> {
+    let raw = std::fs::read_to_string(cache_path(app)?).ok()?;
+    serde_json::from_str(&raw).ok()
+}
+
+fn write_disk_cache(app: &AppHandle, catalog: &ModelCatalog) -> MindgridResult<()> {
+    let path = cache_path(app).ok_or_else(|| MindgridError::io("no_cache_dir", "No cache directory available"))?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let json = serde_json::to_string(catalog)
+        .map_err(|e| MindgridError::io("serialize_failed", "Failed to serialize the model catalog").with_technical(e))?;
+    std::fs::write(&path, json)?;
+    Ok(())
+}
+
+/// Models for every agent, from memory or the on-disk cache while fresh;
+/// `refresh` rescans the agents' configs and CLIs
+#[tauri::command]
+pub async fn list_models(
+    app: AppHandle,
+    state: tauri::State<'_, Arc<ModelCatalogState>>,
+    refresh: Option<bool>,
+) -> MindgridResult<ModelCatalog> {
+    let mut cached = state.catalog.lock().await;
+    if !refresh.unwrap_or(false) {
+        if let Some(catalog) = cached.as_ref().filter(|c| c.is_fresh()) {
+            return Ok(catalog.clone());
+        }
+        if let Some(catalog) = read_disk_cache(&app).filter(|c| c.is_fresh()) {
+            *cached = Some(catalog.clone());
+            return Ok(catalog);
+        }
+    }
+
+    let home = app.path().home_dir().ok();
+    let catalog = discover(home.as_deref()).await;
+    if let Err(e) = write_disk_cache(&app, &catalog) {
+        eprintln!("[MindGrid] Failed to write model catalog cache: {}", e.technical_message());
+    }
+    *cached = Some(catalog.clone());
+    Ok(catalog)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn model_catalog_sources() {
+        let config = "model = \"gpt-5.1-codex\" # default\napproval_policy = \"never\"\n\n[profiles.fast]\nmodel = 'gpt-5.1-codex-mini'\n";
+        assert_eq!(codex_config_models(config), vec!["gpt-5.1-codex", "gpt-5.1-codex-mini"]);
+
+        let cache = r#"{"fetched_at":"2025-11-20","models":[{"slug":"gpt-5.1-codex","display_name":"GPT-5.1 Codex"},"gpt-5.1",{"name":"no id"}]}"#;
+        assert_eq!(
+            parse_models_cache(cache),
+            vec![
+                ("gpt-5.1-codex".to_string(), Some("GPT-5.1 Codex".to_string())),
+                ("gpt-5.1".to_string(), None),
+            ]
+        );
+        assert!(parse_models_cache("not json").is_empty());
+
+        assert_eq!(parse_models("Available: gpt-5.1-codex, `gpt-5.1` and GPT-5.1-codex"), vec!["gpt-5.1-codex", "gpt-5.1"]);
+        assert_eq!(prettify_model_id("gpt-5.1-codex-mini"), "GPT 5.1 Codex Mini");
+    }
+
+    #[tokio::test]
+    async fn discovers_models_from_agent_configs() {
+        if std::env::var_os("CODEX_HOME").is_some() {
+            return;
+        }
+        let home = std::env::temp_dir().join(format!("mindgrid-home-{}", uuid::Uuid::new_v4()));
+        for (path, content) in [
+            (".claude/settings.json", r#"{"model":"claude-sonnet-4-5-20250929"}"#),
+            (".codex/config.toml", "model = \"gpt-5.1-codex-max\"\n"),
+            (".codex/models_cache.json", r#"["gpt-5.1-codex", "gpt-5-pro"]"#),
+            (".gemini/settings.json", r#"{"model":{"name":"gemini-2.5-flash"}}"#),
+        ] {
+            let path = home.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+        let catalog = discover(Some(&home)).await;
+        std::fs::remove_dir_all(&home).unwrap();
+
+        let models = |provider| -> Vec<(&str, ModelSource, bool)> {
+            catalog
+                .models
+                .iter()
+                .filter(|m| m.provider == provider)
+                .map(|m| (m.id.as_str(), m.source, m.is_default))
+                .collect()
+        };
+        assert_eq!(
+            models(ModelProvider::Anthropic),
+            vec![
+                ("claude-sonnet-4-5-20250929", ModelSource::AgentConfig, true),
+                ("sonnet", ModelSource::Builtin, false),
+                ("opus", ModelSource::Builtin, false),
+                ("haiku", ModelSource::Builtin, false),
+            ]
+        );
+        // Cached ids aren't listed again as built in models
+        assert_eq!(
+            models(ModelProvider::Openai),
+            vec![
+                ("gpt-5.1-codex-max", ModelSource::AgentConfig, true),
+                ("gpt-5.1-codex", ModelSource::AgentCache, false),
+                ("gpt-5-pro", ModelSource::AgentCache, false),
+                ("gpt-5.1-codex-mini", ModelSource::Builtin, false),
+                ("gpt-5.1", ModelSource::Builtin, false),
+            ]
+        );
+        assert_eq!(
+            models(ModelProvider::Google),
+            vec![
+                ("gemini-2.5-flash", ModelSource::AgentConfig, true),
+                ("gemini-2.5-pro", ModelSource::Builtin, false),
+                ("gemini-2.5-flash-lite", ModelSource::Builtin, false),
+            ]
+        );
+    }
+}
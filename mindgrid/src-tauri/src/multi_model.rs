@@ -0,0 +1,738 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Sqlite};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::error::{MindgridError, MindgridResult};
+use crate::operations::ProcessTracker;
+
+/// Claude tools that can't change the working tree
+const READ_ONLY_CLAUDE_TOOLS: &str = "Read,Glob,Grep,WebFetch,WebSearch";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum QueryAgent {
+    Claude,
+    Codex,
+}
+
+impl QueryAgent {
+    fn as_str(self) -> &'static str {
+        match self {
+            QueryAgent::Claude => "claude",
+            QueryAgent::Codex => "codex",
+        }
+    }
+}
+
+/// How targets are kept from stepping on each other
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum QueryIsolation {
+    /// All targets share `cwd` with file edits disabled
+    ReadOnly,
+    /// Each target runs in a throwaway detached worktree; its diff is kept
+    Worktree,
+}
+
+impl QueryIsolation {
+    fn as_str(self) -> &'static str {
+        match self {
+            QueryIsolation::ReadOnly => "read_only",
+            QueryIsolation::Worktree => "worktree",
+        }
+    }
+
+    fn from_db(value: &str) -> Self {
+        match value {
+            "worktree" => QueryIsolation::Worktree,
+            _ => QueryIsolation::ReadOnly,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export, optional_fields = nullable)]
+pub struct QueryTarget {
+    /// Channel name suffix; defaults to the target's index
+    pub id: Option<String>,
+    pub agent: QueryAgent,
+    pub model: Option<String>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export, optional_fields = nullable)]
+pub struct MultiModelQueryArgs {
+    /// Lets the caller subscribe to the target channels before the query starts
+    pub query_id: Option<String>,
+    pub prompt: String,
+    pub cwd: String,
+    pub targets: Vec<QueryTarget>,
+    pub isolation: Option<QueryIsolation>,
+    pub system_prompt: Option<String>,
+    /// Permission mode of the session the query runs for, applied to Claude
+    /// targets under worktree isolation
+    pub permission_mode: Option<String>,
+}
+
+/// One raw output line (Claude stream-json or Codex runner JSON) emitted on
+/// `multi-model-query:<query_id>:<target_id>`
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct QueryChunk {
+    pub query_id: String,
+    pub target_id: String,
+    pub line: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum QueryResultStatus {
+    Completed,
+    Failed,
+}
+
+impl QueryResultStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            QueryResultStatus::Completed => "completed",
+            QueryResultStatus::Failed => "failed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct QueryResult {
+    pub target_id: String,
+    pub agent: QueryAgent,
+    pub model: Option<String>,
+    pub status: QueryResultStatus,
+    /// Final reply of the agent
+    pub response: Option<String>,
+    pub error: Option<String>,
+    /// Changes made in the throwaway worktree
+    pub diff: Option<String>,
+    #[ts(type = "number")]
+    pub duration_ms: i64,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct QueryRun {
+    pub id: String,
+    pub prompt: String,
+    pub cwd: String,
+    pub isolation: QueryIsolation,
+    pub results: Vec<QueryResult>,
+    #[ts(type = "number")]
+    pub created_at: i64,
+    /// False when the database wasn't available to store the run
+    pub saved: bool,
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+fn db_error(code: &'static str, message: &'static str) -> impl Fn(sqlx::Error) -> MindgridError {
+    move |e| MindgridError::db(code, message).with_technical(e)
+}
+
+/// Query and target ids end up in channel names, worktree paths and branch
+/// names, so they are limited to `[a-z0-9-]`
+pub(crate) fn validate_id(id: &str, code: &'static str) -> MindgridResult<()> {
+    let valid = !id.is_empty()
+        && id.len() <= 40
+        && id.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+    if !valid {
+        return Err(MindgridError::validation(
+            code,
+            format!("Invalid id \"{}\": use lowercase letters, digits and dashes", id),
+        ));
+    }
+    Ok(())
+}
+
+/// Ids of `targets`, defaulting to their position counted from `first`; see
+/// `validate_id`. Ids must be unique.
+pub(crate) fn target_ids(targets: &[QueryTarget], first: usize) -> MindgridResult<Vec<String>> {
+    let mut ids: Vec<String> = Vec::with_capacity(targets.len());
+    for (index, target) in targets.iter().enumerate() {
+        let id = target.id.clone().unwrap_or_else(|| (index + first).to_string());
+        validate_id(&id, "invalid_target_id")?;
+        if ids.contains(&id) {
+            return Err(MindgridError::validation("duplicate_target_id", format!("The id \"{}\" is used twice", id)));
+        }
+        ids.push(id);
+    }
+    Ok(ids)
+}
+
+async fn pool(app: &AppHandle) -> MindgridResult<Pool<Sqlite>> {
+    crate::db::get_pool(app)
+        .await
+        .map_err(|e| MindgridError::db("unavailable", "The query database is not loaded").with_technical(e))
+}
+
+async fn git_output(dir: &Path, args: &[&str]) -> MindgridResult<std::process::Output> {
+    tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| MindgridError::spawn_failed(&format!("git {}", args[0]), e))
+}
+
+/// Detached worktree at HEAD, removed when dropped
+struct ScratchWorktree {
+    repo: PathBuf,
+    path: PathBuf,
+}
+
+impl ScratchWorktree {
+    async fn create(repo: &Path, name: &str) -> MindgridResult<Self> {
+        // The query id comes from the caller; keep the scratch dir inside the temp dir
+        let name: String = name.chars().filter(|c| c.is_ascii_alphanumeric() || *c == '-').collect();
+        let path = std::env::temp_dir().join(format!("mindgrid-query-{}", name));
+        let path_arg = path.to_string_lossy().to_string();
+        let args = ["worktree", "add", "--detach", path_arg.as_str(), "HEAD"];
+        let output = git_output(repo, &args).await?;
+        if !output.status.success() {
+            return Err(MindgridError::git_failed(
+                "worktree_add_failed",
+                "Failed to create a worktree for the query",
+                &args,
+                &output,
+            ));
+        }
+        Ok(Self {
+            repo: repo.to_path_buf(),
+            path,
+        })
+    }
+
+    /// Everything the agent changed, including new files
+    async fn diff(&self) -> Option<String> {
+        let _ = git_output(&self.path, &["add", "-A"]).await;
+        let output = git_output(&self.path, &["diff", "--cached", "HEAD"]).await.ok()?;
+        let diff = String::from_utf8_lossy(&output.stdout).to_string();
+        (output.status.success() && !diff.trim().is_empty()).then_some(diff)
+    }
+}
+
+impl Drop for ScratchWorktree {
+    fn drop(&mut self) {
+        let repo = self.repo.clone();
+        let path = self.path.to_string_lossy().to_string();
+        // Drop can't await; remove the worktree off the async runtime
+        tauri::async_runtime::spawn_blocking(move || {
+            let removed = std::process::Command::new("git")
+                .arg("-C")
+                .arg(&repo)
+                .args(["worktree", "remove", "--force", &path])
+                .output()
+                .map(|output| output.status.success())
+                .unwrap_or(false);
+            if !removed {
+                eprintln!("[MindGrid] Failed to remove query worktree {}", path);
+            }
+        });
+    }
+}
+
+/// Final reply of an agent run
+pub(crate) struct AgentReply {
+    pub text: String,
+    /// Reported by Claude only
+    pub cost_usd: Option<f64>,
+}
+
+/// Settings shared by every target of a query (or candidate of a best-of-N run)
+pub(crate) struct QueryContext {
+    pub app: AppHandle,
+    pub query_id: String,
+    /// Output streams on `<channel_prefix>:<query_id>:<target_id>`
+    pub channel_prefix: &'static str,
+    pub prompt: String,
+    pub system_prompt: Option<String>,
+    pub cwd: PathBuf,
+    pub isolation: QueryIsolation,
+    /// Session permission mode for Claude under worktree isolation
+    pub permission_mode: Option<String>,
+}
+
+pub(crate) struct TargetRun {
+    pub ctx: Arc<QueryContext>,
+    pub target_id: String,
+    pub target: QueryTarget,
+}
+
+impl TargetRun {
+    fn emit(&self, line: String) {
+        let channel = format!("{}:{}:{}", self.ctx.channel_prefix, self.ctx.query_id, self.target_id);
+        let _ = self.ctx.app.emit(
+            &channel,
+            QueryChunk {
+                query_id: self.ctx.query_id.clone(),
+                target_id: self.target_id.clone(),
+                line,
+            },
+        );
+    }
+
+    async fn claude(&self, dir: &Path, tracker: Option<&ProcessTracker>) -> MindgridResult<AgentReply> {
+        let mut cmd = tokio::process::Command::new("claude");
+        cmd.args(["-p", &self.ctx.prompt, "--output-format", "stream-json", "--verbose"]);
+        match self.ctx.isolation {
+            QueryIsolation::ReadOnly => cmd.args(["--allowedTools", READ_ONLY_CLAUDE_TOOLS]),
+            QueryIsolation::Worktree => cmd.args(crate::orchestrator::permission_args(self.ctx.permission_mode.as_deref())),
+        };
+        if let Some(system_prompt) = &self.ctx.system_prompt {
+            cmd.args(["--append-system-prompt", system_prompt]);
+        }
+        if let Some(model) = &self.target.model {
+            cmd.args(["--model", model]);
+        }
+        // Own process group, so cancelling also stops the tools Claude started
+        #[cfg(unix)]
+        cmd.process_group(0);
+        let mut child = cmd
+            .current_dir(dir)
+            .env("NO_COLOR", "1")
+            .env("CI", "true")
+            .env("CLAUDE_CODE_DISABLE_NONESSENTIAL_TRAFFIC", "1")
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| MindgridError::spawn_failed("claude", e))?;
+        if let (Some(tracker), Some(pid)) = (tracker, child.id()) {
+            tracker.track(pid);
+        }
+
+        let stderr = crate::operations::collect_stderr(&mut child);
+        let mut response = None;
+        let mut cost_usd = None;
+        if let Some(stdout) = child.stdout.take() {
+            let mut lines = BufReader::new(stdout).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                if let Ok(event) = serde_json::from_str::<serde_json::Value>(&line) {
+                    if event.get("type").and_then(|t| t.as_str()) == Some("result") {
+                        response = event.get("result").and_then(|r| r.as_str()).map(|r| r.to_string());
+                        cost_usd = event.get("total_cost_usd").and_then(|c| c.as_f64());
+                    }
+                }
+                self.emit(line);
+            }
+        }
+
+        let output = std::process::Output {
+            status: child.wait().await?,
+            stdout: Vec::new(),
+            stderr: stderr.await.unwrap_or_default(),
+        };
+        match response {
+            Some(text) if output.status.success() => Ok(AgentReply { text, cost_usd }),
+            _ => Err(MindgridError::agent("run_failed", "Claude run failed")
+                .with_command("claude -p")
+                .with_output(&output)),
+        }
+    }
+
+    async fn codex(&self, dir: &Path, tracker: Option<&ProcessTracker>) -> MindgridResult<AgentReply> {
+        let sandbox = match self.ctx.isolation {
+            QueryIsolation::ReadOnly => "read-only",
+            QueryIsolation::Worktree => "workspace-write",
+        };
+        let mut messages = Vec::new();
+        crate::codex::run_codex_streamed(
+            self.ctx.prompt.clone(),
+            self.target.model.clone(),
+            Some(dir.to_string_lossy().to_string()),
+            self.ctx.system_prompt.clone(),
+            sandbox,
+            tracker,
+            |line| {
+                if let Some(text) = codex_message(line) {
+                    messages.push(text);
+                }
+                self.emit(line.to_string());
+            },
+        )
+        .await?;
+        Ok(AgentReply {
+            text: messages.join("\n\n"),
+            cost_usd: None,
+        })
+    }
+
+    /// Run the target's agent in `dir`, streaming its output. Its process is
+    /// registered with `tracker` so a cancelled operation can kill it.
+    pub(crate) async fn agent(&self, dir: &Path, tracker: Option<&ProcessTracker>) -> MindgridResult<AgentReply> {
+        match self.target.agent {
+            QueryAgent::Claude => self.claude(dir, tracker).await,
+            QueryAgent::Codex => self.codex(dir, tracker).await,
+        }
+    }
+
+    async fn run(self) -> QueryResult {
+        let started = Instant::now();
+        let scratch = match self.ctx.isolation {
+            QueryIsolation::Worktree => {
+                let name = format!("{}-{}", self.ctx.query_id, self.target_id);
+                Some(ScratchWorktree::create(&self.ctx.cwd, &name).await)
+            }
+            QueryIsolation::ReadOnly => None,
+        }
+        .transpose();
+
+        let (outcome, diff) = match scratch {
+            Err(e) => (Err(e), None),
+            Ok(scratch) => {
+                let dir = scratch.as_ref().map(|s| s.path.as_path()).unwrap_or(&self.ctx.cwd);
+                let outcome = self.agent(dir, None).await.map(|reply| reply.text);
+                let diff = match &scratch {
+                    Some(scratch) => scratch.diff().await,
+                    None => None,
+                };
+                (outcome, diff)
+            }
+        };
+
+        let (status, response, error) = match outcome {
+            Ok(response) => (QueryResultStatus::Completed, Some(response), None),
+            Err(e) => {
+                eprintln!("[MindGrid] Query target {} failed: {}", self.target_id, e.technical_message());
+                (QueryResultStatus::Failed, None, Some(e.user_message()))
+            }
+        };
+        QueryResult {
+            target_id: self.target_id,
+            agent: self.target.agent,
+            model: self.target.model,
+            status,
+            response,
+            error,
+            diff,
+            duration_ms: started.elapsed().as_millis() as i64,
+        }
+    }
+}
+
+/// Agent message text from a Codex runner line (`{"content": "<event json>"}`)
+fn codex_message(line: &str) -> Option<String> {
+    let wrapper: serde_json::Value = serde_json::from_str(line).ok()?;
+    let event: serde_json::Value = serde_json::from_str(wrapper.get("content")?.as_str()?).ok()?;
+    if event.get("type")?.as_str()? != "item.completed" {
+        return None;
+    }
+    let item = event.get("item")?;
+    let kind = item.get("type").or_else(|| item.get("item_type"))?.as_str()?;
+    if kind != "agent_message" {
+        return None;
+    }
+    item.get("text")?.as_str().map(|t| t.to_string())
+}
+
+async fn save_run(pool: &Pool<Sqlite>, run: &QueryRun) -> MindgridResult<()> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(db_error("transaction_failed", "Failed to save the query"))?;
+    sqlx::query("INSERT INTO model_queries (id, prompt, cwd, isolation, created_at) VALUES (?, ?, ?, ?, ?)")
+        .bind(&run.id)
+        .bind(&run.prompt)
+        .bind(&run.cwd)
+        .bind(run.isolation.as_str())
+        .bind(run.created_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error("insert_failed", "Failed to save the query"))?;
+    for (position, result) in run.results.iter().enumerate() {
+        sqlx::query(
+            "INSERT INTO model_query_results \
+             (query_id, target_id, position, agent, model, status, response, error, diff, duration_ms) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&run.id)
+        .bind(&result.target_id)
+        .bind(position as i64)
+        .bind(result.agent.as_str())
+        .bind(&result.model)
+        .bind(result.status.as_str())
+        .bind(&result.response)
+        .bind(&result.error)
+        .bind(&result.diff)
+        .bind(result.duration_ms)
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error("insert_failed", "Failed to save the query results"))?;
+    }
+    tx.commit()
+        .await
+        .map_err(db_error("transaction_failed", "Failed to save the query"))
+}
+
+/// Ask several agent/model pairs the same prompt concurrently. Each target's
+/// output streams on its own `multi-model-query:<query_id>:<target_id>` event,
+/// and the finished run is stored for later comparison.
+#[tauri::command]
+pub async fn multi_model_query(app: AppHandle, args: MultiModelQueryArgs) -> MindgridResult<QueryRun> {
+    if args.prompt.trim().is_empty() {
+        return Err(MindgridError::validation("empty_prompt", "Enter a prompt to send"));
+    }
+    if args.targets.is_empty() {
+        return Err(MindgridError::validation("no_targets", "Pick at least one model"));
+    }
+    let cwd = PathBuf::from(&args.cwd);
+    if !cwd.is_dir() {
+        return Err(MindgridError::validation("directory_missing", "Working directory does not exist")
+            .with_technical(&args.cwd));
+    }
+    let isolation = args.isolation.unwrap_or(QueryIsolation::ReadOnly);
+    let query_id = args.query_id.unwrap_or_else(|| Uuid::new_v4().to_string());
+    validate_id(&query_id, "invalid_query_id")?;
+    let ids = target_ids(&args.targets, 0)?;
+    if isolation == QueryIsolation::Worktree && !crate::git::is_valid_git_repository(&cwd) {
+        return Err(MindgridError::validation("not_a_repository", "Worktree isolation needs a git repository")
+            .with_technical(&args.cwd));
+    }
+
+    let ctx = Arc::new(QueryContext {
+        app: app.clone(),
+        query_id,
+        channel_prefix: "multi-model-query",
+        prompt: args.prompt,
+        system_prompt: args.system_prompt,
+        cwd,
+        isolation,
+        permission_mode: args.permission_mode,
+    });
+    let created_at = now_ms();
+
+    let handles: Vec<_> = args
+        .targets
+        .into_iter()
+        .zip(ids)
+        .map(|(target, target_id)| {
+            let run = TargetRun {
+                ctx: ctx.clone(),
+                target_id,
+                target,
+            };
+            tauri::async_runtime::spawn(run.run())
+        })
+        .collect();
+
+    let mut results = Vec::with_capacity(handles.len());
+    for handle in handles {
+        results.push(handle.await.map_err(|e| {
+            MindgridError::agent("task_failed", "A query target stopped unexpectedly").with_technical(e)
+        })?);
+    }
+
+    let mut run = QueryRun {
+        id: ctx.query_id.clone(),
+        prompt: ctx.prompt.clone(),
+        cwd: args.cwd,
+        isolation,
+        results,
+        created_at,
+        saved: false,
+    };
+    let saved = async { save_run(&pool(&app).await?, &run).await }.await;
+    match saved {
+        Ok(()) => run.saved = true,
+        Err(e) => eprintln!("[MindGrid] Failed to save query {}: {}", run.id, e.technical_message()),
+    }
+    Ok(run)
+}
+
+/// Stored queries, newest first, optionally limited to one working directory
+#[tauri::command]
+pub async fn list_model_queries(app: AppHandle, cwd: Option<String>, limit: Option<u32>) -> MindgridResult<Vec<QueryRun>> {
+    load_runs(&pool(&app).await?, cwd, limit).await
+}
+
+async fn load_runs(pool: &Pool<Sqlite>, cwd: Option<String>, limit: Option<u32>) -> MindgridResult<Vec<QueryRun>> {
+    let queries: Vec<(String, String, String, String, i64)> = sqlx::query_as(
+        "SELECT id, prompt, cwd, isolation, created_at FROM model_queries \
+         WHERE ?1 IS NULL OR cwd = ?1 ORDER BY created_at DESC LIMIT ?2",
+    )
+    .bind(&cwd)
+    .bind(limit.unwrap_or(50) as i64)
+    .fetch_all(pool)
+    .await
+    .map_err(db_error("query_failed", "Failed to load queries"))?;
+
+    type ResultRow = (String, String, Option<String>, String, Option<String>, Option<String>, Option<String>, i64);
+    let mut runs = Vec::with_capacity(queries.len());
+    for (id, prompt, cwd, isolation, created_at) in queries {
+        let rows: Vec<ResultRow> = sqlx::query_as(
+            "SELECT target_id, agent, model, status, response, error, diff, duration_ms \
+             FROM model_query_results WHERE query_id = ? ORDER BY position",
+        )
+        .bind(&id)
+        .fetch_all(pool)
+        .await
+        .map_err(db_error("query_failed", "Failed to load query results"))?;
+
+        let results = rows
+            .into_iter()
+            .map(|(target_id, agent, model, status, response, error, diff, duration_ms)| QueryResult {
+                target_id,
+                agent: if agent == "codex" { QueryAgent::Codex } else { QueryAgent::Claude },
+                model,
+                status: if status == "completed" { QueryResultStatus::Completed } else { QueryResultStatus::Failed },
+                response,
+                error,
+                diff,
+                duration_ms,
+            })
+            .collect();
+        runs.push(QueryRun {
+            id,
+            prompt,
+            cwd,
+            isolation: QueryIsolation::from_db(&isolation),
+            results,
+            created_at,
+            saved: true,
+        });
+    }
+    Ok(runs)
+}
+
+#[tauri::command]
+pub async fn delete_model_query(app: AppHandle, query_id: String) -> MindgridResult<()> {
+    let pool = pool(&app).await?;
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(db_error("transaction_failed", "Failed to delete the query"))?;
+    sqlx::query("DELETE FROM model_query_results WHERE query_id = ?")
+        .bind(&query_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error("delete_failed", "Failed to delete the query"))?;
+    sqlx::query("DELETE FROM model_queries WHERE id = ?")
+        .bind(&query_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error("delete_failed", "Failed to delete the query"))?;
+    tx.commit()
+        .await
+        .map_err(db_error("transaction_failed", "Failed to delete the query"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::fixtures::TempRepo;
+    use std::time::Duration;
+
+    #[test]
+    fn query_ids_default_to_position() {
+        let targets: Vec<QueryTarget> = (0..2)
+            .map(|_| QueryTarget {
+                id: None,
+                agent: QueryAgent::Codex,
+                model: None,
+            })
+            .collect();
+        assert_eq!(target_ids(&targets, 0).unwrap(), ["0", "1"]);
+
+        assert!(validate_id(&Uuid::new_v4().to_string(), "invalid_query_id").is_ok());
+        for id in ["", "../escape", "Query", "a:b", &"a".repeat(41)] {
+            assert_eq!(validate_id(id, "invalid_query_id").unwrap_err().code, "validation.invalid_query_id");
+        }
+    }
+
+    #[test]
+    fn multi_model_query_args() {
+        let args: MultiModelQueryArgs = serde_json::from_value(serde_json::json!({
+            "prompt": "Explain the build",
+            "cwd": "/tmp/demo",
+            "targets": [{ "agent": "claude" }, { "agent": "codex", "model": "gpt-5-codex", "id": "codex-fast" }],
+            "isolation": "worktree",
+        }))
+        .unwrap();
+        assert_eq!(args.targets[1].agent, QueryAgent::Codex);
+        assert!(args.targets[0].id.is_none() && args.query_id.is_none());
+        assert_eq!(args.isolation, Some(QueryIsolation::Worktree));
+        assert_eq!(target_ids(&args.targets, 0).unwrap(), ["0", "codex-fast"]);
+    }
+
+    #[tokio::test]
+    async fn saved_runs_load_back() {
+        let pool = crate::tests::fixtures::memory_pool().await;
+        let run = QueryRun {
+            id: "q1".into(),
+            prompt: "Explain the build".into(),
+            cwd: "/tmp/demo".into(),
+            isolation: QueryIsolation::Worktree,
+            results: vec![QueryResult {
+                target_id: "0".into(),
+                agent: QueryAgent::Codex,
+                model: Some("gpt-5-codex".into()),
+                status: QueryResultStatus::Completed,
+                response: Some("It uses cargo".into()),
+                error: None,
+                diff: Some("+x".into()),
+                duration_ms: 1200,
+            }],
+            created_at: 1,
+            saved: false,
+        };
+        save_run(&pool, &run).await.unwrap();
+
+        let runs = load_runs(&pool, Some("/tmp/demo".into()), None).await.unwrap();
+        assert_eq!(runs.len(), 1);
+        assert!(runs[0].saved);
+        assert_eq!(runs[0].isolation, QueryIsolation::Worktree);
+        assert_eq!(runs[0].results[0].agent, QueryAgent::Codex);
+        assert_eq!(runs[0].results[0].status, QueryResultStatus::Completed);
+        assert_eq!(runs[0].results[0].response.as_deref(), Some("It uses cargo"));
+        assert!(load_runs(&pool, Some("/elsewhere".into()), None).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn scratch_worktree_diffs_and_cleans_up() {
+        let project = TempRepo::with_files(&[("README.md", "hello\n")]);
+        let scratch = ScratchWorktree::create(&project.path, &format!("../{}", Uuid::new_v4()))
+            .await
+            .unwrap();
+        let path = scratch.path.clone();
+        assert_eq!(path.parent(), Some(std::env::temp_dir().as_path()));
+        assert_eq!(scratch.diff().await, None);
+
+        std::fs::write(path.join("notes.txt"), "new\n").unwrap();
+        let diff = scratch.diff().await.unwrap();
+        assert!(diff.contains("+++ b/notes.txt"), "{}", diff);
+        assert_eq!(project.git(&["status", "--porcelain"]), "");
+
+        drop(scratch);
+        for _ in 0..50 {
+            if !path.exists() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,457 @@
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::process::{Output, Stdio};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::io::AsyncReadExt;
+use tokio::sync::watch;
+use tokio::time::Instant;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::error::{MindgridError, MindgridResult};
+
+/// Default timeout per operation kind, in seconds
+const DEFAULT_TIMEOUTS: &[(&str, u64)] = &[
+    ("git_push", 300),
+    ("git_get_pr_info", 60),
+    ("git_create_pr", 120),
+    ("git_merge_pr", 120),
+    ("run_codex", 1800),
+    ("best_of_n_agent", 3600),
+    ("best_of_n_tests", 900),
+    ("usage", 20),
+];
+
+/// A long-running command that can be cancelled
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct OperationInfo {
+    pub id: String,
+    pub kind: String,
+    /// Unix timestamp (milliseconds)
+    #[ts(type = "number")]
+    pub started_at: i64,
+    #[ts(type = "number | null")]
+    pub timeout_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct OperationTimeout {
+    pub kind: String,
+    /// `null` means no timeout
+    #[ts(type = "number | null")]
+    pub timeout_secs: Option<u64>,
+}
+
+struct Entry {
+    info: OperationInfo,
+    cancel: watch::Sender<bool>,
+    /// Process group leaders started for the operation
+    pids: Mutex<Vec<u32>>,
+}
+
+impl Entry {
+    fn kill(&self) {
+        for pid in self.pids.lock().drain(..) {
+            kill_tree(pid);
+        }
+    }
+}
+
+/// How long a process tree gets to exit after SIGTERM before it's killed
+const KILL_GRACE: Duration = Duration::from_secs(3);
+
+#[cfg(unix)]
+fn signal_group(pid: u32, signal: &str) -> std::io::Result<bool> {
+    std::process::Command::new("kill")
+        .args([signal, "--", &format!("-{}", pid)])
+        .stderr(Stdio::null())
+        .status()
+        .map(|status| status.success())
+}
+
+/// Stop a process and everything it started: SIGTERM first so servers can shut
+/// down cleanly, then SIGKILL for whatever is still running after [`KILL_GRACE`].
+/// Processes spawned through `Operation::output` lead their own process group;
+/// PTY children lead their session. Returns right away, the escalation runs in
+/// the background.
+pub(crate) fn kill_tree(pid: u32) {
+    #[cfg(unix)]
+    {
+        match signal_group(pid, "-TERM") {
+            // Nothing left to stop
+            Ok(false) => return,
+            Ok(true) => {}
+            Err(e) => {
+                eprintln!("[MindGrid] Failed to stop process tree {}: {}", pid, e);
+                return;
+            }
+        }
+        std::thread::spawn(move || {
+            let deadline = std::time::Instant::now() + KILL_GRACE;
+            while std::time::Instant::now() < deadline {
+                std::thread::sleep(Duration::from_millis(100));
+                if !matches!(signal_group(pid, "-0"), Ok(true)) {
+                    return;
+                }
+            }
+            if let Err(e) = signal_group(pid, "-KILL") {
+                eprintln!("[MindGrid] Failed to kill process tree {}: {}", pid, e);
+            }
+        });
+    }
+    #[cfg(windows)]
+    {
+        let _ = KILL_GRACE;
+        let result = std::process::Command::new("taskkill")
+            .args(["/T", "/F", "/PID", &pid.to_string()])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+        if let Err(e) = result {
+            eprintln!("[MindGrid] Failed to kill process tree {}: {}", pid, e);
+        }
+    }
+}
+
+/// Read a child's stderr to the end in the background, so a chatty process
+/// can't fill the pipe and stall while its stdout is being streamed
+pub(crate) fn collect_stderr(child: &mut tokio::process::Child) -> tokio::task::JoinHandle<Vec<u8>> {
+    let stderr = child.stderr.take();
+    tokio::spawn(async move {
+        let mut buffer = Vec::new();
+        if let Some(mut stderr) = stderr {
+            let _ = stderr.read_to_end(&mut buffer).await;
+        }
+        buffer
+    })
+}
+
+/// Registry of running operations, so `cancel_operation` can reach their processes
+pub struct OperationRegistry {
+    operations: Mutex<HashMap<String, Arc<Entry>>>,
+    /// Seconds per kind; `None` disables the timeout
+    timeouts: Mutex<HashMap<String, Option<u64>>>,
+}
+
+impl OperationRegistry {
+    pub fn new() -> Self {
+        Self {
+            operations: Mutex::new(HashMap::new()),
+            timeouts: Mutex::new(
+                DEFAULT_TIMEOUTS
+                    .iter()
+                    .map(|(kind, secs)| (kind.to_string(), Some(*secs)))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Register an operation. `id` comes from the caller so it can cancel
+    /// before the command returns; one is generated when missing.
+    pub fn begin(self: &Arc<Self>, id: Option<String>, kind: &str) -> MindgridResult<Operation> {
+        let id = id.unwrap_or_else(|| Uuid::new_v4().to_string());
+        let timeout_secs = self.timeouts.lock().get(kind).copied().flatten();
+        let started_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as i64)
+            .unwrap_or(0);
+        let entry = Arc::new(Entry {
+            info: OperationInfo {
+                id: id.clone(),
+                kind: kind.to_string(),
+                started_at,
+                timeout_secs,
+            },
+            cancel: watch::channel(false).0,
+            pids: Mutex::new(Vec::new()),
+        });
+
+        let mut operations = self.operations.lock();
+        if operations.contains_key(&id) {
+            return Err(MindgridError::validation("operation_exists", "An operation with this id is already running")
+                .with_technical(&id));
+        }
+        operations.insert(id.clone(), entry.clone());
+        Ok(Operation {
+            registry: self.clone(),
+            deadline: timeout_secs.map(|secs| Instant::now() + Duration::from_secs(secs)),
+            entry,
+        })
+    }
+
+    /// Cancel a running operation. Returns false when it already finished.
+    pub fn cancel(&self, id: &str) -> bool {
+        let entry = self.operations.lock().get(id).cloned();
+        match entry {
+            Some(entry) => {
+                entry.cancel.send_replace(true);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Handle to pass to the thread running blocking work, to register its processes
+#[derive(Clone)]
+pub struct ProcessTracker(Arc<Entry>);
+
+impl ProcessTracker {
+    pub fn track(&self, pid: u32) {
+        self.0.pids.lock().push(pid);
+        // Cancelled before the process existed
+        if *self.0.cancel.borrow() {
+            self.0.kill();
+        }
+    }
+}
+
+/// A running operation; unregistered when dropped
+pub struct Operation {
+    registry: Arc<OperationRegistry>,
+    entry: Arc<Entry>,
+    deadline: Option<Instant>,
+}
+
+impl Operation {
+    pub fn tracker(&self) -> ProcessTracker {
+        ProcessTracker(self.entry.clone())
+    }
+
+    /// Await `work`, failing with `operation.cancelled` or `operation.timed_out`
+    /// (after killing the operation's processes) if that happens first
+    pub async fn run<T>(&self, label: &str, work: impl Future<Output = T>) -> MindgridResult<T> {
+        let mut cancel = self.entry.cancel.subscribe();
+        let deadline = async {
+            match self.deadline {
+                Some(deadline) => tokio::time::sleep_until(deadline).await,
+                None => std::future::pending().await,
+            }
+        };
+
+        tokio::select! {
+            result = work => Ok(result),
+            _ = cancel.wait_for(|cancelled| *cancelled) => {
+                self.entry.kill();
+                Err(MindgridError::operation("cancelled", format!("{} was cancelled", label)).with_command(label))
+            }
+            _ = deadline => {
+                self.entry.kill();
+                let secs = self.entry.info.timeout_secs.unwrap_or_default();
+                Err(MindgridError::operation("timed_out", format!("{} timed out after {}s", label, secs)).with_command(label))
+            }
+        }
+    }
+
+    /// Run a command to completion as part of the operation. It gets its own
+    /// process group so cancelling also stops anything it started.
+    pub async fn output(&self, cmd: &mut tokio::process::Command, label: &str) -> MindgridResult<Output> {
+        #[cfg(unix)]
+        cmd.process_group(0);
+        let child = cmd
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| MindgridError::spawn_failed(label, e))?;
+        if let Some(pid) = child.id() {
+            self.tracker().track(pid);
+        }
+        self.run(label, child.wait_with_output())
+            .await?
+            .map_err(|e| MindgridError::io("wait_failed", format!("Failed to wait for {}", label)).with_technical(e))
+    }
+}
+
+impl Drop for Operation {
+    fn drop(&mut self) {
+        let mut operations = self.registry.operations.lock();
+        if operations
+            .get(&self.entry.info.id)
+            .is_some_and(|entry| Arc::ptr_eq(entry, &self.entry))
+        {
+            operations.remove(&self.entry.info.id);
+        }
+    }
+}
+
+/// Cancel a running operation, killing its process tree. Returns false when
+/// the operation already finished.
+#[tauri::command]
+pub async fn cancel_operation(
+    state: tauri::State<'_, Arc<OperationRegistry>>,
+    operation_id: String,
+) -> MindgridResult<bool> {
+    Ok(state.cancel(&operation_id))
+}
+
+#[tauri::command]
+pub async fn list_operations(state: tauri::State<'_, Arc<OperationRegistry>>) -> MindgridResult<Vec<OperationInfo>> {
+    let mut operations: Vec<OperationInfo> = state.operations.lock().values().map(|e| e.info.clone()).collect();
+    operations.sort_by_key(|o| o.started_at);
+    Ok(operations)
+}
+
+#[tauri::command]
+pub async fn get_operation_timeouts(
+    state: tauri::State<'_, Arc<OperationRegistry>>,
+) -> MindgridResult<Vec<OperationTimeout>> {
+    let mut timeouts: Vec<OperationTimeout> = state
+        .timeouts
+        .lock()
+        .iter()
+        .map(|(kind, timeout_secs)| OperationTimeout {
+            kind: kind.clone(),
+            timeout_secs: *timeout_secs,
+        })
+        .collect();
+    timeouts.sort_by(|a, b| a.kind.cmp(&b.kind));
+    Ok(timeouts)
+}
+
+/// Set the timeout for future operations of a kind; `null` disables it
+#[tauri::command]
+pub async fn set_operation_timeout(
+    state: tauri::State<'_, Arc<OperationRegistry>>,
+    kind: String,
+    timeout_secs: Option<u64>,
+) -> MindgridResult<()> {
+    if timeout_secs == Some(0) {
+        return Err(MindgridError::validation("invalid_timeout", "Timeout must be at least one second"));
+    }
+    state.timeouts.lock().insert(kind, timeout_secs);
+    Ok(())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::process::CommandExt;
+
+    fn spawn_group(script: &str) -> std::process::Child {
+        std::process::Command::new("sh")
+            .args(["-c", script])
+            .process_group(0)
+            .spawn()
+            .expect("Failed to spawn sh")
+    }
+
+    /// Time until `child` exits, giving up after `limit`
+    fn wait_exit(child: &mut std::process::Child, limit: Duration) -> Option<Duration> {
+        let started = std::time::Instant::now();
+        while started.elapsed() < limit {
+            if child.try_wait().unwrap().is_some() {
+                return Some(started.elapsed());
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        None
+    }
+
+    #[test]
+    fn kill_tree_terminates_before_killing() {
+        let mut polite = spawn_group("sleep 30");
+        kill_tree(polite.id());
+        let took = wait_exit(&mut polite, Duration::from_secs(2)).expect("SIGTERM didn't stop sleep");
+        assert!(took < KILL_GRACE);
+
+        let mut stubborn = spawn_group("trap '' TERM; sleep 30");
+        // Let the shell install the trap first
+        std::thread::sleep(Duration::from_millis(300));
+        kill_tree(stubborn.id());
+        assert_eq!(wait_exit(&mut stubborn, Duration::from_secs(1)), None);
+        wait_exit(&mut stubborn, KILL_GRACE + Duration::from_secs(3)).expect("SIGKILL didn't follow");
+    }
+
+    /// Whether `pid` is still running; exited children may linger as zombies
+    /// when nothing reaps them
+    fn running(pid: &str) -> bool {
+        let output = std::process::Command::new("ps").args(["-o", "stat=", "-p", pid]).output().unwrap();
+        let stat = String::from_utf8_lossy(&output.stdout);
+        !stat.trim().is_empty() && !stat.trim().starts_with('Z')
+    }
+
+    /// Run a shell that backgrounds a `sleep` and waits for it, returning the
+    /// result and the pid of the sleep
+    async fn run_sleep(op: &Operation) -> (MindgridResult<Output>, String) {
+        let pid_file = std::env::temp_dir().join(format!("mindgrid-sleep-{}", Uuid::new_v4()));
+        let script = format!("sleep 30 & echo $! > '{}'; wait", pid_file.display());
+        let result = op.output(tokio::process::Command::new("sh").args(["-c", &script]), "sleepy").await;
+        let pid = std::fs::read_to_string(&pid_file).unwrap_or_default().trim().to_string();
+        let _ = std::fs::remove_file(&pid_file);
+        (result, pid)
+    }
+
+    async fn wait_stopped(pid: &str) -> bool {
+        for _ in 0..50 {
+            if !running(pid) {
+                return true;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        false
+    }
+
+    #[test]
+    fn operation_registry_ids() {
+        let registry = Arc::new(OperationRegistry::new());
+        let op = registry.begin(Some("git_push:s1".into()), "git_push").unwrap();
+        assert_eq!(op.entry.info.timeout_secs, Some(300));
+        let duplicate = registry.begin(Some("git_push:s1".into()), "git_push").err().unwrap();
+        assert_eq!(duplicate.code, "validation.operation_exists");
+        // Finished operations free their id
+        drop(op);
+        assert!(registry.begin(Some("git_push:s1".into()), "git_push").is_ok());
+
+        let error = MindgridError::operation("timed_out", "git push timed out after 300s");
+        assert_eq!(error.kind, crate::error::ErrorKind::Operation);
+        assert_eq!(error.code, "operation.timed_out");
+    }
+
+    #[tokio::test]
+    async fn finished_commands_return_their_output() {
+        let registry = Arc::new(OperationRegistry::new());
+        let op = registry.begin(None, "echo").unwrap();
+        let output = op.output(tokio::process::Command::new("echo").arg("hi"), "echo").await.unwrap();
+        assert_eq!(output.stdout, b"hi\n");
+    }
+
+    #[tokio::test]
+    async fn cancelling_kills_the_process_tree() {
+        let registry = Arc::new(OperationRegistry::new());
+        let op = registry.begin(Some("op-1".into()), "sleepy").unwrap();
+        let cancel = registry.operations.lock().get("op-1").unwrap().cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            cancel.send_replace(true);
+        });
+
+        let started = Instant::now();
+        let (result, sleep_pid) = run_sleep(&op).await;
+        assert_eq!(result.unwrap_err().code, "operation.cancelled");
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(!sleep_pid.is_empty());
+        assert!(wait_stopped(&sleep_pid).await, "sleep {} survived the cancel", sleep_pid);
+    }
+
+    #[tokio::test]
+    async fn timeouts_kill_the_process_tree() {
+        let registry = Arc::new(OperationRegistry::new());
+        registry.timeouts.lock().insert("sleepy".to_string(), Some(1));
+        let op = registry.begin(None, "sleepy").unwrap();
+
+        let started = Instant::now();
+        let (result, sleep_pid) = run_sleep(&op).await;
+        let err = result.unwrap_err();
+        assert_eq!(err.code, "operation.timed_out");
+        assert_eq!(err.user_message(), "sleepy timed out after 1s");
+        assert!(started.elapsed() >= Duration::from_secs(1) && started.elapsed() < Duration::from_secs(5));
+        assert!(wait_stopped(&sleep_pid).await, "sleep {} survived the timeout", sleep_pid);
+    }
+}
@@ -0,0 +1,807 @@
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Instant;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::sync::watch;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::error::{MindgridError, MindgridResult};
+use crate::foundations::{FoundationsState, FOUNDATION_FILES};
+
+/// Foundations files only the planner may write. PROGRESS.md is shared.
+const PLANNER_FILES: &[&str] = &["PLAN.md", "CONTEXT.md", "DECISIONS.md"];
+
+const DEFAULT_MAX_ROUNDS: u32 = 3;
+
+/// The reviewer ends its reply with one of these lines
+const APPROVED_MARKER: &str = "REVIEW: APPROVED";
+
+const PLANNER_PROMPT: &str = "You are the Planner in a two-agent team. You research the codebase and \
+write plans; a separate Coder agent implements them. You may only create or edit Markdown files: \
+write the plan to PLAN.md, project knowledge to CONTEXT.md and decisions with their rationale to \
+DECISIONS.md. Never change code; changes to other files are reverted.";
+
+const CODER_PROMPT: &str = "You are the Coder in a two-agent team. A Planner agent writes the plan \
+to PLAN.md; you implement it. Read PLAN.md, CONTEXT.md and DECISIONS.md before starting and record \
+what you completed in PROGRESS.md. Do not edit PLAN.md, CONTEXT.md or DECISIONS.md; changes to them \
+are reverted.";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum AgentRole {
+    Planner,
+    Coder,
+}
+
+/// Hand-off protocol: plan → (plan ready) → code → (ready for review) → review,
+/// which either approves or sends the work back to the coder
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum HandoffPhase {
+    Planning,
+    PlanReady,
+    Coding,
+    ReadyForReview,
+    Reviewing,
+    ChangesRequested,
+    Approved,
+    Failed,
+    Stopped,
+}
+
+impl HandoffPhase {
+    fn is_running(self) -> bool {
+        matches!(self, HandoffPhase::Planning | HandoffPhase::Coding | HandoffPhase::Reviewing)
+    }
+
+    fn is_finished(self) -> bool {
+        matches!(self, HandoffPhase::Approved | HandoffPhase::Failed | HandoffPhase::Stopped)
+    }
+}
+
+/// A file change outside the role's permissions, reported in the status
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct PermissionViolation {
+    pub role: AgentRole,
+    pub path: String,
+    /// "added", "modified" or "deleted"
+    pub change: String,
+    pub reverted: bool,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct OrchestrationStatus {
+    pub session_id: String,
+    pub cwd: String,
+    pub phase: HandoffPhase,
+    /// Role whose agent is running
+    pub active_role: Option<AgentRole>,
+    /// Number of reviews that requested changes
+    pub round: u32,
+    pub max_rounds: u32,
+    pub auto_advance: bool,
+    /// Final reply of the last finished turn
+    pub last_output: Option<String>,
+    pub violations: Vec<PermissionViolation>,
+    pub error: Option<String>,
+}
+
+/// Payload of the `orchestration-output` event: one stream-json line from an agent
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct OrchestrationOutput {
+    pub session_id: String,
+    pub role: AgentRole,
+    pub line: String,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export, optional_fields = nullable)]
+pub struct OrchestrationOptions {
+    pub planner_model: Option<String>,
+    pub coder_model: Option<String>,
+    /// Hand off to the next role without waiting for `orchestrator_advance`
+    pub auto_advance: Option<bool>,
+    /// Review rounds before giving up on approval
+    pub max_rounds: Option<u32>,
+}
+
+struct Run {
+    task: String,
+    status: Mutex<OrchestrationStatus>,
+    models: HashMap<AgentRole, String>,
+    /// Permission mode of the session, applied to the coder
+    permission_mode: Option<String>,
+    /// Claude conversation of each role, resumed on its next turn
+    conversations: Mutex<HashMap<AgentRole, String>>,
+    /// Set once by `orchestrator_stop`; a watch so a stop before the agent starts isn't missed
+    stop: watch::Sender<bool>,
+}
+
+pub struct OrchestratorState {
+    runs: Mutex<HashMap<String, Arc<Run>>>,
+}
+
+impl OrchestratorState {
+    pub fn new() -> Self {
+        Self {
+            runs: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn run(&self, session_id: &str) -> MindgridResult<Arc<Run>> {
+        self.runs.lock().get(session_id).cloned().ok_or_else(|| {
+            MindgridError::validation("no_orchestration", "No orchestration is running for this session")
+        })
+    }
+}
+
+/// Whether a role may write a path (relative to the worktree root)
+pub(crate) fn may_write(role: AgentRole, path: &str) -> bool {
+    match role {
+        AgentRole::Planner => Path::new(path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| e.eq_ignore_ascii_case("md")),
+        AgentRole::Coder => !PLANNER_FILES.contains(&path),
+    }
+}
+
+fn emit_status(app: &AppHandle, run: &Run) {
+    let status = run.status.lock().clone();
+    let _ = app.emit("orchestration-updated", status);
+}
+
+async fn git(cwd: &str, args: &[&str], index: Option<&Path>) -> MindgridResult<String> {
+    let mut cmd = tokio::process::Command::new("git");
+    cmd.arg("-C").arg(cwd).args(args);
+    if let Some(index) = index {
+        cmd.env("GIT_INDEX_FILE", index);
+    }
+    let output = cmd
+        .output()
+        .await
+        .map_err(|e| MindgridError::spawn_failed("git", e))?;
+    if !output.status.success() {
+        return Err(MindgridError::git_failed("snapshot_failed", "Failed to snapshot the worktree", args, &output));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim_end().to_string())
+}
+
+/// Record the whole working tree (minus ignored files) as a tree object without
+/// touching the real index
+pub(crate) async fn snapshot(cwd: &str) -> MindgridResult<String> {
+    let index = std::env::temp_dir().join(format!("mindgrid-orchestrator-{}.index", Uuid::new_v4()));
+    // Starting from the real index lets git skip rehashing unchanged files
+    let real_index = git(cwd, &["rev-parse", "--path-format=absolute", "--git-path", "index"], None).await?;
+    let _ = std::fs::copy(&real_index, &index);
+
+    let tree = async {
+        git(cwd, &["add", "-A"], Some(&index)).await?;
+        git(cwd, &["write-tree"], Some(&index)).await
+    }
+    .await;
+    let _ = std::fs::remove_file(&index);
+    tree
+}
+
+/// Changes between two snapshots as (change, path)
+async fn changes(cwd: &str, before: &str, after: &str) -> MindgridResult<Vec<(String, String)>> {
+    let output = git(cwd, &["diff-tree", "-r", "--no-renames", "--name-status", "-z", before, after], None).await?;
+    let mut fields = output.split('\0').filter(|f| !f.is_empty());
+    let mut changes = Vec::new();
+    while let (Some(status), Some(path)) = (fields.next(), fields.next()) {
+        let change = match status {
+            "A" => "added",
+            "D" => "deleted",
+            _ => "modified",
+        };
+        changes.push((change.to_string(), path.to_string()));
+    }
+    Ok(changes)
+}
+
+/// Restore a path to its content and mode in the `before` snapshot, through a
+/// scratch index so the real one is left alone
+async fn revert(cwd: &str, before: &str, change: &str, path: &str) -> MindgridResult<()> {
+    if change == "added" {
+        return std::fs::remove_file(Path::new(cwd).join(path)).map_err(MindgridError::from);
+    }
+
+    let index = std::env::temp_dir().join(format!("mindgrid-orchestrator-{}.index", Uuid::new_v4()));
+    let restored = async {
+        git(cwd, &["read-tree", before], Some(&index)).await?;
+        git(cwd, &["checkout-index", "-f", "--", path], Some(&index)).await
+    }
+    .await;
+    let _ = std::fs::remove_file(&index);
+    restored
+        .map(|_| ())
+        .map_err(|e| MindgridError::git("revert_failed", "Failed to restore file").with_technical(e.technical_message()))
+}
+
+/// Revert the changes a role wasn't allowed to make during its turn. Foundations
+/// files mirrored in from the project since `started` (a save from the
+/// Foundations panel) aren't the agent's doing: they are kept, and an agent
+/// edit on top of them is rolled back to the mirrored content.
+pub(crate) async fn enforce(
+    cwd: &str,
+    role: AgentRole,
+    before: &str,
+    foundations: Option<&FoundationsState>,
+    started: Instant,
+) -> MindgridResult<Vec<PermissionViolation>> {
+    let after = snapshot(cwd).await?;
+    let mut violations = Vec::new();
+    for (change, path) in changes(cwd, before, &after).await? {
+        if may_write(role, &path) {
+            continue;
+        }
+        let mirrored = match foundations {
+            Some(foundations) if FOUNDATION_FILES.contains(&path.as_str()) => {
+                foundations.mirrored_since(Path::new(cwd), &path, started).await
+            }
+            _ => None,
+        };
+        let full_path = Path::new(cwd).join(&path);
+        let restored = match mirrored {
+            Some(content) if std::fs::read_to_string(&full_path).ok() == content => continue,
+            Some(Some(content)) => std::fs::write(&full_path, content).map_err(MindgridError::from),
+            // Mirrored a deletion, then the agent recreated the file
+            Some(None) => std::fs::remove_file(&full_path).map_err(MindgridError::from),
+            None => revert(cwd, before, &change, &path).await,
+        };
+        let reverted = match restored {
+            Ok(()) => true,
+            Err(e) => {
+                eprintln!("[MindGrid] Failed to revert {}: {}", path, e.technical_message());
+                false
+            }
+        };
+        violations.push(PermissionViolation {
+            role,
+            path,
+            change,
+            reverted,
+        });
+    }
+    Ok(violations)
+}
+
+/// Claude CLI flags for a session permission mode, as useClaudePty.ts passes them
+pub(crate) fn permission_args(mode: Option<&str>) -> Vec<String> {
+    let allowed_tools = match mode {
+        Some("bypassPermissions") => return vec!["--dangerously-skip-permissions".into()],
+        Some("acceptEdits") => "Edit,Write,Read,Glob,Grep,MultiEdit,NotebookEdit",
+        Some("plan") => "Read,Glob,Grep,Task,WebFetch,WebSearch",
+        _ => return Vec::new(),
+    };
+    vec!["--allowedTools".into(), allowed_tools.into()]
+}
+
+fn agent_args(
+    role: AgentRole,
+    prompt: &str,
+    model: Option<&String>,
+    resume: Option<&String>,
+    permission_mode: Option<&str>,
+) -> Vec<String> {
+    let mut args: Vec<String> = vec![
+        "-p".into(),
+        prompt.into(),
+        "--output-format".into(),
+        "stream-json".into(),
+        "--verbose".into(),
+        "--append-system-prompt".into(),
+    ];
+    match role {
+        AgentRole::Planner => {
+            args.push(PLANNER_PROMPT.into());
+            // First line of defence; `enforce` reverts anything that slips through
+            args.push("--allowedTools".into());
+            args.push("Read,Glob,Grep,WebFetch,WebSearch,Edit(**/*.md),Write(**/*.md),MultiEdit(**/*.md)".into());
+        }
+        AgentRole::Coder => {
+            args.push(CODER_PROMPT.into());
+            args.extend(permission_args(permission_mode));
+        }
+    }
+    if let Some(model) = model {
+        args.push("--model".into());
+        args.push(model.clone());
+    }
+    if let Some(conversation) = resume {
+        args.push("--resume".into());
+        args.push(conversation.clone());
+    }
+    args
+}
+
+/// Run one agent turn to completion. Returns the agent's final reply.
+async fn run_agent(app: &AppHandle, run: &Run, session_id: &str, cwd: &str, role: AgentRole, prompt: &str) -> MindgridResult<String> {
+    let mut stop = run.stop.subscribe();
+    if *stop.borrow() {
+        return Err(MindgridError::agent("stopped", "Orchestration was stopped"));
+    }
+    let resume = run.conversations.lock().get(&role).cloned();
+    let args = agent_args(role, prompt, run.models.get(&role), resume.as_ref(), run.permission_mode.as_deref());
+
+    let mut child = tokio::process::Command::new("claude")
+        .args(&args)
+        .current_dir(cwd)
+        .env("NO_COLOR", "1")
+        .env("CI", "true")
+        .env("CLAUDE_CODE_DISABLE_NONESSENTIAL_TRAFFIC", "1")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| {
+            MindgridError::agent("spawn_failed", format!("Failed to start the {:?} agent", role)).with_technical(e)
+        })?;
+
+    let stdout = child.stdout.take();
+    let stderr = crate::operations::collect_stderr(&mut child);
+    let turn = async move {
+        let mut result = None;
+        if let Some(stdout) = stdout {
+            let mut lines = BufReader::new(stdout).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                if let Ok(event) = serde_json::from_str::<serde_json::Value>(&line) {
+                    match event.get("type").and_then(|t| t.as_str()) {
+                        Some("system") => {
+                            if let Some(id) = event.get("session_id").and_then(|s| s.as_str()) {
+                                run.conversations.lock().insert(role, id.to_string());
+                            }
+                        }
+                        Some("result") => {
+                            result = event.get("result").and_then(|r| r.as_str()).map(|r| r.to_string());
+                        }
+                        _ => {}
+                    }
+                }
+                let _ = app.emit(
+                    "orchestration-output",
+                    OrchestrationOutput {
+                        session_id: session_id.to_string(),
+                        role,
+                        line,
+                    },
+                );
+            }
+        }
+        let status = child.wait().await;
+        (result, status)
+    };
+
+    // Stopping drops the turn, which kills the agent
+    let (result, status) = tokio::select! {
+        finished = turn => finished,
+        _ = stop.wait_for(|stopped| *stopped) => {
+            return Err(MindgridError::agent("stopped", "Orchestration was stopped"));
+        }
+    };
+
+    let output = std::process::Output {
+        status: status.map_err(MindgridError::from)?,
+        stdout: Vec::new(),
+        stderr: stderr.await.unwrap_or_default(),
+    };
+    match result {
+        Some(result) if output.status.success() => Ok(result),
+        _ => Err(MindgridError::agent("run_failed", format!("The {:?} agent failed", role))
+            .with_command("claude -p")
+            .with_output(&output)),
+    }
+}
+
+fn turn_prompt(phase: HandoffPhase, task: &str, last_output: Option<&str>, guidance: Option<&str>) -> String {
+    let mut prompt = match phase {
+        HandoffPhase::Planning => format!(
+            "Task: {}\n\nWrite the implementation plan to PLAN.md and any decisions to DECISIONS.md. \
+             Finish with a short summary of the plan.",
+            task
+        ),
+        HandoffPhase::Coding => match last_output {
+            Some(review) => format!(
+                "The Planner reviewed your changes and requested changes:\n\n{}\n\nAddress them and update PROGRESS.md.",
+                review
+            ),
+            None => "Implement the plan in PLAN.md and update PROGRESS.md as you complete steps.".to_string(),
+        },
+        _ => format!(
+            "The Coder has finished. Review the uncommitted changes (git diff) against PLAN.md and update \
+             PROGRESS.md. End your reply with a line `{}` if the work is complete, or `REVIEW: CHANGES REQUESTED` \
+             followed by what must change.",
+            APPROVED_MARKER
+        ),
+    };
+    if let Some(guidance) = guidance.filter(|g| !g.trim().is_empty()) {
+        prompt.push_str("\n\nGuidance from the user:\n");
+        prompt.push_str(guidance);
+    }
+    prompt
+}
+
+/// Run the turn for a running phase, then hand off to the next phase
+fn start_turn(app: AppHandle, run: Arc<Run>, mut guidance: Option<String>) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let (session_id, cwd, phase, previous, round, max_rounds, auto_advance) = {
+                let status = run.status.lock();
+                (
+                    status.session_id.clone(),
+                    status.cwd.clone(),
+                    status.phase,
+                    status.last_output.clone(),
+                    status.round,
+                    status.max_rounds,
+                    status.auto_advance,
+                )
+            };
+            let role = if phase == HandoffPhase::Coding { AgentRole::Coder } else { AgentRole::Planner };
+            // Review feedback only goes to the coder after changes were requested
+            let feedback = previous.filter(|_| phase == HandoffPhase::Coding && round > 0);
+            // User guidance applies to the turn it was given for
+            let guidance = guidance.take();
+            let prompt = turn_prompt(phase, &run.task, feedback.as_deref(), guidance.as_deref());
+
+            run.status.lock().active_role = Some(role);
+            emit_status(&app, &run);
+
+            let turn = async {
+                let started = Instant::now();
+                let before = snapshot(&cwd).await?;
+                let reply = run_agent(&app, &run, &session_id, &cwd, role, &prompt).await;
+                // Enforce permissions even when the turn failed part way
+                let foundations = app.try_state::<Arc<FoundationsState>>();
+                let violations = enforce(&cwd, role, &before, foundations.as_deref().map(|f| f.as_ref()), started).await?;
+                reply.map(|reply| (reply, violations))
+            };
+            let result = turn.await;
+
+            let next = {
+                let mut status = run.status.lock();
+                status.active_role = None;
+                if status.phase == HandoffPhase::Stopped {
+                    None
+                } else {
+                    match result {
+                        Ok((reply, violations)) => {
+                            status.violations.extend(violations);
+                            status.phase = match phase {
+                                HandoffPhase::Planning => HandoffPhase::PlanReady,
+                                HandoffPhase::Coding => HandoffPhase::ReadyForReview,
+                                _ if reply.contains(APPROVED_MARKER) => HandoffPhase::Approved,
+                                _ => {
+                                    status.round += 1;
+                                    if status.round >= max_rounds {
+                                        status.error = Some(format!("Not approved after {} review rounds", max_rounds));
+                                        HandoffPhase::Failed
+                                    } else {
+                                        HandoffPhase::ChangesRequested
+                                    }
+                                }
+                            };
+                            status.last_output = Some(reply);
+                        }
+                        Err(e) => {
+                            eprintln!("[MindGrid] Orchestration turn failed: {}", e.technical_message());
+                            status.phase = HandoffPhase::Failed;
+                            status.error = Some(e.user_message());
+                        }
+                    }
+                    if auto_advance {
+                        next_phase(status.phase).inspect(|next| status.phase = *next)
+                    } else {
+                        None
+                    }
+                }
+            };
+            emit_status(&app, &run);
+
+            if next.is_none() {
+                break;
+            }
+        }
+    });
+}
+
+/// Phase that follows a hand-off point
+fn next_phase(phase: HandoffPhase) -> Option<HandoffPhase> {
+    match phase {
+        HandoffPhase::PlanReady | HandoffPhase::ChangesRequested => Some(HandoffPhase::Coding),
+        HandoffPhase::ReadyForReview => Some(HandoffPhase::Reviewing),
+        _ => None,
+    }
+}
+
+/// Start a Planner + Coder orchestration for a session. Both agents run in the
+/// session's worktree and share its Foundations files; the planner goes first.
+#[tauri::command]
+pub async fn orchestrator_start(
+    app: AppHandle,
+    state: tauri::State<'_, Arc<OrchestratorState>>,
+    session_id: String,
+    cwd: String,
+    task: String,
+    options: Option<OrchestrationOptions>,
+) -> MindgridResult<OrchestrationStatus> {
+    if !crate::git::is_valid_git_repository(Path::new(&cwd)) {
+        return Err(MindgridError::validation("not_a_repository", "Orchestration needs a git worktree")
+            .with_technical(&cwd));
+    }
+    if task.trim().is_empty() {
+        return Err(MindgridError::validation("empty_task", "Describe the task for the planner"));
+    }
+
+    let options = options.unwrap_or(OrchestrationOptions {
+        planner_model: None,
+        coder_model: None,
+        auto_advance: None,
+        max_rounds: None,
+    });
+    let permission_mode = crate::app_store::session(&app, &session_id)?.permission_mode;
+    let mut models = HashMap::new();
+    if let Some(model) = options.planner_model {
+        models.insert(AgentRole::Planner, model);
+    }
+    if let Some(model) = options.coder_model {
+        models.insert(AgentRole::Coder, model);
+    }
+
+    let run = Arc::new(Run {
+        task,
+        status: Mutex::new(OrchestrationStatus {
+            session_id: session_id.clone(),
+            cwd,
+            phase: HandoffPhase::Planning,
+            active_role: None,
+            round: 0,
+            max_rounds: options.max_rounds.unwrap_or(DEFAULT_MAX_ROUNDS).max(1),
+            auto_advance: options.auto_advance.unwrap_or(false),
+            last_output: None,
+            violations: Vec::new(),
+            error: None,
+        }),
+        models,
+        permission_mode,
+        conversations: Mutex::new(HashMap::new()),
+        stop: watch::channel(false).0,
+    });
+
+    {
+        let mut runs = state.runs.lock();
+        if let Some(existing) = runs.get(&session_id) {
+            if !existing.status.lock().phase.is_finished() {
+                return Err(MindgridError::validation(
+                    "orchestration_running",
+                    "An orchestration is already running for this session",
+                ));
+            }
+        }
+        runs.insert(session_id, run.clone());
+    }
+
+    let status = run.status.lock().clone();
+    start_turn(app, run, None);
+    Ok(status)
+}
+
+/// Hand off to the next role (plan ready → code, ready for review → review,
+/// changes requested → code), optionally with guidance for that turn
+#[tauri::command]
+pub async fn orchestrator_advance(
+    app: AppHandle,
+    state: tauri::State<'_, Arc<OrchestratorState>>,
+    session_id: String,
+    guidance: Option<String>,
+) -> MindgridResult<OrchestrationStatus> {
+    let run = state.run(&session_id)?;
+    let status = {
+        let mut status = run.status.lock();
+        if status.phase.is_running() {
+            return Err(MindgridError::validation("agent_running", "Wait for the current agent to finish"));
+        }
+        let next = next_phase(status.phase).ok_or_else(|| {
+            MindgridError::validation("nothing_to_advance", format!("Nothing to hand off in phase {:?}", status.phase))
+        })?;
+        status.phase = next;
+        status.clone()
+    };
+    start_turn(app, run, guidance);
+    Ok(status)
+}
+
+#[tauri::command]
+pub async fn orchestrator_status(
+    state: tauri::State<'_, Arc<OrchestratorState>>,
+    session_id: String,
+) -> MindgridResult<Option<OrchestrationStatus>> {
+    Ok(state
+        .runs
+        .lock()
+        .get(&session_id)
+        .map(|run| run.status.lock().clone()))
+}
+
+/// Stop the running agent and end the orchestration
+#[tauri::command]
+pub async fn orchestrator_stop(
+    app: AppHandle,
+    state: tauri::State<'_, Arc<OrchestratorState>>,
+    session_id: String,
+) -> MindgridResult<()> {
+    let run = state.run(&session_id)?;
+    run.status.lock().phase = HandoffPhase::Stopped;
+    run.stop.send_replace(true);
+    emit_status(&app, &run);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::fixtures::TempRepo;
+
+    fn read(path: &Path) -> Option<String> {
+        std::fs::read_to_string(path).ok()
+    }
+
+    fn violations(found: &[PermissionViolation]) -> Vec<(&str, &str, bool)> {
+        let mut list: Vec<_> = found.iter().map(|v| (v.path.as_str(), v.change.as_str(), v.reverted)).collect();
+        list.sort();
+        list
+    }
+
+    #[test]
+    fn orchestrator_role_permissions() {
+        assert!(may_write(AgentRole::Planner, "PLAN.md"));
+        assert!(may_write(AgentRole::Planner, "docs/notes.MD"));
+        assert!(!may_write(AgentRole::Planner, "src/main.rs"));
+        assert!(may_write(AgentRole::Coder, "src/main.rs"));
+        assert!(may_write(AgentRole::Coder, "PROGRESS.md"));
+        assert!(!may_write(AgentRole::Coder, "DECISIONS.md"));
+        assert_eq!(serde_json::to_value(HandoffPhase::ReadyForReview).unwrap(), serde_json::json!("ready_for_review"));
+
+        assert_eq!(next_phase(HandoffPhase::PlanReady), Some(HandoffPhase::Coding));
+        assert_eq!(next_phase(HandoffPhase::ChangesRequested), Some(HandoffPhase::Coding));
+        assert_eq!(next_phase(HandoffPhase::ReadyForReview), Some(HandoffPhase::Reviewing));
+        assert_eq!(next_phase(HandoffPhase::Approved), None);
+
+        let coder = agent_args(AgentRole::Coder, "go", None, None, Some("bypassPermissions"));
+        assert!(coder.contains(&"--dangerously-skip-permissions".to_string()));
+        for mode in [None, Some("default"), Some("acceptEdits"), Some("plan")] {
+            let coder = agent_args(AgentRole::Coder, "go", None, None, mode);
+            assert!(!coder.contains(&"--dangerously-skip-permissions".to_string()), "{:?}", mode);
+        }
+        let planner = agent_args(AgentRole::Planner, "go", None, None, Some("bypassPermissions"));
+        assert!(!planner.contains(&"--dangerously-skip-permissions".to_string()));
+        assert_eq!(permission_args(Some("plan")), vec!["--allowedTools", "Read,Glob,Grep,Task,WebFetch,WebSearch"]);
+    }
+
+    #[tokio::test]
+    async fn planner_code_edits_are_reverted() {
+        let repo = TempRepo::with_files(&[("src/main.rs", "fn main() {}\n"), ("README.md", "demo\n"), ("Makefile", "all:\n")]);
+        let cwd = repo.path_str();
+        let before = snapshot(&cwd).await.unwrap();
+
+        repo.write("src/main.rs", "fn main() { todo!() }\n");
+        repo.write("src/new.rs", "pub fn new() {}\n");
+        std::fs::remove_file(repo.path.join("Makefile")).unwrap();
+        repo.write("PLAN.md", "# Plan\n");
+        repo.write("README.md", "demo, planned\n");
+
+        let found = enforce(&cwd, AgentRole::Planner, &before, None, Instant::now()).await.unwrap();
+        assert_eq!(
+            violations(&found),
+            vec![("Makefile", "deleted", true), ("src/main.rs", "modified", true), ("src/new.rs", "added", true)]
+        );
+        assert_eq!(repo.read("src/main.rs"), "fn main() {}\n");
+        assert_eq!(repo.read("Makefile"), "all:\n");
+        assert!(!repo.path.join("src/new.rs").exists());
+        // Markdown edits are the planner's to make
+        assert_eq!(repo.read("PLAN.md"), "# Plan\n");
+        assert_eq!(repo.read("README.md"), "demo, planned\n");
+        // Snapshots don't touch the real index
+        assert_eq!(repo.git(&["status", "--porcelain"]), " M README.md\n?? PLAN.md\n");
+    }
+
+    #[tokio::test]
+    async fn coder_plan_edits_are_reverted() {
+        let repo = TempRepo::with_files(&[("PLAN.md", "# Plan\n"), ("PROGRESS.md", "")]);
+        let cwd = repo.path_str();
+        let before = snapshot(&cwd).await.unwrap();
+
+        repo.write("PLAN.md", "# Plan\n\nDone.\n");
+        repo.write("DECISIONS.md", "Use tabs\n");
+        repo.write("PROGRESS.md", "- [x] Step 1\n");
+        repo.write("src/lib.rs", "pub fn run() {}\n");
+
+        let found = enforce(&cwd, AgentRole::Coder, &before, None, Instant::now()).await.unwrap();
+        assert_eq!(violations(&found), vec![("DECISIONS.md", "added", true), ("PLAN.md", "modified", true)]);
+        assert_eq!(repo.read("PLAN.md"), "# Plan\n");
+        assert!(!repo.path.join("DECISIONS.md").exists());
+        assert_eq!(repo.read("PROGRESS.md"), "- [x] Step 1\n");
+        assert_eq!(repo.read("src/lib.rs"), "pub fn run() {}\n");
+
+        // Nothing changed since the last check
+        let before = snapshot(&cwd).await.unwrap();
+        assert!(enforce(&cwd, AgentRole::Coder, &before, None, Instant::now()).await.unwrap().is_empty());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn reverts_restore_the_file_mode() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let repo = TempRepo::with_files(&[("build.sh", "#!/bin/sh\nmake\n")]);
+        repo.git(&["update-index", "--chmod=+x", "build.sh"]);
+        repo.git(&["commit", "-q", "-m", "executable"]);
+        repo.git(&["reset", "-q", "--hard"]);
+        let cwd = repo.path_str();
+        let before = snapshot(&cwd).await.unwrap();
+
+        std::fs::remove_file(repo.path.join("build.sh")).unwrap();
+        let found = enforce(&cwd, AgentRole::Planner, &before, None, Instant::now()).await.unwrap();
+        assert_eq!(violations(&found), vec![("build.sh", "deleted", true)]);
+        assert_eq!(repo.read("build.sh"), "#!/bin/sh\nmake\n");
+        let mode = std::fs::metadata(repo.path.join("build.sh")).unwrap().permissions().mode();
+        assert_eq!(mode & 0o111, 0o111);
+        assert_eq!(repo.git(&["status", "--porcelain"]), "");
+    }
+
+    #[tokio::test]
+    async fn panel_save_during_coder_turn_is_kept() {
+        let project = TempRepo::with_files(&[("PLAN.md", "# Plan\n\n1. First step\n")]);
+        let worktree = project.path.join(".mindgrid/worktrees/coder");
+        project.git(&["worktree", "add", "-q", "-b", "mindgrid/coder", &worktree.to_string_lossy()]);
+        let cwd = worktree.to_string_lossy().to_string();
+        let state = FoundationsState::new();
+        let sync = state.project(&project.path_str());
+        sync.tick().await;
+
+        let started = Instant::now();
+        let before = snapshot(&cwd).await.unwrap();
+        let saved = "# Plan\n\n1. First step\n2. Second step\n";
+        sync.write("PLAN.md", saved.to_string(), None).await.unwrap();
+        assert_eq!(read(&worktree.join("PLAN.md")), Some(saved.to_string()));
+
+        let violations = enforce(&cwd, AgentRole::Coder, &before, Some(&state), started).await.unwrap();
+        assert!(violations.is_empty());
+        sync.tick().await;
+        assert_eq!(project.read("PLAN.md"), saved);
+        assert_eq!(read(&worktree.join("PLAN.md")), Some(saved.to_string()));
+
+        // The coder's own edit is still rolled back, to the saved content
+        let before = snapshot(&cwd).await.unwrap();
+        std::fs::write(worktree.join("PLAN.md"), "# Plan\n\nDone.\n").unwrap();
+        let violations = enforce(&cwd, AgentRole::Coder, &before, Some(&state), started).await.unwrap();
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].reverted);
+        sync.tick().await;
+        assert_eq!(project.read("PLAN.md"), saved);
+        assert_eq!(read(&worktree.join("PLAN.md")), Some(saved.to_string()));
+
+        // A deletion saved meanwhile is kept too
+        let before = snapshot(&cwd).await.unwrap();
+        std::fs::remove_file(project.path.join("PLAN.md")).unwrap();
+        sync.tick().await;
+        assert!(!worktree.join("PLAN.md").exists());
+        let violations = enforce(&cwd, AgentRole::Coder, &before, Some(&state), started).await.unwrap();
+        assert!(violations.is_empty());
+        assert!(!worktree.join("PLAN.md").exists());
+    }
+
+}
@@ -0,0 +1,878 @@
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::Notify;
+use ts_rs::TS;
+
+use crate::app_store;
+use crate::error::MindgridResult;
+
+/// Default time between two polls of the same PR
+const DEFAULT_POLL_INTERVAL_SECS: u64 = 60;
+/// Upper bound for the per-PR backoff after errors
+const MAX_BACKOFF_SECS: u64 = 30 * 60;
+/// How long to pause everything when the forge rate-limits us without saying for how long
+const DEFAULT_RATE_LIMIT_PAUSE_SECS: u64 = 15 * 60;
+/// How long a token from `gh auth token` is reused before asking gh again
+const GH_TOKEN_TTL_SECS: u64 = 60;
+/// Upper bound for `gh auth token`, which may hit the keyring
+const GH_TOKEN_TIMEOUT_SECS: u64 = 10;
+
+/// Aggregated CI results for the PR head commit
+#[derive(Debug, Clone, Serialize, PartialEq, TS)]
+#[ts(export)]
+pub struct CheckSummary {
+    pub total: u32,
+    pub passed: u32,
+    pub failed: u32,
+    pub pending: u32,
+    pub skipped: u32,
+    /// "success", "failure", "pending" or "none"
+    pub state: String,
+    /// Names of the checks that failed
+    pub failing: Vec<String>,
+}
+
+/// Snapshot of a pull request as reported by the forge
+#[derive(Debug, Clone, Serialize, PartialEq, TS)]
+#[ts(export)]
+pub struct PrStatus {
+    pub url: String,
+    #[ts(type = "number")]
+    pub number: i64,
+    pub title: String,
+    /// "open", "closed" or "merged"
+    pub state: String,
+    pub is_draft: bool,
+    /// "approved", "changes_requested", "review_required", "commented" or "none"
+    pub review_decision: String,
+    pub mergeable: Option<bool>,
+    pub mergeable_state: Option<String>,
+    pub head_sha: String,
+    pub checks: CheckSummary,
+}
+
+/// Event sent to the frontend when a session's PR status changes
+#[derive(Clone, Serialize, TS)]
+#[ts(export)]
+pub struct PrStatusChanged {
+    pub session_id: String,
+    pub status: PrStatus,
+    pub previous: Option<PrStatus>,
+    /// New value of `sessions.status` if the poller updated it
+    pub session_status: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum ForgeError {
+    /// The forge asked us to slow down, optionally saying for how long
+    RateLimited(Option<Duration>),
+    NotFound(String),
+    Other(String),
+}
+
+impl std::fmt::Display for ForgeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ForgeError::RateLimited(Some(d)) => write!(f, "Rate limited for {}s", d.as_secs()),
+            ForgeError::RateLimited(None) => write!(f, "Rate limited"),
+            ForgeError::NotFound(what) => write!(f, "Not found: {}", what),
+            ForgeError::Other(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+/// Access to a code forge (GitHub, GitHub Enterprise, or a local stub server)
+#[async_trait]
+pub trait Forge: Send + Sync {
+    async fn pr_status(&self, pr_url: &str) -> Result<PrStatus, ForgeError>;
+}
+
+/// Owner/repo/number parsed from a PR URL like `https://github.com/owner/repo/pull/42`
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrRef {
+    pub host: String,
+    pub owner: String,
+    pub repo: String,
+    pub number: i64,
+}
+
+pub fn parse_pr_url(url: &str) -> Option<PrRef> {
+    let without_scheme = url
+        .trim()
+        .trim_start_matches("https://")
+        .trim_start_matches("http://");
+    let parts: Vec<&str> = without_scheme
+        .split(['/', '?', '#'])
+        .filter(|p| !p.is_empty())
+        .collect();
+
+    if parts.len() < 5 || parts[3] != "pull" {
+        return None;
+    }
+
+    Some(PrRef {
+        host: parts[0].to_string(),
+        owner: parts[1].to_string(),
+        repo: parts[2].to_string(),
+        number: parts[4].parse().ok()?,
+    })
+}
+
+/// GitHub REST API client with conditional requests (ETags) so unchanged PRs
+/// don't count against the rate limit
+pub struct GitHubForge {
+    client: reqwest::Client,
+    /// Overrides the API base URL (e.g. `http://127.0.0.1:8787` for a stub server)
+    api_base: Option<String>,
+    /// Fixed token; without one it is looked up again for each poll
+    token: Option<String>,
+    /// Last answer of `gh auth token` and when it was asked
+    gh_token: tokio::sync::Mutex<Option<(Option<String>, Instant)>>,
+    etag_cache: Mutex<HashMap<String, (String, Value)>>,
+}
+
+impl GitHubForge {
+    pub fn new(api_base: Option<String>, token: Option<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            api_base: api_base.map(|b| b.trim_end_matches('/').to_string()),
+            token,
+            gh_token: tokio::sync::Mutex::new(None),
+            etag_cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Build a forge from the environment: `MINDGRID_FORGE_API_URL` overrides the
+    /// API endpoint. Auth is resolved when polling (see [`Self::token`]).
+    pub fn from_env() -> Self {
+        let api_base = std::env::var("MINDGRID_FORGE_API_URL").ok().filter(|v| !v.is_empty());
+        Self::new(api_base, None)
+    }
+
+    /// Token for the next requests: the fixed one, else `GH_TOKEN`/`GITHUB_TOKEN`,
+    /// else `gh auth token`, so logging in or out of gh takes effect without a restart
+    async fn token(&self) -> Option<String> {
+        if let Some(token) = &self.token {
+            return Some(token.clone());
+        }
+        let from_env = std::env::var("GH_TOKEN")
+            .or_else(|_| std::env::var("GITHUB_TOKEN"))
+            .ok()
+            .filter(|t| !t.is_empty());
+        if from_env.is_some() {
+            return from_env;
+        }
+
+        let mut cached = self.gh_token.lock().await;
+        if let Some((token, at)) = cached.as_ref() {
+            if at.elapsed() < Duration::from_secs(GH_TOKEN_TTL_SECS) {
+                return token.clone();
+            }
+        }
+        let token = gh_auth_token().await;
+        *cached = Some((token.clone(), Instant::now()));
+        token
+    }
+
+    fn api_base_for(&self, host: &str) -> String {
+        if let Some(base) = &self.api_base {
+            return base.clone();
+        }
+        if host == "github.com" {
+            "https://api.github.com".to_string()
+        } else {
+            // GitHub Enterprise Server
+            format!("https://{}/api/v3", host)
+        }
+    }
+
+    async fn get_json(&self, url: &str, token: Option<&str>) -> Result<Value, ForgeError> {
+        let cached_etag = self.etag_cache.lock().get(url).map(|(etag, _)| etag.clone());
+
+        let mut request = self
+            .client
+            .get(url)
+            .header("Accept", "application/vnd.github+json")
+            .header("User-Agent", "mindgrid")
+            .header("X-GitHub-Api-Version", "2022-11-28");
+        if let Some(token) = token {
+            request = request.bearer_auth(token);
+        }
+        if let Some(etag) = &cached_etag {
+            request = request.header("If-None-Match", etag);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| ForgeError::Other(format!("Request to {} failed: {}", url, e)))?;
+        let status = response.status().as_u16();
+
+        if status == 304 {
+            if let Some((_, value)) = self.etag_cache.lock().get(url) {
+                return Ok(value.clone());
+            }
+        }
+
+        if status == 429 || (status == 403 && is_rate_limited(response.headers())) {
+            return Err(ForgeError::RateLimited(rate_limit_wait(response.headers())));
+        }
+
+        if status == 404 {
+            return Err(ForgeError::NotFound(url.to_string()));
+        }
+
+        if !(200..300).contains(&status) {
+            let body = response.text().await.unwrap_or_default();
+            return Err(ForgeError::Other(format!(
+                "GET {} returned {}: {}",
+                url,
+                status,
+                body.chars().take(300).collect::<String>()
+            )));
+        }
+
+        let etag = response
+            .headers()
+            .get("etag")
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.to_string());
+        let value: Value = response
+            .json()
+            .await
+            .map_err(|e| ForgeError::Other(format!("Invalid JSON from {}: {}", url, e)))?;
+
+        if let Some(etag) = etag {
+            self.etag_cache.lock().insert(url.to_string(), (etag, value.clone()));
+        }
+
+        Ok(value)
+    }
+}
+
+#[async_trait]
+impl Forge for GitHubForge {
+    async fn pr_status(&self, pr_url: &str) -> Result<PrStatus, ForgeError> {
+        let pr = parse_pr_url(pr_url)
+            .ok_or_else(|| ForgeError::Other(format!("Unrecognized PR URL: {}", pr_url)))?;
+        let repo_api = format!("{}/repos/{}/{}", self.api_base_for(&pr.host), pr.owner, pr.repo);
+
+        let token = self.token().await;
+        let token = token.as_deref();
+
+        let pull = self
+            .get_json(&format!("{}/pulls/{}", repo_api, pr.number), token)
+            .await?;
+        let head_sha = pull["head"]["sha"].as_str().unwrap_or("").to_string();
+
+        let reviews_url = format!("{}/pulls/{}/reviews?per_page=100", repo_api, pr.number);
+        let check_runs_url = format!("{}/commits/{}/check-runs?per_page=100", repo_api, head_sha);
+        let status_url = format!("{}/commits/{}/status", repo_api, head_sha);
+        let (reviews, check_runs, combined) = tokio::try_join!(
+            self.get_json(&reviews_url, token),
+            self.get_json(&check_runs_url, token),
+            self.get_json(&status_url, token),
+        )?;
+
+        let state = if pull["merged"].as_bool().unwrap_or(false) || !pull["merged_at"].is_null() {
+            "merged".to_string()
+        } else {
+            pull["state"].as_str().unwrap_or("open").to_lowercase()
+        };
+
+        let has_requested_reviewers = pull["requested_reviewers"]
+            .as_array()
+            .map(|a| !a.is_empty())
+            .unwrap_or(false);
+
+        Ok(PrStatus {
+            url: pr_url.to_string(),
+            number: pr.number,
+            title: pull["title"].as_str().unwrap_or("").to_string(),
+            state,
+            is_draft: pull["draft"].as_bool().unwrap_or(false),
+            review_decision: summarize_reviews(
+                reviews.as_array().map(|a| a.as_slice()).unwrap_or(&[]),
+                has_requested_reviewers,
+            ),
+            mergeable: pull["mergeable"].as_bool(),
+            mergeable_state: pull["mergeable_state"].as_str().map(|s| s.to_string()),
+            head_sha,
+            checks: summarize_checks(&check_runs, &combined),
+        })
+    }
+}
+
+async fn gh_auth_token() -> Option<String> {
+    let gh = tokio::task::spawn_blocking(crate::git::find_gh_path).await.ok()??;
+    let output = tokio::time::timeout(
+        Duration::from_secs(GH_TOKEN_TIMEOUT_SECS),
+        tokio::process::Command::new(gh)
+            .args(["auth", "token"])
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .ok()?
+    .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let token = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if token.is_empty() {
+        None
+    } else {
+        Some(token)
+    }
+}
+
+fn is_rate_limited(headers: &reqwest::header::HeaderMap) -> bool {
+    headers.contains_key("retry-after")
+        || headers
+            .get("x-ratelimit-remaining")
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.trim() == "0")
+            .unwrap_or(false)
+}
+
+fn rate_limit_wait(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    let header_u64 = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse::<u64>().ok())
+    };
+
+    if let Some(secs) = header_u64("retry-after") {
+        return Some(Duration::from_secs(secs));
+    }
+
+    // x-ratelimit-reset is an absolute epoch timestamp
+    let reset = header_u64("x-ratelimit-reset")?;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+    Some(Duration::from_secs(reset.saturating_sub(now).max(1)))
+}
+
+/// Reduce the review list to a single decision. Like GitHub, only the latest
+/// approving/blocking review of each reviewer counts.
+fn summarize_reviews(reviews: &[Value], has_requested_reviewers: bool) -> String {
+    let mut latest_by_user: HashMap<String, String> = HashMap::new();
+    let mut has_comments = false;
+
+    for review in reviews {
+        let user = review["user"]["login"].as_str().unwrap_or("").to_string();
+        let state = review["state"].as_str().unwrap_or("").to_uppercase();
+        match state.as_str() {
+            "APPROVED" | "CHANGES_REQUESTED" | "DISMISSED" => {
+                latest_by_user.insert(user, state);
+            }
+            "COMMENTED" => has_comments = true,
+            _ => {}
+        }
+    }
+
+    if latest_by_user.values().any(|s| s == "CHANGES_REQUESTED") {
+        "changes_requested".to_string()
+    } else if latest_by_user.values().any(|s| s == "APPROVED") && !has_requested_reviewers {
+        "approved".to_string()
+    } else if has_requested_reviewers {
+        "review_required".to_string()
+    } else if has_comments {
+        "commented".to_string()
+    } else {
+        "none".to_string()
+    }
+}
+
+/// Merge check runs (GitHub Actions & apps) and legacy commit statuses into one summary
+fn summarize_checks(check_runs: &Value, combined_status: &Value) -> CheckSummary {
+    let mut summary = CheckSummary {
+        total: 0,
+        passed: 0,
+        failed: 0,
+        pending: 0,
+        skipped: 0,
+        state: "none".to_string(),
+        failing: Vec::new(),
+    };
+
+    if let Some(runs) = check_runs["check_runs"].as_array() {
+        for run in runs {
+            summary.total += 1;
+            let name = run["name"].as_str().unwrap_or("check").to_string();
+            if run["status"].as_str() != Some("completed") {
+                summary.pending += 1;
+                continue;
+            }
+            match run["conclusion"].as_str().unwrap_or("") {
+                "success" | "neutral" => summary.passed += 1,
+                "skipped" => summary.skipped += 1,
+                _ => {
+                    summary.failed += 1;
+                    summary.failing.push(name);
+                }
+            }
+        }
+    }
+
+    if let Some(statuses) = combined_status["statuses"].as_array() {
+        for status in statuses {
+            summary.total += 1;
+            let name = status["context"].as_str().unwrap_or("status").to_string();
+            match status["state"].as_str().unwrap_or("") {
+                "success" => summary.passed += 1,
+                "pending" => summary.pending += 1,
+                _ => {
+                    summary.failed += 1;
+                    summary.failing.push(name);
+                }
+            }
+        }
+    }
+
+    summary.state = if summary.failed > 0 {
+        "failure"
+    } else if summary.pending > 0 {
+        "pending"
+    } else if summary.total == 0 {
+        "none"
+    } else {
+        "success"
+    }
+    .to_string();
+
+    summary
+}
+
+/// Poll bookkeeping for one session's PR
+struct TrackedPr {
+    pr_url: String,
+    last: Option<PrStatus>,
+    next_poll: Instant,
+    failures: u32,
+    unchanged_polls: u32,
+}
+
+/// Global state for the background PR status poller
+pub struct PrPollerState {
+    forge: Arc<dyn Forge>,
+    interval: Duration,
+    tracked: Mutex<HashMap<String, TrackedPr>>,
+    paused_until: Mutex<Option<Instant>>,
+    wake: Notify,
+}
+
+impl PrPollerState {
+    pub fn new(forge: Arc<dyn Forge>) -> Self {
+        let interval_secs = std::env::var("MINDGRID_PR_POLL_INTERVAL_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(DEFAULT_POLL_INTERVAL_SECS);
+
+        Self {
+            forge,
+            interval: Duration::from_secs(interval_secs),
+            tracked: Mutex::new(HashMap::new()),
+            paused_until: Mutex::new(None),
+            wake: Notify::new(),
+        }
+    }
+
+    /// Delay before polling again after a successful fetch. PRs that stay
+    /// unchanged are polled progressively less often (up to 8x the interval).
+    fn idle_delay(&self, unchanged_polls: u32) -> Duration {
+        self.interval * (1u32 << (unchanged_polls / 5).min(3))
+    }
+
+    fn error_delay(&self, failures: u32) -> Duration {
+        let backoff = self.interval * (1u32 << failures.min(6));
+        backoff.min(Duration::from_secs(MAX_BACKOFF_SECS))
+    }
+}
+
+/// Start the background poller loop
+pub fn start_poller(app: AppHandle, state: Arc<PrPollerState>) {
+    tauri::async_runtime::spawn(async move {
+        let tick = state.interval.min(Duration::from_secs(15));
+        loop {
+            poll_due_sessions(&app, &state).await;
+            tokio::select! {
+                _ = tokio::time::sleep(tick) => {}
+                _ = state.wake.notified() => {}
+            }
+        }
+    });
+}
+
+async fn poll_due_sessions(app: &AppHandle, state: &PrPollerState) {
+    if let Some(until) = *state.paused_until.lock() {
+        if Instant::now() < until {
+            return;
+        }
+    }
+
+    // Sessions are saved by the frontend in the app store
+    let rows: Vec<(String, String)> = match app_store::sessions(app) {
+        Ok(sessions) => sessions
+            .into_iter()
+            .filter(|s| !s.is_closed())
+            .filter_map(|s| s.pr_url.filter(|url| !url.is_empty()).map(|url| (s.id, url)))
+            .collect(),
+        Err(e) => {
+            eprintln!("[MindGrid] PR poller failed to read sessions: {}", e.technical_message());
+            return;
+        }
+    };
+
+    // Sync the tracked set with the stored sessions
+    let due: Vec<(String, String)> = {
+        let mut tracked = state.tracked.lock();
+        tracked.retain(|id, _| rows.iter().any(|(row_id, _)| row_id == id));
+
+        let now = Instant::now();
+        for (id, url) in &rows {
+            let entry = tracked.entry(id.clone()).or_insert_with(|| TrackedPr {
+                pr_url: url.clone(),
+                last: None,
+                next_poll: now,
+                failures: 0,
+                unchanged_polls: 0,
+            });
+            if entry.pr_url != *url {
+                entry.pr_url = url.clone();
+                entry.last = None;
+                entry.next_poll = now;
+            }
+        }
+
+        tracked
+            .iter()
+            .filter(|(_, t)| t.next_poll <= now)
+            .map(|(id, t)| (id.clone(), t.pr_url.clone()))
+            .collect()
+    };
+
+    for (session_id, pr_url) in due {
+        match state.forge.pr_status(&pr_url).await {
+            Ok(status) => {
+                let previous = {
+                    let mut tracked = state.tracked.lock();
+                    let Some(entry) = tracked.get_mut(&session_id) else {
+                        continue;
+                    };
+                    let changed = entry.last.as_ref() != Some(&status);
+                    let previous = entry.last.replace(status.clone());
+                    entry.failures = 0;
+                    entry.unchanged_polls = if changed { 0 } else { entry.unchanged_polls + 1 };
+                    entry.next_poll = Instant::now() + state.idle_delay(entry.unchanged_polls);
+                    if !changed {
+                        continue;
+                    }
+                    previous
+                };
+
+                if status.state == "merged" {
+                    record_merge(app, &session_id).await;
+                }
+                let session_status = if status.state == "merged" || status.state == "closed" {
+                    close_session(app, &session_id)
+                } else {
+                    None
+                };
+
+                let _ = app.emit(
+                    "pr-status-changed",
+                    PrStatusChanged {
+                        session_id,
+                        status,
+                        previous,
+                        session_status,
+                    },
+                );
+            }
+            Err(ForgeError::RateLimited(wait)) => {
+                let wait = wait.unwrap_or(Duration::from_secs(DEFAULT_RATE_LIMIT_PAUSE_SECS));
+                eprintln!(
+                    "[MindGrid] PR poller rate limited, pausing for {}s",
+                    wait.as_secs()
+                );
+                *state.paused_until.lock() = Some(Instant::now() + wait);
+                return;
+            }
+            Err(e) => {
+                eprintln!("[MindGrid] Failed to fetch PR status for {}: {}", pr_url, e);
+                let mut tracked = state.tracked.lock();
+                if let Some(entry) = tracked.get_mut(&session_id) {
+                    entry.failures += 1;
+                    entry.next_poll = Instant::now() + state.error_delay(entry.failures);
+                }
+            }
+        }
+    }
+}
+
+/// Remember that a session's branch was merged on the forge, so sessions
+/// stacked on it move onto main when they are restacked
+async fn record_merge(app: &AppHandle, session_id: &str) {
+    let Ok(session) = app_store::session(app, session_id) else {
+        return;
+    };
+    if let Err(e) = crate::stack::mark_worktree_merged(&session.cwd).await {
+        eprintln!("[MindGrid] Failed to record merge of session {}: {}", session_id, e.technical_message());
+    }
+}
+
+/// Mark a session whose PR was merged or closed as closed (archived).
+/// Returns the new status if the session was updated.
+fn close_session(app: &AppHandle, session_id: &str) -> Option<String> {
+    match app_store::session(app, session_id) {
+        Ok(session) if session.is_closed() => return None,
+        Ok(_) => {}
+        Err(_) => return None,
+    }
+
+    let now_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0);
+    match app_store::update_session(app, session_id, serde_json::json!({ "status": "closed", "updatedAt": now_ms })) {
+        Ok(true) => Some("closed".to_string()),
+        Ok(false) => None,
+        Err(e) => {
+            eprintln!("[MindGrid] Failed to update status of session {}: {}", session_id, e.technical_message());
+            None
+        }
+    }
+}
+
+/// Get the last known PR status of every tracked session (keyed by session id)
+#[tauri::command]
+pub async fn get_pr_statuses(
+    state: tauri::State<'_, Arc<PrPollerState>>,
+) -> MindgridResult<HashMap<String, PrStatus>> {
+    let tracked = state.tracked.lock();
+    Ok(tracked
+        .iter()
+        .filter_map(|(id, t)| t.last.clone().map(|s| (id.clone(), s)))
+        .collect())
+}
+
+/// Poll a session's PR (or all PRs) right away instead of waiting for the next tick
+#[tauri::command]
+pub async fn refresh_pr_status(
+    state: tauri::State<'_, Arc<PrPollerState>>,
+    session_id: Option<String>,
+) -> MindgridResult<()> {
+    {
+        let mut tracked = state.tracked.lock();
+        let now = Instant::now();
+        for (id, entry) in tracked.iter_mut() {
+            if session_id.as_ref().map(|s| s == id).unwrap_or(true) {
+                entry.next_poll = now;
+            }
+        }
+    }
+    state.wake.notify_one();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Canned responses by request path: status, extra headers and JSON body
+    type Routes = HashMap<String, (u16, Vec<(&'static str, String)>, Value)>;
+
+    /// Minimal GitHub API stand-in: answers each path with a canned response
+    /// and records the Authorization header of every request
+    struct StubForge {
+        base: String,
+        auth: Arc<Mutex<Vec<String>>>,
+    }
+
+    async fn stub_forge(routes: Routes) -> StubForge {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let auth = Arc::new(Mutex::new(Vec::new()));
+        let routes = Arc::new(routes);
+        let seen = auth.clone();
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut socket, _)) = listener.accept().await else {
+                    return;
+                };
+                let routes = routes.clone();
+                let seen = seen.clone();
+                tokio::spawn(async move {
+                    let mut request = Vec::new();
+                    let mut buf = [0u8; 4096];
+                    while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                        match socket.read(&mut buf).await {
+                            Ok(0) | Err(_) => return,
+                            Ok(n) => request.extend_from_slice(&buf[..n]),
+                        }
+                    }
+                    let request = String::from_utf8_lossy(&request).to_string();
+                    let path = request.split_whitespace().nth(1).unwrap_or("");
+                    let path = path.split('?').next().unwrap_or("").to_string();
+                    let header = |name: &str| {
+                        request.lines().find_map(|line| {
+                            let (key, value) = line.split_once(':')?;
+                            key.eq_ignore_ascii_case(name).then(|| value.trim().to_string())
+                        })
+                    };
+                    seen.lock().push(header("authorization").unwrap_or_default());
+
+                    let (status, headers, body) = match routes.get(&path) {
+                        Some((status, headers, body)) => (*status, headers.clone(), body.to_string()),
+                        None => (404, Vec::new(), "{}".to_string()),
+                    };
+                    let etag = headers.iter().find(|(k, _)| *k == "etag").map(|(_, v)| v.clone());
+                    let (status, body) = if etag.is_some() && header("if-none-match") == etag {
+                        (304, String::new())
+                    } else {
+                        (status, body)
+                    };
+                    let mut response = format!(
+                        "HTTP/1.1 {} Stub\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+                        status,
+                        body.len()
+                    );
+                    for (key, value) in headers {
+                        response.push_str(&format!("{}: {}\r\n", key, value));
+                    }
+                    response.push_str("\r\n");
+                    response.push_str(&body);
+                    let _ = socket.write_all(response.as_bytes()).await;
+                    let _ = socket.shutdown().await;
+                });
+            }
+        });
+        StubForge { base, auth }
+    }
+
+    fn pull(number: i64, state: &str, merged: bool, sha: &str) -> Value {
+        json!({
+            "number": number,
+            "title": format!("PR {}", number),
+            "state": state,
+            "draft": false,
+            "merged": merged,
+            "merged_at": if merged { json!("2026-01-01T00:00:00Z") } else { Value::Null },
+            "requested_reviewers": [],
+            "mergeable": true,
+            "mergeable_state": "clean",
+            "head": { "sha": sha },
+        })
+    }
+
+    fn route(routes: &mut Routes, path: &str, body: Value) {
+        routes.insert(format!("/repos/octo/app{}", path), (200, Vec::new(), body));
+    }
+
+    #[tokio::test]
+    async fn polls_stub_forge() {
+        let mut routes = HashMap::new();
+        routes.insert(
+            "/repos/octo/app/pulls/1".to_string(),
+            (200, vec![("etag", "\"pull-1\"".to_string())], pull(1, "open", false, "aaa")),
+        );
+        route(
+            &mut routes,
+            "/pulls/1/reviews",
+            json!([
+                { "user": { "login": "alice" }, "state": "CHANGES_REQUESTED" },
+                { "user": { "login": "bob" }, "state": "COMMENTED" },
+                { "user": { "login": "alice" }, "state": "APPROVED" },
+            ]),
+        );
+        route(
+            &mut routes,
+            "/commits/aaa/check-runs",
+            json!({ "check_runs": [
+                { "name": "build", "status": "completed", "conclusion": "success" },
+                { "name": "lint", "status": "completed", "conclusion": "failure" },
+                { "name": "e2e", "status": "in_progress", "conclusion": null },
+                { "name": "docs", "status": "completed", "conclusion": "skipped" },
+            ]}),
+        );
+        route(
+            &mut routes,
+            "/commits/aaa/status",
+            json!({ "statuses": [{ "context": "ci/legacy", "state": "success" }] }),
+        );
+
+        route(&mut routes, "/pulls/2", pull(2, "closed", true, "bbb"));
+        route(&mut routes, "/pulls/3", pull(3, "closed", false, "ccc"));
+        for (number, sha) in [(2, "bbb"), (3, "ccc")] {
+            route(&mut routes, &format!("/pulls/{}/reviews", number), json!([]));
+            route(&mut routes, &format!("/commits/{}/check-runs", sha), json!({ "check_runs": [] }));
+            route(&mut routes, &format!("/commits/{}/status", sha), json!({ "statuses": [] }));
+        }
+        routes.insert(
+            "/repos/octo/app/pulls/4".to_string(),
+            (
+                403,
+                vec![("x-ratelimit-remaining", "0".to_string()), ("retry-after", "30".to_string())],
+                json!({ "message": "API rate limit exceeded" }),
+            ),
+        );
+
+        let stub = stub_forge(routes).await;
+        let forge = GitHubForge::new(Some(stub.base.clone()), Some("stub-token".to_string()));
+
+        let status = forge.pr_status("https://github.com/octo/app/pull/1").await.unwrap();
+        assert_eq!(status.number, 1);
+        assert_eq!(status.state, "open");
+        assert_eq!(status.head_sha, "aaa");
+        assert_eq!(status.review_decision, "approved");
+        assert_eq!(
+            status.checks,
+            CheckSummary {
+                total: 5,
+                passed: 2,
+                failed: 1,
+                pending: 1,
+                skipped: 1,
+                state: "failure".to_string(),
+                failing: vec!["lint".to_string()],
+            }
+        );
+        assert!(stub.auth.lock().iter().all(|a| a == "Bearer stub-token"));
+
+        // The second poll is answered with 304 from the ETag cache
+        let again = forge.pr_status("https://github.com/octo/app/pull/1").await.unwrap();
+        assert_eq!(again, status);
+
+        let merged = forge.pr_status("https://github.com/octo/app/pull/2").await.unwrap();
+        assert_eq!(merged.state, "merged");
+        let closed = forge.pr_status("https://github.com/octo/app/pull/3").await.unwrap();
+        assert_eq!(closed.state, "closed");
+
+        match forge.pr_status("https://github.com/octo/app/pull/4").await {
+            Err(ForgeError::RateLimited(Some(wait))) => assert_eq!(wait, Duration::from_secs(30)),
+            other => panic!("expected a rate limit, got {:?}", other.map(|s| s.state)),
+        }
+        assert!(matches!(
+            forge.pr_status("https://github.com/octo/app/pull/5").await,
+            Err(ForgeError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn pending_review_requests_block_approval() {
+        let reviews = [json!({ "user": { "login": "alice" }, "state": "APPROVED" })];
+        assert_eq!(summarize_reviews(&reviews, true), "review_required");
+        assert_eq!(summarize_reviews(&reviews, false), "approved");
+        assert_eq!(summarize_reviews(&[], false), "none");
+    }
+}
@@ -0,0 +1,562 @@
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::watch;
+use ts_rs::TS;
+
+use crate::error::{MindgridError, MindgridResult};
+use crate::pty::PtyState;
+use crate::run_scripts::RunScriptState;
+
+/// Ports for the per-session preview proxies. A session keeps its port while
+/// the app runs, so its preview URL stays the same across restarts of the proxy.
+const PREVIEW_PORTS: Range<u16> = 4900..5000;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// A TCP port a session's process listens on
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+pub struct ListeningPort {
+    pub port: u16,
+    pub pid: u32,
+    /// Process name from /proc/<pid>/comm
+    pub process: String,
+}
+
+/// Payload of the `preview-updated` event
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct PreviewInfo {
+    pub session_id: String,
+    /// Stable URL of the session's preview, e.g. http://127.0.0.1:4900/
+    pub url: String,
+    pub proxy_port: u16,
+    /// Dev server port the proxy forwards to; `None` until one is detected
+    pub target_port: Option<u16>,
+    /// The target was chosen by the user and isn't re-detected
+    pub pinned: bool,
+}
+
+/// Listening sockets in a /proc/net/tcp or /proc/net/tcp6 table, as (inode, port)
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub(crate) fn parse_listening_sockets(table: &str) -> Vec<(u64, u16)> {
+    table
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            // sl local_address rem_address st tx:rx tr:when retrnsmt uid timeout inode
+            if fields.len() < 10 || fields[3] != "0A" {
+                return None;
+            }
+            let port = u16::from_str_radix(fields[1].rsplit(':').next()?, 16).ok()?;
+            let inode = fields[9].parse().ok()?;
+            Some((inode, port))
+        })
+        .collect()
+}
+
+/// Parent pid from the contents of /proc/<pid>/stat. The command name can
+/// contain spaces and parentheses, so fields are read after the last ')'.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub(crate) fn parse_stat_ppid(stat: &str) -> Option<u32> {
+    stat.rsplit_once(')')?.1.split_whitespace().nth(1)?.parse().ok()
+}
+
+/// Pick the dev server among a session's listening ports: a run script's
+/// `$PORT` when it's listening, otherwise the lowest port
+pub(crate) fn pick_target(detected: &[ListeningPort], preferred: &[u16]) -> Option<u16> {
+    preferred
+        .iter()
+        .copied()
+        .find(|port| detected.iter().any(|p| p.port == *port))
+        .or_else(|| detected.iter().map(|p| p.port).min())
+}
+
+/// `roots` and every process below them
+#[cfg(target_os = "linux")]
+fn process_tree(roots: &[u32]) -> Vec<u32> {
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    if let Ok(entries) = std::fs::read_dir("/proc") {
+        for entry in entries.flatten() {
+            let Some(pid) = entry.file_name().to_str().and_then(|name| name.parse::<u32>().ok()) else {
+                continue;
+            };
+            let ppid = std::fs::read_to_string(entry.path().join("stat"))
+                .ok()
+                .and_then(|stat| parse_stat_ppid(&stat));
+            if let Some(ppid) = ppid {
+                children.entry(ppid).or_default().push(pid);
+            }
+        }
+    }
+
+    let mut seen: HashSet<u32> = HashSet::new();
+    let mut stack: Vec<u32> = roots.to_vec();
+    while let Some(pid) = stack.pop() {
+        if seen.insert(pid) {
+            stack.extend(children.get(&pid).into_iter().flatten());
+        }
+    }
+    let mut pids: Vec<u32> = seen.into_iter().collect();
+    pids.sort_unstable();
+    pids
+}
+
+/// TCP ports listened on by `roots` or their descendants, lowest first
+#[cfg(target_os = "linux")]
+pub(crate) fn listening_ports(roots: &[u32]) -> Vec<ListeningPort> {
+    let sockets: HashMap<u64, u16> = ["/proc/net/tcp", "/proc/net/tcp6"]
+        .iter()
+        .filter_map(|path| std::fs::read_to_string(path).ok())
+        .flat_map(|table| parse_listening_sockets(&table))
+        .collect();
+    if sockets.is_empty() {
+        return Vec::new();
+    }
+
+    let mut ports: Vec<ListeningPort> = Vec::new();
+    for pid in process_tree(roots) {
+        let Ok(fds) = std::fs::read_dir(format!("/proc/{}/fd", pid)) else { continue };
+        for fd in fds.flatten() {
+            let Ok(link) = std::fs::read_link(fd.path()) else { continue };
+            let inode = link
+                .to_str()
+                .and_then(|link| link.strip_prefix("socket:["))
+                .and_then(|rest| rest.strip_suffix(']'))
+                .and_then(|inode| inode.parse::<u64>().ok());
+            let Some(port) = inode.and_then(|inode| sockets.get(&inode)) else { continue };
+            // The same port shows up for IPv4 and IPv6, and in forked workers
+            if !ports.iter().any(|p| p.port == *port) {
+                let process = std::fs::read_to_string(format!("/proc/{}/comm", pid)).unwrap_or_default();
+                ports.push(ListeningPort {
+                    port: *port,
+                    pid,
+                    process: process.trim().to_string(),
+                });
+            }
+        }
+    }
+    ports.sort_by_key(|p| p.port);
+    ports
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn listening_ports(_roots: &[u32]) -> Vec<ListeningPort> {
+    Vec::new()
+}
+
+/// Whether a PTY started in `dir` belongs to the session working in `session_cwd`.
+/// Session worktrees live under the project root, so a session working in the
+/// root doesn't own what runs in other sessions' worktrees. Sessions sharing
+/// the root itself can't be told apart.
+pub(crate) fn owns_dir(session_cwd: &Path, dir: &Path) -> bool {
+    match dir.strip_prefix(session_cwd) {
+        Ok(rest) => !rest.starts_with(Path::new(".mindgrid").join("worktrees")),
+        Err(_) => false,
+    }
+}
+
+/// Processes a session owns: PTYs started in its directory and its run scripts,
+/// plus the ports allocated to the scripts
+struct SessionProcesses {
+    roots: Vec<u32>,
+    script_ports: Vec<u16>,
+}
+
+fn session_processes(app: &AppHandle, session_id: &str, cwd: &str) -> SessionProcesses {
+    let mut roots: Vec<u32> = app
+        .state::<Arc<PtyState>>()
+        .list()
+        .into_iter()
+        .filter(|pty| pty.cwd.as_deref().is_some_and(|dir| owns_dir(Path::new(cwd), Path::new(dir))))
+        .filter_map(|pty| pty.pid)
+        .collect();
+    let mut script_ports = Vec::new();
+    for (pid, port) in app.state::<Arc<RunScriptState>>().session_processes(session_id) {
+        roots.push(pid);
+        script_ports.push(port);
+    }
+    SessionProcesses { roots, script_ports }
+}
+
+/// Where a preview proxy forwards connections
+pub(crate) struct PreviewTarget {
+    port: Mutex<Option<u16>>,
+    pinned: AtomicBool,
+    /// Finds the session's dev server again when the current one stops answering
+    detect: Box<dyn Fn() -> Option<u16> + Send + Sync>,
+    on_change: Box<dyn Fn(Option<u16>) + Send + Sync>,
+}
+
+impl PreviewTarget {
+    pub(crate) fn new(
+        port: Option<u16>,
+        pinned: bool,
+        detect: impl Fn() -> Option<u16> + Send + Sync + 'static,
+        on_change: impl Fn(Option<u16>) + Send + Sync + 'static,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            port: Mutex::new(port),
+            pinned: AtomicBool::new(pinned),
+            detect: Box::new(detect),
+            on_change: Box::new(on_change),
+        })
+    }
+
+    fn port(&self) -> Option<u16> {
+        *self.port.lock()
+    }
+
+    fn retarget(&self, port: Option<u16>, pinned: bool) {
+        *self.port.lock() = port;
+        self.pinned.store(pinned, Ordering::SeqCst);
+    }
+
+    async fn connect(self: &Arc<Self>) -> Option<TcpStream> {
+        if let Some(port) = self.port() {
+            if let Ok(Ok(stream)) = tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect(("127.0.0.1", port))).await {
+                return Some(stream);
+            }
+        }
+        if self.pinned.load(Ordering::SeqCst) {
+            return None;
+        }
+
+        // The dev server moved or isn't up yet; /proc scans are blocking
+        let target = self.clone();
+        let detected = tokio::task::spawn_blocking(move || (target.detect)()).await.ok().flatten();
+        let changed = {
+            let mut port = self.port.lock();
+            let changed = *port != detected;
+            *port = detected;
+            changed
+        };
+        if changed {
+            (self.on_change)(detected);
+        }
+        let port = detected?;
+        tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect(("127.0.0.1", port)))
+            .await
+            .ok()?
+            .ok()
+    }
+}
+
+const NO_SERVER_PAGE: &str = "<!doctype html><title>No dev server</title>\
+    <p>No dev server is listening for this session yet. Start one and reload.</p>";
+
+/// Forward every connection to the target until `shutdown` flips. Bytes are
+/// copied both ways as is, so WebSocket upgrades (HMR) pass through unchanged.
+pub(crate) async fn run_proxy(listener: TcpListener, target: Arc<PreviewTarget>, mut shutdown: watch::Receiver<bool>) {
+    loop {
+        let accepted = tokio::select! {
+            accepted = listener.accept() => accepted,
+            _ = shutdown.wait_for(|stop| *stop) => return,
+        };
+        let mut inbound = match accepted {
+            Ok((stream, _)) => stream,
+            Err(e) => {
+                eprintln!("[MindGrid] Preview proxy accept failed: {}", e);
+                continue;
+            }
+        };
+        let target = target.clone();
+        tokio::spawn(async move {
+            match target.connect().await {
+                Some(mut outbound) => {
+                    let _ = tokio::io::copy_bidirectional(&mut inbound, &mut outbound).await;
+                }
+                None => {
+                    let response = format!(
+                        "HTTP/1.1 502 Bad Gateway\r\nContent-Type: text/html; charset=utf-8\r\n\
+                         Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        NO_SERVER_PAGE.len(),
+                        NO_SERVER_PAGE
+                    );
+                    let _ = inbound.write_all(response.as_bytes()).await;
+                    let _ = inbound.shutdown().await;
+                }
+            }
+        });
+    }
+}
+
+struct Preview {
+    info: Arc<Mutex<PreviewInfo>>,
+    target: Arc<PreviewTarget>,
+    shutdown: watch::Sender<bool>,
+}
+
+/// Preview proxies per session
+pub struct PreviewState {
+    previews: Mutex<HashMap<String, Preview>>,
+    /// Proxy port last used by each session, reused so its URL doesn't change
+    ports: Mutex<HashMap<String, u16>>,
+}
+
+impl PreviewState {
+    pub fn new() -> Self {
+        Self {
+            previews: Mutex::new(HashMap::new()),
+            ports: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn stop(&self, session_id: &str) -> bool {
+        match self.previews.lock().remove(session_id) {
+            Some(preview) => {
+                preview.shutdown.send_replace(true);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+fn session_cwd(app: &AppHandle, session_id: &str) -> MindgridResult<String> {
+    Ok(crate::app_store::session(app, session_id)?.cwd)
+}
+
+/// Ports the session's processes listen on (Linux only)
+#[tauri::command]
+pub async fn detect_session_ports(app: AppHandle, session_id: String) -> MindgridResult<Vec<ListeningPort>> {
+    if !cfg!(target_os = "linux") {
+        return Err(MindgridError::validation(
+            "unsupported_platform",
+            "Port detection is only available on Linux; pass the port to start_preview instead",
+        ));
+    }
+    let cwd = session_cwd(&app, &session_id)?;
+    let processes = session_processes(&app, &session_id, &cwd);
+    tokio::task::spawn_blocking(move || listening_ports(&processes.roots))
+        .await
+        .map_err(|e| MindgridError::io("detect_failed", "Failed to detect listening ports").with_technical(e))
+}
+
+/// Start the session's preview proxy, or retarget the running one. `target_port`
+/// pins the dev server port; otherwise it is detected and followed when it changes.
+#[tauri::command]
+pub async fn start_preview(
+    app: AppHandle,
+    state: tauri::State<'_, Arc<PreviewState>>,
+    session_id: String,
+    target_port: Option<u16>,
+) -> MindgridResult<PreviewInfo> {
+    let cwd = session_cwd(&app, &session_id)?;
+    let detect = {
+        let app = app.clone();
+        let session_id = session_id.clone();
+        move || {
+            let processes = session_processes(&app, &session_id, &cwd);
+            pick_target(&listening_ports(&processes.roots), &processes.script_ports)
+        }
+    };
+    let initial = match target_port {
+        Some(port) => Some(port),
+        None => tokio::task::spawn_blocking(detect.clone()).await.ok().flatten(),
+    };
+
+    // Already running: retarget it and keep its URL
+    let running = state
+        .previews
+        .lock()
+        .get(&session_id)
+        .map(|preview| (preview.info.clone(), preview.target.clone()));
+    if let Some((info, target)) = running {
+        target.retarget(initial, target_port.is_some());
+        let snapshot = {
+            let mut info = info.lock();
+            info.target_port = initial;
+            info.pinned = target_port.is_some();
+            info.clone()
+        };
+        let _ = app.emit("preview-updated", &snapshot);
+        return Ok(snapshot);
+    }
+
+    let listener = bind_proxy(&state, &session_id).await?;
+    let proxy_port = listener
+        .local_addr()
+        .map_err(|e| MindgridError::io("bind_failed", "Failed to start the preview proxy").with_technical(e))?
+        .port();
+    let info = Arc::new(Mutex::new(PreviewInfo {
+        session_id: session_id.clone(),
+        url: format!("http://127.0.0.1:{}/", proxy_port),
+        proxy_port,
+        target_port: initial,
+        pinned: target_port.is_some(),
+    }));
+
+    let on_change = {
+        let app = app.clone();
+        let info = info.clone();
+        move |port: Option<u16>| {
+            let updated = {
+                let mut info = info.lock();
+                info.target_port = port;
+                info.clone()
+            };
+            let _ = app.emit("preview-updated", updated);
+        }
+    };
+    let target = PreviewTarget::new(initial, target_port.is_some(), detect, on_change);
+    let (shutdown, shutdown_rx) = watch::channel(false);
+    tauri::async_runtime::spawn(run_proxy(listener, target.clone(), shutdown_rx));
+
+    let snapshot = info.lock().clone();
+    state.previews.lock().insert(session_id, Preview { info, target, shutdown });
+    let _ = app.emit("preview-updated", &snapshot);
+    Ok(snapshot)
+}
+
+/// Bind the session's proxy port, preferring the one it had before
+async fn bind_proxy(state: &PreviewState, session_id: &str) -> MindgridResult<TcpListener> {
+    let preferred = state.ports.lock().get(session_id).copied();
+    let taken: HashSet<u16> = state
+        .ports
+        .lock()
+        .iter()
+        .filter(|(id, _)| id.as_str() != session_id)
+        .map(|(_, port)| *port)
+        .collect();
+    for port in preferred.into_iter().chain(PREVIEW_PORTS) {
+        if taken.contains(&port) {
+            continue;
+        }
+        if let Ok(listener) = TcpListener::bind(("127.0.0.1", port)).await {
+            state.ports.lock().insert(session_id.to_string(), port);
+            return Ok(listener);
+        }
+    }
+    Err(MindgridError::io("no_free_port", "No free port left for the preview proxy"))
+}
+
+#[tauri::command]
+pub async fn get_preview(
+    state: tauri::State<'_, Arc<PreviewState>>,
+    session_id: String,
+) -> MindgridResult<Option<PreviewInfo>> {
+    Ok(state.previews.lock().get(&session_id).map(|preview| preview.info.lock().clone()))
+}
+
+#[tauri::command]
+pub async fn stop_preview(state: tauri::State<'_, Arc<PreviewState>>, session_id: String) -> MindgridResult<()> {
+    state.stop(&session_id);
+    Ok(())
+}
+
+/// Stop a session's preview from backend code (session pause and delete)
+pub fn stop_session_preview(app: &AppHandle, session_id: &str) -> bool {
+    app.try_state::<Arc<PreviewState>>()
+        .is_some_and(|state| state.stop(session_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sessions_own_their_directory_only() {
+        let root = Path::new("/work/app");
+        let worktree = Path::new("/work/app/.mindgrid/worktrees/feature");
+        assert!(owns_dir(root, root));
+        assert!(owns_dir(root, Path::new("/work/app/web")));
+        assert!(!owns_dir(root, worktree));
+        assert!(!owns_dir(root, Path::new("/work/app/.mindgrid/worktrees/feature/web")));
+        assert!(!owns_dir(root, Path::new("/work/application")));
+        assert!(owns_dir(worktree, worktree));
+        assert!(owns_dir(worktree, Path::new("/work/app/.mindgrid/worktrees/feature/web")));
+        assert!(!owns_dir(worktree, root));
+    }
+
+    #[test]
+    fn preview_port_detection() {
+        let tcp = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode\n   0: 0100007F:1435 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 48213 1 0000000000000000 100 0 0 10 0\n   1: 0100007F:1435 0100007F:D2A4 01 00000000:00000000 00:00000000 00000000  1000        0 48250 1 0000000000000000 20 4 30 10 -1\n";
+        assert_eq!(parse_listening_sockets(tcp), vec![(48213, 5173)]);
+        let tcp6 = "  sl  local_address                         remote_address                        st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode\n   0: 00000000000000000000000000000000:0BB8 00000000000000000000000000000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 51002 1 0000000000000000 100 0 0 10 0\n";
+        assert_eq!(parse_listening_sockets(tcp6), vec![(51002, 3000)]);
+
+        assert_eq!(parse_stat_ppid("4242 (node (vite) x) S 4100 4242 4100 0 -1"), Some(4100));
+        assert_eq!(parse_stat_ppid("garbage"), None);
+
+        let port = |port: u16| ListeningPort {
+            port,
+            pid: 1,
+            process: "node".to_string(),
+        };
+        let detected = vec![port(3000), port(4100), port(24678)];
+        // A run script's $PORT wins over lower ports
+        assert_eq!(pick_target(&detected, &[4100]), Some(4100));
+        assert_eq!(pick_target(&detected, &[4101]), Some(3000));
+        assert_eq!(pick_target(&[], &[4100]), None);
+
+        #[cfg(target_os = "linux")]
+        {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            let bound = listener.local_addr().unwrap().port();
+            let ports = crate::preview::listening_ports(&[std::process::id()]);
+            assert!(ports.iter().any(|p| p.port == bound && p.pid == std::process::id()));
+        }
+    }
+
+    #[test]
+    fn preview_proxy_forwards() {
+        use std::io::{Read, Write};
+
+        // A local dev server answering one request per connection
+        let server = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let server_port = server.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            for mut stream in server.incoming().flatten() {
+                let mut buf = [0u8; 1024];
+                let _ = stream.read(&mut buf);
+                let _ = stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\nConnection: close\r\n\r\nhello");
+            }
+        });
+
+        let fetch = |port: u16| {
+            let mut stream = std::net::TcpStream::connect(("127.0.0.1", port)).unwrap();
+            stream.write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let detected = Arc::new(std::sync::Mutex::new(None));
+        let changes = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let target = {
+            let detected = detected.clone();
+            let changes = changes.clone();
+            PreviewTarget::new(
+                None,
+                false,
+                move || *detected.lock().unwrap(),
+                move |port| changes.lock().unwrap().push(port),
+            )
+        };
+        let (shutdown, shutdown_rx) = tokio::sync::watch::channel(false);
+        let listener = runtime.block_on(tokio::net::TcpListener::bind("127.0.0.1:0")).unwrap();
+        let proxy_port = listener.local_addr().unwrap().port();
+        runtime.spawn(run_proxy(listener, target, shutdown_rx));
+
+        assert!(fetch(proxy_port).starts_with("HTTP/1.1 502 Bad Gateway"));
+        // The dev server comes up later and is picked up on the next connection
+        *detected.lock().unwrap() = Some(server_port);
+        let response = fetch(proxy_port);
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.ends_with("hello"));
+        assert_eq!(*changes.lock().unwrap(), vec![Some(server_port)]);
+
+        shutdown.send_replace(true);
+    }
+}
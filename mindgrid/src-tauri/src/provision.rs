@@ -0,0 +1,376 @@
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
+
+use crate::error::{MindgridError, MindgridResult};
+
+/// Ignored directories worth carrying over from the main checkout when a
+/// worktree is provisioned, so the session doesn't start with a cold build
+pub const DEFAULT_CLONE_DIRS: &[&str] = &[
+    "node_modules",
+    "target",
+    ".next",
+    ".turbo",
+    ".gradle",
+    ".venv",
+    "venv",
+    "build",
+    "dist",
+    "Pods",
+];
+
+/// Optional provisioning for new session worktrees. Without it, worktrees are
+/// a full checkout of the branch.
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+#[ts(export, optional_fields = nullable)]
+pub struct ProvisionOptions {
+    /// Sparse-checkout patterns; when set only these paths are checked out
+    pub sparse_patterns: Option<Vec<String>>,
+    /// Interpret the patterns as directories (cone mode, the default) or as gitignore-style patterns
+    pub cone: Option<bool>,
+    /// Ignored directory names to clone from the main checkout; defaults to [`DEFAULT_CLONE_DIRS`]
+    pub clone_dirs: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum CloneMethod {
+    /// Copy-on-write clone sharing blocks with the source
+    Reflink,
+    /// Byte-for-byte copy
+    Copy,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct ClonedDir {
+    pub path: String,
+    pub method: CloneMethod,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct ProvisionReport {
+    pub worktree_path: String,
+    pub sparse: bool,
+    pub cloned: Vec<ClonedDir>,
+    pub errors: Vec<String>,
+}
+
+async fn git(dir: &Path, args: &[&str], code: &str, message: &str) -> MindgridResult<String> {
+    let output = tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| MindgridError::spawn_failed("git", e))?;
+
+    if !output.status.success() {
+        return Err(MindgridError::git_failed(code, message, args, &output));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+async fn set_sparse(worktree: &Path, patterns: &[String], cone: bool) -> MindgridResult<()> {
+    let mut args = vec!["sparse-checkout", "set", if cone { "--cone" } else { "--no-cone" }];
+    args.extend(patterns.iter().map(|p| p.as_str()));
+    git(worktree, &args, "sparse_checkout_failed", "Failed to set sparse-checkout patterns")
+        .await
+        .map(|_| ())
+}
+
+/// Restrict a worktree created with `--no-checkout` to the given patterns and
+/// populate it. Sparse settings are stored per worktree, so the main checkout
+/// and other sessions are unaffected. With `skip_lfs_smudge`, LFS files are
+/// left as pointers for a batched `git lfs pull` afterwards.
+pub async fn apply_sparse_checkout(
+    worktree: &Path,
+    patterns: &[String],
+    cone: bool,
+    skip_lfs_smudge: bool,
+) -> MindgridResult<()> {
+    set_sparse(worktree, patterns, cone).await?;
+
+    // The index is still empty after --no-checkout; read HEAD into it honouring the sparse patterns
+    let args = ["read-tree", "-mu", "HEAD"];
+    let mut cmd = tokio::process::Command::new("git");
+    cmd.arg("-C").arg(worktree).args(args);
+    if skip_lfs_smudge {
+        cmd.env("GIT_LFS_SKIP_SMUDGE", "1");
+    }
+    let output = cmd
+        .output()
+        .await
+        .map_err(|e| MindgridError::spawn_failed("git read-tree", e))?;
+    if !output.status.success() {
+        return Err(MindgridError::git_failed(
+            "sparse_checkout_failed",
+            "Failed to populate the sparse worktree",
+            &args,
+            &output,
+        ));
+    }
+    Ok(())
+}
+
+/// Ignored directories in the main checkout whose name is in `names`
+async fn heavy_dirs(project: &Path, names: &[String]) -> Vec<PathBuf> {
+    let listing = match git(
+        project,
+        &["ls-files", "--others", "--ignored", "--exclude-standard", "--directory", "-z"],
+        "ls_files_failed",
+        "Failed to list ignored directories",
+    )
+    .await
+    {
+        Ok(listing) => listing,
+        Err(e) => {
+            eprintln!("[MindGrid] {}", e.technical_message());
+            return Vec::new();
+        }
+    };
+
+    listing
+        .split('\0')
+        .filter(|entry| entry.ends_with('/'))
+        .map(|entry| PathBuf::from(entry.trim_end_matches('/')))
+        .filter(|path| !path.starts_with(".mindgrid"))
+        .filter(|path| {
+            path.file_name()
+                .map(|name| names.iter().any(|n| name.to_string_lossy() == n.as_str()))
+                .unwrap_or(false)
+        })
+        .collect()
+}
+
+/// Clone a directory tree using the platform's copy-on-write support
+#[cfg(target_os = "linux")]
+async fn reflink_dir(src: &Path, dst: &Path) -> bool {
+    tokio::process::Command::new("cp")
+        .args(["-a", "--reflink=always"])
+        .arg(src)
+        .arg(dst)
+        .output()
+        .await
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+#[cfg(target_os = "macos")]
+async fn reflink_dir(src: &Path, dst: &Path) -> bool {
+    // -c uses clonefile(2), which only works on APFS
+    tokio::process::Command::new("cp")
+        .args(["-c", "-R", "-p"])
+        .arg(src)
+        .arg(dst)
+        .output()
+        .await
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+async fn reflink_dir(_src: &Path, _dst: &Path) -> bool {
+    false
+}
+
+/// Recursive copy that recreates symlinks instead of following them
+/// (`node_modules/.bin` and workspace packages are symlinks)
+fn copy_tree(src: &Path, dst: &Path) -> io::Result<()> {
+    std::fs::create_dir_all(dst)?;
+    for entry in std::fs::read_dir(src)? {
+        let entry = entry?;
+        let src_path = entry.path();
+        let dst_path = dst.join(entry.file_name());
+        let file_type = entry.file_type()?;
+        if file_type.is_symlink() {
+            let target = std::fs::read_link(&src_path)?;
+            #[cfg(unix)]
+            std::os::unix::fs::symlink(&target, &dst_path)?;
+            #[cfg(not(unix))]
+            {
+                let _ = target;
+                std::fs::copy(&src_path, &dst_path)?;
+            }
+        } else if file_type.is_dir() {
+            copy_tree(&src_path, &dst_path)?;
+        } else {
+            std::fs::copy(&src_path, &dst_path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Clone `src` to `dst` (which must not exist), preferring a reflink
+pub async fn clone_dir(src: &Path, dst: &Path) -> MindgridResult<CloneMethod> {
+    if reflink_dir(src, dst).await {
+        return Ok(CloneMethod::Reflink);
+    }
+
+    // A failed reflink attempt may leave a partial tree behind
+    if dst.exists() {
+        let _ = std::fs::remove_dir_all(dst);
+    }
+
+    let (src, dst) = (src.to_path_buf(), dst.to_path_buf());
+    tokio::task::spawn_blocking(move || copy_tree(&src, &dst))
+        .await
+        .map_err(|e| MindgridError::io("copy_failed", "Failed to copy directory").with_technical(e))?
+        .map_err(|e| MindgridError::io("copy_failed", "Failed to copy directory").with_technical(e))?;
+    Ok(CloneMethod::Copy)
+}
+
+/// Clone heavy ignored directories from the main checkout into a new worktree.
+/// With sparse checkout, directories whose parent wasn't checked out are skipped.
+pub async fn clone_heavy_dirs(project: &Path, worktree: &Path, names: &[String]) -> (Vec<ClonedDir>, Vec<String>) {
+    let mut cloned = Vec::new();
+    let mut errors = Vec::new();
+
+    for relative in heavy_dirs(project, names).await {
+        let dst = worktree.join(&relative);
+        let parent_present = dst.parent().map(|p| p.is_dir()).unwrap_or(false);
+        if !parent_present || std::fs::symlink_metadata(&dst).is_ok() {
+            continue;
+        }
+
+        match clone_dir(&project.join(&relative), &dst).await {
+            Ok(method) => cloned.push(ClonedDir {
+                path: relative.to_string_lossy().to_string(),
+                method,
+            }),
+            Err(e) => errors.push(format!("{}: {}", relative.display(), e.technical_message())),
+        }
+    }
+
+    (cloned, errors)
+}
+
+/// Change the sparse-checkout patterns of an existing session worktree
+#[tauri::command]
+pub async fn set_worktree_sparse_patterns(
+    worktree_path: String,
+    patterns: Vec<String>,
+    cone: Option<bool>,
+) -> MindgridResult<()> {
+    let worktree = Path::new(&worktree_path);
+    if patterns.is_empty() {
+        git(
+            worktree,
+            &["sparse-checkout", "disable"],
+            "sparse_checkout_failed",
+            "Failed to disable sparse checkout",
+        )
+        .await?;
+        return Ok(());
+    }
+
+    set_sparse(worktree, &patterns, cone.unwrap_or(true)).await
+}
+
+/// Current sparse-checkout patterns of a worktree, or `None` for a full checkout
+#[tauri::command]
+pub async fn get_worktree_sparse_patterns(worktree_path: String) -> MindgridResult<Option<Vec<String>>> {
+    let worktree = Path::new(&worktree_path);
+    let enabled = git(worktree, &["config", "--get", "core.sparseCheckout"], "config_failed", "Failed to read core.sparseCheckout")
+        .await
+        .map(|v| v.trim() == "true")
+        .unwrap_or(false);
+    if !enabled {
+        return Ok(None);
+    }
+
+    let patterns = git(
+        worktree,
+        &["sparse-checkout", "list"],
+        "sparse_checkout_failed",
+        "Failed to read sparse-checkout patterns",
+    )
+    .await?;
+    Ok(Some(patterns.lines().map(|l| l.to_string()).collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::fixtures::TempRepo;
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn finds_heavy_ignored_dirs() {
+        let repo = TempRepo::with_files(&[
+            (".gitignore", "node_modules/\ntarget/\n*.log\n"),
+            ("web/package.json", "{}\n"),
+        ]);
+        repo.write("node_modules/left-pad/index.js", "module.exports = 1;\n");
+        repo.write("web/node_modules/react/index.js", "module.exports = 2;\n");
+        repo.write("target/debug/app", "binary\n");
+        repo.write("debug.log", "noise\n");
+        repo.write(".mindgrid/worktrees/s1/node_modules/x.js", "nested\n");
+
+        let mut found = heavy_dirs(&repo.path, &names(&["node_modules", "target"])).await;
+        found.sort();
+        assert_eq!(
+            found,
+            [PathBuf::from("node_modules"), PathBuf::from("target"), PathBuf::from("web/node_modules")]
+        );
+        assert!(heavy_dirs(&repo.path, &names(&["vendor"])).await.is_empty());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn clones_keep_symlinks() {
+        let repo = TempRepo::new();
+        repo.write("src/node_modules/pkg/index.js", "module.exports = 1;\n");
+        std::fs::create_dir_all(repo.path.join("src/node_modules/.bin")).unwrap();
+        std::os::unix::fs::symlink("../pkg/index.js", repo.path.join("src/node_modules/.bin/pkg")).unwrap();
+
+        let dst = repo.path.join("dst/node_modules");
+        std::fs::create_dir_all(dst.parent().unwrap()).unwrap();
+        clone_dir(&repo.path.join("src/node_modules"), &dst).await.unwrap();
+
+        assert_eq!(repo.read("dst/node_modules/pkg/index.js"), "module.exports = 1;\n");
+        let link = dst.join(".bin/pkg");
+        assert!(std::fs::symlink_metadata(&link).unwrap().file_type().is_symlink());
+        assert_eq!(std::fs::read_link(&link).unwrap(), PathBuf::from("../pkg/index.js"));
+    }
+
+    #[tokio::test]
+    async fn sparse_worktrees_only_get_dirs_they_check_out() {
+        let project = TempRepo::with_files(&[
+            (".gitignore", "node_modules/\n"),
+            ("app/package.json", "{}\n"),
+            ("docs/package.json", "{}\n"),
+        ]);
+        project.write("app/node_modules/a.js", "a\n");
+        project.write("docs/node_modules/d.js", "d\n");
+
+        let worktree = project.path.join(".mindgrid/worktrees/sparse");
+        project.git(&["worktree", "add", "-q", "--no-checkout", "-b", "mindgrid/sparse", &worktree.to_string_lossy()]);
+        apply_sparse_checkout(&worktree, &names(&["app"]), true, false).await.unwrap();
+        assert!(worktree.join("app/package.json").exists());
+        assert!(!worktree.join("docs").exists());
+
+        let (cloned, errors) = clone_heavy_dirs(&project.path, &worktree, &names(&["node_modules"])).await;
+        assert!(errors.is_empty(), "{:?}", errors);
+        let cloned: Vec<&str> = cloned.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(cloned, ["app/node_modules"]);
+        assert!(worktree.join("app/node_modules/a.js").exists());
+
+        // Already present directories are left alone on a second run
+        let (again, _) = clone_heavy_dirs(&project.path, &worktree, &names(&["node_modules"])).await;
+        assert!(again.is_empty());
+
+        let path = worktree.to_string_lossy().to_string();
+        let patterns = get_worktree_sparse_patterns(path.clone()).await.unwrap().unwrap();
+        assert_eq!(patterns, ["app"]);
+        set_worktree_sparse_patterns(path.clone(), Vec::new(), None).await.unwrap();
+        assert_eq!(get_worktree_sparse_patterns(path).await.unwrap(), None);
+        assert!(worktree.join("docs/package.json").exists());
+    }
+}
@@ -3,20 +3,27 @@ use portable_pty::{native_pty_system, CommandBuilder, PtySize};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::io::{Read, Write};
+use std::path::Path;
 use std::sync::Arc;
 use std::thread;
 use tauri::{AppHandle, Emitter};
+use ts_rs::TS;
 use uuid::Uuid;
 
+use crate::error::{MindgridError, MindgridResult};
+use crate::operations::{OperationRegistry, ProcessTracker};
+
 /// Output event sent to the frontend
-#[derive(Clone, Serialize)]
+#[derive(Clone, Serialize, TS)]
+#[ts(export)]
 pub struct PtyOutput {
     pub id: String,
     pub data: String,
 }
 
 /// Exit event sent to the frontend
-#[derive(Clone, Serialize)]
+#[derive(Clone, Serialize, TS)]
+#[ts(export)]
 pub struct PtyExit {
     pub id: String,
     pub code: Option<u32>,
@@ -25,11 +32,25 @@ pub struct PtyExit {
 /// Holds a PTY writer for sending input
 struct PtyProcess {
     writer: Box<dyn Write + Send>,
+    info: PtyInfo,
     // We keep the child and master alive by holding references
     _child: Box<dyn portable_pty::Child + Send + Sync>,
     _master: Box<dyn portable_pty::MasterPty + Send>,
 }
 
+/// What a PTY is running, for resource reporting
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct PtyInfo {
+    pub id: String,
+    pub pid: Option<u32>,
+    pub cmd: String,
+    pub cwd: Option<String>,
+    /// Unix timestamp (seconds)
+    #[ts(type = "number")]
+    pub started_at: u64,
+}
+
 /// Global state for managing PTY processes
 pub struct PtyState {
     processes: Mutex<HashMap<String, PtyProcess>>,
@@ -41,10 +62,30 @@ impl PtyState {
             processes: Mutex::new(HashMap::new()),
         }
     }
+
+    /// Snapshot of all running PTYs
+    pub fn list(&self) -> Vec<PtyInfo> {
+        self.processes.lock().values().map(|p| p.info.clone()).collect()
+    }
+
+    /// Kill every PTY started in `dir` or below it. Returns the killed ids.
+    pub fn kill_in(&self, dir: &Path) -> Vec<String> {
+        let mut processes = self.processes.lock();
+        let ids: Vec<String> = processes
+            .values()
+            .filter(|p| p.info.cwd.as_deref().is_some_and(|cwd| Path::new(cwd).starts_with(dir)))
+            .map(|p| p.info.id.clone())
+            .collect();
+        for id in &ids {
+            processes.remove(id);
+        }
+        ids
+    }
 }
 
 /// Arguments for spawning a PTY
-#[derive(Deserialize)]
+#[derive(Debug, Clone, Deserialize, TS)]
+#[ts(export, optional_fields = nullable)]
 pub struct SpawnArgs {
     pub cmd: String,
     pub args: Vec<String>,
@@ -59,7 +100,12 @@ pub fn spawn_pty(
     app: AppHandle,
     state: tauri::State<'_, Arc<PtyState>>,
     args: SpawnArgs,
-) -> Result<String, String> {
+) -> MindgridResult<String> {
+    spawn(app, &state, args)
+}
+
+/// Spawn a PTY from backend code (e.g. a session's agent)
+pub fn spawn(app: AppHandle, state: &Arc<PtyState>, args: SpawnArgs) -> MindgridResult<String> {
     let pty_system = native_pty_system();
 
     let size = PtySize {
@@ -71,7 +117,7 @@ pub fn spawn_pty(
 
     let pair = pty_system
         .openpty(size)
-        .map_err(|e| format!("Failed to open PTY: {}", e))?;
+        .map_err(|e| MindgridError::pty("open_failed", "Failed to open a terminal").with_technical(e))?;
 
     let mut cmd = CommandBuilder::new(&args.cmd);
     cmd.args(&args.args);
@@ -110,10 +156,18 @@ pub fn spawn_pty(
         cmd.env("ZDOTDIR", zdotdir.to_string_lossy().to_string());
     }
 
+    let command_line = std::iter::once(args.cmd.as_str())
+        .chain(args.args.iter().map(|a| a.as_str()))
+        .collect::<Vec<_>>()
+        .join(" ");
     let child = pair
         .slave
         .spawn_command(cmd)
-        .map_err(|e| format!("Failed to spawn command: {}", e))?;
+        .map_err(|e| {
+            MindgridError::pty("spawn_failed", format!("Failed to start {}", args.cmd))
+                .with_command(command_line)
+                .with_technical(e)
+        })?;
 
     let id = Uuid::new_v4().to_string();
 
@@ -121,12 +175,23 @@ pub fn spawn_pty(
     let mut reader = pair
         .master
         .try_clone_reader()
-        .map_err(|e| format!("Failed to clone reader: {}", e))?;
+        .map_err(|e| MindgridError::pty("io_failed", "Failed to read from the terminal").with_technical(e))?;
 
     let writer = pair
         .master
         .take_writer()
-        .map_err(|e| format!("Failed to take writer: {}", e))?;
+        .map_err(|e| MindgridError::pty("io_failed", "Failed to write to the terminal").with_technical(e))?;
+
+    let info = PtyInfo {
+        id: id.clone(),
+        pid: child.process_id(),
+        cmd: args.cmd.clone(),
+        cwd: args.cwd.clone(),
+        started_at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+    };
 
     // Store the process
     {
@@ -135,6 +200,7 @@ pub fn spawn_pty(
             id.clone(),
             PtyProcess {
                 writer,
+                info,
                 _child: child,
                 _master: pair.master,
             },
@@ -143,7 +209,7 @@ pub fn spawn_pty(
 
     // Spawn a thread to read output and emit events
     let id_clone = id.clone();
-    let state_clone = Arc::clone(&state);
+    let state_clone = Arc::clone(state);
     thread::spawn(move || {
         let mut buf = [0u8; 4096];
         loop {
@@ -186,21 +252,26 @@ pub fn write_pty(
     state: tauri::State<'_, Arc<PtyState>>,
     id: String,
     data: String,
-) -> Result<(), String> {
+) -> MindgridResult<()> {
+    write_to_pty(&state, &id, &data)
+}
+
+/// Write data to a PTY from backend code (e.g. follow-up prompts for an agent)
+pub fn write_to_pty(state: &PtyState, id: &str, data: &str) -> MindgridResult<()> {
     let mut processes = state.processes.lock();
     let process = processes
-        .get_mut(&id)
-        .ok_or_else(|| format!("PTY not found: {}", id))?;
+        .get_mut(id)
+        .ok_or_else(|| MindgridError::pty("not_found", format!("Terminal {} is no longer running", id)))?;
 
     process
         .writer
         .write_all(data.as_bytes())
-        .map_err(|e| format!("Failed to write to PTY: {}", e))?;
+        .map_err(|e| MindgridError::pty("write_failed", "Failed to write to the terminal").with_technical(e))?;
 
     process
         .writer
         .flush()
-        .map_err(|e| format!("Failed to flush PTY: {}", e))?;
+        .map_err(|e| MindgridError::pty("write_failed", "Failed to write to the terminal").with_technical(e))?;
 
     Ok(())
 }
@@ -212,7 +283,7 @@ pub fn resize_pty(
     _id: String,
     _cols: u16,
     _rows: u16,
-) -> Result<(), String> {
+) -> MindgridResult<()> {
     // Note: portable-pty doesn't easily expose resize after creation
     // This would require keeping a reference to the master pty
     // For now, we'll skip this - can be implemented later if needed
@@ -221,45 +292,37 @@ pub fn resize_pty(
 
 /// Kill a PTY process
 #[tauri::command]
-pub fn kill_pty(state: tauri::State<'_, Arc<PtyState>>, id: String) -> Result<(), String> {
+pub fn kill_pty(state: tauri::State<'_, Arc<PtyState>>, id: String) -> MindgridResult<()> {
+    kill(&state, &id)
+}
+
+/// Kill a PTY from backend code
+pub fn kill(state: &PtyState, id: &str) -> MindgridResult<()> {
     let mut processes = state.processes.lock();
-    if processes.remove(&id).is_some() {
+    if processes.remove(id).is_some() {
         Ok(())
     } else {
-        Err(format!("PTY not found: {}", id))
+        Err(MindgridError::pty("not_found", format!("Terminal {} is no longer running", id)))
     }
 }
 
 /// Fetch Claude usage data by executing /usage command via PTY
 #[tauri::command]
-pub async fn get_claude_usage() -> Result<String, String> {
-    use std::time::Duration;
-    use std::sync::mpsc;
-
-    // Run the blocking PTY operations in a separate thread with timeout
-    let (tx, rx) = mpsc::channel();
-
-    let handle = thread::spawn(move || {
-        let result = get_claude_usage_blocking();
-        let _ = tx.send(result);
-    });
-
-    // Wait up to 20 seconds for the result
-    match rx.recv_timeout(Duration::from_secs(20)) {
-        Ok(result) => {
-            let _ = handle.join();
-            result
-        }
-        Err(_) => {
-            // Timeout - thread is still running, but we can't easily kill it
-            // It will eventually exit when claude exits
-            Err("Timeout: Claude usage fetch took too long (>20s)".to_string())
-        }
-    }
+pub async fn get_claude_usage(
+    operations: tauri::State<'_, Arc<OperationRegistry>>,
+    operation_id: Option<String>,
+) -> MindgridResult<String> {
+    let op = operations.begin(operation_id, "usage")?;
+    let tracker = op.tracker();
+    // The PTY work is blocking; cancelling or timing out kills claude, which ends the thread
+    let task = tokio::task::spawn_blocking(move || get_claude_usage_blocking(&tracker));
+    op.run("claude /usage", task)
+        .await?
+        .map_err(|e| MindgridError::agent("usage_panicked", "Claude usage fetch crashed").with_technical(e))?
 }
 
 /// Blocking implementation of Claude usage fetch using a spawned thread for reading
-fn get_claude_usage_blocking() -> Result<String, String> {
+fn get_claude_usage_blocking(tracker: &ProcessTracker) -> MindgridResult<String> {
     use std::time::Duration;
     use std::io::Read;
     use std::sync::{Arc, Mutex};
@@ -275,7 +338,7 @@ fn get_claude_usage_blocking() -> Result<String, String> {
 
     let pair = pty_system
         .openpty(size)
-        .map_err(|e| format!("Failed to open PTY: {}", e))?;
+        .map_err(|e| MindgridError::pty("open_failed", "Failed to open a terminal").with_technical(e))?;
 
     let mut cmd = CommandBuilder::new("claude");
 
@@ -290,13 +353,17 @@ fn get_claude_usage_blocking() -> Result<String, String> {
     let mut child = pair
         .slave
         .spawn_command(cmd)
-        .map_err(|e| format!("Failed to spawn claude: {}", e))?;
+        .map_err(|e| MindgridError::agent("spawn_failed", "Failed to start claude").with_command("claude").with_technical(e))?;
+    // PTY children lead their own session, so the pid is also the process group
+    if let Some(pid) = child.process_id() {
+        tracker.track(pid);
+    }
 
     let reader = pair.master.try_clone_reader()
-        .map_err(|e| format!("Failed to clone reader: {}", e))?;
+        .map_err(|e| MindgridError::pty("io_failed", "Failed to read from the terminal").with_technical(e))?;
 
     let mut writer = pair.master.take_writer()
-        .map_err(|e| format!("Failed to take writer: {}", e))?;
+        .map_err(|e| MindgridError::pty("io_failed", "Failed to write to the terminal").with_technical(e))?;
 
     // Use a shared buffer for the reader thread
     let output = Arc::new(Mutex::new(String::new()));
@@ -335,16 +402,17 @@ fn get_claude_usage_blocking() -> Result<String, String> {
         *stop_flag.lock().unwrap() = true;
         let _ = child.kill();
         let _ = reader_handle.join();
-        return Err(format!("Claude did not start. Got: {}",
-            startup_output.chars().take(300).collect::<String>()));
+        return Err(MindgridError::agent("not_started", "Claude did not start")
+            .with_command("claude")
+            .with_technical(startup_output.chars().take(300).collect::<String>()));
     }
 
     // Send /usage command character by character (Claude TUI may need this)
     for c in "/usage".bytes() {
         writer.write_all(&[c])
-            .map_err(|e| format!("Failed to write char: {}", e))?;
+            .map_err(|e| MindgridError::pty("write_failed", "Failed to send /usage to claude").with_technical(e))?;
         writer.flush()
-            .map_err(|e| format!("Failed to flush char: {}", e))?;
+            .map_err(|e| MindgridError::pty("write_failed", "Failed to send /usage to claude").with_technical(e))?;
         thread::sleep(Duration::from_millis(30));
     }
 
@@ -353,17 +421,17 @@ fn get_claude_usage_blocking() -> Result<String, String> {
 
     // Press Escape to dismiss autocomplete dropdown
     writer.write_all(b"\x1b")
-        .map_err(|e| format!("Failed to write escape: {}", e))?;
+        .map_err(|e| MindgridError::pty("write_failed", "Failed to send /usage to claude").with_technical(e))?;
     writer.flush()
-        .map_err(|e| format!("Failed to flush escape: {}", e))?;
+        .map_err(|e| MindgridError::pty("write_failed", "Failed to send /usage to claude").with_technical(e))?;
 
     thread::sleep(Duration::from_millis(100));
 
     // Now press Enter to execute command
     writer.write_all(b"\r")
-        .map_err(|e| format!("Failed to write enter: {}", e))?;
+        .map_err(|e| MindgridError::pty("write_failed", "Failed to send /usage to claude").with_technical(e))?;
     writer.flush()
-        .map_err(|e| format!("Failed to flush enter: {}", e))?;
+        .map_err(|e| MindgridError::pty("write_failed", "Failed to send /usage to claude").with_technical(e))?;
 
     // Wait for usage panel to render
     thread::sleep(Duration::from_millis(3000));
@@ -388,7 +456,7 @@ fn get_claude_usage_blocking() -> Result<String, String> {
 
     // Return the output
     if final_output.is_empty() {
-        Err("No output from Claude PTY".to_string())
+        Err(MindgridError::agent("no_output", "Claude produced no output").with_command("claude /usage"))
     } else {
         Ok(final_output)
     }
@@ -396,30 +464,20 @@ fn get_claude_usage_blocking() -> Result<String, String> {
 
 /// Fetch Codex usage data by executing /status command via PTY
 #[tauri::command]
-pub async fn get_codex_usage() -> Result<String, String> {
-    use std::time::Duration;
-    use std::sync::mpsc;
-
-    let (tx, rx) = mpsc::channel();
-
-    let handle = thread::spawn(move || {
-        let result = get_codex_usage_blocking();
-        let _ = tx.send(result);
-    });
-
-    match rx.recv_timeout(Duration::from_secs(20)) {
-        Ok(result) => {
-            let _ = handle.join();
-            result
-        }
-        Err(_) => {
-            Err("Timeout: Codex usage fetch took too long (>20s)".to_string())
-        }
-    }
+pub async fn get_codex_usage(
+    operations: tauri::State<'_, Arc<OperationRegistry>>,
+    operation_id: Option<String>,
+) -> MindgridResult<String> {
+    let op = operations.begin(operation_id, "usage")?;
+    let tracker = op.tracker();
+    let task = tokio::task::spawn_blocking(move || get_codex_usage_blocking(&tracker));
+    op.run("codex /status", task)
+        .await?
+        .map_err(|e| MindgridError::agent("usage_panicked", "Codex usage fetch crashed").with_technical(e))?
 }
 
 /// Blocking implementation of Codex usage fetch
-fn get_codex_usage_blocking() -> Result<String, String> {
+fn get_codex_usage_blocking(tracker: &ProcessTracker) -> MindgridResult<String> {
     use std::time::Duration;
     use std::io::Read;
     use std::sync::{Arc, Mutex};
@@ -435,7 +493,7 @@ fn get_codex_usage_blocking() -> Result<String, String> {
 
     let pair = pty_system
         .openpty(size)
-        .map_err(|e| format!("Failed to open PTY: {}", e))?;
+        .map_err(|e| MindgridError::pty("open_failed", "Failed to open a terminal").with_technical(e))?;
 
     let mut cmd = CommandBuilder::new("codex");
 
@@ -448,13 +506,17 @@ fn get_codex_usage_blocking() -> Result<String, String> {
     let mut child = pair
         .slave
         .spawn_command(cmd)
-        .map_err(|e| format!("Failed to spawn codex: {}", e))?;
+        .map_err(|e| MindgridError::agent("spawn_failed", "Failed to start codex").with_command("codex").with_technical(e))?;
+    // PTY children lead their own session, so the pid is also the process group
+    if let Some(pid) = child.process_id() {
+        tracker.track(pid);
+    }
 
     let reader = pair.master.try_clone_reader()
-        .map_err(|e| format!("Failed to clone reader: {}", e))?;
+        .map_err(|e| MindgridError::pty("io_failed", "Failed to read from the terminal").with_technical(e))?;
 
     let writer = pair.master.take_writer()
-        .map_err(|e| format!("Failed to take writer: {}", e))?;
+        .map_err(|e| MindgridError::pty("io_failed", "Failed to write to the terminal").with_technical(e))?;
 
     let output = Arc::new(Mutex::new(String::new()));
     let output_clone = Arc::clone(&output);
@@ -523,8 +585,9 @@ fn get_codex_usage_blocking() -> Result<String, String> {
         *stop_flag.lock().unwrap() = true;
         let _ = child.kill();
         let _ = reader_handle.join();
-        return Err(format!("Codex did not start. Got: {}",
-            startup_output.chars().take(300).collect::<String>()));
+        return Err(MindgridError::agent("not_started", "Codex did not start")
+            .with_command("codex")
+            .with_technical(startup_output.chars().take(300).collect::<String>()));
     }
 
     // Send /status command
@@ -532,9 +595,9 @@ fn get_codex_usage_blocking() -> Result<String, String> {
         let mut writer = writer_arc.lock().unwrap();
         for c in "/status".bytes() {
             writer.write_all(&[c])
-                .map_err(|e| format!("Failed to write char: {}", e))?;
+                .map_err(|e| MindgridError::pty("write_failed", "Failed to send /status to codex").with_technical(e))?;
             writer.flush()
-                .map_err(|e| format!("Failed to flush char: {}", e))?;
+                .map_err(|e| MindgridError::pty("write_failed", "Failed to send /status to codex").with_technical(e))?;
             thread::sleep(Duration::from_millis(30));
         }
 
@@ -542,9 +605,9 @@ fn get_codex_usage_blocking() -> Result<String, String> {
 
         // Press Enter to execute
         writer.write_all(b"\r")
-            .map_err(|e| format!("Failed to write enter: {}", e))?;
+            .map_err(|e| MindgridError::pty("write_failed", "Failed to send /status to codex").with_technical(e))?;
         writer.flush()
-            .map_err(|e| format!("Failed to flush enter: {}", e))?;
+            .map_err(|e| MindgridError::pty("write_failed", "Failed to send /status to codex").with_technical(e))?;
     }
 
     // Wait for status to render
@@ -566,7 +629,7 @@ fn get_codex_usage_blocking() -> Result<String, String> {
     let _ = reader_handle.join();
 
     if final_output.is_empty() {
-        Err("No output from Codex PTY".to_string())
+        Err(MindgridError::agent("no_output", "Codex produced no output").with_command("codex /status"))
     } else {
         Ok(final_output)
     }
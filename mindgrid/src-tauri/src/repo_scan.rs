@@ -0,0 +1,408 @@
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use ts_rs::TS;
+
+use crate::error::{MindgridError, MindgridResult};
+
+/// Default number of directory levels below the scan root to look at
+const DEFAULT_MAX_DEPTH: usize = 3;
+
+/// Directories that never contain repositories worth listing and are expensive to walk
+const DEFAULT_IGNORES: &[&str] = &[
+    "node_modules",
+    "target",
+    "vendor",
+    "dist",
+    "build",
+    "out",
+    "Pods",
+    "DerivedData",
+    "__pycache__",
+    "venv",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum RepoKind {
+    /// Regular checkout with a `.git` directory
+    Repository,
+    /// Linked worktree (`.git` file pointing at `<repo>/.git/worktrees/<name>`)
+    Worktree,
+    /// Submodule checkout (`.git` file pointing at `<repo>/.git/modules/<name>`)
+    Submodule,
+    /// Bare repository (HEAD, objects and refs directly in the directory)
+    Bare,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct GitRepoInfo {
+    pub name: String,
+    pub path: String,
+    pub kind: RepoKind,
+    /// Resolved git directory (differs from `<path>/.git` for worktrees and submodules)
+    pub git_dir: String,
+    /// Unix timestamp (seconds) of the last commit on HEAD
+    #[ts(type = "number | null")]
+    pub last_commit_at: Option<i64>,
+    /// URL of `origin`, or of the first remote if there is no origin
+    pub remote_url: Option<String>,
+}
+
+/// Options for a repository scan
+#[derive(Debug, Clone, Default)]
+pub struct ScanOptions {
+    pub max_depth: Option<usize>,
+    /// Directory names (or `*suffix` / `prefix*` patterns) to skip in addition to the defaults
+    pub ignore: Option<Vec<String>>,
+    /// Also descend into hidden directories (except `.git`)
+    pub include_hidden: Option<bool>,
+}
+
+/// Cache of scan results, keyed by scan root and options
+pub struct RepoScanCache {
+    entries: Mutex<HashMap<String, Vec<GitRepoInfo>>>,
+}
+
+impl RepoScanCache {
+    pub fn new() -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn invalidate_root(&self, root: &str) {
+        let prefix = format!("{}\u{0}", root);
+        self.entries.lock().retain(|key, _| !key.starts_with(&prefix));
+    }
+}
+
+fn cache_key(root: &str, options: &ScanOptions) -> String {
+    let mut ignore = options.ignore.clone().unwrap_or_default();
+    ignore.sort();
+    format!(
+        "{}\u{0}{}\u{0}{}\u{0}{}",
+        root,
+        options.max_depth.unwrap_or(DEFAULT_MAX_DEPTH),
+        options.include_hidden.unwrap_or(false),
+        ignore.join(",")
+    )
+}
+
+fn matches_ignore(name: &str, pattern: &str) -> bool {
+    if let Some(suffix) = pattern.strip_prefix('*') {
+        name.ends_with(suffix)
+    } else if let Some(prefix) = pattern.strip_suffix('*') {
+        name.starts_with(prefix)
+    } else {
+        name == pattern
+    }
+}
+
+/// Detect whether `dir` is a repository root, returning its kind and git dir
+pub fn detect_repo(dir: &Path) -> Option<(RepoKind, PathBuf)> {
+    let dot_git = dir.join(".git");
+
+    if let Ok(meta) = std::fs::symlink_metadata(&dot_git) {
+        if meta.is_dir() {
+            return Some((RepoKind::Repository, dot_git));
+        }
+        if meta.is_file() {
+            // "gitdir: <path>" written by `git worktree add` and submodules
+            let content = std::fs::read_to_string(&dot_git).ok()?;
+            let target = content.lines().find_map(|l| l.strip_prefix("gitdir:"))?.trim();
+            let target = PathBuf::from(target);
+            let git_dir = if target.is_absolute() { target } else { dir.join(target) };
+            let git_dir_str = git_dir.to_string_lossy().replace('\\', "/");
+
+            let kind = if git_dir_str.contains("/modules/") {
+                RepoKind::Submodule
+            } else {
+                RepoKind::Worktree
+            };
+            return Some((kind, git_dir));
+        }
+    }
+
+    let is_bare = dir.join("HEAD").is_file()
+        && dir.join("objects").is_dir()
+        && dir.join("refs").is_dir();
+    if is_bare {
+        return Some((RepoKind::Bare, dir.to_path_buf()));
+    }
+
+    None
+}
+
+fn git_output(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = std::process::Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if text.is_empty() {
+        None
+    } else {
+        Some(text)
+    }
+}
+
+fn repo_info(dir: &Path, kind: RepoKind, git_dir: PathBuf) -> GitRepoInfo {
+    let last_commit_at = git_output(dir, &["log", "-1", "--format=%ct"]).and_then(|t| t.parse().ok());
+
+    let remote_url = git_output(dir, &["config", "--get", "remote.origin.url"]).or_else(|| {
+        let first_remote = git_output(dir, &["remote"])?.lines().next()?.to_string();
+        git_output(dir, &["config", "--get", &format!("remote.{}.url", first_remote)])
+    });
+
+    let name = dir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+
+    GitRepoInfo {
+        name,
+        path: dir.to_string_lossy().to_string(),
+        kind,
+        git_dir: git_dir.to_string_lossy().to_string(),
+        last_commit_at,
+        remote_url,
+    }
+}
+
+/// Walk `root` in parallel and return every repository found within `max_depth` levels.
+/// Symlinked directories are not followed. Repositories are descended into so nested
+/// layouts (monorepos with embedded repos, submodules) are found as well.
+pub fn scan(root: &Path, options: &ScanOptions) -> Vec<GitRepoInfo> {
+    let max_depth = options.max_depth.unwrap_or(DEFAULT_MAX_DEPTH);
+    let include_hidden = options.include_hidden.unwrap_or(false);
+    let mut ignores: Vec<String> = DEFAULT_IGNORES.iter().map(|s| s.to_string()).collect();
+    ignores.extend(options.ignore.clone().unwrap_or_default());
+
+    // Directories to visit; `None` tells a worker to stop
+    let (queue, jobs) = mpsc::channel::<Option<(PathBuf, usize)>>();
+    let jobs = Mutex::new(jobs);
+    let results: Mutex<Vec<GitRepoInfo>> = Mutex::new(Vec::new());
+    // Directories queued or being processed; the scan is done when it reaches zero
+    let pending = AtomicUsize::new(0);
+
+    // The root itself is listed only if it is a repository
+    if let Some((kind, git_dir)) = detect_repo(root) {
+        results.lock().push(repo_info(root, kind, git_dir));
+    }
+    pending.fetch_add(1, Ordering::SeqCst);
+    let _ = queue.send(Some((root.to_path_buf(), 0)));
+
+    let workers = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4)
+        .min(8);
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let next = jobs.lock().recv();
+                let Ok(Some((dir, depth))) = next else {
+                    break;
+                };
+
+                if depth < max_depth {
+                    if let Ok(entries) = std::fs::read_dir(&dir) {
+                        for entry in entries.flatten() {
+                            // file_type() does not follow symlinks
+                            let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+                            if !is_dir {
+                                continue;
+                            }
+
+                            let name = entry.file_name().to_string_lossy().to_string();
+                            if name == ".git" || (!include_hidden && name.starts_with('.')) {
+                                continue;
+                            }
+                            if ignores.iter().any(|p| matches_ignore(&name, p)) {
+                                continue;
+                            }
+
+                            let path = entry.path();
+                            if let Some((kind, git_dir)) = detect_repo(&path) {
+                                results.lock().push(repo_info(&path, kind.clone(), git_dir));
+                                // Bare repositories only contain git internals
+                                if kind == RepoKind::Bare {
+                                    continue;
+                                }
+                            }
+
+                            pending.fetch_add(1, Ordering::SeqCst);
+                            let _ = queue.send(Some((path, depth + 1)));
+                        }
+                    }
+                }
+
+                // The last directory is done: wake every worker so it can stop
+                if pending.fetch_sub(1, Ordering::SeqCst) == 1 {
+                    for _ in 0..workers {
+                        let _ = queue.send(None);
+                    }
+                }
+            });
+        }
+    });
+
+    let mut repos = results.into_inner();
+    repos.sort_by_key(|r| r.path.to_lowercase());
+    repos
+}
+
+/// Scan a directory for git repositories, using the cache when possible
+pub async fn scan_cached(
+    cache: &RepoScanCache,
+    parent_directory: &str,
+    options: ScanOptions,
+) -> MindgridResult<Vec<GitRepoInfo>> {
+    let parent = Path::new(parent_directory);
+    if !parent.exists() || !parent.is_dir() {
+        return Err(MindgridError::validation("directory_missing", "Directory does not exist")
+            .with_technical(parent_directory));
+    }
+
+    let key = cache_key(parent_directory, &options);
+    if let Some(cached) = cache.entries.lock().get(&key) {
+        return Ok(cached.clone());
+    }
+
+    let root = parent.to_path_buf();
+    let scan_options = options.clone();
+    let repos = tokio::task::spawn_blocking(move || scan(&root, &scan_options))
+        .await
+        .map_err(|e| MindgridError::io("scan_failed", "Failed to scan for repositories").with_technical(e))?;
+
+    cache.entries.lock().insert(key, repos.clone());
+    Ok(repos)
+}
+
+/// Drop cached scan results for a directory and scan it again
+#[tauri::command]
+pub async fn refresh_git_repos(
+    cache: tauri::State<'_, Arc<RepoScanCache>>,
+    parent_directory: String,
+    max_depth: Option<usize>,
+    ignore: Option<Vec<String>>,
+    include_hidden: Option<bool>,
+) -> MindgridResult<Vec<GitRepoInfo>> {
+    cache.invalidate_root(&parent_directory);
+    let options = ScanOptions {
+        max_depth,
+        ignore,
+        include_hidden,
+    };
+    scan_cached(&cache, &parent_directory, options).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::fixtures::TempRepo;
+
+    /// Repositories found below `root`, relative to it, with their kinds
+    fn found(root: &TempRepo, options: &ScanOptions) -> Vec<(String, RepoKind)> {
+        scan(&root.path, options)
+            .into_iter()
+            .map(|r| {
+                let relative = Path::new(&r.path).strip_prefix(&root.path).unwrap();
+                (relative.to_string_lossy().to_string(), r.kind)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn ignore_patterns_and_cache_keys() {
+        assert!(matches_ignore("node_modules", "node_modules"));
+        assert!(matches_ignore("app.xcodeproj", "*.xcodeproj"));
+        assert!(matches_ignore("tmp-build", "tmp*"));
+        assert!(!matches_ignore("my_node_modules", "node_modules"));
+
+        let options = |ignore: &[&str]| ScanOptions {
+            ignore: Some(ignore.iter().map(|s| s.to_string()).collect()),
+            ..ScanOptions::default()
+        };
+        assert_eq!(cache_key("/src", &options(&["a", "b"])), cache_key("/src", &options(&["b", "a"])));
+        assert_ne!(cache_key("/src", &options(&["a"])), cache_key("/src/app", &options(&["a"])));
+    }
+
+    #[test]
+    fn finds_every_kind_of_repository() {
+        let root = TempRepo::with_files(&[("README.md", "root\n")]);
+        let library = TempRepo::with_files(&[("lib.txt", "library\n")]);
+        root.git(&["clone", "-q", &root.path_str(), "apps/web"]);
+        root.git(&["worktree", "add", "-q", "apps/web-wt"]);
+        root.git(&["clone", "-q", "--bare", &root.path_str(), "libs/shared.git"]);
+        root.git(&["-c", "protocol.file.allow=always", "submodule", "add", "-q", &library.path_str(), "deps/lib"]);
+        root.git(&["init", "-q", "node_modules/pkg"]);
+        root.git(&["init", "-q", ".hidden/tool"]);
+        root.git(&["init", "-q", "a/b/c/deep"]);
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(root.path.join("apps"), root.path.join("linked")).unwrap();
+
+        assert_eq!(
+            found(&root, &ScanOptions::default()),
+            vec![
+                (String::new(), RepoKind::Repository),
+                ("apps/web".to_string(), RepoKind::Repository),
+                ("apps/web-wt".to_string(), RepoKind::Worktree),
+                ("deps/lib".to_string(), RepoKind::Submodule),
+                ("libs/shared.git".to_string(), RepoKind::Bare),
+            ]
+        );
+
+        let web = scan(&root.path, &ScanOptions::default())
+            .into_iter()
+            .find(|r| r.path.ends_with("apps/web"))
+            .unwrap();
+        assert_eq!(web.remote_url, Some(root.path_str()));
+        assert!(web.last_commit_at.is_some());
+        assert!(web.git_dir.ends_with(".git"));
+
+        let wider = ScanOptions {
+            max_depth: Some(4),
+            ignore: Some(vec!["web*".to_string()]),
+            include_hidden: Some(true),
+        };
+        assert_eq!(
+            found(&root, &wider),
+            vec![
+                (String::new(), RepoKind::Repository),
+                (".hidden/tool".to_string(), RepoKind::Repository),
+                ("a/b/c/deep".to_string(), RepoKind::Repository),
+                ("deps/lib".to_string(), RepoKind::Submodule),
+                ("libs/shared.git".to_string(), RepoKind::Bare),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn cached_scans_are_refreshed_per_root() {
+        let root = TempRepo::new();
+        let root_str = root.path_str();
+        let cache = RepoScanCache::new();
+        let scan_root = |cache| scan_cached(cache, &root_str, ScanOptions::default());
+
+        assert_eq!(scan_root(&cache).await.unwrap().len(), 1);
+        root.git(&["init", "-q", "added"]);
+        assert_eq!(scan_root(&cache).await.unwrap().len(), 1);
+        cache.invalidate_root(&root_str);
+        assert_eq!(scan_root(&cache).await.unwrap().len(), 2);
+
+        let missing = scan_cached(&cache, "/nonexistent/mindgrid-scan", ScanOptions::default()).await;
+        assert_eq!(missing.unwrap_err().code, "validation.directory_missing");
+    }
+}
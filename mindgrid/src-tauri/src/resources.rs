@@ -0,0 +1,497 @@
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager};
+use ts_rs::TS;
+
+use crate::error::{MindgridError, MindgridResult};
+use crate::pty::{PtyInfo, PtyState};
+
+/// Disk usage older than this is recomputed on the next request
+const SIZE_TTL: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub command: String,
+    /// Resident memory in KiB
+    #[ts(type = "number")]
+    pub rss_kb: u64,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct PtyResource {
+    pub pty: PtyInfo,
+    /// The PTY's process and everything it spawned
+    pub processes: Vec<ProcessInfo>,
+    #[ts(type = "number")]
+    pub rss_kb: u64,
+}
+
+/// Result of walking a worktree on disk
+#[derive(Debug, Clone, Copy, Serialize, TS)]
+#[ts(export)]
+pub struct DiskUsage {
+    /// Allocated size, counting hardlinked files once and not following symlinks
+    #[ts(type = "number")]
+    pub total_bytes: u64,
+    /// Part of `total_bytes` in git-ignored paths (build output, dependencies)
+    #[ts(type = "number")]
+    pub artifact_bytes: u64,
+    #[ts(type = "number")]
+    pub file_count: u64,
+    /// Newest modification time of a non-ignored file (Unix seconds)
+    #[ts(type = "number | null")]
+    pub last_activity: Option<i64>,
+    /// When this was computed (Unix seconds)
+    #[ts(type = "number")]
+    pub computed_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct WorktreeResources {
+    pub path: String,
+    pub branch: Option<String>,
+    pub is_main: bool,
+    /// `None` until the background scan has finished once
+    pub disk: Option<DiskUsage>,
+    /// A background scan for this worktree is in progress
+    pub computing: bool,
+    pub ptys: Vec<PtyResource>,
+}
+
+/// Cached disk usage per worktree and scans in flight
+pub struct ResourceState {
+    usage: Mutex<HashMap<String, (DiskUsage, SystemTime)>>,
+    computing: Mutex<HashSet<String>>,
+}
+
+impl ResourceState {
+    pub fn new() -> Self {
+        Self {
+            usage: Mutex::new(HashMap::new()),
+            computing: Mutex::new(HashSet::new()),
+        }
+    }
+}
+
+fn now_secs() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// (path, branch, is_main) for every worktree of the project
+pub(crate) async fn list_worktrees(project_path: &str) -> MindgridResult<Vec<(String, Option<String>, bool)>> {
+    let args = ["worktree", "list", "--porcelain"];
+    let output = tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(project_path)
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| MindgridError::spawn_failed("git worktree list", e))?;
+
+    if !output.status.success() {
+        return Err(MindgridError::git_failed("worktree_list_failed", "Failed to list worktrees", &args, &output));
+    }
+
+    Ok(parse_worktree_list(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Parse `git worktree list --porcelain`
+fn parse_worktree_list(text: &str) -> Vec<(String, Option<String>, bool)> {
+    let mut worktrees: Vec<(String, Option<String>, bool)> = Vec::new();
+    let mut is_bare = false;
+    let mut first = true;
+    for line in text.lines() {
+        if let Some(path) = line.strip_prefix("worktree ") {
+            // The first entry is the main worktree (or the bare repository itself)
+            worktrees.push((path.to_string(), None, first));
+            first = false;
+            is_bare = false;
+        } else if let Some(branch) = line.strip_prefix("branch ") {
+            if let Some(last) = worktrees.last_mut() {
+                last.1 = Some(branch.trim_start_matches("refs/heads/").to_string());
+            }
+        } else if line == "bare" {
+            is_bare = true;
+        } else if line.is_empty() && is_bare {
+            // A bare repository has no working tree to measure
+            worktrees.pop();
+            is_bare = false;
+        }
+    }
+    if is_bare {
+        worktrees.pop();
+    }
+    worktrees
+}
+
+/// Git-ignored paths in a worktree, relative, without trailing slashes
+async fn ignored_paths(worktree: &str) -> HashSet<PathBuf> {
+    let output = tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(worktree)
+        .args(["ls-files", "--others", "--ignored", "--exclude-standard", "--directory", "-z"])
+        .output()
+        .await;
+
+    match output {
+        Ok(o) if o.status.success() => String::from_utf8_lossy(&o.stdout)
+            .split('\0')
+            .filter(|p| !p.is_empty())
+            .map(|p| PathBuf::from(p.trim_end_matches('/')))
+            .collect(),
+        _ => HashSet::new(),
+    }
+}
+
+#[cfg(unix)]
+fn allocated_size(meta: &std::fs::Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    meta.blocks() * 512
+}
+
+#[cfg(not(unix))]
+fn allocated_size(meta: &std::fs::Metadata) -> u64 {
+    meta.len()
+}
+
+/// Identity of a file with several hardlinks, so it is only counted once
+#[cfg(unix)]
+fn hardlink_key(meta: &std::fs::Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    if meta.nlink() > 1 {
+        Some((meta.dev(), meta.ino()))
+    } else {
+        None
+    }
+}
+
+#[cfg(not(unix))]
+fn hardlink_key(_meta: &std::fs::Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+/// Walk a worktree without following symlinks. Other worktrees nested inside it
+/// (`.mindgrid/worktrees/*` in the main checkout) are skipped so nothing is counted twice.
+fn measure(root: &Path, ignored: &HashSet<PathBuf>, skip: &HashSet<PathBuf>) -> DiskUsage {
+    let mut usage = DiskUsage {
+        total_bytes: 0,
+        artifact_bytes: 0,
+        file_count: 0,
+        last_activity: None,
+        computed_at: now_secs(),
+    };
+    let mut seen_links: HashSet<(u64, u64)> = HashSet::new();
+    let mut stack: Vec<(PathBuf, bool)> = vec![(root.to_path_buf(), false)];
+
+    while let Some((dir, in_artifact)) = stack.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(meta) = std::fs::symlink_metadata(&path) else {
+                continue;
+            };
+
+            let relative = path.strip_prefix(root).unwrap_or(&path);
+            let is_artifact = in_artifact || ignored.contains(relative);
+            let is_git_dir = relative.starts_with(".git");
+
+            if meta.is_dir() {
+                if !skip.contains(&path) {
+                    stack.push((path, is_artifact));
+                }
+                continue;
+            }
+
+            if let Some(key) = hardlink_key(&meta) {
+                if !seen_links.insert(key) {
+                    continue;
+                }
+            }
+
+            let size = allocated_size(&meta);
+            usage.total_bytes += size;
+            usage.file_count += 1;
+            if is_artifact {
+                usage.artifact_bytes += size;
+            } else if !is_git_dir {
+                let modified = meta
+                    .modified()
+                    .ok()
+                    .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                    .map(|d| d.as_secs() as i64);
+                if modified > usage.last_activity {
+                    usage.last_activity = modified;
+                }
+            }
+        }
+    }
+
+    usage
+}
+
+/// (pid, ppid, rss_kb, command) for every process on the system, from a single `ps`
+#[cfg(unix)]
+async fn process_table() -> Vec<(u32, u32, u64, String)> {
+    let output = tokio::process::Command::new("ps")
+        .args(["-A", "-o", "pid=,ppid=,rss=,args="])
+        .output()
+        .await;
+    match output {
+        Ok(output) => parse_process_table(&String::from_utf8_lossy(&output.stdout)),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Parse `ps -A -o pid=,ppid=,rss=,args=`
+#[cfg(unix)]
+fn parse_process_table(text: &str) -> Vec<(u32, u32, u64, String)> {
+    text.lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let pid = parts.next()?.parse().ok()?;
+            let ppid = parts.next()?.parse().ok()?;
+            let rss = parts.next()?.parse().unwrap_or(0);
+            let command = parts.collect::<Vec<_>>().join(" ");
+            Some((pid, ppid, rss, command))
+        })
+        .collect()
+}
+
+#[cfg(not(unix))]
+async fn process_table() -> Vec<(u32, u32, u64, String)> {
+    Vec::new()
+}
+
+/// A PTY's process tree
+fn pty_resource(pty: PtyInfo, table: &[(u32, u32, u64, String)]) -> PtyResource {
+    let mut processes = Vec::new();
+    if let Some(root) = pty.pid {
+        let mut frontier = vec![root];
+        while let Some(pid) = frontier.pop() {
+            if let Some((_, _, rss, command)) = table.iter().find(|(p, ..)| *p == pid) {
+                processes.push(ProcessInfo {
+                    pid,
+                    command: command.clone(),
+                    rss_kb: *rss,
+                });
+            }
+            frontier.extend(table.iter().filter(|(_, ppid, ..)| *ppid == pid).map(|(p, ..)| *p));
+        }
+    }
+    let rss_kb = processes.iter().map(|p| p.rss_kb).sum();
+    PtyResource { pty, processes, rss_kb }
+}
+
+/// Recompute disk usage for the given worktrees one at a time, emitting
+/// `worktree-resources-updated` after each so the UI fills in progressively
+fn spawn_measurement(app: AppHandle, worktrees: Vec<(String, Option<String>, bool)>, all_paths: HashSet<PathBuf>) {
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<Arc<ResourceState>>().inner().clone();
+
+        for (path, branch, is_main) in worktrees {
+            let ignored = ignored_paths(&path).await;
+            let skip: HashSet<PathBuf> = all_paths
+                .iter()
+                .filter(|p| p.as_path() != Path::new(&path))
+                .cloned()
+                .collect();
+
+            let root = PathBuf::from(&path);
+            let usage = match tokio::task::spawn_blocking(move || measure(&root, &ignored, &skip)).await {
+                Ok(usage) => usage,
+                Err(e) => {
+                    eprintln!("[MindGrid] Failed to measure worktree {}: {}", path, e);
+                    state.computing.lock().remove(&path);
+                    continue;
+                }
+            };
+
+            state.usage.lock().insert(path.clone(), (usage, SystemTime::now()));
+            state.computing.lock().remove(&path);
+
+            let _ = app.emit(
+                "worktree-resources-updated",
+                WorktreeResources {
+                    path,
+                    branch,
+                    is_main,
+                    disk: Some(usage),
+                    computing: false,
+                    ptys: Vec::new(),
+                },
+            );
+        }
+    });
+}
+
+/// Per-worktree disk usage, build artifact size, last activity and running PTYs.
+/// Returns cached disk usage immediately and refreshes stale entries in the background
+/// (`worktree-resources-updated` is emitted as each worktree finishes).
+#[tauri::command]
+pub async fn get_worktree_resources(
+    app: AppHandle,
+    state: tauri::State<'_, Arc<ResourceState>>,
+    pty_state: tauri::State<'_, Arc<PtyState>>,
+    project_path: String,
+    refresh: Option<bool>,
+) -> MindgridResult<Vec<WorktreeResources>> {
+    let worktrees = list_worktrees(&project_path).await?;
+    let force = refresh.unwrap_or(false);
+
+    // Decide what needs (re)measuring and mark it as in progress
+    let mut stale = Vec::new();
+    {
+        let usage = state.usage.lock();
+        let mut computing = state.computing.lock();
+        for (path, branch, is_main) in &worktrees {
+            let fresh = usage
+                .get(path)
+                .map(|(_, at)| at.elapsed().map(|e| e < SIZE_TTL).unwrap_or(false))
+                .unwrap_or(false);
+            if (force || !fresh) && computing.insert(path.clone()) {
+                stale.push((path.clone(), branch.clone(), *is_main));
+            }
+        }
+    }
+
+    let ptys = pty_state.list();
+    let table = if ptys.is_empty() { Vec::new() } else { process_table().await };
+
+    let result = {
+        let usage = state.usage.lock();
+        let computing = state.computing.lock();
+        worktrees
+            .iter()
+            .map(|(path, branch, is_main)| {
+                let worktree = Path::new(path);
+                let ptys = ptys
+                    .iter()
+                    .filter(|p| {
+                        let Some(cwd) = &p.cwd else {
+                            return false;
+                        };
+                        // PTYs in a nested worktree belong to that worktree, not the main one
+                        Path::new(cwd).starts_with(worktree)
+                            && !worktrees.iter().any(|(other, ..)| {
+                                other != path
+                                    && Path::new(other).starts_with(worktree)
+                                    && Path::new(cwd).starts_with(other)
+                            })
+                    })
+                    .map(|p| pty_resource(p.clone(), &table))
+                    .collect();
+                WorktreeResources {
+                    path: path.clone(),
+                    branch: branch.clone(),
+                    is_main: *is_main,
+                    disk: usage.get(path).map(|(u, _)| *u),
+                    computing: computing.contains(path),
+                    ptys,
+                }
+            })
+            .collect()
+    };
+
+    if !stale.is_empty() {
+        let all_paths = worktrees.iter().map(|(p, ..)| PathBuf::from(p)).collect();
+        spawn_measurement(app, stale, all_paths);
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_worktree_list() {
+        // Captured from `git worktree list --porcelain`
+        let checkout = "worktree /tmp/capb\nHEAD f37ecbdc3858a6bc442808c3a15f69bc9dcb5c46\nbranch refs/heads/main\n\n\
+                        worktree /tmp/capb-det\nHEAD f37ecbdc3858a6bc442808c3a15f69bc9dcb5c46\ndetached\n\n\
+                        worktree /tmp/capb-wt\nHEAD f37ecbdc3858a6bc442808c3a15f69bc9dcb5c46\nbranch refs/heads/mindgrid/wt\n\n";
+        assert_eq!(
+            parse_worktree_list(checkout),
+            [
+                ("/tmp/capb".to_string(), Some("main".to_string()), true),
+                ("/tmp/capb-det".to_string(), None, false),
+                ("/tmp/capb-wt".to_string(), Some("mindgrid/wt".to_string()), false),
+            ]
+        );
+
+        // A bare repository lists itself first; its linked worktrees are not the main one
+        let bare = "worktree /tmp/capb.git\nbare\n\n\
+                    worktree /tmp/capb-bare-wt\nHEAD f37ecbdc3858a6bc442808c3a15f69bc9dcb5c46\nbranch refs/heads/capb-bare-wt\n\n";
+        assert_eq!(
+            parse_worktree_list(bare),
+            [("/tmp/capb-bare-wt".to_string(), Some("capb-bare-wt".to_string()), false)]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn builds_pty_process_trees() {
+        // Captured from `ps -A -o pid=,ppid=,rss=,args=`, trimmed
+        let table = parse_process_table(
+            "    1     0  9784 /sbin/init splash\n\
+             4100     1  2048 zsh -l\n\
+             4200  4100 51200 node   server.js --port 3000\n\
+             4201  4200  1024 esbuild --service=0.19.0\n\
+             4300     1   512 sleep 30\n",
+        );
+        assert_eq!(table[2], (4200, 4100, 51200, "node server.js --port 3000".to_string()));
+
+        let pty = PtyInfo {
+            id: "pty-1".to_string(),
+            pid: Some(4100),
+            cmd: "zsh".to_string(),
+            cwd: None,
+            started_at: 0,
+        };
+        let resource = pty_resource(pty, &table);
+        let mut pids: Vec<u32> = resource.processes.iter().map(|p| p.pid).collect();
+        pids.sort();
+        assert_eq!(pids, [4100, 4200, 4201]);
+        assert_eq!(resource.rss_kb, 2048 + 51200 + 1024);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn measures_each_file_once() {
+        let root = std::env::temp_dir().join(format!("mindgrid-measure-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join("target/debug")).unwrap();
+        std::fs::create_dir_all(root.join(".mindgrid/worktrees/other")).unwrap();
+        std::fs::write(root.join("src/main.rs"), "fn main() {}\n".repeat(500)).unwrap();
+        std::fs::write(root.join("target/debug/app"), vec![7u8; 64 * 1024]).unwrap();
+        std::fs::hard_link(root.join("src/main.rs"), root.join("src/alias.rs")).unwrap();
+        std::os::unix::fs::symlink(root.join("target/debug/app"), root.join("app-link")).unwrap();
+        std::fs::write(root.join(".mindgrid/worktrees/other/file"), vec![1u8; 32 * 1024]).unwrap();
+
+        let size = |path: &str| allocated_size(&std::fs::symlink_metadata(root.join(path)).unwrap());
+        let (source, binary, link) = (size("src/main.rs"), size("target/debug/app"), size("app-link"));
+        let ignored: HashSet<PathBuf> = [PathBuf::from("target")].into();
+        let skip: HashSet<PathBuf> = [root.join(".mindgrid/worktrees/other")].into();
+        let usage = measure(&root, &ignored, &skip);
+        let _ = std::fs::remove_dir_all(&root);
+
+        // The hardlink counts once, the symlink as itself, the nested worktree not at all
+        assert_eq!(usage.file_count, 3);
+        assert_eq!(usage.total_bytes, source + binary + link);
+        assert_eq!(usage.artifact_bytes, binary);
+        assert!(usage.last_activity.is_some());
+    }
+}
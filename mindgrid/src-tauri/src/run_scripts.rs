@@ -0,0 +1,695 @@
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Sqlite};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::ops::Range;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::sync::Notify;
+use ts_rs::TS;
+
+use crate::error::{MindgridError, MindgridResult};
+
+/// Ports handed out to scripts through `$PORT`, so parallel sessions don't collide
+const PORT_RANGE: Range<u16> = 4100..4900;
+/// Output lines kept for the exit event
+const TAIL_LINES: usize = 40;
+/// Crash restarts in a row before giving up; a run that stays up for
+/// `STABLE_RUN` resets the count
+const MAX_RESTARTS: u32 = 5;
+const STABLE_RUN: Duration = Duration::from_secs(60);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// A named command for a project, run in a session's worktree
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct RunScript {
+    pub project_id: String,
+    /// e.g. "dev", "test", "build", "lint"
+    pub name: String,
+    /// Shell command; `$PORT` holds the port allocated for the run
+    pub command: String,
+    /// Start the script again when it exits with an error
+    pub restart_on_crash: bool,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export, optional_fields = nullable)]
+pub struct SaveRunScriptArgs {
+    pub project_id: String,
+    pub name: String,
+    pub command: String,
+    pub restart_on_crash: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum ScriptStatus {
+    Running,
+    /// Crashed and waiting to be started again
+    Restarting,
+    /// Exited with code 0
+    Exited,
+    /// Exited with an error and won't be restarted
+    Crashed,
+    /// Stopped from mindgrid
+    Stopped,
+}
+
+/// A script started in a session; finished runs are kept until the script
+/// is started again or the session stops
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct RunningScript {
+    pub session_id: String,
+    pub name: String,
+    pub command: String,
+    pub cwd: String,
+    pub port: u16,
+    pub pid: Option<u32>,
+    pub status: ScriptStatus,
+    /// Crash restarts in a row
+    pub restarts: u32,
+    pub exit_code: Option<i32>,
+    /// Unix timestamp (milliseconds) of the current run
+    #[ts(type = "number")]
+    pub started_at: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum ScriptStream {
+    Stdout,
+    Stderr,
+}
+
+/// Payload of the `run-script-output` event
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct ScriptOutput {
+    pub session_id: String,
+    pub name: String,
+    pub stream: ScriptStream,
+    pub line: String,
+}
+
+/// Payload of the `run-script-exited` event, worded so it can be passed to
+/// the session's agent as is
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct ScriptExited {
+    pub session_id: String,
+    pub name: String,
+    /// `None` when the process was killed by a signal
+    pub exit_code: Option<i32>,
+    pub status: ScriptStatus,
+    pub will_restart: bool,
+    /// e.g. "dev exited with code 1"
+    pub summary: String,
+    /// Last lines of output, oldest first
+    pub output_tail: Vec<String>,
+}
+
+pub(crate) fn exit_summary(name: &str, exit_code: Option<i32>, status: ScriptStatus) -> String {
+    match (status, exit_code) {
+        (ScriptStatus::Stopped, _) => format!("{} was stopped", name),
+        (_, Some(code)) => format!("{} exited with code {}", name, code),
+        (_, None) => format!("{} was killed by a signal", name),
+    }
+}
+
+/// First port in `range` that isn't `taken` and passes `is_free`, trying
+/// `preferred` (the port of a previous run) first
+pub(crate) fn next_free_port(
+    range: Range<u16>,
+    preferred: Option<u16>,
+    taken: &HashSet<u16>,
+    is_free: impl Fn(u16) -> bool,
+) -> Option<u16> {
+    preferred
+        .into_iter()
+        .chain(range)
+        .find(|port| !taken.contains(port) && is_free(*port))
+}
+
+fn port_is_free(port: u16) -> bool {
+    std::net::TcpListener::bind(("127.0.0.1", port)).is_ok()
+}
+
+struct ScriptProcess {
+    info: Mutex<RunningScript>,
+    stopping: AtomicBool,
+    stopped: Notify,
+}
+
+impl ScriptProcess {
+    fn is_live(&self) -> bool {
+        matches!(self.info.lock().status, ScriptStatus::Running | ScriptStatus::Restarting)
+    }
+
+    fn stop(&self) {
+        self.stopping.store(true, Ordering::SeqCst);
+        self.stopped.notify_one();
+        if let Some(pid) = self.info.lock().pid {
+            crate::operations::kill_tree(pid);
+        }
+    }
+}
+
+/// Scripts per (session id, script name)
+pub struct RunScriptState {
+    scripts: Mutex<HashMap<(String, String), Arc<ScriptProcess>>>,
+}
+
+impl RunScriptState {
+    pub fn new() -> Self {
+        Self {
+            scripts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Stop every script of a session, e.g. when it's paused or deleted
+    pub fn stop_session(&self, session_id: &str) -> usize {
+        let mut scripts = self.scripts.lock();
+        let keys: Vec<(String, String)> = scripts.keys().filter(|(s, _)| s == session_id).cloned().collect();
+        let mut stopped = 0;
+        for key in keys {
+            if let Some(process) = scripts.remove(&key) {
+                if process.is_live() {
+                    process.stop();
+                    stopped += 1;
+                }
+            }
+        }
+        stopped
+    }
+
+    /// Pid and allocated port of each live script of a session
+    pub fn session_processes(&self, session_id: &str) -> Vec<(u32, u16)> {
+        self.scripts
+            .lock()
+            .iter()
+            .filter(|((s, _), process)| s == session_id && process.is_live())
+            .filter_map(|(_, process)| {
+                let info = process.info.lock();
+                info.pid.map(|pid| (pid, info.port))
+            })
+            .collect()
+    }
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+fn db_error(code: &'static str, message: &'static str) -> impl Fn(sqlx::Error) -> MindgridError {
+    move |e| MindgridError::db(code, message).with_technical(e)
+}
+
+async fn pool(app: &AppHandle) -> MindgridResult<Pool<Sqlite>> {
+    crate::db::get_pool(app)
+        .await
+        .map_err(|e| MindgridError::db("unavailable", "The script database is not loaded").with_technical(e))
+}
+
+fn validate_name(name: &str) -> MindgridResult<()> {
+    let valid = !name.is_empty()
+        && name.len() <= 40
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == ':');
+    if valid {
+        Ok(())
+    } else {
+        Err(MindgridError::validation(
+            "invalid_script_name",
+            "Script names may only contain letters, digits, '-', '_' and ':'",
+        )
+        .with_technical(name))
+    }
+}
+
+type ScriptRow = (String, String, String, i64);
+
+fn to_script((project_id, name, command, restart_on_crash): ScriptRow) -> RunScript {
+    RunScript {
+        project_id,
+        name,
+        command,
+        restart_on_crash: restart_on_crash != 0,
+    }
+}
+
+#[tauri::command]
+pub async fn list_run_scripts(app: AppHandle, project_id: String) -> MindgridResult<Vec<RunScript>> {
+    load_scripts(&pool(&app).await?, &project_id).await
+}
+
+async fn load_scripts(pool: &Pool<Sqlite>, project_id: &str) -> MindgridResult<Vec<RunScript>> {
+    let rows: Vec<ScriptRow> = sqlx::query_as(
+        "SELECT project_id, name, command, restart_on_crash FROM run_scripts WHERE project_id = ? ORDER BY name",
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await
+    .map_err(db_error("query_failed", "Failed to load run scripts"))?;
+    Ok(rows.into_iter().map(to_script).collect())
+}
+
+async fn upsert_script(pool: &Pool<Sqlite>, script: &RunScript) -> MindgridResult<()> {
+    sqlx::query(
+        "INSERT INTO run_scripts (project_id, name, command, restart_on_crash, updated_at) VALUES (?, ?, ?, ?, ?) \
+         ON CONFLICT(project_id, name) DO UPDATE SET command = excluded.command, \
+         restart_on_crash = excluded.restart_on_crash, updated_at = excluded.updated_at",
+    )
+    .bind(&script.project_id)
+    .bind(&script.name)
+    .bind(&script.command)
+    .bind(script.restart_on_crash as i64)
+    .bind(now_ms())
+    .execute(pool)
+    .await
+    .map_err(db_error("insert_failed", "Failed to save the run script"))?;
+    Ok(())
+}
+
+/// Create or replace a project's script
+#[tauri::command]
+pub async fn save_run_script(app: AppHandle, args: SaveRunScriptArgs) -> MindgridResult<RunScript> {
+    let name = args.name.trim().to_string();
+    validate_name(&name)?;
+    let command = args.command.trim().to_string();
+    if command.is_empty() {
+        return Err(MindgridError::validation("empty_command", "The script needs a command"));
+    }
+    // Projects live in the app store, so the database can't check the reference
+    crate::app_store::project(&app, &args.project_id)?;
+    let script = RunScript {
+        project_id: args.project_id,
+        name,
+        command,
+        restart_on_crash: args.restart_on_crash.unwrap_or(false),
+    };
+
+    upsert_script(&pool(&app).await?, &script).await?;
+    Ok(script)
+}
+
+#[tauri::command]
+pub async fn delete_run_script(app: AppHandle, project_id: String, name: String) -> MindgridResult<()> {
+    let pool = pool(&app).await?;
+    sqlx::query("DELETE FROM run_scripts WHERE project_id = ? AND name = ?")
+        .bind(&project_id)
+        .bind(&name)
+        .execute(&pool)
+        .await
+        .map_err(db_error("delete_failed", "Failed to delete the run script"))?;
+    Ok(())
+}
+
+/// Start a project script in the session's worktree with its own `$PORT`.
+/// A script that is already running is returned as is.
+#[tauri::command]
+pub async fn start_run_script(
+    app: AppHandle,
+    state: tauri::State<'_, Arc<RunScriptState>>,
+    session_id: String,
+    name: String,
+) -> MindgridResult<RunningScript> {
+    let session = crate::app_store::session(&app, &session_id)?;
+    let cwd = session.cwd;
+    let pool = pool(&app).await?;
+    let row: Option<ScriptRow> = sqlx::query_as(
+        "SELECT project_id, name, command, restart_on_crash FROM run_scripts WHERE project_id = ? AND name = ?",
+    )
+    .bind(&session.project_id)
+    .bind(&name)
+    .fetch_optional(&pool)
+    .await
+    .map_err(db_error("query_failed", "Failed to load the run script"))?;
+    let script = row.map(to_script).ok_or_else(|| {
+        MindgridError::validation("script_not_found", format!("The project has no \"{}\" script", name))
+    })?;
+    if !std::path::Path::new(&cwd).is_dir() {
+        return Err(MindgridError::validation("worktree_missing", "The session's worktree no longer exists")
+            .with_technical(&cwd));
+    }
+
+    let key = (session_id.clone(), name.clone());
+    let process = {
+        let mut scripts = state.scripts.lock();
+        let previous = scripts.get(&key).cloned();
+        if let Some(previous) = previous.as_ref().filter(|p| p.is_live()) {
+            return Ok(previous.info.lock().clone());
+        }
+        let taken: HashSet<u16> = scripts
+            .values()
+            .filter(|p| p.is_live())
+            .map(|p| p.info.lock().port)
+            .collect();
+        let preferred = previous.map(|p| p.info.lock().port);
+        let port = next_free_port(PORT_RANGE, preferred, &taken, port_is_free)
+            .ok_or_else(|| MindgridError::validation("no_free_port", "No free port left for run scripts"))?;
+
+        let process = Arc::new(ScriptProcess {
+            info: Mutex::new(RunningScript {
+                session_id: session_id.clone(),
+                name: name.clone(),
+                command: script.command.clone(),
+                cwd,
+                port,
+                pid: None,
+                status: ScriptStatus::Running,
+                restarts: 0,
+                exit_code: None,
+                started_at: now_ms(),
+            }),
+            stopping: AtomicBool::new(false),
+            stopped: Notify::new(),
+        });
+        scripts.insert(key, process.clone());
+        process
+    };
+
+    let run = match spawn_run(&app, &process) {
+        Ok(run) => run,
+        Err(e) => {
+            process.info.lock().status = ScriptStatus::Crashed;
+            return Err(e);
+        }
+    };
+    let info = process.info.lock().clone();
+    tauri::async_runtime::spawn(supervise(app, process, run, script.restart_on_crash));
+    Ok(info)
+}
+
+#[tauri::command]
+pub async fn stop_run_script(
+    state: tauri::State<'_, Arc<RunScriptState>>,
+    session_id: String,
+    name: String,
+) -> MindgridResult<()> {
+    let process = state.scripts.lock().get(&(session_id, name)).cloned();
+    if let Some(process) = process.filter(|p| p.is_live()) {
+        process.stop();
+    }
+    Ok(())
+}
+
+/// Scripts started in a session, or in every session when `session_id` is omitted
+#[tauri::command]
+pub async fn list_running_scripts(
+    state: tauri::State<'_, Arc<RunScriptState>>,
+    session_id: Option<String>,
+) -> MindgridResult<Vec<RunningScript>> {
+    let mut scripts: Vec<RunningScript> = state
+        .scripts
+        .lock()
+        .values()
+        .map(|p| p.info.lock().clone())
+        .filter(|info| session_id.as_ref().is_none_or(|id| &info.session_id == id))
+        .collect();
+    scripts.sort_by(|a, b| (&a.session_id, &a.name).cmp(&(&b.session_id, &b.name)));
+    Ok(scripts)
+}
+
+/// One run of a script: its process and the tasks streaming its output
+struct Run {
+    child: tokio::process::Child,
+    tail: Arc<Mutex<VecDeque<String>>>,
+    readers: Vec<tauri::async_runtime::JoinHandle<()>>,
+}
+
+/// Start one run of the script and stream its output
+fn spawn_run(app: &AppHandle, process: &ScriptProcess) -> MindgridResult<Run> {
+    let info = process.info.lock().clone();
+    let mut cmd = if cfg!(windows) {
+        let mut cmd = tokio::process::Command::new("cmd");
+        cmd.args(["/C", &info.command]);
+        cmd
+    } else {
+        let mut cmd = tokio::process::Command::new("sh");
+        cmd.args(["-c", &info.command]);
+        cmd
+    };
+    // Own process group, so stopping also ends the servers the script starts
+    #[cfg(unix)]
+    cmd.process_group(0);
+    let mut child = cmd
+        .current_dir(&info.cwd)
+        .env("PORT", info.port.to_string())
+        .env("MINDGRID_PORT", info.port.to_string())
+        .env("MINDGRID_SESSION_ID", &info.session_id)
+        .env("MINDGRID_SCRIPT", &info.name)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| MindgridError::spawn_failed(&info.command, e))?;
+
+    let tail = Arc::new(Mutex::new(VecDeque::with_capacity(TAIL_LINES)));
+    let mut readers = Vec::new();
+    if let Some(stdout) = child.stdout.take() {
+        readers.push(stream_lines(app.clone(), &info, ScriptStream::Stdout, stdout, tail.clone()));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        readers.push(stream_lines(app.clone(), &info, ScriptStream::Stderr, stderr, tail.clone()));
+    }
+
+    let info = {
+        let mut current = process.info.lock();
+        current.pid = child.id();
+        current.status = ScriptStatus::Running;
+        current.exit_code = None;
+        current.started_at = now_ms();
+        current.clone()
+    };
+    let _ = app.emit("run-script-updated", &info);
+    Ok(Run { child, tail, readers })
+}
+
+fn stream_lines(
+    app: AppHandle,
+    info: &RunningScript,
+    stream: ScriptStream,
+    reader: impl AsyncRead + Unpin + Send + 'static,
+    tail: Arc<Mutex<VecDeque<String>>>,
+) -> tauri::async_runtime::JoinHandle<()> {
+    let session_id = info.session_id.clone();
+    let name = info.name.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut lines = BufReader::new(reader).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            {
+                let mut tail = tail.lock();
+                if tail.len() == TAIL_LINES {
+                    tail.pop_front();
+                }
+                tail.push_back(line.clone());
+            }
+            let _ = app.emit(
+                "run-script-output",
+                ScriptOutput {
+                    session_id: session_id.clone(),
+                    name: name.clone(),
+                    stream,
+                    line,
+                },
+            );
+        }
+    })
+}
+
+/// Wait for each run to end, report it, and restart crashed runs with backoff
+async fn supervise(app: AppHandle, process: Arc<ScriptProcess>, mut run: Run, restart_on_crash: bool) {
+    loop {
+        let started = tokio::time::Instant::now();
+        let exit_code = match run.child.wait().await {
+            Ok(status) => status.code(),
+            Err(e) => {
+                eprintln!("[MindGrid] Failed to wait for run script: {}", e);
+                None
+            }
+        };
+        // Take down whatever the script left running; it would hold the port
+        // (and the output pipes the readers wait on)
+        if let Some(pid) = process.info.lock().pid {
+            crate::operations::kill_tree(pid);
+        }
+        for reader in run.readers {
+            let _ = reader.await;
+        }
+
+        let stopping = process.stopping.load(Ordering::SeqCst);
+        let (status, will_restart, delay) = {
+            let mut info = process.info.lock();
+            if started.elapsed() >= STABLE_RUN {
+                info.restarts = 0;
+            }
+            let status = match exit_code {
+                _ if stopping => ScriptStatus::Stopped,
+                Some(0) => ScriptStatus::Exited,
+                _ if restart_on_crash && info.restarts < MAX_RESTARTS => ScriptStatus::Restarting,
+                _ => ScriptStatus::Crashed,
+            };
+            let will_restart = status == ScriptStatus::Restarting;
+            if will_restart {
+                info.restarts += 1;
+            }
+            info.status = status;
+            info.exit_code = exit_code;
+            info.pid = None;
+            let delay = Duration::from_secs(1 << info.restarts.min(5)).min(MAX_BACKOFF);
+            (status, will_restart, delay)
+        };
+
+        let info = process.info.lock().clone();
+        let summary = exit_summary(&info.name, exit_code, status);
+        eprintln!("[MindGrid] Run script {} in session {}: {}", info.name, info.session_id, summary);
+        let _ = app.emit(
+            "run-script-exited",
+            ScriptExited {
+                session_id: info.session_id.clone(),
+                name: info.name.clone(),
+                exit_code,
+                status,
+                will_restart,
+                summary,
+                output_tail: run.tail.lock().iter().cloned().collect(),
+            },
+        );
+        let _ = app.emit("run-script-updated", &info);
+        if !will_restart {
+            return;
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(delay) => {}
+            _ = process.stopped.notified() => {}
+        }
+        if process.stopping.load(Ordering::SeqCst) {
+            process.info.lock().status = ScriptStatus::Stopped;
+            let _ = app.emit("run-script-updated", &*process.info.lock());
+            return;
+        }
+        run = match spawn_run(&app, &process) {
+            Ok(run) => run,
+            Err(e) => {
+                eprintln!("[MindGrid] Failed to restart run script: {}", e.technical_message());
+                process.info.lock().status = ScriptStatus::Crashed;
+                let _ = app.emit("run-script-updated", &*process.info.lock());
+                return;
+            }
+        };
+    }
+}
+
+/// Stop a session's scripts from backend code (session pause and delete)
+pub fn stop_session_scripts(app: &AppHandle, session_id: &str) -> usize {
+    app.try_state::<Arc<RunScriptState>>()
+        .map(|state| state.stop_session(session_id))
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn script(session_id: &str, name: &str, pid: Option<u32>, status: ScriptStatus) -> Arc<ScriptProcess> {
+        Arc::new(ScriptProcess {
+            info: Mutex::new(RunningScript {
+                session_id: session_id.to_string(),
+                name: name.to_string(),
+                command: "npm run dev".to_string(),
+                cwd: "/tmp".to_string(),
+                port: 4100,
+                pid,
+                status,
+                restarts: 0,
+                exit_code: None,
+                started_at: 0,
+            }),
+            stopping: AtomicBool::new(false),
+            stopped: Notify::new(),
+        })
+    }
+
+    #[test]
+    fn run_script_ports_and_exits() {
+        let taken: HashSet<u16> = [4100, 4101].into_iter().collect();
+        assert_eq!(next_free_port(4100..4110, None, &taken, |p| p != 4102), Some(4103));
+        // A restarted script keeps its port while it's free
+        assert_eq!(next_free_port(4100..4110, Some(4105), &taken, |_| true), Some(4105));
+        assert_eq!(next_free_port(4100..4110, Some(4101), &taken, |_| true), Some(4102));
+        assert_eq!(next_free_port(4100..4102, None, &taken, |_| true), None);
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        assert!(!port_is_free(listener.local_addr().unwrap().port()));
+
+        assert_eq!(exit_summary("dev", Some(1), ScriptStatus::Crashed), "dev exited with code 1");
+        assert_eq!(exit_summary("test", Some(0), ScriptStatus::Exited), "test exited with code 0");
+        assert_eq!(exit_summary("dev", None, ScriptStatus::Restarting), "dev was killed by a signal");
+        assert_eq!(exit_summary("dev", None, ScriptStatus::Stopped), "dev was stopped");
+        assert_eq!(serde_json::to_value(ScriptStatus::Restarting).unwrap(), json!("restarting"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn stopping_a_session_kills_its_live_scripts() {
+        use std::os::unix::process::CommandExt;
+
+        let mut child = std::process::Command::new("sh")
+            .args(["-c", "sleep 30"])
+            .process_group(0)
+            .spawn()
+            .unwrap();
+        let state = RunScriptState::new();
+        let dev = script("s1", "dev", Some(child.id()), ScriptStatus::Running);
+        let mut scripts = state.scripts.lock();
+        scripts.insert(("s1".into(), "dev".into()), dev.clone());
+        scripts.insert(("s1".into(), "test".into()), script("s1", "test", None, ScriptStatus::Exited));
+        scripts.insert(("s2".into(), "dev".into()), script("s2", "dev", Some(1), ScriptStatus::Running));
+        drop(scripts);
+
+        assert_eq!(state.session_processes("s1"), vec![(child.id(), 4100)]);
+        assert_eq!(state.stop_session("s1"), 1);
+        assert!(dev.stopping.load(Ordering::SeqCst));
+        assert!(state.session_processes("s1").is_empty());
+        assert_eq!(state.scripts.lock().len(), 1);
+
+        let started = std::time::Instant::now();
+        while child.try_wait().unwrap().is_none() {
+            assert!(started.elapsed() < Duration::from_secs(2), "the script wasn't stopped");
+            std::thread::sleep(Duration::from_millis(50));
+        }
+    }
+
+    #[tokio::test]
+    async fn scripts_are_stored_per_project() {
+        // Projects live in the app store, not in the database
+        let pool = crate::tests::fixtures::memory_pool().await;
+        let mut dev = RunScript {
+            project_id: "p1".into(),
+            name: "dev".into(),
+            command: "npm run dev".into(),
+            restart_on_crash: false,
+        };
+        upsert_script(&pool, &dev).await.unwrap();
+        dev.restart_on_crash = true;
+        upsert_script(&pool, &dev).await.unwrap();
+
+        let scripts = load_scripts(&pool, "p1").await.unwrap();
+        assert_eq!(scripts.len(), 1);
+        assert!(scripts[0].restart_on_crash);
+        assert!(load_scripts(&pool, "p2").await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,667 @@
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::app_store;
+use crate::error::{MindgridError, MindgridResult};
+use crate::provision::ProvisionOptions;
+use crate::pty::{PtyState, SpawnArgs};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum SessionStatus {
+    Active,
+    /// Agent stopped, worktree kept
+    Paused,
+    /// Archived after its PR was merged or closed
+    Closed,
+}
+
+impl SessionStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            SessionStatus::Active => "active",
+            SessionStatus::Paused => "paused",
+            SessionStatus::Closed => "closed",
+        }
+    }
+
+    fn from_store(value: Option<&str>) -> Self {
+        match value.unwrap_or("active") {
+            "paused" => SessionStatus::Paused,
+            "closed" => SessionStatus::Closed,
+            _ => SessionStatus::Active,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum SessionOperation {
+    Create,
+    Pause,
+    Resume,
+    Delete,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum SessionStep {
+    Worktree,
+    CopyFiles,
+    Agent,
+    Database,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum StepStatus {
+    Started,
+    Completed,
+    Failed,
+    /// Undone after a later step failed
+    RolledBack,
+}
+
+/// Payload of the `session-progress` event
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct SessionProgress {
+    pub session_id: String,
+    pub operation: SessionOperation,
+    pub step: SessionStep,
+    pub status: StepStatus,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export, optional_fields = nullable)]
+pub struct CreateSessionArgs {
+    /// Session id chosen by the caller; generated when omitted
+    pub id: Option<String>,
+    pub project_id: String,
+    pub project_path: String,
+    pub name: String,
+    /// Working directory for projects that aren't git repositories
+    pub cwd: Option<String>,
+    /// Parent session worktree name for stacked sessions
+    pub parent: Option<String>,
+    pub model: Option<String>,
+    pub provision: Option<ProvisionOptions>,
+    /// Gitignored files to copy from the project into the worktree
+    pub copy_files: Option<Vec<String>>,
+    /// Agent to start in the session's working directory (`cwd` is ignored)
+    pub agent: Option<SpawnArgs>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct SessionRecord {
+    pub id: String,
+    pub project_id: String,
+    pub name: String,
+    pub cwd: String,
+    /// The session runs in its own worktree under `.mindgrid/worktrees`
+    pub worktree: bool,
+    pub status: SessionStatus,
+    pub model: Option<String>,
+    /// PTY of the agent started by the session manager
+    pub agent_pty_id: Option<String>,
+    /// Files copied into the worktree on creation
+    pub copied_files: Vec<String>,
+    #[ts(type = "number")]
+    pub created_at: i64,
+    #[ts(type = "number")]
+    pub updated_at: i64,
+}
+
+/// Owns session lifecycles. Each operation runs its steps in order and undoes
+/// the completed ones if a later step fails, so a session is never left with
+/// a worktree but no stored entry (or the reverse).
+pub struct SessionManager {
+    /// Sessions with a lifecycle operation in flight
+    busy: Mutex<HashSet<String>>,
+    /// Agent PTY started for each session
+    agents: Mutex<HashMap<String, String>>,
+}
+
+impl SessionManager {
+    pub fn new() -> Self {
+        Self {
+            busy: Mutex::new(HashSet::new()),
+            agents: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn begin(&self, session_id: &str) -> MindgridResult<BusyGuard<'_>> {
+        if !self.busy.lock().insert(session_id.to_string()) {
+            return Err(MindgridError::validation(
+                "session_busy",
+                "Another operation is already running for this session",
+            ));
+        }
+        Ok(BusyGuard {
+            manager: self,
+            session_id: session_id.to_string(),
+        })
+    }
+
+    /// Stop the session's agent and any other PTY running in its worktree
+    fn stop_agents(&self, pty_state: &PtyState, session_id: &str, cwd: &str, worktree: bool) -> Vec<String> {
+        let mut stopped = Vec::new();
+        if let Some(pty_id) = self.agents.lock().remove(session_id) {
+            if crate::pty::kill(pty_state, &pty_id).is_ok() {
+                stopped.push(pty_id);
+            }
+        }
+        // Outside a worktree the directory is shared with other sessions
+        if worktree {
+            stopped.extend(pty_state.kill_in(Path::new(cwd)));
+        }
+        stopped
+    }
+}
+
+struct BusyGuard<'a> {
+    manager: &'a SessionManager,
+    session_id: String,
+}
+
+impl Drop for BusyGuard<'_> {
+    fn drop(&mut self) {
+        self.manager.busy.lock().remove(&self.session_id);
+    }
+}
+
+struct Progress<'a> {
+    app: &'a AppHandle,
+    session_id: &'a str,
+    operation: SessionOperation,
+}
+
+impl Progress<'_> {
+    fn emit(&self, step: SessionStep, status: StepStatus, message: Option<String>) {
+        let _ = self.app.emit(
+            "session-progress",
+            SessionProgress {
+                session_id: self.session_id.to_string(),
+                operation: self.operation,
+                step,
+                status,
+                message,
+            },
+        );
+    }
+
+    fn started(&self, step: SessionStep) {
+        self.emit(step, StepStatus::Started, None);
+    }
+
+    /// Report the outcome of a step and pass the result through
+    fn finish<T>(&self, step: SessionStep, result: MindgridResult<T>) -> MindgridResult<T> {
+        match &result {
+            Ok(_) => self.emit(step, StepStatus::Completed, None),
+            Err(e) => self.emit(step, StepStatus::Failed, Some(e.user_message())),
+        }
+        result
+    }
+
+    /// Roll back `undo`, killing agents in `pty_state` and emitting each step
+    async fn rollback(&self, pty_state: &PtyState, undo: Vec<Undo>) {
+        rollback(
+            undo,
+            |pty_id| {
+                let _ = crate::pty::kill(pty_state, pty_id);
+            },
+            |step, status, message| self.emit(step, status, message),
+        )
+        .await;
+    }
+}
+
+/// Completed step to undo if a later one fails
+enum Undo {
+    Worktree { project_path: String, worktree_path: String },
+    Agent(String),
+}
+
+/// Undo completed steps newest first. Agents are stopped through `kill_agent`
+/// and each undone step is reported through `report`.
+async fn rollback(
+    undo: Vec<Undo>,
+    kill_agent: impl Fn(&str),
+    report: impl Fn(SessionStep, StepStatus, Option<String>),
+) {
+    for step in undo.into_iter().rev() {
+        match step {
+            Undo::Agent(pty_id) => {
+                kill_agent(&pty_id);
+                report(SessionStep::Agent, StepStatus::RolledBack, None);
+            }
+            Undo::Worktree {
+                project_path,
+                worktree_path,
+            } => match crate::git::remove_workspace_worktree(project_path, worktree_path).await {
+                Ok(()) => report(SessionStep::Worktree, StepStatus::RolledBack, None),
+                Err(e) => {
+                    eprintln!("[MindGrid] Failed to roll back session worktree: {}", e.technical_message());
+                    report(SessionStep::Worktree, StepStatus::Failed, Some(e.user_message()));
+                }
+            },
+        }
+    }
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// Same naming as the frontend used: sanitized name plus the start of the id
+fn worktree_name(name: &str, id: &str) -> String {
+    let sanitized: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '-' })
+        .collect();
+    let short_id: String = id.chars().take(6).collect();
+    format!("{}-{}", sanitized.to_lowercase(), short_id)
+}
+
+fn is_session_worktree(cwd: &str) -> bool {
+    cwd.contains(".mindgrid/worktrees/")
+}
+
+/// A stored session and the path of its project
+struct StoredSession {
+    record: SessionRecord,
+    project_path: Option<String>,
+}
+
+/// Sessions live in the app store the frontend saves them to
+fn load_session(app: &AppHandle, session_id: &str) -> MindgridResult<StoredSession> {
+    let session = app_store::session(app, session_id)?;
+    let project_path = app_store::project(app, &session.project_id).ok().map(|p| p.path);
+
+    Ok(StoredSession {
+        record: SessionRecord {
+            worktree: is_session_worktree(&session.cwd),
+            status: SessionStatus::from_store(session.status.as_deref()),
+            id: session.id,
+            project_id: session.project_id,
+            name: session.name,
+            cwd: session.cwd,
+            model: session.model,
+            agent_pty_id: None,
+            copied_files: Vec::new(),
+            created_at: session.created_at,
+            updated_at: session.updated_at,
+        },
+        project_path,
+    })
+}
+
+fn set_status(app: &AppHandle, session_id: &str, status: SessionStatus) -> MindgridResult<i64> {
+    let now = now_ms();
+    let updated = app_store::update_session(app, session_id, json!({ "status": status.as_str(), "updatedAt": now }))?;
+    if !updated {
+        return Err(MindgridError::validation("session_not_found", "Session not found").with_technical(session_id));
+    }
+    Ok(now)
+}
+
+/// Create a session: worktree (for git projects), copied files, agent process
+/// and stored entry. Any failure undoes the steps that already completed.
+#[tauri::command]
+pub async fn create_session(
+    app: AppHandle,
+    manager: tauri::State<'_, Arc<SessionManager>>,
+    pty_state: tauri::State<'_, Arc<PtyState>>,
+    args: CreateSessionArgs,
+) -> MindgridResult<SessionRecord> {
+    let id = args.id.clone().unwrap_or_else(|| Uuid::new_v4().to_string());
+    let _busy = manager.begin(&id)?;
+    let progress = Progress {
+        app: &app,
+        session_id: &id,
+        operation: SessionOperation::Create,
+    };
+    let mut undo = Vec::new();
+    match create_steps(&app, &pty_state, &progress, &id, args, &mut undo).await {
+        Ok(record) => {
+            if let Some(pty_id) = &record.agent_pty_id {
+                manager.agents.lock().insert(id.clone(), pty_id.clone());
+            }
+            Ok(record)
+        }
+        Err(e) => {
+            eprintln!("[MindGrid] Failed to create session {}: {}", id, e.technical_message());
+            progress.rollback(&pty_state, undo).await;
+            Err(e)
+        }
+    }
+}
+
+async fn create_steps(
+    app: &AppHandle,
+    pty_state: &Arc<PtyState>,
+    progress: &Progress<'_>,
+    id: &str,
+    args: CreateSessionArgs,
+    undo: &mut Vec<Undo>,
+) -> MindgridResult<SessionRecord> {
+    let worktree = crate::git::is_valid_git_repository(Path::new(&args.project_path));
+    let cwd = if worktree {
+        progress.started(SessionStep::Worktree);
+        let created = crate::git::create_workspace_worktree(
+            app.clone(),
+            args.project_path.clone(),
+            worktree_name(&args.name, id),
+            args.parent.clone(),
+            Some(id.to_string()),
+            args.provision.clone(),
+        )
+        .await;
+        let path = progress.finish(SessionStep::Worktree, created)?;
+        undo.push(Undo::Worktree {
+            project_path: args.project_path.clone(),
+            worktree_path: path.clone(),
+        });
+        path
+    } else {
+        args.cwd.clone().unwrap_or_else(|| args.project_path.clone())
+    };
+
+    let copied_files = match args.copy_files.clone().filter(|files| !files.is_empty()) {
+        Some(files) if worktree => {
+            progress.started(SessionStep::CopyFiles);
+            let copied = crate::git::copy_files_to_worktree(args.project_path.clone(), cwd.clone(), files).await;
+            progress.finish(SessionStep::CopyFiles, copied)?
+        }
+        _ => Vec::new(),
+    };
+
+    let agent_pty_id = match args.agent.clone() {
+        Some(mut agent) => {
+            progress.started(SessionStep::Agent);
+            agent.cwd = Some(cwd.clone());
+            let pty_id = progress.finish(SessionStep::Agent, crate::pty::spawn(app.clone(), pty_state, agent))?;
+            undo.push(Undo::Agent(pty_id.clone()));
+            Some(pty_id)
+        }
+        None => None,
+    };
+
+    progress.started(SessionStep::Database);
+    let now = now_ms();
+    let inserted = app_store::insert_session(app, session_entry(&args, id, &cwd, now));
+    progress.finish(SessionStep::Database, inserted)?;
+
+    Ok(SessionRecord {
+        id: id.to_string(),
+        project_id: args.project_id,
+        name: args.name,
+        cwd,
+        worktree,
+        status: SessionStatus::Active,
+        model: args.model,
+        agent_pty_id,
+        copied_files,
+        created_at: now,
+        updated_at: now,
+    })
+}
+
+/// Entry in the shape the frontend saves (`saveSession` in src/lib/database.ts),
+/// so the session loads even before the frontend saves its own copy
+fn session_entry(args: &CreateSessionArgs, id: &str, cwd: &str, now: i64) -> serde_json::Value {
+    json!({
+        "id": id,
+        "name": args.name,
+        "projectId": args.project_id,
+        "claudeSessionId": null,
+        "ptyId": null,
+        "messages": [],
+        "chatWindows": [],
+        "isRunning": false,
+        "totalCost": 0,
+        "model": args.model,
+        "cwd": cwd,
+        "createdAt": now,
+        "updatedAt": now,
+        "permissionMode": "default",
+        "commitMode": "checkpoint",
+        "status": SessionStatus::Active.as_str(),
+        "prUrl": null,
+    })
+}
+
+/// Pause a session: mark it paused, then stop its agent. The worktree is kept.
+#[tauri::command]
+pub async fn pause_session(
+    app: AppHandle,
+    manager: tauri::State<'_, Arc<SessionManager>>,
+    pty_state: tauri::State<'_, Arc<PtyState>>,
+    session_id: String,
+) -> MindgridResult<SessionRecord> {
+    let _busy = manager.begin(&session_id)?;
+    let progress = Progress {
+        app: &app,
+        session_id: &session_id,
+        operation: SessionOperation::Pause,
+    };
+    let mut record = load_session(&app, &session_id)?.record;
+
+    match record.status {
+        SessionStatus::Paused => return Ok(record),
+        SessionStatus::Closed => {
+            return Err(MindgridError::validation("session_closed", "Closed sessions can't be paused"));
+        }
+        SessionStatus::Active => {}
+    }
+
+    // The status change is the only step that can fail, so it goes first
+    progress.started(SessionStep::Database);
+    record.updated_at = progress.finish(
+        SessionStep::Database,
+        set_status(&app, &session_id, SessionStatus::Paused),
+    )?;
+    record.status = SessionStatus::Paused;
+
+    progress.started(SessionStep::Agent);
+    let stopped = manager.stop_agents(&pty_state, &session_id, &record.cwd, record.worktree);
+    let scripts = crate::run_scripts::stop_session_scripts(&app, &session_id);
+    crate::preview::stop_session_preview(&app, &session_id);
+    progress.emit(
+        SessionStep::Agent,
+        StepStatus::Completed,
+        Some(format!("Stopped {} process(es)", stopped.len() + scripts)),
+    );
+
+    Ok(record)
+}
+
+/// Resume a paused session, optionally starting an agent in its worktree
+#[tauri::command]
+pub async fn resume_session(
+    app: AppHandle,
+    manager: tauri::State<'_, Arc<SessionManager>>,
+    pty_state: tauri::State<'_, Arc<PtyState>>,
+    session_id: String,
+    agent: Option<SpawnArgs>,
+) -> MindgridResult<SessionRecord> {
+    let _busy = manager.begin(&session_id)?;
+    let progress = Progress {
+        app: &app,
+        session_id: &session_id,
+        operation: SessionOperation::Resume,
+    };
+    let mut record = load_session(&app, &session_id)?.record;
+
+    if record.status == SessionStatus::Closed {
+        return Err(MindgridError::validation("session_closed", "Closed sessions can't be resumed"));
+    }
+    if !Path::new(&record.cwd).is_dir() {
+        return Err(MindgridError::validation("worktree_missing", "The session's worktree no longer exists")
+            .with_technical(&record.cwd));
+    }
+
+    let mut undo = Vec::new();
+    if let Some(mut agent) = agent {
+        progress.started(SessionStep::Agent);
+        agent.cwd = Some(record.cwd.clone());
+        let pty_id = progress.finish(SessionStep::Agent, crate::pty::spawn(app.clone(), &pty_state, agent))?;
+        undo.push(Undo::Agent(pty_id.clone()));
+        record.agent_pty_id = Some(pty_id);
+    }
+
+    progress.started(SessionStep::Database);
+    let updated = set_status(&app, &session_id, SessionStatus::Active);
+    match progress.finish(SessionStep::Database, updated) {
+        Ok(updated_at) => record.updated_at = updated_at,
+        Err(e) => {
+            progress.rollback(&pty_state, undo).await;
+            return Err(e);
+        }
+    }
+    record.status = SessionStatus::Active;
+
+    if let Some(pty_id) = &record.agent_pty_id {
+        manager.agents.lock().insert(session_id.clone(), pty_id.clone());
+    }
+    Ok(record)
+}
+
+/// Delete a session: stop its agent, remove its worktree and branch, and
+/// remove its stored entry. The entry is only removed once the worktree is
+/// gone, so a failed removal leaves the session intact.
+#[tauri::command]
+pub async fn delete_session(
+    app: AppHandle,
+    manager: tauri::State<'_, Arc<SessionManager>>,
+    pty_state: tauri::State<'_, Arc<PtyState>>,
+    session_id: String,
+) -> MindgridResult<()> {
+    let _busy = manager.begin(&session_id)?;
+    let progress = Progress {
+        app: &app,
+        session_id: &session_id,
+        operation: SessionOperation::Delete,
+    };
+    let StoredSession { record, project_path } = load_session(&app, &session_id)?;
+
+    progress.started(SessionStep::Agent);
+    let stopped = manager.stop_agents(&pty_state, &session_id, &record.cwd, record.worktree);
+    let scripts = crate::run_scripts::stop_session_scripts(&app, &session_id);
+    crate::preview::stop_session_preview(&app, &session_id);
+    progress.emit(
+        SessionStep::Agent,
+        StepStatus::Completed,
+        Some(format!("Stopped {} process(es)", stopped.len() + scripts)),
+    );
+
+    if let (true, Some(project_path)) = (record.worktree, project_path) {
+        progress.started(SessionStep::Worktree);
+        let removed = crate::git::remove_workspace_worktree(project_path, record.cwd.clone()).await;
+        progress.finish(SessionStep::Worktree, removed)?;
+    }
+
+    progress.started(SessionStep::Database);
+    let removed = app_store::remove_session(&app, &session_id).map(|_| ());
+    progress.finish(SessionStep::Database, removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::fixtures::TempRepo;
+
+    #[test]
+    fn session_lifecycle_types() {
+        let args: CreateSessionArgs = serde_json::from_value(json!({
+            "project_id": "p1",
+            "project_path": "/tmp/demo",
+            "name": "fix bug",
+        }))
+        .unwrap();
+        assert!(args.id.is_none() && args.agent.is_none() && args.copy_files.is_none());
+
+        for (status, name) in [
+            (SessionStatus::Active, "active"),
+            (SessionStatus::Paused, "paused"),
+            (SessionStatus::Closed, "closed"),
+        ] {
+            assert_eq!(serde_json::to_value(status).unwrap(), json!(name));
+            assert_eq!(SessionStatus::from_store(Some(status.as_str())), status);
+        }
+        assert_eq!(worktree_name("Fix bug #12", "a1b2c3d4"), "fix-bug--12-a1b2c3");
+    }
+
+    #[tokio::test]
+    async fn failed_agent_spawn_rolls_back_the_worktree() {
+        let repo = TempRepo::with_files(&[("README.md", "demo\n")]);
+        let name = worktree_name("fix bug", "a1b2c3d4");
+        let worktree = repo.path.join(".mindgrid/worktrees").join(&name);
+        let worktree_path = worktree.to_string_lossy().to_string();
+        repo.git(&["worktree", "add", "-b", &format!("mindgrid/{}", name), &worktree_path]);
+
+        // The worktree and a first agent were created before a later step failed
+        let undo = vec![
+            Undo::Worktree {
+                project_path: repo.path_str(),
+                worktree_path: worktree_path.clone(),
+            },
+            Undo::Agent("pty-1".to_string()),
+        ];
+        let killed = Mutex::new(Vec::new());
+        let reported = Mutex::new(Vec::new());
+        rollback(
+            undo,
+            |pty_id| killed.lock().push(pty_id.to_string()),
+            |step, status, _| reported.lock().push((step, status)),
+        )
+        .await;
+
+        assert_eq!(*killed.lock(), vec!["pty-1"]);
+        assert_eq!(
+            *reported.lock(),
+            vec![
+                (SessionStep::Agent, StepStatus::RolledBack),
+                (SessionStep::Worktree, StepStatus::RolledBack),
+            ]
+        );
+        assert!(!worktree.exists());
+        assert!(repo.git(&["branch", "--list", "mindgrid/*"]).trim().is_empty());
+    }
+
+    #[tokio::test]
+    async fn failed_rollback_is_reported() {
+        let repo = TempRepo::with_files(&[("README.md", "demo\n")]);
+        let worktree = repo.path.join(".mindgrid/worktrees/locked");
+        let worktree_path = worktree.to_string_lossy().to_string();
+        repo.git(&["worktree", "add", "-b", "mindgrid/locked", &worktree_path]);
+        // A single --force doesn't remove a locked worktree
+        repo.git(&["worktree", "lock", &worktree_path]);
+
+        let undo = vec![Undo::Worktree {
+            project_path: repo.path_str(),
+            worktree_path,
+        }];
+        let reported = Mutex::new(Vec::new());
+        rollback(undo, |_| {}, |step, status, message| reported.lock().push((step, status, message.is_some()))).await;
+        assert_eq!(*reported.lock(), vec![(SessionStep::Worktree, StepStatus::Failed, true)]);
+        assert!(worktree.exists());
+    }
+}
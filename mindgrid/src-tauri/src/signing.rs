@@ -0,0 +1,491 @@
+use serde::Serialize;
+use ts_rs::TS;
+
+use crate::error::{MindgridError, MindgridResult};
+
+// The signing policy lives in the repository's local git config
+// (`mindgrid.signingPolicy`) so it applies to every session worktree of the
+// project and can be inspected with plain git:
+//
+//   always - sign every mindgrid commit and refuse to merge unsigned branches
+//   never  - never sign, even if commit.gpgsign is set
+//   config - follow the repository's git configuration (default); with
+//            commit.gpgsign set, unsigned branches are refused like `always`
+//
+// Branches are verified before every merge, so a bad or revoked signature is
+// refused under any policy.
+
+const POLICY_KEY: &str = "mindgrid.signingPolicy";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum SigningPolicy {
+    Always,
+    Never,
+    Config,
+}
+
+impl SigningPolicy {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "always" => Some(SigningPolicy::Always),
+            "never" => Some(SigningPolicy::Never),
+            "config" => Some(SigningPolicy::Config),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            SigningPolicy::Always => "always",
+            SigningPolicy::Never => "never",
+            SigningPolicy::Config => "config",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
+pub struct SigningConfig {
+    pub policy: SigningPolicy,
+    /// Value of commit.gpgsign
+    pub gpgsign: bool,
+    /// Value of gpg.format ("openpgp", "ssh" or "x509")
+    pub format: String,
+    pub signing_key: Option<String>,
+    /// Whether commits made by mindgrid will be signed
+    pub will_sign: bool,
+    pub key_available: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
+pub struct CommitSignature {
+    pub hash: String,
+    pub subject: String,
+    /// Raw `%G?` code from git log
+    pub code: String,
+    /// "good", "unknown_validity", "bad", "expired", "expired_key", "revoked", "unverifiable" or "unsigned"
+    pub status: String,
+    pub signer: Option<String>,
+    pub key: Option<String>,
+}
+
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
+pub struct SignatureReport {
+    pub branch: String,
+    pub base: String,
+    pub commits: Vec<CommitSignature>,
+    pub all_signed: bool,
+}
+
+async fn git_config(repo: &str, key: &str) -> Option<String> {
+    let output = tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(["config", "--get", key])
+        .output()
+        .await
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if value.is_empty() {
+        None
+    } else {
+        Some(value)
+    }
+}
+
+async fn git_config_bool(repo: &str, key: &str) -> bool {
+    git_config(repo, key)
+        .await
+        .map(|v| matches!(v.to_lowercase().as_str(), "true" | "yes" | "on" | "1"))
+        .unwrap_or(false)
+}
+
+pub async fn get_policy(repo: &str) -> SigningPolicy {
+    git_config(repo, POLICY_KEY)
+        .await
+        .and_then(|v| SigningPolicy::parse(&v))
+        .unwrap_or(SigningPolicy::Config)
+}
+
+/// Whether commits made by mindgrid are meant to be signed: the policy is
+/// `always`, or `config` with commit.gpgsign set
+pub async fn signing_required(repo: &str) -> bool {
+    match get_policy(repo).await {
+        SigningPolicy::Always => true,
+        SigningPolicy::Never => false,
+        SigningPolicy::Config => git_config_bool(repo, "commit.gpgsign").await,
+    }
+}
+
+/// Check that the configured signing key can actually be used
+async fn check_key_available(repo: &str, format: &str, signing_key: Option<&str>) -> Result<(), String> {
+    match format {
+        "ssh" => {
+            if git_config(repo, "gpg.ssh.defaultKeyCommand").await.is_some() && signing_key.is_none() {
+                return Ok(());
+            }
+            let key = signing_key.ok_or_else(|| {
+                "Commit signing uses SSH but user.signingkey is not set".to_string()
+            })?;
+
+            let literal = key.strip_prefix("key::").unwrap_or(key);
+            if literal.starts_with("ssh-") || literal.starts_with("ecdsa-") || literal.starts_with("sk-") {
+                // Literal public key: the private half must be loaded in ssh-agent
+                let agent_keys = tokio::process::Command::new("ssh-add")
+                    .arg("-L")
+                    .output()
+                    .await
+                    .map(|o| String::from_utf8_lossy(&o.stdout).to_string())
+                    .unwrap_or_default();
+                let key_body = literal.split_whitespace().nth(1).unwrap_or(literal);
+                if agent_keys.contains(key_body) {
+                    Ok(())
+                } else {
+                    Err("SSH signing key is not loaded in ssh-agent. Run: ssh-add".to_string())
+                }
+            } else {
+                let path = expand_home(key);
+                if std::path::Path::new(&path).exists() {
+                    Ok(())
+                } else {
+                    Err(format!("SSH signing key file not found: {}", path))
+                }
+            }
+        }
+        "x509" => {
+            let program = git_config(repo, "gpg.x509.program")
+                .await
+                .unwrap_or_else(|| "gpgsm".to_string());
+            list_secret_keys(&program, signing_key).await
+        }
+        _ => {
+            let program = match git_config(repo, "gpg.openpgp.program").await {
+                Some(p) => p,
+                None => git_config(repo, "gpg.program")
+                    .await
+                    .unwrap_or_else(|| "gpg".to_string()),
+            };
+            list_secret_keys(&program, signing_key).await
+        }
+    }
+}
+
+async fn list_secret_keys(program: &str, signing_key: Option<&str>) -> Result<(), String> {
+    let mut cmd = tokio::process::Command::new(program);
+    cmd.args(["--list-secret-keys", "--with-colons"]);
+    if let Some(key) = signing_key {
+        cmd.arg(key);
+    }
+
+    let output = cmd
+        .output()
+        .await
+        .map_err(|e| format!("Signing program '{}' is not available: {}", program, e))?;
+
+    let has_key = output.status.success()
+        && String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .any(|l| l.starts_with("sec") || l.starts_with("crs"));
+
+    if has_key {
+        Ok(())
+    } else {
+        match signing_key {
+            Some(key) => Err(format!("Signing key {} has no secret key available in {}", key, program)),
+            None => Err(format!("No secret signing key found in {}", program)),
+        }
+    }
+}
+
+fn expand_home(path: &str) -> String {
+    if let Some(rest) = path.strip_prefix("~/") {
+        if let Ok(home) = std::env::var("HOME") {
+            return format!("{}/{}", home, rest);
+        }
+    }
+    path.to_string()
+}
+
+/// Read the project's signing setup and check whether the key is usable
+pub async fn read_signing_config(repo: &str) -> SigningConfig {
+    let policy = get_policy(repo).await;
+    let gpgsign = git_config_bool(repo, "commit.gpgsign").await;
+    let format = git_config(repo, "gpg.format")
+        .await
+        .unwrap_or_else(|| "openpgp".to_string());
+    let signing_key = git_config(repo, "user.signingkey").await;
+
+    let will_sign = match policy {
+        SigningPolicy::Always => true,
+        SigningPolicy::Never => false,
+        SigningPolicy::Config => gpgsign,
+    };
+
+    let (key_available, error) = if will_sign {
+        match check_key_available(repo, &format, signing_key.as_deref()).await {
+            Ok(()) => (true, None),
+            Err(e) => (false, Some(e)),
+        }
+    } else {
+        (false, None)
+    };
+
+    SigningConfig {
+        policy,
+        gpgsign,
+        format,
+        signing_key,
+        will_sign,
+        key_available,
+        error,
+    }
+}
+
+/// Extra `git commit` argument implementing the project's policy.
+/// Fails early with a readable message when a signature is required but the key is unavailable.
+pub async fn commit_sign_arg(repo: &str) -> MindgridResult<Option<&'static str>> {
+    let config = read_signing_config(repo).await;
+
+    if config.will_sign && !config.key_available {
+        return Err(MindgridError::git(
+            "signing_key_unavailable",
+            format!(
+                "Cannot create a signed commit: {}",
+                config.error.unwrap_or_else(|| "signing key unavailable".to_string())
+            ),
+        ));
+    }
+
+    Ok(match config.policy {
+        SigningPolicy::Always => Some("-S"),
+        SigningPolicy::Never => Some("--no-gpg-sign"),
+        SigningPolicy::Config => None,
+    })
+}
+
+/// Turn common signing failures from git's stderr into an actionable message
+pub fn explain_signing_failure(stderr: &str) -> Option<String> {
+    let lower = stderr.to_lowercase();
+    if lower.contains("gpg failed to sign the data") || lower.contains("no secret key") {
+        Some(format!(
+            "GPG could not sign the commit. Check that your signing key is available and gpg-agent is running.\n{}",
+            stderr.trim()
+        ))
+    } else if lower.contains("load key") || (lower.contains("ssh-keygen") && lower.contains("sign")) {
+        Some(format!(
+            "SSH signing failed. Check user.signingkey and that the key is loaded in ssh-agent.\n{}",
+            stderr.trim()
+        ))
+    } else {
+        None
+    }
+}
+
+fn describe_signature_code(code: &str) -> &'static str {
+    match code {
+        "G" => "good",
+        "U" => "unknown_validity",
+        "B" => "bad",
+        "X" => "expired",
+        "Y" => "expired_key",
+        "R" => "revoked",
+        "E" => "unverifiable",
+        _ => "unsigned",
+    }
+}
+
+/// Parse `git log --format=%H%x1f%G?%x1f%GS%x1f%GK%x1f%s` output
+fn parse_signature_log(text: &str) -> Vec<CommitSignature> {
+    text.lines()
+        .filter(|l| !l.is_empty())
+        .map(|line| {
+            let fields: Vec<&str> = line.split('\u{1f}').collect();
+            let field = |i: usize| fields.get(i).map(|s| s.trim()).unwrap_or("");
+            let non_empty = |s: &str| if s.is_empty() { None } else { Some(s.to_string()) };
+            CommitSignature {
+                hash: field(0).to_string(),
+                code: field(1).to_string(),
+                status: describe_signature_code(field(1)).to_string(),
+                signer: non_empty(field(2)),
+                key: non_empty(field(3)),
+                subject: field(4).to_string(),
+            }
+        })
+        .collect()
+}
+
+/// Check the signatures of every commit on `branch` that is not on `base`
+pub async fn verify_branch(repo: &str, base: &str, branch: &str) -> MindgridResult<SignatureReport> {
+    let range = format!("{}..{}", base, branch);
+    let args = ["log", "--format=%H%x1f%G?%x1f%GS%x1f%GK%x1f%s", range.as_str()];
+    let output = tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| MindgridError::spawn_failed("git log", e))?;
+
+    if !output.status.success() {
+        return Err(MindgridError::git_failed("log_failed", "Failed to read signatures", &args, &output));
+    }
+
+    let commits = parse_signature_log(&String::from_utf8_lossy(&output.stdout));
+
+    // Untrusted-but-valid signatures ("U") still prove the commit was signed
+    let all_signed = commits.iter().all(|c| c.code == "G" || c.code == "U");
+
+    Ok(SignatureReport {
+        branch: branch.to_string(),
+        base: base.to_string(),
+        commits,
+        all_signed,
+    })
+}
+
+/// Commits that keep a branch from being merged. A bad or revoked signature
+/// blocks under every policy; when signing is required, so does any commit
+/// without a valid signature.
+pub fn blocking_commits(report: &SignatureReport, signing_required: bool) -> Vec<&CommitSignature> {
+    report
+        .commits
+        .iter()
+        .filter(|c| match c.code.as_str() {
+            "G" | "U" => false,
+            "B" | "R" => true,
+            _ => signing_required,
+        })
+        .collect()
+}
+
+/// Get the project's signing policy and whether signing will work
+#[tauri::command]
+pub async fn get_signing_config(project_path: String) -> MindgridResult<SigningConfig> {
+    Ok(read_signing_config(&project_path).await)
+}
+
+/// Set the project's signing policy ("always", "never" or "config")
+#[tauri::command]
+pub async fn set_signing_policy(project_path: String, policy: String) -> MindgridResult<SigningConfig> {
+    let parsed = SigningPolicy::parse(&policy).ok_or_else(|| {
+        MindgridError::validation(
+            "unknown_signing_policy",
+            format!("Unknown signing policy: {} (expected always, never or config)", policy),
+        )
+    })?;
+
+    let args = ["config", "--local", POLICY_KEY, parsed.as_str()];
+    let output = tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(&project_path)
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| MindgridError::spawn_failed("git config", e))?;
+
+    if !output.status.success() {
+        return Err(MindgridError::git_failed("config_failed", "Failed to save signing policy", &args, &output));
+    }
+
+    Ok(read_signing_config(&project_path).await)
+}
+
+/// Verify the signatures of the session branch's commits that are not yet on main
+#[tauri::command]
+pub async fn git_verify_branch_signatures(
+    working_directory: String,
+    project_path: String,
+) -> MindgridResult<SignatureReport> {
+    let main_branch = crate::git::detect_main_branch(&project_path)
+        .await
+        .unwrap_or_else(|| "main".to_string());
+    verify_branch(&working_directory, &main_branch, "HEAD").await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::fixtures::TempRepo;
+
+    #[test]
+    fn parses_signature_log() {
+        // Captured from `git log --format=%H%x1f%G?%x1f%GS%x1f%GK%x1f%s main..feature`
+        let log = "4f1c2e9a8b7d6c5e4f3a2b1c0d9e8f7a6b5c4d3e\u{1f}G\u{1f}Ada Lovelace <ada@example.com>\u{1f}3AA5C34371567BD2\u{1f}Add parser\n\
+                   9e8d7c6b5a4f3e2d1c0b9a8f7e6d5c4b3a2f1e0d\u{1f}N\u{1f}\u{1f}\u{1f}WIP: parser tests\n\
+                   0a1b2c3d4e5f60718293a4b5c6d7e8f901234567\u{1f}E\u{1f}\u{1f}SHA256:q1w2e3r4\u{1f}Signed elsewhere\n";
+        let commits = parse_signature_log(log);
+        assert_eq!(commits.len(), 3);
+
+        assert_eq!(commits[0].status, "good");
+        assert_eq!(commits[0].signer.as_deref(), Some("Ada Lovelace <ada@example.com>"));
+        assert_eq!(commits[0].key.as_deref(), Some("3AA5C34371567BD2"));
+        assert_eq!(commits[0].subject, "Add parser");
+
+        assert_eq!(commits[1].status, "unsigned");
+        assert_eq!(commits[1].signer, None);
+        assert_eq!(commits[1].key, None);
+        assert_eq!(commits[1].subject, "WIP: parser tests");
+
+        assert_eq!(commits[2].status, "unverifiable");
+        assert_eq!(commits[2].key.as_deref(), Some("SHA256:q1w2e3r4"));
+    }
+
+    #[test]
+    fn explains_signing_failures() {
+        let gpg = "error: gpg failed to sign the data\nfatal: failed to write commit object\n";
+        assert!(explain_signing_failure(gpg).unwrap().starts_with("GPG could not sign"));
+
+        let ssh = "error: Load key \"/home/dev/.ssh/id_ed25519\": invalid format?\nfatal: failed to write commit object\n";
+        assert!(explain_signing_failure(ssh).unwrap().starts_with("SSH signing failed"));
+
+        assert_eq!(explain_signing_failure("fatal: not a git repository"), None);
+    }
+
+    #[test]
+    fn bad_signatures_block_under_every_policy() {
+        let log = "a1\u{1f}G\u{1f}\u{1f}\u{1f}Signed\n\
+                   b2\u{1f}N\u{1f}\u{1f}\u{1f}Unsigned\n\
+                   c3\u{1f}B\u{1f}\u{1f}\u{1f}Tampered\n\
+                   d4\u{1f}E\u{1f}\u{1f}\u{1f}Unknown key\n";
+        let report = SignatureReport {
+            branch: "mindgrid/feature".to_string(),
+            base: "main".to_string(),
+            commits: parse_signature_log(log),
+            all_signed: false,
+        };
+        let hashes = |required| -> Vec<&str> {
+            blocking_commits(&report, required).iter().map(|c| c.hash.as_str()).collect()
+        };
+
+        assert_eq!(hashes(false), vec!["c3"]);
+        assert_eq!(hashes(true), vec!["b2", "c3", "d4"]);
+    }
+
+    #[tokio::test]
+    async fn policy_decides_the_commit_argument() {
+        let repo = TempRepo::new();
+        let path = repo.path_str();
+        assert_eq!(commit_sign_arg(&path).await.unwrap(), None);
+
+        repo.git(&["config", POLICY_KEY, "never"]);
+        repo.git(&["config", "commit.gpgsign", "true"]);
+        assert_eq!(commit_sign_arg(&path).await.unwrap(), Some("--no-gpg-sign"));
+        assert!(!signing_required(&path).await);
+
+        repo.git(&["config", POLICY_KEY, "Always"]);
+        repo.git(&["config", "gpg.format", "ssh"]);
+        repo.git(&["config", "user.signingkey", "/nonexistent/mindgrid-test-key"]);
+        assert!(signing_required(&path).await);
+        let err = commit_sign_arg(&path).await.unwrap_err();
+        assert_eq!(err.code, "git.signing_key_unavailable");
+        assert!(err.message.contains("/nonexistent/mindgrid-test-key"), "{}", err.message);
+    }
+}
@@ -0,0 +1,452 @@
+use serde::Serialize;
+use std::collections::VecDeque;
+use ts_rs::TS;
+
+use crate::error::{MindgridError, MindgridResult};
+
+// Stacked sessions record their parent in the repository's git config so the
+// relationship survives restarts and is visible to plain git tooling:
+//
+//   branch.mindgrid/<child>.mindgridParent = mindgrid/<parent>
+//   branch.mindgrid/<child>.mindgridBase   = <parent commit the child is based on>
+//   branch.mindgrid/<parent>.mindgridMerged = true   (merges main's history can't show)
+//
+// `git branch -D` removes the whole `branch.<name>` section, so deleting a
+// session cleans up its stack metadata as well.
+
+const PARENT_KEY: &str = "mindgridParent";
+const BASE_KEY: &str = "mindgridBase";
+const MERGED_KEY: &str = "mindgridMerged";
+
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
+pub struct StackEntry {
+    pub branch: String,
+    pub parent: String,
+    pub base: Option<String>,
+    pub worktree_path: Option<String>,
+}
+
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
+pub struct RestackResult {
+    pub branch: String,
+    pub worktree_path: Option<String>,
+    /// Branch the child now sits on (the parent, or main once the parent merged)
+    pub new_base: String,
+    /// "rebased", "up_to_date", "conflict" or "skipped"
+    pub status: String,
+    pub message: Option<String>,
+}
+
+async fn git(repo: &str, args: &[&str]) -> Option<std::process::Output> {
+    tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .output()
+        .await
+        .ok()
+}
+
+async fn git_stdout(repo: &str, args: &[&str]) -> Option<String> {
+    let output = git(repo, args).await?;
+    if output.status.success() {
+        Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        None
+    }
+}
+
+/// Resolve a ref to a commit hash
+pub async fn resolve_ref(repo: &str, refname: &str) -> Option<String> {
+    git_stdout(repo, &["rev-parse", "--verify", "--quiet", &format!("{}^{{commit}}", refname)])
+        .await
+        .filter(|s| !s.is_empty())
+}
+
+async fn config_get(repo: &str, branch: &str, key: &str) -> Option<String> {
+    git_stdout(repo, &["config", "--get", &format!("branch.{}.{}", branch, key)])
+        .await
+        .filter(|s| !s.is_empty())
+}
+
+async fn config_set(repo: &str, branch: &str, key: &str, value: &str) -> MindgridResult<()> {
+    let name = format!("branch.{}.{}", branch, key);
+    let args = ["config", name.as_str(), value];
+    let output = tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| MindgridError::spawn_failed("git config", e))?;
+    if !output.status.success() {
+        return Err(MindgridError::git_failed(
+            "stack_config_failed",
+            format!("Failed to record {} for {}", key, branch),
+            &args,
+            &output,
+        ));
+    }
+    Ok(())
+}
+
+async fn config_unset(repo: &str, branch: &str, key: &str) {
+    let _ = git(repo, &["config", "--unset", &format!("branch.{}.{}", branch, key)]).await;
+}
+
+/// Record that `branch` is stacked on `parent_branch` at `parent_sha`
+pub async fn record_parent(
+    repo: &str,
+    branch: &str,
+    parent_branch: &str,
+    parent_sha: &str,
+) -> MindgridResult<()> {
+    config_set(repo, branch, PARENT_KEY, parent_branch).await?;
+    config_set(repo, branch, BASE_KEY, parent_sha).await
+}
+
+/// Record that `branch` was merged into main. Needed for merges that leave no
+/// trace in main's history: fast-forwards, and PRs merged on the forge before
+/// main is fetched.
+pub async fn mark_merged(repo: &str, branch: &str) -> MindgridResult<()> {
+    config_set(repo, branch, MERGED_KEY, "true").await
+}
+
+/// `mark_merged` for the branch checked out in a worktree
+pub async fn mark_worktree_merged(worktree: &str) -> MindgridResult<()> {
+    let branch = git_stdout(worktree, &["symbolic-ref", "--short", "HEAD"])
+        .await
+        .ok_or_else(|| MindgridError::git("detached_head", format!("No branch checked out in {}", worktree)))?;
+    mark_merged(worktree, &branch).await
+}
+
+/// Get the parent branch of a stacked session branch
+pub async fn parent_of(repo: &str, branch: &str) -> Option<String> {
+    config_get(repo, branch, PARENT_KEY).await
+}
+
+/// All branches stacked directly on `parent_branch`
+async fn children_of(repo: &str, parent_branch: &str) -> Vec<String> {
+    list_stack(repo)
+        .await
+        .into_iter()
+        .filter(|(_, parent)| parent == parent_branch)
+        .map(|(branch, _)| branch)
+        .collect()
+}
+
+/// (branch, parent) pairs for every stacked branch in the repository
+async fn list_stack(repo: &str) -> Vec<(String, String)> {
+    let pattern = format!(r"^branch\..*\.{}$", PARENT_KEY.to_lowercase());
+    let Some(text) = git_stdout(repo, &["config", "--get-regexp", &pattern]).await else {
+        return Vec::new();
+    };
+
+    let suffix = format!(".{}", PARENT_KEY.to_lowercase());
+    text.lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(' ')?;
+            let branch = key.strip_prefix("branch.")?.strip_suffix(&suffix)?;
+            Some((branch.to_string(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+/// Find the worktree where `branch` is checked out
+async fn worktree_for_branch(repo: &str, branch: &str) -> Option<String> {
+    let text = git_stdout(repo, &["worktree", "list", "--porcelain"]).await?;
+    let target = format!("branch refs/heads/{}", branch);
+    let mut current_path: Option<&str> = None;
+
+    for line in text.lines() {
+        if let Some(path) = line.strip_prefix("worktree ") {
+            current_path = Some(path);
+        } else if line == target {
+            return current_path.map(|p| p.to_string());
+        }
+    }
+    None
+}
+
+/// Whether `commit` is on the first-parent history of `main_branch`, i.e. main
+/// went through it rather than merging it in from a side branch
+async fn on_first_parent_line(repo: &str, commit: &str, main_branch: &str) -> bool {
+    let Some(tip) = resolve_ref(repo, commit).await else {
+        return false;
+    };
+    let Some(newer) = git_stdout(
+        repo,
+        &["rev-list", "--first-parent", "--reverse", &format!("{}..{}", tip, main_branch)],
+    )
+    .await
+    else {
+        return false;
+    };
+    match newer.lines().next() {
+        // Main is at the commit
+        None => true,
+        Some(next) => resolve_ref(repo, &format!("{}^", next)).await.as_deref() == Some(tip.as_str()),
+    }
+}
+
+/// Whether `branch` has been merged into `main_branch`. A branch without
+/// commits of its own (every freshly created parent) is contained in main as
+/// well, so containment alone doesn't count. Merged means one of:
+/// - the merge was recorded with `mark_merged`
+/// - main merged the branch tip in through a merge commit
+/// - the branch has commits main lacks, but merging it would not change main's
+///   tree (squash and rebase merges, the default for mindgrid)
+async fn is_merged_into(repo: &str, branch: &str, main_branch: &str) -> bool {
+    if config_get(repo, branch, MERGED_KEY).await.is_some() {
+        return true;
+    }
+
+    let is_ancestor = git(repo, &["merge-base", "--is-ancestor", branch, main_branch])
+        .await
+        .map(|o| o.status.success())
+        .unwrap_or(false);
+    if is_ancestor {
+        // A tip on main's own history is where the branch started, not a merge
+        return !on_first_parent_line(repo, branch, main_branch).await;
+    }
+
+    let merged_tree = git_stdout(repo, &["merge-tree", "--write-tree", main_branch, branch])
+        .await
+        .and_then(|out| out.lines().next().map(|l| l.trim().to_string()));
+    let main_tree = git_stdout(repo, &["rev-parse", &format!("{}^{{tree}}", main_branch)]).await;
+
+    matches!((merged_tree, main_tree), (Some(a), Some(b)) if a == b)
+}
+
+/// Base branch a PR for `branch` should target: the parent branch while it is
+/// still unmerged, otherwise `None` (the repository default)
+pub async fn pr_base_branch(working_directory: &str, branch: &str) -> Option<String> {
+    let parent = parent_of(working_directory, branch).await?;
+    resolve_ref(working_directory, &parent).await?;
+
+    let main_branch = crate::git::detect_main_branch(working_directory).await?;
+    if is_merged_into(working_directory, &parent, &main_branch).await {
+        None
+    } else {
+        Some(parent)
+    }
+}
+
+/// Rebase every session stacked (directly or transitively) on `parent_branch`
+pub async fn restack_children(repo: &str, parent_branch: &str) -> Vec<RestackResult> {
+    let main_branch = crate::git::detect_main_branch(repo)
+        .await
+        .unwrap_or_else(|| "main".to_string());
+
+    let mut results = Vec::new();
+    let mut queue: VecDeque<String> = children_of(repo, parent_branch).await.into();
+
+    while let Some(child) = queue.pop_front() {
+        let result = restack_branch(repo, &child, &main_branch).await;
+        let rebased_or_current = result.status == "rebased" || result.status == "up_to_date";
+        results.push(result);
+
+        // Grandchildren only move once their own parent has moved successfully
+        if rebased_or_current {
+            queue.extend(children_of(repo, &child).await);
+        }
+    }
+
+    results
+}
+
+async fn restack_branch(repo: &str, branch: &str, main_branch: &str) -> RestackResult {
+    let parent = parent_of(repo, branch).await.unwrap_or_else(|| main_branch.to_string());
+    let worktree_path = worktree_for_branch(repo, branch).await;
+
+    let parent_exists = resolve_ref(repo, &parent).await.is_some();
+    let parent_merged = !parent_exists || is_merged_into(repo, &parent, main_branch).await;
+    let new_base = if parent_merged { main_branch.to_string() } else { parent.clone() };
+
+    let make_result = |status: &str, message: Option<String>| RestackResult {
+        branch: branch.to_string(),
+        worktree_path: worktree_path.clone(),
+        new_base: new_base.clone(),
+        status: status.to_string(),
+        message,
+    };
+
+    let Some(new_base_sha) = resolve_ref(repo, &new_base).await else {
+        return make_result("skipped", Some(format!("Cannot resolve {}", new_base)));
+    };
+
+    // Where the child's own commits start: the parent commit recorded at
+    // creation/last restack, or the merge-base as a fallback
+    let old_base = match config_get(repo, branch, BASE_KEY).await {
+        Some(base) => base,
+        None => match git_stdout(repo, &["merge-base", branch, &new_base]).await {
+            Some(base) => base,
+            None => return make_result("skipped", Some("No common ancestor found".to_string())),
+        },
+    };
+
+    if old_base == new_base_sha {
+        if parent_merged {
+            config_unset(repo, branch, PARENT_KEY).await;
+        }
+        return make_result("up_to_date", None);
+    }
+
+    let Some(worktree) = worktree_path.clone() else {
+        return make_result(
+            "skipped",
+            Some("Branch is not checked out in a worktree".to_string()),
+        );
+    };
+
+    let output = git(
+        &worktree,
+        &["rebase", "--autostash", "--onto", &new_base_sha, &old_base],
+    )
+    .await;
+
+    match output {
+        Some(o) if o.status.success() => {}
+        Some(o) => {
+            let _ = git(&worktree, &["rebase", "--abort"]).await;
+            let stderr = String::from_utf8_lossy(&o.stderr).to_string();
+            let stdout = String::from_utf8_lossy(&o.stdout).to_string();
+            return make_result("conflict", Some(format!("{}{}", stdout, stderr).trim().to_string()));
+        }
+        None => return make_result("skipped", Some("Failed to run git rebase".to_string())),
+    }
+
+    if let Err(e) = config_set(repo, branch, BASE_KEY, &new_base_sha).await {
+        eprintln!("[MindGrid] {}", e.technical_message());
+    }
+    if parent_merged {
+        // The child now sits directly on main; its PR should target main too
+        config_unset(repo, branch, PARENT_KEY).await;
+    }
+
+    make_result("rebased", Some(format!("Rebased {} onto {}", branch, new_base)))
+}
+
+/// Rebase all sessions stacked on the given session (by worktree name) after
+/// the parent gained commits or was merged
+#[tauri::command]
+pub async fn git_restack(project_path: String, name: String) -> MindgridResult<Vec<RestackResult>> {
+    let parent_branch = format!("mindgrid/{}", name);
+    Ok(restack_children(&project_path, &parent_branch).await)
+}
+
+/// List stacked session branches and their parents
+#[tauri::command]
+pub async fn git_get_stack(project_path: String) -> MindgridResult<Vec<StackEntry>> {
+    let mut entries = Vec::new();
+    for (branch, parent) in list_stack(&project_path).await {
+        let base = config_get(&project_path, &branch, BASE_KEY).await;
+        let worktree_path = worktree_for_branch(&project_path, &branch).await;
+        entries.push(StackEntry {
+            branch,
+            parent,
+            base,
+            worktree_path,
+        });
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::fixtures::{git_in, TempRepo};
+    use std::path::{Path, PathBuf};
+
+    /// Session worktree on `mindgrid/<name>`, branched from `from`
+    fn session(repo: &TempRepo, name: &str, from: &str) -> PathBuf {
+        let path = repo.path.join(".mindgrid").join("worktrees").join(name);
+        let branch = format!("mindgrid/{}", name);
+        repo.git(&["worktree", "add", "-q", "-b", &branch, &path.to_string_lossy(), from]);
+        path
+    }
+
+    fn commit_file(worktree: &Path, file: &str) {
+        std::fs::write(worktree.join(file), file).unwrap();
+        git_in(worktree, &["add", file]);
+        git_in(worktree, &["commit", "-q", "-m", file]);
+    }
+
+    /// A parent session and a child stacked on it with one commit
+    async fn stack(repo: &TempRepo) -> (PathBuf, PathBuf) {
+        let parent = session(repo, "parent", "main");
+        let child = session(repo, "child", "mindgrid/parent");
+        let parent_sha = resolve_ref(&repo.path_str(), "mindgrid/parent").await.unwrap();
+        record_parent(&repo.path_str(), "mindgrid/child", "mindgrid/parent", &parent_sha)
+            .await
+            .unwrap();
+        commit_file(&child, "child.txt");
+        (parent, child)
+    }
+
+    #[tokio::test]
+    async fn fresh_parent_is_not_merged() {
+        let repo = TempRepo::with_files(&[("README.md", "readme\n")]);
+        let (_parent, child) = stack(&repo).await;
+        // Main moving on makes the empty parent branch an ancestor of main
+        repo.write("main.txt", "main\n");
+        repo.commit_all("Main moves on");
+
+        let child_dir = child.to_string_lossy().to_string();
+        assert_eq!(pr_base_branch(&child_dir, "mindgrid/child").await.as_deref(), Some("mindgrid/parent"));
+
+        let results = restack_children(&repo.path_str(), "mindgrid/parent").await;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].status, "up_to_date");
+        assert_eq!(results[0].new_base, "mindgrid/parent");
+        assert_eq!(parent_of(&repo.path_str(), "mindgrid/child").await.as_deref(), Some("mindgrid/parent"));
+    }
+
+    #[tokio::test]
+    async fn squash_merged_parent_moves_children_to_main() {
+        let repo = TempRepo::with_files(&[("README.md", "readme\n")]);
+        let (parent, child) = stack(&repo).await;
+        commit_file(&parent, "parent.txt");
+        let results = restack_children(&repo.path_str(), "mindgrid/parent").await;
+        assert_eq!(results[0].status, "rebased");
+        assert_eq!(results[0].new_base, "mindgrid/parent");
+
+        repo.git(&["merge", "-q", "--squash", "mindgrid/parent"]);
+        repo.commit_all("Squash parent");
+
+        let child_dir = child.to_string_lossy().to_string();
+        assert_eq!(pr_base_branch(&child_dir, "mindgrid/child").await, None);
+
+        let results = restack_children(&repo.path_str(), "mindgrid/parent").await;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].status, "rebased");
+        assert_eq!(results[0].new_base, "main");
+        assert_eq!(parent_of(&repo.path_str(), "mindgrid/child").await, None);
+        assert_eq!(git_in(&child, &["rev-list", "--count", "main..HEAD"]).trim(), "1");
+        assert!(child.join("parent.txt").is_file());
+    }
+
+    #[tokio::test]
+    async fn merge_commits_and_recorded_merges_count() {
+        let repo = TempRepo::with_files(&[("README.md", "readme\n")]);
+        let (parent, child) = stack(&repo).await;
+        commit_file(&parent, "parent.txt");
+        let child_dir = child.to_string_lossy().to_string();
+
+        // A fast-forward leaves main's history looking like the parent never branched
+        repo.git(&["merge", "-q", "--ff-only", "mindgrid/parent"]);
+        assert_eq!(pr_base_branch(&child_dir, "mindgrid/child").await.as_deref(), Some("mindgrid/parent"));
+        mark_merged(&repo.path_str(), "mindgrid/parent").await.unwrap();
+        assert_eq!(pr_base_branch(&child_dir, "mindgrid/child").await, None);
+
+        let other = session(&repo, "other", "main");
+        commit_file(&other, "other.txt");
+        repo.write("main.txt", "main\n");
+        repo.commit_all("Main moves on");
+        assert!(!is_merged_into(&repo.path_str(), "mindgrid/other", "main").await);
+        repo.git(&["merge", "-q", "--no-ff", "-m", "Merge other", "mindgrid/other"]);
+        assert!(is_merged_into(&repo.path_str(), "mindgrid/other", "main").await);
+    }
+}
@@ -0,0 +1,140 @@
+use serde::Serialize;
+use std::path::Path;
+use ts_rs::TS;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum SubmoduleState {
+    Clean,
+    /// The checked-out commit differs from the one recorded in the superproject
+    Modified,
+    Uninitialized,
+    Conflict,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct SubmoduleStatus {
+    pub path: String,
+    /// Commit checked out in the submodule (recorded commit when uninitialized)
+    pub commit: String,
+    pub state: SubmoduleState,
+}
+
+pub fn has_submodules(worktree: &Path) -> bool {
+    worktree.join(".gitmodules").is_file()
+}
+
+/// Submodule paths declared in `.gitmodules`
+async fn declared_paths(worktree: &Path) -> Vec<String> {
+    let output = tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(worktree)
+        .args(["config", "-f", ".gitmodules", "--get-regexp", r"^submodule\..*\.path$"])
+        .output()
+        .await;
+
+    match output {
+        Ok(o) if o.status.success() => String::from_utf8_lossy(&o.stdout)
+            .lines()
+            .filter_map(|l| l.split_once(' ').map(|(_, path)| path.to_string()))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// `git submodule` in `worktree`. Tests clone submodules from local paths,
+/// which git refuses by default since 2.38.1.
+fn submodule_command(worktree: &Path) -> tokio::process::Command {
+    let mut cmd = tokio::process::Command::new("git");
+    cmd.arg("-C").arg(worktree);
+    #[cfg(test)]
+    cmd.args(["-c", "protocol.file.allow=always"]);
+    cmd.arg("submodule");
+    cmd
+}
+
+/// Initialize and check out the submodules of a new worktree. Submodules already
+/// cloned in the main checkout are used as a reference so their objects are
+/// borrowed instead of fetched again. Returns the errors for submodules that
+/// couldn't be initialized.
+pub async fn init_submodules(project: &Path, worktree: &Path) -> Vec<String> {
+    let mut errors = Vec::new();
+
+    for path in declared_paths(worktree).await {
+        // Paths outside a sparse checkout have no directory and are left alone
+        if !worktree.join(&path).is_dir() {
+            continue;
+        }
+
+        let mut cmd = submodule_command(worktree);
+        cmd.args(["update", "--init"]);
+        let reference = project.join(&path);
+        if crate::repo_scan::detect_repo(&reference).is_some() {
+            cmd.arg("--reference").arg(&reference);
+        }
+        cmd.arg("--").arg(&path);
+
+        match cmd.output().await {
+            Ok(o) if o.status.success() => {}
+            Ok(o) => errors.push(format!("{}: {}", path, String::from_utf8_lossy(&o.stderr).trim())),
+            Err(e) => errors.push(format!("{}: {}", path, e)),
+        }
+    }
+
+    // Nested submodules of the ones initialized above
+    if let Ok(o) = submodule_command(worktree)
+        .args(["update", "--init", "--recursive"])
+        .output()
+        .await
+    {
+        if !o.status.success() {
+            errors.push(String::from_utf8_lossy(&o.stderr).trim().to_string());
+        }
+    }
+
+    errors
+}
+
+/// Parse `git submodule status` output
+pub fn parse_status(text: &str) -> Vec<SubmoduleStatus> {
+    text.lines()
+        .filter_map(|line| {
+            let mut chars = line.chars();
+            let state = match chars.next()? {
+                '+' => SubmoduleState::Modified,
+                '-' => SubmoduleState::Uninitialized,
+                'U' => SubmoduleState::Conflict,
+                _ => SubmoduleState::Clean,
+            };
+            let mut fields = chars.as_str().split_whitespace();
+            let commit = fields.next()?.to_string();
+            let path = fields.next()?.to_string();
+            Some(SubmoduleStatus {
+                path,
+                commit,
+                state,
+            })
+        })
+        .collect()
+}
+
+/// Status of every submodule, or an empty list when the repo has none
+pub async fn submodule_status(working_directory: &str) -> Vec<SubmoduleStatus> {
+    if !has_submodules(Path::new(working_directory)) {
+        return Vec::new();
+    }
+
+    let output = tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(working_directory)
+        .args(["submodule", "status"])
+        .output()
+        .await;
+
+    match output {
+        Ok(o) if o.status.success() => parse_status(&String::from_utf8_lossy(&o.stdout)),
+        _ => Vec::new(),
+    }
+}
@@ -0,0 +1,245 @@
+// Serde contract between the Rust command types and the TypeScript frontend.
+// The TypeScript side is generated by ts-rs into src/bindings; these tests pin
+// the JSON shapes so a rename or a new variant is a deliberate change.
+
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use ts_rs::TS;
+
+use crate::diff::DiffScope;
+use crate::error::{ErrorKind, MindgridError};
+use crate::git::{determine_state, operation_in_progress, GitState, GitStatus};
+use crate::provision::{CloneMethod, ProvisionOptions};
+use crate::repo_scan::RepoKind;
+use crate::submodule::{parse_status, SubmoduleState};
+use crate::trailers::CommitProvenance;
+
+const ALL_STATES: &[(GitState, &str)] = &[
+    (GitState::Rebasing, "rebasing"),
+    (GitState::Applying, "applying"),
+    (GitState::Merging, "merging"),
+    (GitState::CherryPicking, "cherry_picking"),
+    (GitState::Reverting, "reverting"),
+    (GitState::Bisecting, "bisecting"),
+    (GitState::Detached, "detached"),
+    (GitState::Conflict, "conflict"),
+    (GitState::Diverged, "diverged"),
+    (GitState::Modified, "modified"),
+    (GitState::Untracked, "untracked"),
+    (GitState::Ahead, "ahead"),
+    (GitState::Behind, "behind"),
+    (GitState::Clean, "clean"),
+    (GitState::Unknown, "unknown"),
+];
+
+fn temp_git_dir() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("mindgrid-contract-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).expect("Failed to create temp git dir");
+    dir
+}
+
+#[test]
+fn git_state_round_trips_as_snake_case_strings() {
+    for (state, name) in ALL_STATES {
+        assert_eq!(serde_json::to_value(state).unwrap(), json!(name));
+        let parsed: GitState = serde_json::from_value(json!(name)).unwrap();
+        assert_eq!(parsed, *state);
+    }
+}
+
+#[test]
+fn git_state_binding_lists_every_variant() {
+    let union = GitState::inline();
+    let variants: Vec<&str> = union.split(" | ").map(|v| v.trim_matches('"')).collect();
+    let expected: Vec<&str> = ALL_STATES.iter().map(|(_, name)| *name).collect();
+    assert_eq!(variants, expected);
+}
+
+#[test]
+fn git_status_json_fields() {
+    let status = GitStatus {
+        state: GitState::Merging,
+        ahead: Some(1),
+        behind: None,
+        additions: None,
+        deletions: None,
+        files_changed: None,
+        is_ready_to_merge: Some(false),
+        has_uncommitted_changes: Some(true),
+        has_untracked_files: Some(false),
+        current_branch: Some("mindgrid/test".to_string()),
+        main_branch: Some("main".to_string()),
+        is_detached: Some(false),
+        has_conflicts: Some(true),
+        submodules: None,
+        lfs_pointers: None,
+    };
+    let value = serde_json::to_value(&status).unwrap();
+    let Value::Object(fields) = &value else {
+        panic!("GitStatus should serialize to an object");
+    };
+
+    let mut keys: Vec<&str> = fields.keys().map(|k| k.as_str()).collect();
+    keys.sort_unstable();
+    assert_eq!(
+        keys,
+        vec![
+            "additions",
+            "ahead",
+            "behind",
+            "current_branch",
+            "deletions",
+            "files_changed",
+            "has_conflicts",
+            "has_uncommitted_changes",
+            "has_untracked_files",
+            "is_detached",
+            "is_ready_to_merge",
+            "lfs_pointers",
+            "main_branch",
+            "state",
+            "submodules",
+        ]
+    );
+    assert_eq!(value["state"], json!("merging"));
+    assert_eq!(value["behind"], Value::Null);
+}
+
+#[test]
+fn determine_state_priority() {
+    assert_eq!(determine_state(Some(1), Some(1), true, true, true), GitState::Conflict);
+    assert_eq!(determine_state(Some(1), Some(2), false, true, true), GitState::Diverged);
+    assert_eq!(determine_state(Some(1), None, false, true, true), GitState::Modified);
+    assert_eq!(determine_state(Some(1), None, false, false, true), GitState::Untracked);
+    assert_eq!(determine_state(Some(1), Some(0), false, false, false), GitState::Ahead);
+    assert_eq!(determine_state(None, Some(3), false, false, false), GitState::Behind);
+    assert_eq!(determine_state(None, None, false, false, false), GitState::Clean);
+}
+
+#[test]
+fn operations_detected_from_state_files() {
+    let cases: &[(&str, bool, GitState)] = &[
+        ("rebase-merge", true, GitState::Rebasing),
+        ("rebase-apply", true, GitState::Rebasing),
+        ("MERGE_HEAD", false, GitState::Merging),
+        ("CHERRY_PICK_HEAD", false, GitState::CherryPicking),
+        ("REVERT_HEAD", false, GitState::Reverting),
+        ("BISECT_LOG", false, GitState::Bisecting),
+    ];
+
+    for (marker, is_dir, expected) in cases {
+        let git_dir = temp_git_dir();
+        assert_eq!(operation_in_progress(&git_dir), None);
+
+        let path = git_dir.join(marker);
+        if *is_dir {
+            std::fs::create_dir_all(&path).unwrap();
+        } else {
+            std::fs::write(&path, "").unwrap();
+        }
+        assert_eq!(operation_in_progress(&git_dir), Some(*expected), "{}", marker);
+        assert!(expected.is_operation());
+
+        std::fs::remove_dir_all(&git_dir).ok();
+    }
+
+    let git_dir = temp_git_dir();
+    std::fs::create_dir_all(git_dir.join("rebase-apply")).unwrap();
+    std::fs::write(git_dir.join("rebase-apply").join("applying"), "").unwrap();
+    assert_eq!(operation_in_progress(&git_dir), Some(GitState::Applying));
+    std::fs::remove_dir_all(&git_dir).ok();
+}
+
+#[test]
+fn enum_arguments_and_results() {
+    let scope: DiffScope = serde_json::from_value(json!("vs_main")).unwrap();
+    assert_eq!(scope, DiffScope::VsMain);
+    assert_eq!(serde_json::to_value(DiffScope::Unstaged).unwrap(), json!("unstaged"));
+
+    assert_eq!(serde_json::to_value(RepoKind::Worktree).unwrap(), json!("worktree"));
+    assert_eq!(serde_json::to_value(CloneMethod::Reflink).unwrap(), json!("reflink"));
+    assert_eq!(
+        serde_json::to_value(SubmoduleState::Uninitialized).unwrap(),
+        json!("uninitialized")
+    );
+}
+
+#[test]
+fn optional_arguments_may_be_omitted() {
+    let provenance: CommitProvenance = serde_json::from_value(json!({ "agent": "claude" })).unwrap();
+    assert_eq!(provenance.agent.as_deref(), Some("claude"));
+    assert!(provenance.session_id.is_none());
+
+    let options: ProvisionOptions = serde_json::from_value(json!({})).unwrap();
+    assert!(options.sparse_patterns.is_none() && options.clone_dirs.is_none());
+}
+
+#[test]
+fn submodule_status_lines() {
+    let statuses = parse_status(
+        " 1111111111111111111111111111111111111111 lib/a (heads/main)\n\
+         +2222222222222222222222222222222222222222 lib/b (v1.0-3-g2222222)\n\
+         -3333333333333333333333333333333333333333 lib/c\n",
+    );
+    let states: Vec<(&str, SubmoduleState)> =
+        statuses.iter().map(|s| (s.path.as_str(), s.state)).collect();
+    assert_eq!(
+        states,
+        vec![
+            ("lib/a", SubmoduleState::Clean),
+            ("lib/b", SubmoduleState::Modified),
+            ("lib/c", SubmoduleState::Uninitialized),
+        ]
+    );
+}
+
+#[test]
+fn error_json_fields() {
+    let output = std::process::Command::new("sh")
+        .args(["-c", "echo 'fatal: boom' >&2; exit 3"])
+        .output()
+        .unwrap();
+    let error = MindgridError::git_failed("worktree_add_failed", "Failed to add worktree", &["worktree", "add"], &output);
+    assert_eq!(
+        serde_json::to_value(&error).unwrap(),
+        json!({
+            "kind": "git",
+            "code": "git.worktree_add_failed",
+            "message": "Failed to add worktree",
+            "technical": null,
+            "command": "git worktree add",
+            "stderr": "fatal: boom",
+            "exit_code": 3,
+        })
+    );
+    assert_eq!(error.user_message(), "Failed to add worktree: fatal: boom");
+
+    let kinds = [
+        (ErrorKind::Git, "git"),
+        (ErrorKind::Pty, "pty"),
+        (ErrorKind::Io, "io"),
+        (ErrorKind::Db, "db"),
+        (ErrorKind::Agent, "agent"),
+        (ErrorKind::Validation, "validation"),
+        (ErrorKind::Operation, "operation"),
+    ];
+    for (kind, name) in kinds {
+        assert_eq!(serde_json::to_value(kind).unwrap(), json!(name));
+    }
+    assert_eq!(MindgridError::pty("not_found", "PTY not found").code, "pty.not_found");
+}
+
+/// The SQL plugin has to preload the database the migrations are registered
+/// for; the config files name the dev database for every mode
+#[test]
+fn preloads_the_database_of_the_current_mode() {
+    let context = crate::app_context();
+    assert_eq!(
+        context.config().plugins.0["sql"],
+        json!({ "preload": [format!("sqlite:{}", crate::get_db_name())] })
+    );
+
+    let mut config = context.config().clone();
+    crate::preload_database(&mut config, "sqlite:mindgrid-dev.db");
+    assert_eq!(config.plugins.0["sql"]["preload"], json!(["sqlite:mindgrid-dev.db"]));
+}
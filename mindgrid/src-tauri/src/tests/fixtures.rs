@@ -0,0 +1,100 @@
+// Scratch git repositories for tests that drive real git commands.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// A repository in the system temp dir, removed when dropped
+pub struct TempRepo {
+    pub path: PathBuf,
+}
+
+impl TempRepo {
+    /// An empty repository on `main` with a committer configured
+    pub fn new() -> Self {
+        let path = std::env::temp_dir().join(format!("mindgrid-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&path).expect("Failed to create temp repo dir");
+        let repo = Self { path };
+        repo.git(&["init", "-q", "-b", "main"]);
+        repo.git(&["config", "user.name", "Mindgrid Test"]);
+        repo.git(&["config", "user.email", "test@mindgrid.invalid"]);
+        repo.git(&["config", "commit.gpgsign", "false"]);
+        repo
+    }
+
+    /// A repository with one commit containing `files`
+    pub fn with_files(files: &[(&str, &str)]) -> Self {
+        let repo = Self::new();
+        for (path, content) in files {
+            repo.write(path, content);
+        }
+        repo.commit_all("Initial commit");
+        repo
+    }
+
+    pub fn path_str(&self) -> String {
+        self.path.to_string_lossy().to_string()
+    }
+
+    /// Run git in the repository, panicking with its stderr on failure
+    pub fn git(&self, args: &[&str]) -> String {
+        git_in(&self.path, args)
+    }
+
+    pub fn write(&self, path: &str, content: &str) {
+        let file = self.path.join(path);
+        if let Some(parent) = file.parent() {
+            std::fs::create_dir_all(parent).expect("Failed to create parent dir");
+        }
+        std::fs::write(file, content).expect("Failed to write file");
+    }
+
+    pub fn read(&self, path: &str) -> String {
+        std::fs::read_to_string(self.path.join(path)).expect("Failed to read file")
+    }
+
+    /// Commit everything and return the new commit's sha
+    pub fn commit_all(&self, message: &str) -> String {
+        self.git(&["add", "-A"]);
+        self.git(&["commit", "-q", "--allow-empty", "-m", message]);
+        self.git(&["rev-parse", "HEAD"]).trim().to_string()
+    }
+}
+
+impl Drop for TempRepo {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
+
+pub fn git_in(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .expect("Failed to run git");
+    assert!(
+        output.status.success(),
+        "git {} failed: {}",
+        args.join(" "),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+/// An in-memory app database with every migration applied
+pub async fn memory_pool() -> sqlx::Pool<sqlx::Sqlite> {
+    // One connection: each in-memory connection is a database of its own
+    let pool = sqlx::sqlite::SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .expect("Failed to open an in-memory database");
+    for migration in crate::migrations() {
+        sqlx::raw_sql(migration.sql)
+            .execute(&pool)
+            .await
+            .unwrap_or_else(|e| panic!("Migration {} failed: {}", migration.description, e));
+    }
+    pool
+}
@@ -0,0 +1,3 @@
+// Test module declarations
+pub mod contract;
+pub mod fixtures;
@@ -0,0 +1,816 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+use ts_rs::TS;
+
+use crate::error::{MindgridError, MindgridResult};
+
+// Commits created by mindgrid carry git trailers that link them back to the
+// session, agent run and prompt that produced them:
+//
+//   Mindgrid-Session: <session id>
+//   Mindgrid-Agent: claude
+//   Mindgrid-Model: opus
+//   Mindgrid-Prompt-Hash: <first 12 hex digits of the prompt's git blob hash>
+//   Mindgrid-Checkpoint: true          (automatic checkpoint commits only)
+//   Co-authored-by: Claude <noreply@anthropic.com>
+//
+// The session id of a worktree is stored next to the stack metadata in the
+// repository's git config (`branch.mindgrid/<name>.mindgridSession`), so any
+// commit command can find it from the working directory alone.
+//
+// Which trailers are written is configured per project in local git config:
+//
+//   mindgrid.trailers = session,agent,model,prompt,checkpoint,coauthor
+//   mindgrid.coAuthor = Name <email>      (overrides the agent's default identity)
+
+pub const SESSION_TRAILER: &str = "Mindgrid-Session";
+pub const CHECKPOINT_TRAILER: &str = "Mindgrid-Checkpoint";
+pub const AGENT_TRAILER: &str = "Mindgrid-Agent";
+pub const MODEL_TRAILER: &str = "Mindgrid-Model";
+pub const PROMPT_HASH_TRAILER: &str = "Mindgrid-Prompt-Hash";
+pub const CO_AUTHOR_TRAILER: &str = "Co-authored-by";
+
+const SESSION_KEY: &str = "mindgridSession";
+const TRAILERS_KEY: &str = "mindgrid.trailers";
+const CO_AUTHOR_KEY: &str = "mindgrid.coAuthor";
+
+/// Trailer kinds accepted in `mindgrid.trailers`
+const TRAILER_KINDS: &[&str] = &["session", "agent", "model", "prompt", "checkpoint", "coauthor"];
+
+/// Who produced a commit, as known to the frontend
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+#[ts(export, optional_fields = nullable)]
+pub struct CommitProvenance {
+    /// Overrides the session recorded for the worktree's branch
+    pub session_id: Option<String>,
+    /// "claude", "codex" or "gemini"
+    pub agent: Option<String>,
+    pub model: Option<String>,
+    /// Prompt text; only its hash is written to the commit
+    pub prompt: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct TrailerConfig {
+    /// Enabled trailer kinds (see `TRAILER_KINDS`)
+    pub enabled: Vec<String>,
+    /// Value of mindgrid.coAuthor, if set
+    pub co_author: Option<String>,
+}
+
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
+pub struct SessionCommitRecord {
+    pub hash: String,
+    pub subject: String,
+    pub author: String,
+    /// Unix timestamp (seconds)
+    #[ts(type = "number")]
+    pub timestamp: i64,
+    /// All trailers of the commit as (key, value) pairs
+    pub trailers: Vec<(String, String)>,
+    pub is_checkpoint: bool,
+    /// Reachable from the main branch
+    pub in_main: bool,
+}
+
+/// All-zero hash git blame uses for lines that are not committed yet
+const UNCOMMITTED_HASH: &str = "0000000000000000000000000000000000000000";
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct BlameCommit {
+    pub hash: String,
+    pub short_hash: String,
+    pub author: String,
+    /// Unix timestamp (seconds)
+    #[ts(type = "number")]
+    pub author_time: i64,
+    pub summary: String,
+    pub session_id: Option<String>,
+    pub is_checkpoint: bool,
+    /// Lines changed in the working tree but not committed yet
+    pub is_uncommitted: bool,
+}
+
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
+pub struct BlameLine {
+    pub line_number: usize,
+    pub content: String,
+    pub commit: String,
+}
+
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
+pub struct BlameRange {
+    pub path: String,
+    pub lines: Vec<BlameLine>,
+    /// Commits referenced by `lines`, in order of first appearance
+    pub commits: Vec<BlameCommit>,
+}
+
+async fn git_stdout(repo: &str, args: &[&str]) -> Option<String> {
+    let output = tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .output()
+        .await
+        .ok()?;
+    if output.status.success() {
+        Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        None
+    }
+}
+
+/// Remember which session a branch belongs to
+pub async fn record_session(repo: &str, branch: &str, session_id: &str) -> MindgridResult<()> {
+    let key = format!("branch.{}.{}", branch, SESSION_KEY);
+    let args = ["config", key.as_str(), session_id];
+    let output = tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| MindgridError::spawn_failed("git config", e))?;
+    if !output.status.success() {
+        return Err(MindgridError::git_failed(
+            "config_failed",
+            format!("Failed to record session for {}", branch),
+            &args,
+            &output,
+        ));
+    }
+    Ok(())
+}
+
+/// Session id recorded for a branch
+pub async fn session_for_branch(repo: &str, branch: &str) -> Option<String> {
+    git_stdout(repo, &["config", "--get", &format!("branch.{}.{}", branch, SESSION_KEY)])
+        .await
+        .filter(|s| !s.is_empty())
+}
+
+/// Session id of the branch checked out in a working directory
+pub async fn current_session(working_directory: &str) -> Option<String> {
+    let branch = git_stdout(working_directory, &["rev-parse", "--abbrev-ref", "HEAD"]).await?;
+    session_for_branch(working_directory, &branch).await
+}
+
+/// Append trailers to a commit message using `git interpret-trailers`, so they
+/// end up in the same trailer block as any the user or a hook already added
+pub async fn append_trailers(working_directory: &str, message: &str, trailers: &[(&str, String)]) -> String {
+    if trailers.is_empty() {
+        return message.to_string();
+    }
+
+    let mut cmd = tokio::process::Command::new("git");
+    cmd.arg("-C")
+        .arg(working_directory)
+        .args(["interpret-trailers", "--if-exists", "addIfDifferent"]);
+    for (key, value) in trailers {
+        cmd.arg("--trailer").arg(format!("{}: {}", key, value));
+    }
+
+    let child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn();
+
+    let result = match child {
+        Ok(mut child) => {
+            if let Some(mut stdin) = child.stdin.take() {
+                // Without a final newline git treats a subject-only message as the trailer block
+                let _ = stdin.write_all(format!("{}\n", message.trim_end()).as_bytes()).await;
+            }
+            child.wait_with_output().await.ok()
+        }
+        Err(_) => None,
+    };
+
+    match result {
+        Some(output) if output.status.success() => String::from_utf8_lossy(&output.stdout).to_string(),
+        _ => {
+            // Fall back to a plain trailer block
+            let block: Vec<String> = trailers.iter().map(|(k, v)| format!("{}: {}", k, v)).collect();
+            format!("{}\n\n{}\n", message.trim_end(), block.join("\n"))
+        }
+    }
+}
+
+/// Read the project's trailer configuration (all trailers are enabled by default)
+pub async fn read_trailer_config(repo: &str) -> TrailerConfig {
+    let enabled = match git_stdout(repo, &["config", "--get", TRAILERS_KEY]).await {
+        Some(value) => value
+            .split(',')
+            .map(|k| k.trim().to_lowercase())
+            .filter(|k| TRAILER_KINDS.contains(&k.as_str()))
+            .collect(),
+        None => TRAILER_KINDS.iter().map(|k| k.to_string()).collect(),
+    };
+    let co_author = git_stdout(repo, &["config", "--get", CO_AUTHOR_KEY])
+        .await
+        .filter(|s| !s.is_empty());
+    TrailerConfig { enabled, co_author }
+}
+
+/// Default co-author identity for an agent
+fn agent_identity(agent: &str) -> Option<&'static str> {
+    match agent.to_lowercase().as_str() {
+        "claude" => Some("Claude <noreply@anthropic.com>"),
+        "codex" => Some("Codex <noreply@openai.com>"),
+        "gemini" => Some("Gemini <noreply@google.com>"),
+        _ => None,
+    }
+}
+
+/// Short, stable hash of a prompt (git's blob hash, so it can be recomputed with `git hash-object`)
+async fn prompt_hash(working_directory: &str, prompt: &str) -> Option<String> {
+    let mut child = tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(working_directory)
+        .args(["hash-object", "--stdin"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    let mut stdin = child.stdin.take()?;
+    stdin.write_all(prompt.as_bytes()).await.ok()?;
+    drop(stdin);
+
+    let output = child.wait_with_output().await.ok()?;
+    let hash = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if output.status.success() && hash.len() >= 12 {
+        Some(hash[..12].to_string())
+    } else {
+        None
+    }
+}
+
+/// Provenance trailers for a commit made in `working_directory`, filtered by the project's configuration
+pub async fn commit_trailers(
+    working_directory: &str,
+    provenance: &CommitProvenance,
+    checkpoint: bool,
+) -> Vec<(&'static str, String)> {
+    let config = read_trailer_config(working_directory).await;
+    let enabled = |kind: &str| config.enabled.iter().any(|k| k == kind);
+    let mut trailers = Vec::new();
+
+    if enabled("session") {
+        let session_id = match &provenance.session_id {
+            Some(id) => Some(id.clone()),
+            None => current_session(working_directory).await,
+        };
+        if let Some(session_id) = session_id {
+            trailers.push((SESSION_TRAILER, session_id));
+        }
+    }
+    if let (true, Some(agent)) = (enabled("agent"), &provenance.agent) {
+        trailers.push((AGENT_TRAILER, agent.clone()));
+    }
+    if let (true, Some(model)) = (enabled("model"), &provenance.model) {
+        trailers.push((MODEL_TRAILER, model.clone()));
+    }
+    if let (true, Some(prompt)) = (enabled("prompt"), &provenance.prompt) {
+        if let Some(hash) = prompt_hash(working_directory, prompt).await {
+            trailers.push((PROMPT_HASH_TRAILER, hash));
+        }
+    }
+    if checkpoint && enabled("checkpoint") {
+        trailers.push((CHECKPOINT_TRAILER, "true".to_string()));
+    }
+    if enabled("coauthor") {
+        let identity = config
+            .co_author
+            .clone()
+            .or_else(|| provenance.agent.as_deref().and_then(agent_identity).map(|s| s.to_string()));
+        if let Some(identity) = identity {
+            trailers.push((CO_AUTHOR_TRAILER, identity));
+        }
+    }
+    trailers
+}
+
+/// Read the mindgrid trailers of several commits with a single `git log`
+async fn trailers_for_commits(repo: &str, hashes: &[String]) -> HashMap<String, (Option<String>, bool)> {
+    if hashes.is_empty() {
+        return HashMap::new();
+    }
+
+    let format = format!(
+        "--format=%H%x1f%(trailers:key={},valueonly,separator=%x2c)%x1f%(trailers:key={},valueonly,separator=%x2c)%x1e",
+        SESSION_TRAILER, CHECKPOINT_TRAILER
+    );
+    let output = tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(["log", "--no-walk=unsorted", &format])
+        .args(hashes)
+        .output()
+        .await;
+
+    match output {
+        Ok(output) => parse_trailer_records(&String::from_utf8_lossy(&output.stdout)),
+        Err(_) => HashMap::new(),
+    }
+}
+
+/// Session and checkpoint flag per commit from `trailers_for_commits`' log format
+fn parse_trailer_records(text: &str) -> HashMap<String, (Option<String>, bool)> {
+    let mut result = HashMap::new();
+    for record in text.split('\u{1e}') {
+        let fields: Vec<&str> = record.trim().split('\u{1f}').collect();
+        if fields.len() < 3 || fields[0].is_empty() {
+            continue;
+        }
+        // The last value wins if a commit carries the trailer more than once
+        let session = fields[1].split(',').map(|s| s.trim()).rfind(|s| !s.is_empty());
+        let checkpoint = fields[2].split(',').any(|v| v.trim() == "true");
+        result.insert(fields[0].to_string(), (session.map(|s| s.to_string()), checkpoint));
+    }
+    result
+}
+
+/// (key, value) pairs of `%(trailers:unfold,only,separator=%x1d)`
+fn parse_trailers(block: &str) -> Vec<(String, String)> {
+    block
+        .split('\u{1d}')
+        .filter_map(|t| t.split_once(':'))
+        .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+        .collect()
+}
+
+/// Lines and commits (in order of first appearance) from `git blame --porcelain`.
+/// A commit's author and summary follow only its first line.
+fn parse_blame_porcelain(text: &str) -> (Vec<BlameLine>, Vec<BlameCommit>) {
+    let mut lines = Vec::new();
+    let mut commits: Vec<BlameCommit> = Vec::new();
+    let mut index_by_hash: HashMap<String, usize> = HashMap::new();
+    let mut current: Option<(String, usize)> = None;
+
+    for line in text.lines() {
+        if let Some(content) = line.strip_prefix('\t') {
+            if let Some((hash, line_number)) = current.take() {
+                lines.push(BlameLine {
+                    line_number,
+                    content: content.to_string(),
+                    commit: hash,
+                });
+            }
+            continue;
+        }
+
+        let mut parts = line.split(' ');
+        let first = parts.next().unwrap_or("");
+        if first.len() == 40 && first.chars().all(|c| c.is_ascii_hexdigit()) {
+            // "<hash> <orig line> <final line> [<group size>]"
+            let final_line = parts.nth(1).and_then(|n| n.parse().ok()).unwrap_or(0);
+            if !index_by_hash.contains_key(first) {
+                index_by_hash.insert(first.to_string(), commits.len());
+                commits.push(BlameCommit {
+                    hash: first.to_string(),
+                    short_hash: first[..8].to_string(),
+                    author: String::new(),
+                    author_time: 0,
+                    summary: String::new(),
+                    session_id: None,
+                    is_checkpoint: false,
+                    is_uncommitted: first == UNCOMMITTED_HASH,
+                });
+            }
+            current = Some((first.to_string(), final_line));
+            continue;
+        }
+
+        let Some((hash, _)) = &current else {
+            continue;
+        };
+        let Some(commit) = index_by_hash.get(hash).map(|&i| &mut commits[i]) else {
+            continue;
+        };
+        if let Some(author) = line.strip_prefix("author ") {
+            commit.author = author.to_string();
+        } else if let Some(time) = line.strip_prefix("author-time ") {
+            commit.author_time = time.parse().unwrap_or(0);
+        } else if let Some(summary) = line.strip_prefix("summary ") {
+            commit.summary = summary.to_string();
+        }
+    }
+    (lines, commits)
+}
+
+/// Blame lines `start..=end` of a file and attach session/checkpoint info to each commit
+pub async fn blame_range(working_directory: &str, path: &str, start: usize, end: usize) -> MindgridResult<BlameRange> {
+    if start == 0 || end < start {
+        return Err(MindgridError::validation(
+            "invalid_line_range",
+            format!("Invalid line range {}-{}", start, end),
+        ));
+    }
+
+    let range = format!("{},{}", start, end);
+    let args = ["blame", "--porcelain", "-L", range.as_str(), "--", path];
+    let output = tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(working_directory)
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| MindgridError::spawn_failed("git blame", e))?;
+
+    if !output.status.success() {
+        return Err(MindgridError::git_failed("blame_failed", format!("Failed to blame {}", path), &args, &output));
+    }
+
+    let (lines, mut commits) = parse_blame_porcelain(&String::from_utf8_lossy(&output.stdout));
+
+    let committed: Vec<String> = commits
+        .iter()
+        .filter(|c| !c.is_uncommitted)
+        .map(|c| c.hash.clone())
+        .collect();
+    let trailers = trailers_for_commits(working_directory, &committed).await;
+    let worktree_session = current_session(working_directory).await;
+
+    for commit in commits.iter_mut() {
+        if commit.is_uncommitted {
+            // Uncommitted lines belong to whichever session owns this worktree
+            commit.session_id = worktree_session.clone();
+        } else if let Some((session_id, checkpoint)) = trailers.get(&commit.hash) {
+            commit.session_id = session_id.clone();
+            commit.is_checkpoint = *checkpoint;
+        }
+    }
+
+    Ok(BlameRange {
+        path: path.to_string(),
+        lines,
+        commits,
+    })
+}
+
+/// Blame a line range and map each line to the mindgrid session and checkpoint that introduced it
+#[tauri::command]
+pub async fn git_blame_range(
+    working_directory: String,
+    path: String,
+    start: usize,
+    end: usize,
+) -> MindgridResult<BlameRange> {
+    blame_range(&working_directory, &path, start, end).await
+}
+
+/// Get the project's commit trailer configuration
+#[tauri::command]
+pub async fn get_trailer_config(project_path: String) -> MindgridResult<TrailerConfig> {
+    Ok(read_trailer_config(&project_path).await)
+}
+
+/// Choose which trailers mindgrid adds to commits, and optionally the co-author identity
+/// (an empty `co_author` clears it)
+#[tauri::command]
+pub async fn set_trailer_config(
+    project_path: String,
+    enabled: Vec<String>,
+    co_author: Option<String>,
+) -> MindgridResult<TrailerConfig> {
+    let mut kinds = Vec::new();
+    for kind in &enabled {
+        let kind = kind.trim().to_lowercase();
+        if !TRAILER_KINDS.contains(&kind.as_str()) {
+            return Err(MindgridError::validation(
+                "unknown_trailer",
+                format!("Unknown trailer: {} (expected one of {})", kind, TRAILER_KINDS.join(", ")),
+            ));
+        }
+        if !kinds.contains(&kind) {
+            kinds.push(kind);
+        }
+    }
+
+    let mut updates = vec![(TRAILERS_KEY, Some(kinds.join(",")))];
+    if let Some(co_author) = co_author {
+        let co_author = co_author.trim().to_string();
+        updates.push((CO_AUTHOR_KEY, if co_author.is_empty() { None } else { Some(co_author) }));
+    }
+
+    for (key, value) in updates {
+        let args = match &value {
+            Some(value) => vec!["config", "--local", key, value.as_str()],
+            None => vec!["config", "--local", "--unset", key],
+        };
+        let output = tokio::process::Command::new("git")
+            .arg("-C")
+            .arg(&project_path)
+            .args(&args)
+            .output()
+            .await
+            .map_err(|e| MindgridError::spawn_failed("git config", e))?;
+        // Exit code 5 means there was nothing to unset
+        let nothing_to_unset = value.is_none() && output.status.code() == Some(5);
+        if !output.status.success() && !nothing_to_unset {
+            return Err(MindgridError::git_failed(
+                "config_failed",
+                format!("Failed to save {}", key),
+                &args,
+                &output,
+            ));
+        }
+    }
+
+    Ok(read_trailer_config(&project_path).await)
+}
+
+/// Commits carrying `Mindgrid-Session: <session_id>`, newest first. Searches the main
+/// branch by default, or every branch when `all_branches` is set.
+#[tauri::command]
+pub async fn find_commits_by_session(
+    project_path: String,
+    session_id: String,
+    all_branches: Option<bool>,
+) -> MindgridResult<Vec<SessionCommitRecord>> {
+    let main_branch = crate::git::detect_main_branch(&project_path)
+        .await
+        .unwrap_or_else(|| "main".to_string());
+
+    // --grep narrows the walk cheaply; trailers are then matched exactly
+    let grep = format!("--grep={}: {}", SESSION_TRAILER, session_id);
+    let format = "--format=%H%x1f%s%x1f%an%x1f%ct%x1f%(trailers:unfold,only,separator=%x1d)%x1e";
+
+    let log = |revs: Vec<String>| {
+        let project_path = project_path.clone();
+        let grep = grep.clone();
+        async move {
+            let mut args = vec!["log", "--fixed-strings", grep.as_str(), format];
+            args.extend(revs.iter().map(|r| r.as_str()));
+            let output = tokio::process::Command::new("git")
+                .arg("-C")
+                .arg(&project_path)
+                .args(&args)
+                .output()
+                .await
+                .map_err(|e| MindgridError::spawn_failed("git log", e))?;
+            if !output.status.success() {
+                return Err(MindgridError::git_failed(
+                    "log_failed",
+                    "Failed to search commits for the session",
+                    &args,
+                    &output,
+                ));
+            }
+            Ok(String::from_utf8_lossy(&output.stdout).to_string())
+        }
+    };
+
+    let main_text = log(vec![main_branch.clone()]).await?;
+    let text = if all_branches.unwrap_or(false) {
+        log(vec!["--all".to_string()]).await?
+    } else {
+        main_text.clone()
+    };
+
+    let in_main: HashSet<&str> = main_text
+        .split('\u{1e}')
+        .filter_map(|r| r.trim().split('\u{1f}').next())
+        .filter(|h| !h.is_empty())
+        .collect();
+
+    let mut commits = Vec::new();
+    for record in text.split('\u{1e}') {
+        let fields: Vec<&str> = record.trim().split('\u{1f}').collect();
+        if fields.len() < 5 || fields[0].is_empty() {
+            continue;
+        }
+
+        let trailers = parse_trailers(fields[4]);
+
+        let matches_session = trailers
+            .iter()
+            .any(|(k, v)| k.eq_ignore_ascii_case(SESSION_TRAILER) && *v == session_id);
+        if !matches_session {
+            continue;
+        }
+
+        let is_checkpoint = trailers
+            .iter()
+            .any(|(k, v)| k.eq_ignore_ascii_case(CHECKPOINT_TRAILER) && v == "true");
+
+        commits.push(SessionCommitRecord {
+            hash: fields[0].to_string(),
+            subject: fields[1].to_string(),
+            author: fields[2].to_string(),
+            timestamp: fields[3].parse().unwrap_or(0),
+            in_main: in_main.contains(fields[0]),
+            trailers,
+            is_checkpoint,
+        });
+    }
+
+    Ok(commits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::fixtures::TempRepo;
+
+    // Captured from `git blame --porcelain -L 1,5 -- f.txt` with an uncommitted last line
+    const BLAME: &str = "\
+a0c76f844b5f155b33c570f7442c959099bd9493 1 1 1
+author Dev One
+author-mail <dev@example.com>
+author-time 1700000000
+author-tz +0000
+committer Dev One
+committer-mail <dev@example.com>
+committer-time 1792343089
+committer-tz +0000
+summary First
+boundary
+filename f.txt
+\ta
+d4464ef34838dc8c1a69cce60e0d668151f4f14d 2 2 1
+author Dev One
+author-mail <dev@example.com>
+author-time 1792343089
+author-tz +0000
+committer Dev One
+committer-mail <dev@example.com>
+committer-time 1792343089
+committer-tz +0000
+summary Second
+previous a0c76f844b5f155b33c570f7442c959099bd9493 f.txt
+filename f.txt
+\tB2
+a0c76f844b5f155b33c570f7442c959099bd9493 3 3 1
+\tc
+d4464ef34838dc8c1a69cce60e0d668151f4f14d 4 4 1
+\td
+0000000000000000000000000000000000000000 5 5 1
+author Not Committed Yet
+author-mail <not.committed.yet>
+author-time 1792343089
+author-tz +0000
+committer Not Committed Yet
+committer-mail <not.committed.yet>
+committer-time 1792343089
+committer-tz +0000
+summary Version of f.txt from f.txt
+previous d4464ef34838dc8c1a69cce60e0d668151f4f14d f.txt
+filename f.txt
+\twip
+";
+
+    #[test]
+    fn parses_blame_porcelain() {
+        let (lines, commits) = parse_blame_porcelain(BLAME);
+
+        let lines: Vec<(usize, &str, &str)> = lines
+            .iter()
+            .map(|l| (l.line_number, l.content.as_str(), &l.commit[..8]))
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                (1, "a", "a0c76f84"),
+                (2, "B2", "d4464ef3"),
+                (3, "c", "a0c76f84"),
+                (4, "d", "d4464ef3"),
+                (5, "wip", "00000000"),
+            ]
+        );
+
+        assert_eq!(commits.len(), 3);
+        assert_eq!(commits[0].short_hash, "a0c76f84");
+        assert_eq!(commits[0].author, "Dev One");
+        assert_eq!(commits[0].author_time, 1700000000);
+        assert_eq!(commits[0].summary, "First");
+        assert_eq!(commits[1].summary, "Second");
+        assert!(!commits[1].is_uncommitted);
+        assert!(commits[2].is_uncommitted);
+    }
+
+    #[tokio::test]
+    async fn blame_attaches_sessions_and_checkpoints() {
+        let repo = TempRepo::with_files(&[("f.txt", "a\n")]);
+        repo.git(&["checkout", "-q", "-b", "mindgrid/blamed"]);
+        repo.write("f.txt", "a\nb\n");
+        repo.git(&["commit", "-qam", "Agent edit\n\nMindgrid-Session: s-1\nMindgrid-Checkpoint: true"]);
+        repo.write("f.txt", "a\nb\nwip\n");
+        let path = repo.path_str();
+        record_session(&path, "mindgrid/blamed", "s-2").await.unwrap();
+
+        let blame = blame_range(&path, "f.txt", 1, 3).await.unwrap();
+        let sessions: Vec<(Option<&str>, bool, bool)> = blame
+            .commits
+            .iter()
+            .map(|c| (c.session_id.as_deref(), c.is_checkpoint, c.is_uncommitted))
+            .collect();
+        assert_eq!(sessions, [(None, false, false), (Some("s-1"), true, false), (Some("s-2"), false, true)]);
+
+        let err = blame_range(&path, "f.txt", 3, 1).await.unwrap_err();
+        assert_eq!(err.code, "validation.invalid_line_range");
+    }
+
+    #[test]
+    fn parses_trailer_output() {
+        // Captured from `%(trailers:unfold,only,separator=%x1d)`; the co-author was folded
+        let block = "Mindgrid-Session: s-1\u{1d}Mindgrid-Agent: claude\u{1d}Mindgrid-Checkpoint: true\u{1d}\
+                     Co-authored-by: Claude <noreply@anthropic.com>";
+        assert_eq!(
+            parse_trailers(block),
+            [
+                ("Mindgrid-Session".to_string(), "s-1".to_string()),
+                ("Mindgrid-Agent".to_string(), "claude".to_string()),
+                ("Mindgrid-Checkpoint".to_string(), "true".to_string()),
+                ("Co-authored-by".to_string(), "Claude <noreply@anthropic.com>".to_string()),
+            ]
+        );
+        assert!(parse_trailers("").is_empty());
+
+        // Captured from `trailers_for_commits`' format; the second commit has no checkpoint
+        let records = "f37ecbdc3858a6bc442808c3a15f69bc9dcb5c46\u{1f}s-1\u{1f}true\u{1e}\n\
+                       d4464ef34838dc8c1a69cce60e0d668151f4f14d\u{1f}s-0,s-1\u{1f}\u{1e}\n\
+                       a0c76f844b5f155b33c570f7442c959099bd9493\u{1f}\u{1f}\u{1e}\n";
+        let records = parse_trailer_records(records);
+        assert_eq!(records["f37ecbdc3858a6bc442808c3a15f69bc9dcb5c46"], (Some("s-1".to_string()), true));
+        assert_eq!(records["d4464ef34838dc8c1a69cce60e0d668151f4f14d"], (Some("s-1".to_string()), false));
+        assert_eq!(records["a0c76f844b5f155b33c570f7442c959099bd9493"], (None, false));
+    }
+
+    #[tokio::test]
+    async fn trailers_follow_the_project_config() {
+        let repo = TempRepo::with_files(&[("f.txt", "a\n")]);
+        repo.git(&["checkout", "-q", "-b", "mindgrid/traced"]);
+        let path = repo.path_str();
+        record_session(&path, "mindgrid/traced", "s-7").await.unwrap();
+        let provenance = CommitProvenance {
+            agent: Some("claude".to_string()),
+            model: Some("opus".to_string()),
+            prompt: Some("Fix the bug".to_string()),
+            ..Default::default()
+        };
+
+        let trailers = commit_trailers(&path, &provenance, true).await;
+        let keys: Vec<&str> = trailers.iter().map(|(k, _)| *k).collect();
+        assert_eq!(
+            keys,
+            [SESSION_TRAILER, AGENT_TRAILER, MODEL_TRAILER, PROMPT_HASH_TRAILER, CHECKPOINT_TRAILER, CO_AUTHOR_TRAILER]
+        );
+        assert_eq!(trailers[0].1, "s-7");
+        // `printf 'Fix the bug' | git hash-object --stdin`
+        assert_eq!(trailers[3].1, "889fa66fb9fa");
+        assert_eq!(trailers[5].1, "Claude <noreply@anthropic.com>");
+
+        set_trailer_config(path.clone(), vec!["Session".to_string(), "coauthor".to_string()], Some("Pair <pair@example.com>".to_string()))
+            .await
+            .unwrap();
+        let trailers = commit_trailers(&path, &provenance, true).await;
+        assert_eq!(
+            trailers,
+            [
+                (SESSION_TRAILER, "s-7".to_string()),
+                (CO_AUTHOR_TRAILER, "Pair <pair@example.com>".to_string()),
+            ]
+        );
+        let err = set_trailer_config(path.clone(), vec!["ticket".to_string()], None).await.unwrap_err();
+        assert_eq!(err.code, "validation.unknown_trailer");
+
+        // Trailers join a block a hook or the user already wrote
+        let message = append_trailers(&path, "Fix bug\n\nSigned-off-by: Dev <dev@example.com>", &trailers).await;
+        assert_eq!(
+            message,
+            "Fix bug\n\nSigned-off-by: Dev <dev@example.com>\nMindgrid-Session: s-7\nCo-authored-by: Pair <pair@example.com>\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn finds_commits_by_session() {
+        let repo = TempRepo::with_files(&[("f.txt", "a\n")]);
+        repo.git(&["checkout", "-q", "-b", "mindgrid/found"]);
+        repo.git(&["commit", "-q", "--allow-empty", "-m", "Checkpoint\n\nMindgrid-Session: s-3\nMindgrid-Checkpoint: true"]);
+        repo.git(&["checkout", "-q", "main"]);
+        repo.git(&["commit", "-q", "--allow-empty", "-m", "Merged work\n\nMindgrid-Session: s-3"]);
+        // Mentioned in the body only, not a trailer
+        repo.git(&["commit", "-q", "--allow-empty", "-m", "Notes\n\nMindgrid-Session: s-3 was slow\n\nReviewed-by: Dev"]);
+        let path = repo.path_str();
+
+        let on_main = find_commits_by_session(path.clone(), "s-3".to_string(), None).await.unwrap();
+        let subjects: Vec<(&str, bool, bool)> = on_main.iter().map(|c| (c.subject.as_str(), c.in_main, c.is_checkpoint)).collect();
+        assert_eq!(subjects, [("Merged work", true, false)]);
+
+        let everywhere = find_commits_by_session(path, "s-3".to_string(), Some(true)).await.unwrap();
+        let mut subjects: Vec<(&str, bool, bool)> = everywhere.iter().map(|c| (c.subject.as_str(), c.in_main, c.is_checkpoint)).collect();
+        subjects.sort();
+        assert_eq!(subjects, [("Checkpoint", false, true), ("Merged work", true, false)]);
+    }
+}
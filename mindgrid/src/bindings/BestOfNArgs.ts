@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { QueryTarget } from "./QueryTarget";
+
+export type BestOfNArgs = { 
+/**
+ * Lets the caller subscribe to the candidate channels before the run starts
+ */
+run_id?: string | null, project_path: string, task: string, candidates: Array<QueryTarget>, 
+/**
+ * Shell command run in each worktree once its agent finishes
+ */
+test_command?: string | null, system_prompt?: string | null, 
+/**
+ * Permission mode for Claude candidates, as for a session
+ */
+permission_mode?: string | null, };
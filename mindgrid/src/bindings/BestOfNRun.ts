@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Candidate } from "./Candidate";
+
+export type BestOfNRun = { id: string, project_path: string, task: string, test_command: string | null, candidates: Array<Candidate>, 
+/**
+ * Id of the merged candidate
+ */
+winner: string | null, created_at: number, };
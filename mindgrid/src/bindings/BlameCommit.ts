@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type BlameCommit = { hash: string, short_hash: string, author: string, 
+/**
+ * Unix timestamp (seconds)
+ */
+author_time: number, summary: string, session_id: string | null, is_checkpoint: boolean, 
+/**
+ * Lines changed in the working tree but not committed yet
+ */
+is_uncommitted: boolean, };
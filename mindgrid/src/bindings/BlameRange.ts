@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BlameCommit } from "./BlameCommit";
+import type { BlameLine } from "./BlameLine";
+
+export type BlameRange = { path: string, lines: Array<BlameLine>, 
+/**
+ * Commits referenced by `lines`, in order of first appearance
+ */
+commits: Array<BlameCommit>, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CandidateStatus } from "./CandidateStatus";
+import type { DiffSummary } from "./DiffSummary";
+import type { QueryAgent } from "./QueryAgent";
+import type { TestRun } from "./TestRun";
+
+/**
+ * Comparison report for one candidate
+ */
+export type Candidate = { id: string, agent: QueryAgent, model: string | null, worktree_path: string, branch: string, status: CandidateStatus, 
+/**
+ * Final reply of the agent
+ */
+response: string | null, error: string | null, 
+/**
+ * Changes against the main branch, committed or not
+ */
+diff: DiffSummary | null, test: TestRun | null, cost_usd: number | null, duration_ms: number | null, };
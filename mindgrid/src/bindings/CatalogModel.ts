@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ModelProvider } from "./ModelProvider";
+import type { ModelSource } from "./ModelSource";
+
+export type CatalogModel = { 
+/**
+ * Value passed to the agent's `--model` flag
+ */
+id: string, name: string, provider: ModelProvider, source: ModelSource, 
+/**
+ * The agent's default when no model is chosen
+ */
+is_default: boolean, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Aggregated CI results for the PR head commit
+ */
+export type CheckSummary = { total: number, passed: number, failed: number, pending: number, skipped: number, 
+/**
+ * "success", "failure", "pending" or "none"
+ */
+state: string, 
+/**
+ * Names of the checks that failed
+ */
+failing: Array<string>, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MindgridError } from "./MindgridError";
+
+/**
+ * What the Codex runner needs, so the UI can explain a broken setup
+ */
+export type CodexRunnerDiagnostics = { ready: boolean, node_path: string | null, node_version: string | null, runner_path: string | null, sdk_path: string | null, problems: Array<MindgridError>, };
@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type CodexThreadArgs = { 
+/**
+ * Reopen a stored thread
+ */
+thread_id?: string | null, 
+/**
+ * Without `thread_id`, continue the session's most recent thread
+ */
+session_id?: string | null, 
+/**
+ * Codex conversation to continue when the stored thread has none, e.g. the
+ * one a thread closed for a model change had already started
+ */
+codex_thread_id?: string | null, cwd: string, model?: string | null, 
+/**
+ * Codex SDK sandbox; defaults to "workspace-write"
+ */
+sandbox_mode?: string | null, system_prompt?: string | null, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload of `codex-thread-event`: one runner envelope, as `run_codex` returns them
+ */
+export type CodexThreadEvent = { thread_id: string, turn_id: string, line: string, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type CodexThreadInfo = { thread_id: string, 
+/**
+ * Assigned by Codex on the first turn
+ */
+codex_thread_id: string | null, session_id: string | null, cwd: string, model: string | null, 
+/**
+ * The thread continues an earlier Codex conversation
+ */
+resumed: boolean, };
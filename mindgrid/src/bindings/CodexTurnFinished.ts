@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CodexTurnStatus } from "./CodexTurnStatus";
+
+/**
+ * Payload of `codex-turn-finished`
+ */
+export type CodexTurnFinished = { thread_id: string, turn_id: string, status: CodexTurnStatus, error: string | null, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Who produced a commit, as known to the frontend
+ */
+export type CommitProvenance = { 
+/**
+ * Overrides the session recorded for the worktree's branch
+ */
+session_id?: string | null, 
+/**
+ * "claude", "codex" or "gemini"
+ */
+agent?: string | null, model?: string | null, 
+/**
+ * Prompt text; only its hash is written to the commit
+ */
+prompt?: string | null, };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { HookRun } from "./HookRun";
+
+export type CommitResult = { success: boolean, commit_hash: string | null, error: string | null, 
+/**
+ * Hooks that ran before the commit, in order
+ */
+hooks: Array<HookRun>, 
+/**
+ * Name of the hook that blocked the commit, if any
+ */
+failed_hook: string | null, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type CommitSignature = { hash: string, subject: string, 
+/**
+ * Raw `%G?` code from git log
+ */
+code: string, 
+/**
+ * "good", "unknown_validity", "bad", "expired", "expired_key", "revoked", "unverifiable" or "unsigned"
+ */
+status: string, signer: string | null, key: string | null, };
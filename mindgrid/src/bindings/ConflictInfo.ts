@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ConflictCommits } from "./ConflictCommits";
+
+export type ConflictInfo = { has_conflicts: boolean, conflicting_files: Array<string>, conflicting_commits: ConflictCommits | null, };
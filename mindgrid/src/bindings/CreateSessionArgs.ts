@@ -0,0 +1,25 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ProvisionOptions } from "./ProvisionOptions";
+import type { SpawnArgs } from "./SpawnArgs";
+
+export type CreateSessionArgs = { 
+/**
+ * Session id chosen by the caller; generated when omitted
+ */
+id?: string | null, project_id: string, project_path: string, name: string, 
+/**
+ * Working directory for projects that aren't git repositories
+ */
+cwd?: string | null, 
+/**
+ * Parent session worktree name for stacked sessions
+ */
+parent?: string | null, model?: string | null, provision?: ProvisionOptions | null, 
+/**
+ * Gitignored files to copy from the project into the worktree
+ */
+copy_files?: Array<string> | null, 
+/**
+ * Agent to start in the session's working directory (`cwd` is ignored)
+ */
+agent?: SpawnArgs | null, };
@@ -0,0 +1,27 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type DiffFileEntry = { path: string, 
+/**
+ * Previous path for renames and copies
+ */
+old_path: string | null, 
+/**
+ * "added", "modified", "deleted", "renamed", "copied", "typechange" or "untracked"
+ */
+status: string, additions: number, deletions: number, is_binary: boolean, 
+/**
+ * Rename/copy similarity in percent
+ */
+similarity: number | null, 
+/**
+ * Untracked file too large to count lines in
+ */
+too_large: boolean, 
+/**
+ * Gitlink entry; the change is to the commit the submodule points at
+ */
+submodule: boolean, 
+/**
+ * Tracked by Git LFS; line counts refer to the pointer file
+ */
+lfs: boolean, };
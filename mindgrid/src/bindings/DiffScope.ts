@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Which changes a diff covers
+ */
+export type DiffScope = "working" | "staged" | "unstaged" | "untracked" | "vs_main";
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DiffFileEntry } from "./DiffFileEntry";
+import type { DiffScope } from "./DiffScope";
+
+export type DiffSummary = { scope: DiffScope, files: Array<DiffFileEntry>, total_additions: number, total_deletions: number, 
+/**
+ * More files changed than `max_files`
+ */
+truncated: boolean, };
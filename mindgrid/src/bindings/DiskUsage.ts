@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Result of walking a worktree on disk
+ */
+export type DiskUsage = { 
+/**
+ * Allocated size, counting hardlinked files once and not following symlinks
+ */
+total_bytes: number, 
+/**
+ * Part of `total_bytes` in git-ignored paths (build output, dependencies)
+ */
+artifact_bytes: number, file_count: number, 
+/**
+ * Newest modification time of a non-ignored file (Unix seconds)
+ */
+last_activity: number | null, 
+/**
+ * When this was computed (Unix seconds)
+ */
+computed_at: number, };
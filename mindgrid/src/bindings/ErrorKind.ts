@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Area an error comes from, so the UI can react without matching on messages
+ */
+export type ErrorKind = "git" | "pty" | "io" | "db" | "agent" | "validation" | "operation";
@@ -0,0 +1,21 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LfsChange } from "./LfsChange";
+import type { WordDiffHunk } from "./WordDiffHunk";
+
+export type FileDiff = { path: string, old_path: string | null, status: string, patch: string, old_value: string, new_value: string, is_binary: boolean, 
+/**
+ * The patch was cut at the line or byte limit
+ */
+truncated: boolean, 
+/**
+ * One side was too large to load into `old_value`/`new_value`
+ */
+too_large: boolean, word_diff: Array<WordDiffHunk> | null, 
+/**
+ * Submodule change; `patch` holds the submodule's commit log instead of a diff
+ */
+submodule: boolean, 
+/**
+ * Pointer change of an LFS-tracked file; `old_value`/`new_value` are left empty
+ */
+lfs: LfsChange | null, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ChangeSource } from "./ChangeSource";
+
+/**
+ * Payload of the `foundation-changed` event
+ */
+export type FoundationChange = { project_path: string, file: string, sources: Array<ChangeSource>, 
+/**
+ * Unified diff from the previous synced content
+ */
+diff: string, content: string, 
+/**
+ * The file was deleted and removed from every copy
+ */
+deleted: boolean, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload of the `foundation-conflict` event. The worktree copy is left
+ * untouched until the conflict is resolved with `foundations_write`.
+ */
+export type FoundationConflict = { project_path: string, file: string, worktree_path: string, base: string, ours: string, theirs: string, 
+/**
+ * Merge result with conflict markers
+ */
+merged: string, };
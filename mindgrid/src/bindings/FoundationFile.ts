@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type FoundationFile = { name: string, path: string, exists: boolean, content: string, 
+/**
+ * Worktrees whose copy conflicts with the project copy
+ */
+conflicts: Array<string>, };
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RepoKind } from "./RepoKind";
+
+export type GitRepoInfo = { name: string, path: string, kind: RepoKind, 
+/**
+ * Resolved git directory (differs from `<path>/.git` for worktrees and submodules)
+ */
+git_dir: string, 
+/**
+ * Unix timestamp (seconds) of the last commit on HEAD
+ */
+last_commit_at: number | null, 
+/**
+ * URL of `origin`, or of the first remote if there is no origin
+ */
+remote_url: string | null, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Overall state of a working tree, most significant first: an operation in
+ * progress wins over a detached HEAD, which wins over local changes
+ */
+export type GitState = "rebasing" | "applying" | "merging" | "cherry_picking" | "reverting" | "bisecting" | "detached" | "conflict" | "diverged" | "modified" | "untracked" | "ahead" | "behind" | "clean" | "unknown";
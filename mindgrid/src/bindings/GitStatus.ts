@@ -0,0 +1,17 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { GitState } from "./GitState";
+import type { SubmoduleStatus } from "./SubmoduleStatus";
+
+export type GitStatus = { state: GitState, ahead: number | null, behind: number | null, additions: number | null, deletions: number | null, files_changed: number | null, is_ready_to_merge: boolean | null, has_uncommitted_changes: boolean | null, has_untracked_files: boolean | null, current_branch: string | null, main_branch: string | null, is_detached: boolean | null, 
+/**
+ * Unmerged paths exist (also during a merge, rebase, cherry-pick or revert)
+ */
+has_conflicts: boolean | null, 
+/**
+ * Submodule states; `None` when the repository has no submodules
+ */
+submodules: Array<SubmoduleStatus> | null, 
+/**
+ * LFS files checked out as pointers; `None` without LFS or git-lfs
+ */
+lfs_pointers: number | null, };
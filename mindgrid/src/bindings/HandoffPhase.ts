@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Hand-off protocol: plan → (plan ready) → code → (ready for review) → review,
+ * which either approves or sends the work back to the coder
+ */
+export type HandoffPhase = "planning" | "plan_ready" | "coding" | "ready_for_review" | "reviewing" | "changes_requested" | "approved" | "failed" | "stopped";
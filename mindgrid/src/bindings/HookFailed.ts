@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { HookRun } from "./HookRun";
+
+/**
+ * Event sent when a hook blocks a commit
+ */
+export type HookFailed = { working_directory: string, run: HookRun, 
+/**
+ * Prompt the session's agent can use to fix the failure
+ */
+follow_up_prompt: string, 
+/**
+ * Whether the prompt was written to the agent's PTY
+ */
+sent_to_agent: boolean, };
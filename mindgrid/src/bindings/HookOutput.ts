@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One line of hook output streamed to the frontend
+ */
+export type HookOutput = { working_directory: string, hook: string, 
+/**
+ * "stdout" or "stderr"
+ */
+stream: string, line: string, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Result of running one git hook
+ */
+export type HookRun = { hook: string, success: boolean, exit_code: number | null, 
+/**
+ * Lines of stdout and stderr in the order they arrived, truncated from the front if very long
+ */
+output: string, duration_ms: number, };
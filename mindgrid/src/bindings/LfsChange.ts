@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LfsPointer } from "./LfsPointer";
+
+/**
+ * Both sides of a change to an LFS-tracked file
+ */
+export type LfsChange = { old: LfsPointer | null, new: LfsPointer | null, };
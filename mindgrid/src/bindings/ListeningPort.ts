@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A TCP port a session's process listens on
+ */
+export type ListeningPort = { port: number, pid: number, 
+/**
+ * Process name from /proc/<pid>/comm
+ */
+process: string, };
@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ErrorKind } from "./ErrorKind";
+
+/**
+ * Error returned by mindgrid commands
+ */
+export type MindgridError = { kind: ErrorKind, 
+/**
+ * Stable identifier such as "git.worktree_add_failed" or "pty.not_found"
+ */
+code: string, 
+/**
+ * Short message suitable for showing to the user
+ */
+message: string, 
+/**
+ * Underlying error for logs and "show details" views
+ */
+technical: string | null, 
+/**
+ * The external command that failed, e.g. "git worktree add -B ..."
+ */
+command: string | null, stderr: string | null, exit_code: number | null, };
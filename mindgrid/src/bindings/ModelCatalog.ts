@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CatalogModel } from "./CatalogModel";
+
+export type ModelCatalog = { models: Array<CatalogModel>, 
+/**
+ * Unix timestamp (milliseconds)
+ */
+fetched_at: number, };
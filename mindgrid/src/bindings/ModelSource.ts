@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Where a model entry came from
+ */
+export type ModelSource = "builtin" | "agent_config" | "agent_cache" | "cli_scrape";
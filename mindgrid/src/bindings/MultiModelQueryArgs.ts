@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { QueryIsolation } from "./QueryIsolation";
+import type { QueryTarget } from "./QueryTarget";
+
+export type MultiModelQueryArgs = { 
+/**
+ * Lets the caller subscribe to the target channels before the query starts
+ */
+query_id?: string | null, prompt: string, cwd: string, targets: Array<QueryTarget>, isolation?: QueryIsolation | null, system_prompt?: string | null, 
+/**
+ * Permission mode of the session the query runs for, applied to Claude
+ * targets under worktree isolation
+ */
+permission_mode?: string | null, };
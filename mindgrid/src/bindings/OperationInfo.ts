@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A long-running command that can be cancelled
+ */
+export type OperationInfo = { id: string, kind: string, 
+/**
+ * Unix timestamp (milliseconds)
+ */
+started_at: number, timeout_secs: number | null, };
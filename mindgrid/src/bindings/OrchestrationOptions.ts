@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type OrchestrationOptions = { planner_model?: string | null, coder_model?: string | null, 
+/**
+ * Hand off to the next role without waiting for `orchestrator_advance`
+ */
+auto_advance?: boolean | null, 
+/**
+ * Review rounds before giving up on approval
+ */
+max_rounds?: number | null, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AgentRole } from "./AgentRole";
+
+/**
+ * Payload of the `orchestration-output` event: one stream-json line from an agent
+ */
+export type OrchestrationOutput = { session_id: string, role: AgentRole, line: string, };
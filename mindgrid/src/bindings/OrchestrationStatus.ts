@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AgentRole } from "./AgentRole";
+import type { HandoffPhase } from "./HandoffPhase";
+import type { PermissionViolation } from "./PermissionViolation";
+
+export type OrchestrationStatus = { session_id: string, cwd: string, phase: HandoffPhase, 
+/**
+ * Role whose agent is running
+ */
+active_role: AgentRole | null, 
+/**
+ * Number of reviews that requested changes
+ */
+round: number, max_rounds: number, auto_advance: boolean, 
+/**
+ * Final reply of the last finished turn
+ */
+last_output: string | null, violations: Array<PermissionViolation>, error: string | null, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AgentRole } from "./AgentRole";
+
+/**
+ * A file change outside the role's permissions, reported in the status
+ */
+export type PermissionViolation = { role: AgentRole, path: string, 
+/**
+ * "added", "modified" or "deleted"
+ */
+change: string, reverted: boolean, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CheckSummary } from "./CheckSummary";
+
+/**
+ * Snapshot of a pull request as reported by the forge
+ */
+export type PrStatus = { url: string, number: number, title: string, 
+/**
+ * "open", "closed" or "merged"
+ */
+state: string, is_draft: boolean, 
+/**
+ * "approved", "changes_requested", "review_required", "commented" or "none"
+ */
+review_decision: string, mergeable: boolean | null, mergeable_state: string | null, head_sha: string, checks: CheckSummary, };
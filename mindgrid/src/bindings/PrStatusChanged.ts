@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PrStatus } from "./PrStatus";
+
+/**
+ * Event sent to the frontend when a session's PR status changes
+ */
+export type PrStatusChanged = { session_id: string, status: PrStatus, previous: PrStatus | null, 
+/**
+ * New value of `sessions.status` if the poller updated it
+ */
+session_status: string | null, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload of the `preview-updated` event
+ */
+export type PreviewInfo = { session_id: string, 
+/**
+ * Stable URL of the session's preview, e.g. http://127.0.0.1:4900/
+ */
+url: string, proxy_port: number, 
+/**
+ * Dev server port the proxy forwards to; `None` until one is detected
+ */
+target_port: number | null, 
+/**
+ * The target was chosen by the user and isn't re-detected
+ */
+pinned: boolean, };
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Optional provisioning for new session worktrees. Without it, worktrees are
+ * a full checkout of the branch.
+ */
+export type ProvisionOptions = { 
+/**
+ * Sparse-checkout patterns; when set only these paths are checked out
+ */
+sparse_patterns?: Array<string> | null, 
+/**
+ * Interpret the patterns as directories (cone mode, the default) or as gitignore-style patterns
+ */
+cone?: boolean | null, 
+/**
+ * Ignored directory names to clone from the main checkout; defaults to [`DEFAULT_CLONE_DIRS`]
+ */
+clone_dirs?: Array<string> | null, };
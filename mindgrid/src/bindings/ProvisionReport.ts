@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ClonedDir } from "./ClonedDir";
+
+export type ProvisionReport = { worktree_path: string, sparse: boolean, cloned: Array<ClonedDir>, errors: Array<string>, };
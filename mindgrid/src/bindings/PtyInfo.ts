@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What a PTY is running, for resource reporting
+ */
+export type PtyInfo = { id: string, pid: number | null, cmd: string, cwd: string | null, 
+/**
+ * Unix timestamp (seconds)
+ */
+started_at: number, };
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ProcessInfo } from "./ProcessInfo";
+import type { PtyInfo } from "./PtyInfo";
+
+export type PtyResource = { pty: PtyInfo, 
+/**
+ * The PTY's process and everything it spawned
+ */
+processes: Array<ProcessInfo>, rss_kb: number, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One raw output line (Claude stream-json or Codex runner JSON) emitted on
+ * `multi-model-query:<query_id>:<target_id>`
+ */
+export type QueryChunk = { query_id: string, target_id: string, line: string, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How targets are kept from stepping on each other
+ */
+export type QueryIsolation = "read_only" | "worktree";
@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { QueryAgent } from "./QueryAgent";
+import type { QueryResultStatus } from "./QueryResultStatus";
+
+export type QueryResult = { target_id: string, agent: QueryAgent, model: string | null, status: QueryResultStatus, 
+/**
+ * Final reply of the agent
+ */
+response: string | null, error: string | null, 
+/**
+ * Changes made in the throwaway worktree
+ */
+diff: string | null, duration_ms: number, };
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { QueryIsolation } from "./QueryIsolation";
+import type { QueryResult } from "./QueryResult";
+
+export type QueryRun = { id: string, prompt: string, cwd: string, isolation: QueryIsolation, results: Array<QueryResult>, created_at: number, 
+/**
+ * False when the database wasn't available to store the run
+ */
+saved: boolean, };
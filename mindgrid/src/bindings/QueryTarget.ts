@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { QueryAgent } from "./QueryAgent";
+
+export type QueryTarget = { 
+/**
+ * Channel name suffix; defaults to the target's index
+ */
+id?: string | null, agent: QueryAgent, model?: string | null, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type RestackResult = { branch: string, worktree_path: string | null, 
+/**
+ * Branch the child now sits on (the parent, or main once the parent merged)
+ */
+new_base: string, 
+/**
+ * "rebased", "up_to_date", "conflict" or "skipped"
+ */
+status: string, message: string | null, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A named command for a project, run in a session's worktree
+ */
+export type RunScript = { project_id: string, 
+/**
+ * e.g. "dev", "test", "build", "lint"
+ */
+name: string, 
+/**
+ * Shell command; `$PORT` holds the port allocated for the run
+ */
+command: string, 
+/**
+ * Start the script again when it exits with an error
+ */
+restart_on_crash: boolean, };
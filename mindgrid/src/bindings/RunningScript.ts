@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ScriptStatus } from "./ScriptStatus";
+
+/**
+ * A script started in a session; finished runs are kept until the script
+ * is started again or the session stops
+ */
+export type RunningScript = { session_id: string, name: string, command: string, cwd: string, port: number, pid: number | null, status: ScriptStatus, 
+/**
+ * Crash restarts in a row
+ */
+restarts: number, exit_code: number | null, 
+/**
+ * Unix timestamp (milliseconds) of the current run
+ */
+started_at: number, };
@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ScriptStatus } from "./ScriptStatus";
+
+/**
+ * Payload of the `run-script-exited` event, worded so it can be passed to
+ * the session's agent as is
+ */
+export type ScriptExited = { session_id: string, name: string, 
+/**
+ * `None` when the process was killed by a signal
+ */
+exit_code: number | null, status: ScriptStatus, will_restart: boolean, 
+/**
+ * e.g. "dev exited with code 1"
+ */
+summary: string, 
+/**
+ * Last lines of output, oldest first
+ */
+output_tail: Array<string>, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ScriptStream } from "./ScriptStream";
+
+/**
+ * Payload of the `run-script-output` event
+ */
+export type ScriptOutput = { session_id: string, name: string, stream: ScriptStream, line: string, };
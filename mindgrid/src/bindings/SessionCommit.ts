@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DiffFileEntry } from "./DiffFileEntry";
+
+export type SessionCommit = { hash: string, short_hash: string, subject: string, author: string, 
+/**
+ * Unix timestamp (seconds)
+ */
+timestamp: number, files: Array<DiffFileEntry>, additions: number, deletions: number, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SessionCommitRecord = { hash: string, subject: string, author: string, 
+/**
+ * Unix timestamp (seconds)
+ */
+timestamp: number, 
+/**
+ * All trailers of the commit as (key, value) pairs
+ */
+trailers: Array<[string, string]>, is_checkpoint: boolean, 
+/**
+ * Reachable from the main branch
+ */
+in_main: boolean, };
@@ -0,0 +1,25 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DiffSummary } from "./DiffSummary";
+import type { SessionCommit } from "./SessionCommit";
+
+export type SessionDiff = { main_branch: string, 
+/**
+ * merge-base(main, HEAD) the session is compared against
+ */
+base: string, 
+/**
+ * Commits made in the session, oldest first
+ */
+commits: Array<SessionCommit>, 
+/**
+ * More commits than listed
+ */
+commits_truncated: boolean, 
+/**
+ * Everything the session changed, committed or not, against the base
+ */
+aggregate: DiffSummary, 
+/**
+ * The part of `aggregate` that is not committed yet
+ */
+uncommitted: DiffSummary, };
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SessionOperation } from "./SessionOperation";
+import type { SessionStep } from "./SessionStep";
+import type { StepStatus } from "./StepStatus";
+
+/**
+ * Payload of the `session-progress` event
+ */
+export type SessionProgress = { session_id: string, operation: SessionOperation, step: SessionStep, status: StepStatus, message: string | null, };
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SessionStatus } from "./SessionStatus";
+
+export type SessionRecord = { id: string, project_id: string, name: string, cwd: string, 
+/**
+ * The session runs in its own worktree under `.mindgrid/worktrees`
+ */
+worktree: boolean, status: SessionStatus, model: string | null, 
+/**
+ * PTY of the agent started by the session manager
+ */
+agent_pty_id: string | null, 
+/**
+ * Files copied into the worktree on creation
+ */
+copied_files: Array<string>, created_at: number, updated_at: number, };
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CommitSignature } from "./CommitSignature";
+
+export type SignatureReport = { branch: string, base: string, commits: Array<CommitSignature>, all_signed: boolean, };
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SigningPolicy } from "./SigningPolicy";
+
+export type SigningConfig = { policy: SigningPolicy, 
+/**
+ * Value of commit.gpgsign
+ */
+gpgsign: boolean, 
+/**
+ * Value of gpg.format ("openpgp", "ssh" or "x509")
+ */
+format: string, signing_key: string | null, 
+/**
+ * Whether commits made by mindgrid will be signed
+ */
+will_sign: boolean, key_available: boolean, error: string | null, };
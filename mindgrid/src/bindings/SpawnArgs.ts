@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Arguments for spawning a PTY
+ */
+export type SpawnArgs = { cmd: string, args: Array<string>, cwd?: string | null, cols?: number | null, rows?: number | null, };
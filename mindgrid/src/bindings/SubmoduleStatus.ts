@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SubmoduleState } from "./SubmoduleState";
+
+export type SubmoduleStatus = { path: string, 
+/**
+ * Commit checked out in the submodule (recorded commit when uninitialized)
+ */
+commit: string, state: SubmoduleState, };
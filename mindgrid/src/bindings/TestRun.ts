@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type TestRun = { command: string, passed: boolean, exit_code: number | null, 
+/**
+ * Combined stdout/stderr, truncated from the front if very long
+ */
+output: string, duration_ms: number, };
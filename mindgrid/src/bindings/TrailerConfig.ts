@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type TrailerConfig = { 
+/**
+ * Enabled trailer kinds (see `TRAILER_KINDS`)
+ */
+enabled: Array<string>, 
+/**
+ * Value of mindgrid.coAuthor, if set
+ */
+co_author: string | null, };
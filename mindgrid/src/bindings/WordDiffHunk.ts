@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { WordSegment } from "./WordSegment";
+
+export type WordDiffHunk = { header: string, 
+/**
+ * One entry per line of the new file, each split into segments
+ */
+lines: Array<Array<WordSegment>>, };
@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DiskUsage } from "./DiskUsage";
+import type { PtyResource } from "./PtyResource";
+
+export type WorktreeResources = { path: string, branch: string | null, is_main: boolean, 
+/**
+ * `None` until the background scan has finished once
+ */
+disk: DiskUsage | null, 
+/**
+ * A background scan for this worktree is in progress
+ */
+computing: boolean, ptys: Array<PtyResource>, };
@@ -16,6 +16,8 @@ import { useSessionStore } from "../stores/sessionStore";
 import { getModelById } from "../lib/models";
 import { Terminal } from "./Terminal";
 import { openRunCommandWindow } from "../lib/window-manager";
+import { errorMessage } from "../lib/errors";
+import { watchRunScripts, withScriptExits, type ScriptExited } from "../lib/runScripts";
 
 interface PrInfo {
   number: number;
@@ -458,6 +460,28 @@ export function ChatUI({
   const [hasStarted, setHasStarted] = useState(false);
   const [showModeDropdown, setShowModeDropdown] = useState(false);
   const initialPromptSentRef = useRef(false);
+  // Run script exits not yet handed to the agent
+  const scriptExitsRef = useRef<ScriptExited[]>([]);
+
+  useEffect(() => {
+    if (!sessionId) return;
+    scriptExitsRef.current = [];
+    let stop: (() => void) | undefined;
+    let cancelled = false;
+    watchRunScripts(sessionId, {
+      onExit: (exit) => {
+        // Stops come from the user, the agent doesn't need to hear about them
+        if (exit.status !== "stopped") scriptExitsRef.current.push(exit);
+      },
+    }).then((unlisten) => {
+      if (cancelled) unlisten();
+      else stop = unlisten;
+    });
+    return () => {
+      cancelled = true;
+      stop?.();
+    };
+  }, [sessionId]);
   const [showCommitDropdown, setShowCommitDropdown] = useState(false);
   const [showClearConfirm, setShowClearConfirm] = useState(false);
   const [isCommitting, setIsCommitting] = useState(false);
@@ -613,6 +637,7 @@ export function ChatUI({
 
   const { runCodex, isRunning: isCodexRunning } = useCodexRunner({
     cwd,
+    sessionId,
     systemPrompt,
     onMessage: (message) => {
       if (!thinkingMode && message.isThinking) return;
@@ -779,9 +804,10 @@ export function ChatUI({
     if (!input.trim()) return;
 
     const baseMessage = input.trim();
+    const prompt = withScriptExits(baseMessage, scriptExitsRef.current.splice(0));
     const message = thinkingMode
-      ? `${baseMessage}\n\n(Think through the problem and share a brief reasoning summary before the final answer.)`
-      : `${baseMessage}\n\n(Do not include thinking or reasoning steps; reply concisely with just the answer/output.)`;
+      ? `${prompt}\n\n(Think through the problem and share a brief reasoning summary before the final answer.)`
+      : `${prompt}\n\n(Do not include thinking or reasoning steps; reply concisely with just the answer/output.)`;
     setInput("");
 
     // Immediately add user message to UI (don't wait for Claude to echo it)
@@ -855,7 +881,7 @@ export function ChatUI({
         setCommitError(null);
       }
     } catch (error) {
-      setCommitError(error instanceof Error ? error.message : "Failed to commit changes");
+      setCommitError(errorMessage(error, "Failed to commit changes"));
     } finally {
       setIsCommitting(false);
     }
@@ -877,7 +903,7 @@ export function ChatUI({
         }
       }
     } catch (error) {
-      setPushError(error instanceof Error ? error.message : "Failed to push to remote");
+      setPushError(errorMessage(error, "Failed to push to remote"));
     } finally {
       setIsPushing(false);
     }
@@ -936,7 +962,7 @@ export function ChatUI({
         setMergeError(result.error || "Failed to merge PR");
       }
     } catch (error) {
-      setMergeError(error instanceof Error ? error.message : "Failed to merge PR");
+      setMergeError(errorMessage(error, "Failed to merge PR"));
     } finally {
       setIsMergingPr(false);
     }
@@ -963,7 +989,7 @@ export function ChatUI({
       });
     } catch (error) {
       console.error("[ChatUI] Failed to run command:", error);
-      setSuccessMessage(`Failed to run: ${error instanceof Error ? error.message : String(error)}`);
+      setSuccessMessage(`Failed to run: ${errorMessage(error)}`);
       setTimeout(() => setSuccessMessage(null), 5000);
     }
   }, [runCommand, cwd, sessionId, sessionName, projectName]);
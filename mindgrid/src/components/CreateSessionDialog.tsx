@@ -10,6 +10,7 @@ import { GitignoreFilesSelector } from "./GitignoreFilesSelector";
 import { TemplateQuickSelect } from "./BulkSessionTemplateGrid";
 import type { SessionTemplate } from "../lib/session-templates";
 import type { PermissionMode, CommitMode } from "../lib/claude-types";
+import { errorMessage } from "../lib/errors";
 
 export interface SessionVariantConfig {
   id: string;
@@ -207,7 +208,7 @@ export function CreateSessionDialog({
       });
       onClose();
     } catch (err) {
-      setError(err instanceof Error ? err.message : "Failed to create session");
+      setError(errorMessage(err, "Failed to create session"));
       setIsCreating(false);
     }
   };
@@ -55,6 +55,9 @@ const XIcon = ({ className }: { className?: string }) => (
   </svg>
 );
 
+// Error codes for a branch or commit that doesn't exist
+const MISSING_REF_CODES = ['git.no_main_branch', 'git.no_merge_base', 'validation.parent_missing'];
+
 function getGitErrorTips(errorDetails: GitErrorDetails): string[] {
   const tips: string[] = [];
 
@@ -62,14 +65,11 @@ function getGitErrorTips(errorDetails: GitErrorDetails): string[] {
     tips.push('Use Claude Code to automatically resolve conflicts');
     tips.push('Or manually resolve conflicts in each file, then run "git add <file>" and "git rebase --continue"');
     tips.push('To abort the rebase, run "git rebase --abort"');
-  } else if (errorDetails.message.includes('CONFLICT')) {
+  } else if (errorDetails.code === 'git.merge_failed') {
     tips.push('Merge conflicts need to be resolved before continuing');
     tips.push('Open conflicting files and look for conflict markers (<<<<<<<, =======, >>>>>>>)');
     tips.push('After resolving, stage the files and complete the merge');
-  } else if (errorDetails.message.includes('uncommitted')) {
-    tips.push('Commit or stash your changes before proceeding');
-    tips.push('Use "git stash" to temporarily save changes');
-  } else if (errorDetails.message.includes('not found') || errorDetails.message.includes('does not exist')) {
+  } else if (errorDetails.code && MISSING_REF_CODES.includes(errorDetails.code)) {
     tips.push('Check that the branch or file exists');
     tips.push('Run "git fetch" to update remote tracking branches');
   } else {
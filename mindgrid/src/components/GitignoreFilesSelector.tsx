@@ -1,5 +1,6 @@
 import { useState, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { errorMessage } from "../lib/errors";
 
 interface GitIgnoredFile {
   path: string;
@@ -64,7 +65,7 @@ export function GitignoreFilesSelector({
       }
     } catch (err) {
       console.error("Failed to load gitignored files:", err);
-      setError(err instanceof Error ? err.message : "Failed to load files");
+      setError(errorMessage(err, "Failed to load files"));
       setFiles([]);
     } finally {
       setLoading(false);
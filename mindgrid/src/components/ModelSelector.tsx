@@ -1,7 +1,7 @@
 import { useEffect, useMemo, useState, useRef, useCallback } from 'react';
 import { createPortal } from 'react-dom';
-import { invoke } from '@tauri-apps/api/core';
 import { MODELS, type ModelConfig } from '../lib/models';
+import { listModels, toModelConfig } from '../lib/modelCatalog';
 
 interface ModelSelectorProps {
   value: string | null;
@@ -59,23 +59,11 @@ export function ModelSelector({
 
     const fetchModels = async () => {
       try {
-        const models = await invoke<Array<{ id: string; name?: string; shortName?: string }>>('codex_list_models');
-        if (!isMounted || !models) return;
-
-        const mapped: ModelConfig[] = models
-          .filter((m) => m.id)
-          .map((m) => ({
-            id: m.id,
-            name: m.name || prettifyModel(m.id),
-            shortName: m.shortName || prettifyShortName(m.id),
-            color: '#22c55e',
-            provider: 'openai',
-            contextWindow: 200000,
-          }));
-
-        setRemoteModels(mapped);
+        const catalog = await listModels();
+        if (!isMounted) return;
+        setRemoteModels(catalog.models.map(toModelConfig));
       } catch (err) {
-        console.warn('Failed to load Codex models from CLI, using defaults', err);
+        console.warn('Failed to load the model catalog, using defaults', err);
       } finally {
         if (isMounted) {
           setRemoteLoaded(true);
@@ -91,7 +79,7 @@ export function ModelSelector({
   }, []);
 
   const availableModels = useMemo(() => {
-    const combined = [...MODELS, ...remoteModels].filter((m) => {
+    const combined = [...remoteModels, ...MODELS].filter((m) => {
       if (!allowedProviders || allowedProviders.length === 0) return true;
       return allowedProviders.includes(m.provider);
     });
@@ -231,16 +219,3 @@ export function ModelBadge({ modelId, size = 'sm' }: ModelBadgeProps) {
     </span>
   );
 }
-
-function prettifyModel(id: string) {
-  return id
-    .split(/[-_]/)
-    .filter(Boolean)
-    .map(part => part.charAt(0).toUpperCase() + part.slice(1))
-    .join(' ');
-}
-
-function prettifyShortName(id: string) {
-  const cleaned = prettifyModel(id);
-  return cleaned.length > 12 ? cleaned.slice(0, 12) : cleaned;
-}
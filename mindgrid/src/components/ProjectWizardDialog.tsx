@@ -7,6 +7,7 @@ import { ModelSelector } from "./ModelSelector";
 import type { SessionVariantConfig } from "./CreateSessionDialog";
 import { generateDefaultSessionName, validateSessionName } from "../lib/session-utils";
 import { useSessionStore } from "../stores/sessionStore";
+import { errorMessage } from "../lib/errors";
 
 // Hardcoded projects directory - will be a setting later
 const PROJECTS_DIRECTORY = "/Users/gustavollermalylarrain/Documents/proyectos/personales";
@@ -25,6 +26,10 @@ const PROJECT_COMMANDS: Record<string, { buildCommand: string; runCommand: strin
 interface GitRepoInfo {
   name: string;
   path: string;
+  kind: "repository" | "worktree" | "submodule" | "bare";
+  git_dir: string;
+  last_commit_at: number | null;
+  remote_url: string | null;
 }
 
 interface ProjectWizardDialogProps {
@@ -289,7 +294,7 @@ export function ProjectWizardDialog({
       );
       onClose();
     } catch (err) {
-      const msg = err instanceof Error ? err.message : "Failed to create project";
+      const msg = errorMessage(err, "Failed to create project");
       setError(msg);
       setIsCreating(false);
       if (err instanceof Error && err.message.toLowerCase().includes("project already exists")) {
@@ -368,7 +373,7 @@ export function ProjectWizardDialog({
       // Retry the creation
       await handleSubmit();
     } catch (err) {
-      const msg = err instanceof Error ? err.message : "Failed to delete existing project";
+      const msg = errorMessage(err, "Failed to delete existing project");
       setError(msg);
       setConflictProject(null);
     } finally {
@@ -2,6 +2,7 @@ import { useState, useEffect, useRef } from "react";
 import type { DashboardProject } from "./types";
 import type { PermissionMode, CommitMode } from "../../lib/claude-types";
 import { ModelSelector } from "../ModelSelector";
+import { errorMessage } from "../../lib/errors";
 
 interface EditProjectDialogProps {
   isOpen: boolean;
@@ -85,7 +86,7 @@ export function EditProjectDialog({
       });
       onClose();
     } catch (err) {
-      setError(err instanceof Error ? err.message : "Failed to save project");
+      setError(errorMessage(err, "Failed to save project"));
     } finally {
       setIsSaving(false);
     }
@@ -9,6 +9,7 @@ import type { DashboardSessionStatus } from "./types";
 import { MonacoDiffViewer } from "../diff/MonacoDiffViewer";
 import { openChatWindow, openNewChatInSession } from "../../lib/window-manager";
 import { getSessionColor } from "../../lib/session-colors";
+import { errorMessage } from "../../lib/errors";
 
 const SESSION_TABS = [
   { id: "overview", label: "Overview" },
@@ -114,12 +115,13 @@ export function SessionDetailView({
         workingDirectory: session.cwd,
         filePath: file.path,
         status: file.status,
+        oldPath: file.old_path,
       });
       lastFetchedPathRef.current = `${file.status}-${file.path}`;
       setFileDiff(result);
     } catch (error) {
       setFileDiff(null);
-      setFileDiffError(error instanceof Error ? error.message : "Failed to load diff");
+      setFileDiffError(errorMessage(error, "Failed to load diff"));
     } finally {
       setFileDiffLoading(false);
     }
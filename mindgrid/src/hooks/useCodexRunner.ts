@@ -1,33 +1,74 @@
-import { useCallback, useState } from "react";
-import { invoke } from "@tauri-apps/api/core";
+import { useCallback, useEffect, useRef, useState } from "react";
 import { CodexStreamParser } from "../lib/codexStreamParser";
 import type { ParsedMessage } from "../lib/claude-types";
+import { errorMessage } from "../lib/errors";
+import {
+  cancelCodex,
+  closeCodexThread,
+  runCodexTurn,
+  startCodexThread,
+  type CodexThreadInfo,
+} from "../lib/codexThread";
 
 interface UseCodexRunnerOptions {
   cwd?: string;
+  /** Continues the session's Codex thread across app restarts */
+  sessionId?: string;
   systemPrompt?: string | null;
   onMessage?: (message: ParsedMessage) => void;
   onError?: (error: string) => void;
 }
 
 export function useCodexRunner(options: UseCodexRunnerOptions = {}) {
-  const { cwd, systemPrompt, onMessage, onError } = options;
+  const { cwd, sessionId, systemPrompt, onMessage, onError } = options;
   const [isRunning, setIsRunning] = useState(false);
+  const threadRef = useRef<CodexThreadInfo | null>(null);
+
+  // Stop the runner when the chat moves elsewhere; the thread stays stored
+  useEffect(() => {
+    return () => {
+      const thread = threadRef.current;
+      threadRef.current = null;
+      if (thread) closeCodexThread(thread.thread_id).catch(() => {});
+    };
+  }, [cwd, sessionId]);
+
+  const ensureThread = useCallback(
+    async (model?: string) => {
+      const current = threadRef.current;
+      if (current && (current.model ?? null) === (model ?? null)) return current;
+      // Reopen under the same id so the conversation continues with the new model.
+      // The Codex thread id is assigned on the first turn, after `current` was returned.
+      const closed = current ? await closeCodexThread(current.thread_id) : null;
+      const thread = await startCodexThread({
+        thread_id: current?.thread_id ?? null,
+        session_id: sessionId ?? null,
+        codex_thread_id: closed?.codex_thread_id ?? current?.codex_thread_id ?? null,
+        cwd: cwd!,
+        model: model ?? null,
+        sandbox_mode: null,
+        system_prompt: systemPrompt ?? null,
+      });
+      threadRef.current = thread;
+      return thread;
+    },
+    [cwd, sessionId, systemPrompt]
+  );
 
   const runCodex = useCallback(
     async (prompt: string, model?: string) => {
       if (!prompt.trim()) return;
+      if (!cwd) {
+        onError?.("No working directory for Codex");
+        return;
+      }
       const parser = new CodexStreamParser();
       setIsRunning(true);
       try {
-        const output = await invoke<string>("run_codex", {
-          prompt,
-          model,
-          cwd,
-          system_prompt: systemPrompt,
-        });
-        const parsed = parseCodexOutput(parser, output || "");
-        if (parsed.length === 0) {
+        const thread = await ensureThread(model);
+        const finished = await runCodexTurn(thread.thread_id, prompt, (event) => parser.feed(event.line));
+        const parsed = toParsedMessages(parser);
+        if (parsed.length === 0 && finished.status === "completed") {
           onMessage?.({
             id: `codex-${Date.now()}`,
             role: "assistant",
@@ -39,28 +80,30 @@ export function useCodexRunner(options: UseCodexRunnerOptions = {}) {
             onMessage?.(message);
           }
         }
+        if (finished.status === "failed") {
+          onError?.(finished.error ?? "Codex run failed");
+        }
       } catch (err) {
-        const message = err instanceof Error ? err.message : String(err);
+        const message = errorMessage(err);
         onError?.(message);
       } finally {
         setIsRunning(false);
       }
     },
-    [cwd, systemPrompt, onMessage, onError]
+    [cwd, ensureThread, onMessage, onError]
   );
 
-  return { runCodex, isRunning };
-}
+  const cancel = useCallback(async () => {
+    const thread = threadRef.current;
+    if (thread) await cancelCodex(thread.thread_id);
+  }, []);
 
-function parseCodexOutput(parser: CodexStreamParser, raw: string): ParsedMessage[] {
-  if (!raw.trim()) return [];
+  return { runCodex, cancel, isRunning };
+}
 
-  const lines = raw.split(/\r?\n/).filter((l) => l.trim().length > 0);
+function toParsedMessages(parser: CodexStreamParser): ParsedMessage[] {
   let counter = 0;
   const timestampBase = Date.now();
-  for (const line of lines) {
-    parser.feed(line);
-  }
 
   return parser.toMessages().map((m) => ({
     id: `codex-${++counter}`,
@@ -5,6 +5,7 @@ import type { Terminal } from "@xterm/xterm";
 import { ClaudeStreamParser } from "../lib/claude-parser";
 import type { ClaudeEvent, ParsedMessage } from "../lib/claude-types";
 import { debug } from "../stores/debugStore";
+import { errorMessage } from "../lib/errors";
 
 interface PtyOutput {
   id: string;
@@ -142,7 +143,7 @@ export function usePty(terminal: Terminal | null, options: UsePtyOptions = {}) {
       return id;
     } catch (err) {
       debug.error("PTY", "Spawn failed", err);
-      term.writeln(`\r\nError: ${err}`);
+      term.writeln(`\r\nError: ${errorMessage(err)}`);
       return null;
     }
   }, []);
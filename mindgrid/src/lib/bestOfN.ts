@@ -0,0 +1,71 @@
+// Best-of-N: one coding task given to several agents/models, each in its own
+// worktree, compared side by side before one is merged (see src-tauri/src/best_of_n.rs).
+
+import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import type { BestOfNArgs } from "../bindings/BestOfNArgs";
+import type { BestOfNRun } from "../bindings/BestOfNRun";
+import type { QueryChunk } from "../bindings/QueryChunk";
+import { cancelOperation } from "./operations";
+
+export type { BestOfNArgs, BestOfNRun };
+export type { Candidate } from "../bindings/Candidate";
+export type { CandidateStatus } from "../bindings/CandidateStatus";
+export type { TestRun } from "../bindings/TestRun";
+
+export function candidateChannel(runId: string, candidateId: string): string {
+  return `best-of-n:${runId}:${candidateId}`;
+}
+
+/**
+ * Create the candidate worktrees and start the agents. Resolves once they are
+ * running; follow progress with `watchBestOfN`.
+ */
+export function startBestOfN(args: BestOfNArgs): Promise<BestOfNRun> {
+  return invoke<BestOfNRun>("best_of_n_start", { args });
+}
+
+export function getBestOfN(runId: string): Promise<BestOfNRun> {
+  return invoke<BestOfNRun>("best_of_n_status", { runId });
+}
+
+/** Merge a candidate into the main branch and archive the others. */
+export function pickBestOfN(runId: string, candidateId: string, commitMessage?: string): Promise<BestOfNRun> {
+  return invoke<BestOfNRun>("best_of_n_pick", { runId, candidateId, commitMessage: commitMessage ?? null });
+}
+
+export function discardBestOfN(runId: string): Promise<void> {
+  return invoke("best_of_n_discard", { runId });
+}
+
+/**
+ * Stop a candidate's agent or test command; the candidate then reports as
+ * failed. Resolves false if it already finished.
+ */
+export function cancelCandidate(runId: string, candidateId: string): Promise<boolean> {
+  return cancelOperation(`best_of_n:${runId}:${candidateId}`);
+}
+
+/**
+ * Follow a run's reports and, optionally, each candidate's raw agent output.
+ * Returns a function that stops listening.
+ */
+export async function watchBestOfN(
+  run: BestOfNRun,
+  onUpdate: (run: BestOfNRun) => void,
+  onChunk?: (chunk: QueryChunk) => void,
+): Promise<() => void> {
+  const unlisteners: UnlistenFn[] = [
+    await listen<BestOfNRun>("best-of-n-updated", (event) => {
+      if (event.payload.id === run.id) onUpdate(event.payload);
+    }),
+  ];
+  if (onChunk) {
+    for (const candidate of run.candidates) {
+      unlisteners.push(
+        await listen<QueryChunk>(candidateChannel(run.id, candidate.id), (event) => onChunk(event.payload)),
+      );
+    }
+  }
+  return () => unlisteners.forEach((unlisten) => unlisten());
+}
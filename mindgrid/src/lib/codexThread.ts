@@ -0,0 +1,67 @@
+// Multi-turn Codex threads backed by a long-lived SDK runner per thread
+// (see src-tauri/src/codex_thread.rs). Thread ids are stored, so reopening a
+// thread after a restart continues the same Codex conversation.
+
+import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import type { CodexRunnerDiagnostics } from "../bindings/CodexRunnerDiagnostics";
+import type { CodexThreadArgs } from "../bindings/CodexThreadArgs";
+import type { CodexThreadEvent } from "../bindings/CodexThreadEvent";
+import type { CodexThreadInfo } from "../bindings/CodexThreadInfo";
+import type { CodexTurnFinished } from "../bindings/CodexTurnFinished";
+
+export type { CodexRunnerDiagnostics, CodexThreadArgs, CodexThreadEvent, CodexThreadInfo, CodexTurnFinished };
+export type { CodexTurnStatus } from "../bindings/CodexTurnStatus";
+
+/** Check Node.js, the bundled runner and the Codex SDK; `problems` explains what's missing. */
+export function codexRunnerDiagnostics(): Promise<CodexRunnerDiagnostics> {
+  return invoke<CodexRunnerDiagnostics>("codex_runner_diagnostics");
+}
+
+export function startCodexThread(args: CodexThreadArgs): Promise<CodexThreadInfo> {
+  return invoke<CodexThreadInfo>("codex_start_thread", { args });
+}
+
+/** Send a prompt; resolves with the turn id once the runner accepted it. */
+export function sendCodex(threadId: string, prompt: string): Promise<string> {
+  return invoke<string>("codex_send", { threadId, prompt });
+}
+
+export function cancelCodex(threadId: string): Promise<void> {
+  return invoke("codex_cancel", { threadId });
+}
+
+/** Stop the thread's runner; resolves to its last state, or null if it wasn't running. */
+export function closeCodexThread(threadId: string): Promise<CodexThreadInfo | null> {
+  return invoke<CodexThreadInfo | null>("codex_close_thread", { threadId });
+}
+
+/**
+ * Run one turn, passing each runner envelope to `onEvent` as it arrives.
+ * Resolves when the turn completes, fails or is cancelled.
+ */
+export async function runCodexTurn(
+  threadId: string,
+  prompt: string,
+  onEvent: (event: CodexThreadEvent) => void,
+): Promise<CodexTurnFinished> {
+  const unlisteners: UnlistenFn[] = [];
+  try {
+    let resolveFinished: (finished: CodexTurnFinished) => void = () => {};
+    const finished = new Promise<CodexTurnFinished>((resolve) => {
+      resolveFinished = resolve;
+    });
+    unlisteners.push(
+      await listen<CodexThreadEvent>("codex-thread-event", (event) => {
+        if (event.payload.thread_id === threadId) onEvent(event.payload);
+      }),
+      await listen<CodexTurnFinished>("codex-turn-finished", (event) => {
+        if (event.payload.thread_id === threadId) resolveFinished(event.payload);
+      }),
+    );
+    await sendCodex(threadId, prompt);
+    return await finished;
+  } finally {
+    unlisteners.forEach((unlisten) => unlisten());
+  }
+}
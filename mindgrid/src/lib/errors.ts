@@ -0,0 +1,44 @@
+// Errors returned by Tauri commands. Backend commands reject with a structured
+// MindgridError (see src-tauri/src/error.rs); older code paths may still reject
+// with a plain string or an Error.
+
+import type { ErrorKind } from "../bindings/ErrorKind";
+import type { MindgridError } from "../bindings/MindgridError";
+
+export type { ErrorKind, MindgridError };
+
+export function isMindgridError(err: unknown): err is MindgridError {
+  return (
+    typeof err === "object" &&
+    err !== null &&
+    typeof (err as MindgridError).kind === "string" &&
+    typeof (err as MindgridError).code === "string" &&
+    typeof (err as MindgridError).message === "string"
+  );
+}
+
+/**
+ * User-facing message for any rejected invoke, including git's stderr when present
+ */
+export function errorMessage(err: unknown, fallback = "Unknown error"): string {
+  if (isMindgridError(err)) {
+    return err.stderr ? `${err.message}: ${err.stderr}` : err.message;
+  }
+  if (err instanceof Error) return err.message;
+  if (typeof err === "string" && err.length > 0) return err;
+  return fallback;
+}
+
+/**
+ * Error kind, or null for errors that did not come from a mindgrid command
+ */
+export function errorKind(err: unknown): ErrorKind | null {
+  return isMindgridError(err) ? err.kind : null;
+}
+
+/**
+ * Whether the error has the given code, e.g. hasErrorCode(err, "git.worktree_add_failed")
+ */
+export function hasErrorCode(err: unknown, code: string): boolean {
+  return isMindgridError(err) && err.code === code;
+}
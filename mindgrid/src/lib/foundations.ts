@@ -0,0 +1,59 @@
+// Foundations: shared markdown memory (PLAN.md, CONTEXT.md, DECISIONS.md,
+// PROGRESS.md) kept in the project root and synced into session worktrees by
+// the backend (see src-tauri/src/foundations.rs).
+
+import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import type { FoundationChange } from "../bindings/FoundationChange";
+import type { FoundationConflict } from "../bindings/FoundationConflict";
+import type { FoundationFile } from "../bindings/FoundationFile";
+import type { FoundationWriteResult } from "../bindings/FoundationWriteResult";
+
+export type { FoundationChange, FoundationConflict, FoundationFile, FoundationWriteResult };
+export type { ChangeSource } from "../bindings/ChangeSource";
+export type { WriteStatus } from "../bindings/WriteStatus";
+
+export function listFoundations(projectPath: string): Promise<FoundationFile[]> {
+  return invoke<FoundationFile[]>("foundations_list", { projectPath });
+}
+
+/**
+ * Save a file. Pass the content the editor was opened with as `base` so edits
+ * made meanwhile by an agent are merged instead of overwritten.
+ */
+export function writeFoundation(
+  projectPath: string,
+  name: string,
+  content: string,
+  base: string | null,
+): Promise<FoundationWriteResult> {
+  return invoke<FoundationWriteResult>("foundations_write", { projectPath, name, content, base });
+}
+
+/**
+ * Watch a project's Foundations files. Returns a function that stops watching.
+ */
+export async function watchFoundations(
+  projectPath: string,
+  onChange: (change: FoundationChange) => void,
+  onConflict?: (conflict: FoundationConflict) => void,
+): Promise<() => void> {
+  const unlisteners: UnlistenFn[] = [
+    await listen<FoundationChange>("foundation-changed", (event) => {
+      if (event.payload.project_path === projectPath) onChange(event.payload);
+    }),
+  ];
+  if (onConflict) {
+    unlisteners.push(
+      await listen<FoundationConflict>("foundation-conflict", (event) => {
+        if (event.payload.project_path === projectPath) onConflict(event.payload);
+      }),
+    );
+  }
+  await invoke("foundations_watch", { projectPath });
+
+  return () => {
+    unlisteners.forEach((unlisten) => unlisten());
+    invoke("foundations_unwatch", { projectPath }).catch(() => {});
+  };
+}
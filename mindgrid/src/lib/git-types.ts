@@ -1,56 +1,50 @@
-// Git status types matching the Rust backend
-
-export type GitState = 'clean' | 'modified' | 'untracked' | 'ahead' | 'behind' | 'diverged' | 'conflict' | 'detached' | 'unknown';
-
-export interface GitStatus {
-  state: GitState;
-  ahead?: number;
-  behind?: number;
-  additions?: number;
-  deletions?: number;
-  files_changed?: number;
-  is_ready_to_merge?: boolean;
-  has_uncommitted_changes?: boolean;
-  has_untracked_files?: boolean;
-  current_branch?: string;
-  main_branch?: string;
-  is_detached?: boolean;
-}
-
-export interface GitDiffFile {
-  path: string;
-  status: string;
-  additions: number;
-  deletions: number;
-}
-
-export interface GitDiffResult {
-  files: GitDiffFile[];
-  total_additions: number;
-  total_deletions: number;
-}
-
-export interface GitFileDiff {
-  path: string;
-  status: string;
-  patch: string;
-  old_value: string;
-  new_value: string;
-  is_binary: boolean;
-}
-
-export interface ConflictInfo {
-  has_conflicts: boolean;
-  conflicting_files: string[];
-  conflicting_commits?: {
-    ours: string[];
-    theirs: string[];
-  };
-}
+// Git types shared with the Rust backend. The command types are generated by
+// ts-rs from the Rust structs (run `cargo test` in src-tauri to regenerate
+// src/bindings), so a contract change surfaces here as a type error.
+
+import type { DiffFileEntry } from "../bindings/DiffFileEntry";
+import type { DiffSummary } from "../bindings/DiffSummary";
+import type { FileDiff } from "../bindings/FileDiff";
+import type { GitState } from "../bindings/GitState";
+import type { GitStatus } from "../bindings/GitStatus";
+
+export type { GitState, GitStatus, DiffFileEntry, DiffSummary, FileDiff };
+export type { SubmoduleState } from "../bindings/SubmoduleState";
+export type { SubmoduleStatus } from "../bindings/SubmoduleStatus";
+export type { LfsPointer } from "../bindings/LfsPointer";
+export type { LfsChange } from "../bindings/LfsChange";
+export type { DiffScope } from "../bindings/DiffScope";
+export type { SessionCommit } from "../bindings/SessionCommit";
+export type { SessionDiff } from "../bindings/SessionDiff";
+export type { WordSegment } from "../bindings/WordSegment";
+export type { WordDiffHunk } from "../bindings/WordDiffHunk";
+export type { BlameCommit } from "../bindings/BlameCommit";
+export type { BlameLine } from "../bindings/BlameLine";
+export type { BlameRange } from "../bindings/BlameRange";
+export type { TrailerConfig } from "../bindings/TrailerConfig";
+export type { CommitProvenance } from "../bindings/CommitProvenance";
+export type { SessionCommitRecord } from "../bindings/SessionCommitRecord";
+export type { PtyInfo } from "../bindings/PtyInfo";
+export type { ProcessInfo } from "../bindings/ProcessInfo";
+export type { PtyResource } from "../bindings/PtyResource";
+export type { DiskUsage } from "../bindings/DiskUsage";
+export type { WorktreeResources } from "../bindings/WorktreeResources";
+export type { ProvisionOptions } from "../bindings/ProvisionOptions";
+export type { ProvisionReport } from "../bindings/ProvisionReport";
+export type { ConflictInfo } from "../bindings/ConflictInfo";
+
+// Names used by the diff views
+export type GitDiffFile = DiffFileEntry;
+export type GitDiffResult = DiffSummary;
+export type GitFileDiff = FileDiff;
+
+export type TrailerKind = 'session' | 'agent' | 'model' | 'prompt' | 'checkpoint' | 'coauthor';
 
 export interface GitErrorDetails {
   title: string;
   message: string;
+  /** Code of the MindgridError behind the dialog, e.g. "git.merge_failed" */
+  code?: string;
   command?: string;
   commands?: string[];
   output: string;
@@ -74,7 +68,30 @@ export interface GitStatusConfig {
   description: string;
 }
 
+const OPERATION_LABELS: Partial<Record<GitState, string>> = {
+  rebasing: 'Rebasing',
+  applying: 'Applying patches',
+  merging: 'Merging',
+  cherry_picking: 'Cherry-picking',
+  reverting: 'Reverting',
+  bisecting: 'Bisecting',
+};
+
 export function getGitStatusConfig(gitStatus: GitStatus): GitStatusConfig {
+  // Rebase, merge, cherry-pick, revert, am or bisect in progress (HEAD is often detached meanwhile)
+  const operation = OPERATION_LABELS[gitStatus.state];
+  if (operation) {
+    return {
+      color: gitStatus.has_conflicts ? 'text-red-500' : 'text-amber-500',
+      bgColor: gitStatus.has_conflicts ? 'bg-red-500/20' : 'bg-amber-500/20',
+      icon: 'alert',
+      label: operation,
+      description: gitStatus.has_conflicts
+        ? `${operation} with conflicts - resolve them to continue`
+        : `${operation} in progress - continue or abort it`
+    };
+  }
+
   // Ready to Merge - Has commits, clean working directory, not behind
   if (gitStatus.is_ready_to_merge ||
       (gitStatus.ahead && gitStatus.ahead > 0 &&
@@ -0,0 +1,38 @@
+// Models for every agent from the backend catalog (see src-tauri/src/model_catalog.rs).
+// The catalog reads each agent's config and cached model list, falls back to
+// scraping the Codex CLI, and is cached on disk for a day.
+
+import { invoke } from "@tauri-apps/api/core";
+import type { CatalogModel } from "../bindings/CatalogModel";
+import type { ModelCatalog } from "../bindings/ModelCatalog";
+import { getModelById, type ModelConfig } from "./models";
+
+export type { CatalogModel, ModelCatalog };
+export type { ModelProvider } from "../bindings/ModelProvider";
+export type { ModelSource } from "../bindings/ModelSource";
+
+const PROVIDER_COLORS: Record<CatalogModel["provider"], string> = {
+  anthropic: "#8b5cf6",
+  openai: "#22c55e",
+  google: "#4285F4",
+};
+
+/** Pass `refresh` to rescan the agents instead of using the cached catalog. */
+export function listModels(refresh = false): Promise<ModelCatalog> {
+  return invoke<ModelCatalog>("list_models", { refresh });
+}
+
+/** Picker entry for a catalog model, keeping the styling of models known to the UI. */
+export function toModelConfig(model: CatalogModel): ModelConfig {
+  const known = getModelById(model.id);
+  if (known) return { ...known, isDefault: model.is_default };
+  return {
+    id: model.id,
+    name: model.name,
+    shortName: model.name.length > 12 ? model.name.slice(0, 12) : model.name,
+    color: PROVIDER_COLORS[model.provider],
+    provider: model.provider,
+    contextWindow: model.provider === "google" ? 1000000 : 200000,
+    isDefault: model.is_default,
+  };
+}
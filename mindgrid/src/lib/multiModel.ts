@@ -0,0 +1,51 @@
+// Multi-model query: the same prompt sent to several agent/model pairs at once
+// (see src-tauri/src/multi_model.rs). Each target streams on its own event.
+
+import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import type { MultiModelQueryArgs } from "../bindings/MultiModelQueryArgs";
+import type { QueryChunk } from "../bindings/QueryChunk";
+import type { QueryRun } from "../bindings/QueryRun";
+
+export type { MultiModelQueryArgs, QueryChunk, QueryRun };
+export type { QueryAgent } from "../bindings/QueryAgent";
+export type { QueryIsolation } from "../bindings/QueryIsolation";
+export type { QueryResult } from "../bindings/QueryResult";
+export type { QueryResultStatus } from "../bindings/QueryResultStatus";
+export type { QueryTarget } from "../bindings/QueryTarget";
+
+export function queryChannel(queryId: string, targetId: string): string {
+  return `multi-model-query:${queryId}:${targetId}`;
+}
+
+/**
+ * Run a query. `onChunk` receives each target's raw output lines (Claude
+ * stream-json or Codex runner JSON) as they arrive.
+ */
+export async function multiModelQuery(
+  args: MultiModelQueryArgs,
+  onChunk?: (chunk: QueryChunk) => void,
+): Promise<QueryRun> {
+  const queryId = args.query_id ?? crypto.randomUUID();
+  const targets = args.targets.map((target, index) => ({ ...target, id: target.id ?? String(index) }));
+
+  const unlisteners: UnlistenFn[] = [];
+  if (onChunk) {
+    for (const target of targets) {
+      unlisteners.push(await listen<QueryChunk>(queryChannel(queryId, target.id), (event) => onChunk(event.payload)));
+    }
+  }
+  try {
+    return await invoke<QueryRun>("multi_model_query", { args: { ...args, query_id: queryId, targets } });
+  } finally {
+    unlisteners.forEach((unlisten) => unlisten());
+  }
+}
+
+export function listModelQueries(cwd?: string, limit?: number): Promise<QueryRun[]> {
+  return invoke<QueryRun[]>("list_model_queries", { cwd: cwd ?? null, limit: limit ?? null });
+}
+
+export function deleteModelQuery(queryId: string): Promise<void> {
+  return invoke("delete_model_query", { queryId });
+}
@@ -0,0 +1,33 @@
+// Cancellation and timeouts for long-running commands (see src-tauri/src/operations.rs).
+// Commands such as git_push take an optional `operationId`; passing a known id lets
+// the UI cancel them while they run. Cancelled or timed out commands reject with
+// an error of kind "operation".
+
+import { invoke } from "@tauri-apps/api/core";
+import type { OperationInfo } from "../bindings/OperationInfo";
+import type { OperationTimeout } from "../bindings/OperationTimeout";
+
+export type { OperationInfo, OperationTimeout };
+
+/** Stable id for a session's operation of one kind, e.g. its running push. */
+export function sessionOperationId(kind: string, sessionId: string): string {
+  return `${kind}:${sessionId}`;
+}
+
+/** Kill the operation's process tree; resolves false if it already finished. */
+export function cancelOperation(operationId: string): Promise<boolean> {
+  return invoke<boolean>("cancel_operation", { operationId });
+}
+
+export function listOperations(): Promise<OperationInfo[]> {
+  return invoke<OperationInfo[]>("list_operations");
+}
+
+export function getOperationTimeouts(): Promise<OperationTimeout[]> {
+  return invoke<OperationTimeout[]>("get_operation_timeouts");
+}
+
+/** Applies to operations started afterwards; `null` disables the timeout. */
+export function setOperationTimeout(kind: string, timeoutSecs: number | null): Promise<void> {
+  return invoke("set_operation_timeout", { kind, timeoutSecs });
+}
@@ -0,0 +1,63 @@
+// Planner + Coder orchestration: two claude agents take turns in a session's
+// worktree, handing off through the shared Foundations files. Writes outside a
+// role's permissions are reverted by the backend (see src-tauri/src/orchestrator.rs).
+
+import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import type { OrchestrationOptions } from "../bindings/OrchestrationOptions";
+import type { OrchestrationOutput } from "../bindings/OrchestrationOutput";
+import type { OrchestrationStatus } from "../bindings/OrchestrationStatus";
+
+export type { OrchestrationOptions, OrchestrationOutput, OrchestrationStatus };
+export type { AgentRole } from "../bindings/AgentRole";
+export type { HandoffPhase } from "../bindings/HandoffPhase";
+export type { PermissionViolation } from "../bindings/PermissionViolation";
+
+export function startOrchestration(
+  sessionId: string,
+  cwd: string,
+  task: string,
+  options?: OrchestrationOptions,
+): Promise<OrchestrationStatus> {
+  return invoke<OrchestrationStatus>("orchestrator_start", { sessionId, cwd, task, options: options ?? null });
+}
+
+/**
+ * Hand off to the next role: plan ready → code, ready for review → review,
+ * changes requested → code. `guidance` is added to that turn's prompt.
+ */
+export function advanceOrchestration(sessionId: string, guidance?: string): Promise<OrchestrationStatus> {
+  return invoke<OrchestrationStatus>("orchestrator_advance", { sessionId, guidance: guidance ?? null });
+}
+
+export function getOrchestration(sessionId: string): Promise<OrchestrationStatus | null> {
+  return invoke<OrchestrationStatus | null>("orchestrator_status", { sessionId });
+}
+
+export function stopOrchestration(sessionId: string): Promise<void> {
+  return invoke("orchestrator_stop", { sessionId });
+}
+
+/**
+ * Follow a session's orchestration. `onOutput` receives the agents' raw
+ * stream-json lines. Returns a function that stops listening.
+ */
+export async function watchOrchestration(
+  sessionId: string,
+  onStatus: (status: OrchestrationStatus) => void,
+  onOutput?: (output: OrchestrationOutput) => void,
+): Promise<() => void> {
+  const unlisteners: UnlistenFn[] = [
+    await listen<OrchestrationStatus>("orchestration-updated", (event) => {
+      if (event.payload.session_id === sessionId) onStatus(event.payload);
+    }),
+  ];
+  if (onOutput) {
+    unlisteners.push(
+      await listen<OrchestrationOutput>("orchestration-output", (event) => {
+        if (event.payload.session_id === sessionId) onOutput(event.payload);
+      }),
+    );
+  }
+  return () => unlisteners.forEach((unlisten) => unlisten());
+}
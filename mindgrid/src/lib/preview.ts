@@ -0,0 +1,43 @@
+// Live browser preview per session: a local proxy on a stable port that
+// forwards to the dev server the session's processes listen on, WebSockets
+// (HMR) included (see src-tauri/src/preview.rs).
+
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import type { ListeningPort } from "../bindings/ListeningPort";
+import type { PreviewInfo } from "../bindings/PreviewInfo";
+
+export type { ListeningPort, PreviewInfo };
+
+/** Ports listened on by the session's terminals and run scripts (Linux only). */
+export function detectSessionPorts(sessionId: string): Promise<ListeningPort[]> {
+  return invoke<ListeningPort[]>("detect_session_ports", { sessionId });
+}
+
+/**
+ * Start the session's preview, or retarget it. Without `targetPort` the dev
+ * server is detected and followed when it moves.
+ */
+export function startPreview(sessionId: string, targetPort?: number): Promise<PreviewInfo> {
+  return invoke<PreviewInfo>("start_preview", { sessionId, targetPort: targetPort ?? null });
+}
+
+export function getPreview(sessionId: string): Promise<PreviewInfo | null> {
+  return invoke<PreviewInfo | null>("get_preview", { sessionId });
+}
+
+export function stopPreview(sessionId: string): Promise<void> {
+  return invoke("stop_preview", { sessionId });
+}
+
+/**
+ * Follow a session's preview target. Returns a function that stops listening.
+ */
+export async function watchPreview(
+  sessionId: string,
+  onUpdate: (preview: PreviewInfo) => void,
+): Promise<() => void> {
+  return listen<PreviewInfo>("preview-updated", (event) => {
+    if (event.payload.session_id === sessionId) onUpdate(event.payload);
+  });
+}
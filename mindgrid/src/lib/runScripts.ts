@@ -0,0 +1,96 @@
+// Per-project run scripts (dev, test, build, lint) started in a session's
+// worktree with their own $PORT, restarted on crash when configured
+// (see src-tauri/src/run_scripts.rs).
+
+import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import type { RunScript } from "../bindings/RunScript";
+import type { RunningScript } from "../bindings/RunningScript";
+import type { SaveRunScriptArgs } from "../bindings/SaveRunScriptArgs";
+import type { ScriptExited } from "../bindings/ScriptExited";
+import type { ScriptOutput } from "../bindings/ScriptOutput";
+
+export type { RunScript, RunningScript, SaveRunScriptArgs, ScriptExited, ScriptOutput };
+export type { ScriptStatus } from "../bindings/ScriptStatus";
+export type { ScriptStream } from "../bindings/ScriptStream";
+
+export function listRunScripts(projectId: string): Promise<RunScript[]> {
+  return invoke<RunScript[]>("list_run_scripts", { projectId });
+}
+
+export function saveRunScript(args: SaveRunScriptArgs): Promise<RunScript> {
+  return invoke<RunScript>("save_run_script", { args });
+}
+
+export function deleteRunScript(projectId: string, name: string): Promise<void> {
+  return invoke("delete_run_script", { projectId, name });
+}
+
+/** Start a script in the session's worktree; resolves with its allocated port. */
+export function startRunScript(sessionId: string, name: string): Promise<RunningScript> {
+  return invoke<RunningScript>("start_run_script", { sessionId, name });
+}
+
+export function stopRunScript(sessionId: string, name: string): Promise<void> {
+  return invoke("stop_run_script", { sessionId, name });
+}
+
+export function listRunningScripts(sessionId?: string): Promise<RunningScript[]> {
+  return invoke<RunningScript[]>("list_running_scripts", { sessionId: sessionId ?? null });
+}
+
+/**
+ * Follow a session's scripts. Returns a function that stops listening.
+ */
+export async function watchRunScripts(
+  sessionId: string,
+  handlers: {
+    onOutput?: (output: ScriptOutput) => void;
+    onUpdate?: (script: RunningScript) => void;
+    onExit?: (exit: ScriptExited) => void;
+  },
+): Promise<() => void> {
+  const unlisteners: UnlistenFn[] = [];
+  if (handlers.onOutput) {
+    const onOutput = handlers.onOutput;
+    unlisteners.push(
+      await listen<ScriptOutput>("run-script-output", (event) => {
+        if (event.payload.session_id === sessionId) onOutput(event.payload);
+      }),
+    );
+  }
+  if (handlers.onUpdate) {
+    const onUpdate = handlers.onUpdate;
+    unlisteners.push(
+      await listen<RunningScript>("run-script-updated", (event) => {
+        if (event.payload.session_id === sessionId) onUpdate(event.payload);
+      }),
+    );
+  }
+  if (handlers.onExit) {
+    const onExit = handlers.onExit;
+    unlisteners.push(
+      await listen<ScriptExited>("run-script-exited", (event) => {
+        if (event.payload.session_id === sessionId) onExit(event.payload);
+      }),
+    );
+  }
+  return () => unlisteners.forEach((unlisten) => unlisten());
+}
+
+/** An exit event as a message for the session's agent. */
+export function describeScriptExit(exit: ScriptExited): string {
+  const restart = exit.will_restart ? " (restarting)" : "";
+  if (exit.output_tail.length === 0) return `${exit.summary}${restart}.`;
+  return `${exit.summary}${restart}. Last output:\n\n${exit.output_tail.join("\n")}`;
+}
+
+/**
+ * Prefix a prompt with the script exits that happened since the agent's last
+ * turn. Agents run once per message, so exits are handed over with the next one.
+ */
+export function withScriptExits(message: string, exits: ScriptExited[]): string {
+  if (exits.length === 0) return message;
+  const notes = exits.map((exit) => `[Run script] ${describeScriptExit(exit)}`);
+  return `${notes.join("\n\n")}\n\n${message}`;
+}
@@ -1,9 +1,15 @@
 import { create } from "zustand";
 import { invoke } from "@tauri-apps/api/core";
 import type { ParsedMessage, ClaudeEvent, PermissionMode, CommitMode } from "../lib/claude-types";
-import type { GitStatus } from "../lib/git-types";
+import type { GitStatus, ProvisionOptions } from "../lib/git-types";
 import { debug } from "./debugStore";
 import * as db from "../lib/database";
+import { getModelById } from "../lib/models";
+import { errorMessage, hasErrorCode } from "../lib/errors";
+import { sessionOperationId } from "../lib/operations";
+import type { CreateSessionArgs } from "../bindings/CreateSessionArgs";
+import type { SessionRecord } from "../bindings/SessionRecord";
+import type { SessionStatus } from "../bindings/SessionStatus";
 
 // Lazy import to avoid circular dependency with window-manager
 const getWindowManager = () => import("../lib/window-manager");
@@ -95,7 +101,21 @@ export interface ChatWindow {
   markedForDeletion?: boolean; // Flag for cleanup when unpinned window is closed
 }
 
-export type SessionStatus = 'active' | 'closed';
+export type { SessionStatus };
+
+// Agent CLI that runs a model, recorded in commit trailers
+function agentForModel(model: string | null): string | null {
+  switch (getModelById(model)?.provider) {
+    case "anthropic":
+      return "claude";
+    case "openai":
+      return "codex";
+    case "google":
+      return "gemini";
+    default:
+      return null;
+  }
+}
 
 export interface Session {
   id: string;
@@ -117,7 +137,7 @@ export interface Session {
   permissionMode: PermissionMode;
   commitMode: CommitMode;
   initialPrompt?: string; // The initial prompt used when creating the session
-  status: SessionStatus; // active = can work on, closed = archived after PR merge
+  status: SessionStatus; // active = can work on, paused = agent stopped, closed = archived after PR merge
   prUrl?: string | null; // PR URL after merge, for historical reference
 }
 
@@ -166,9 +186,11 @@ interface SessionState {
   deleteProject: (id: string) => Promise<void>;
 
   // Session actions
-  createSession: (projectId: string, name: string, cwd: string) => Promise<Session>;
+  createSession: (projectId: string, name: string, cwd: string, provision?: ProvisionOptions) => Promise<Session>;
   updateSession: (id: string, updates: Partial<Session>) => Promise<void>;
   deleteSession: (id: string) => Promise<void>;
+  pauseSession: (id: string) => Promise<void>;
+  resumeSession: (id: string) => Promise<void>;
   setActiveSession: (id: string | null) => void;
 
   // ChatWindow actions
@@ -390,7 +412,7 @@ export const useSessionStore = create<SessionState>((set, get) => ({
               debug.info("SessionStore", "Loaded saved session data from worktree", { wtPath: wt.path, messageCount: savedData.messages?.length || 0 });
             }
           } catch (err) {
-            debug.warn("SessionStore", "Failed to load session data from worktree", { wtPath: wt.path, error: String(err) });
+            debug.warn("SessionStore", "Failed to load session data from worktree", { wtPath: wt.path, error: errorMessage(err) });
           }
 
           const session: Session = {
@@ -441,7 +463,7 @@ export const useSessionStore = create<SessionState>((set, get) => ({
       }
     } catch (err) {
       console.error("Failed to check for existing worktrees:", err);
-      debug.warn("SessionStore", "Failed to check for existing worktrees", { error: String(err) });
+      debug.warn("SessionStore", "Failed to check for existing worktrees", { error: errorMessage(err) });
     }
 
     // Save to DB
@@ -482,11 +504,11 @@ export const useSessionStore = create<SessionState>((set, get) => ({
         try {
           await closeAllSessionChatWindows(sessionId);
         } catch (err) {
-          debug.warn("SessionStore", "Failed to close chat windows for session", { sessionId, error: String(err) });
+          debug.warn("SessionStore", "Failed to close chat windows for session", { sessionId, error: errorMessage(err) });
         }
       }
     } catch (err) {
-      debug.warn("SessionStore", "Failed to import window-manager", { error: String(err) });
+      debug.warn("SessionStore", "Failed to import window-manager", { error: errorMessage(err) });
     }
 
     // Delete all sessions (including worktree cleanup)
@@ -534,42 +556,34 @@ export const useSessionStore = create<SessionState>((set, get) => ({
     });
   },
 
-  createSession: async (projectId, name, cwd) => {
+  createSession: async (projectId, name, cwd, provision) => {
     const id = generateId();
-    let sessionCwd = cwd;
+    const project = get().projects[projectId];
+    if (!project) {
+      throw new Error(`Project not found: ${projectId}`);
+    }
 
-    // Try to create worktree for isolation
+    // The backend creates the worktree (for git projects) and the stored session,
+    // rolling both back if either fails. Progress is reported via "session-progress".
+    let record: SessionRecord;
     try {
-      const project = get().projects[projectId];
-      if (project) {
-        const isGit = await invoke<boolean>("validate_git_repository", { projectPath: project.path });
-        if (isGit) {
-           // Use sanitized name + short ID for uniqueness and readability
-           const sanitizedName = name.replace(/[^a-zA-Z0-9-_]/g, "-").toLowerCase();
-           const worktreeName = `${sanitizedName}-${id.slice(0, 6)}`;
-
-           debug.info("SessionStore", "Creating worktree for session", { worktreeName });
-
-           const worktreePath = await invoke<string>("create_workspace_worktree", {
-             projectPath: project.path,
-             name: worktreeName
-           });
-
-           // Validate worktree was created successfully
-           if (worktreePath && worktreePath !== project.path && worktreePath.includes('.mindgrid/worktrees')) {
-             sessionCwd = worktreePath;
-             debug.info("SessionStore", "Created worktree", { worktreePath });
-           } else {
-             throw new Error(`Worktree creation returned invalid path: ${worktreePath}`);
-           }
-        }
-      }
+      const args: CreateSessionArgs = {
+        id,
+        project_id: projectId,
+        project_path: project.path,
+        name,
+        cwd,
+        model: project.defaultModel || null,
+        provision: provision ?? null,
+      };
+      record = await invoke<SessionRecord>("create_session", { args });
+      debug.info("SessionStore", "Created session", { id, cwd: record.cwd, worktree: record.worktree });
     } catch (err) {
-      console.error("Failed to create worktree:", err);
-      debug.error("SessionStore", "Failed to create worktree", err);
-      // Re-throw to prevent session creation with invalid cwd
-      throw new Error(`Failed to create isolated worktree for session: ${err}`);
+      console.error("Failed to create session:", err);
+      debug.error("SessionStore", "Failed to create session", err);
+      throw new Error(`Failed to create session: ${errorMessage(err)}`);
     }
+    const sessionCwd = record.cwd;
 
     // Get project's defaults
     const projectDefaults = get().projects[projectId];
@@ -607,6 +621,10 @@ export const useSessionStore = create<SessionState>((set, get) => ({
       console.log("db.saveSession completed successfully");
     } catch (err) {
       console.error("db.saveSession FAILED:", err);
+      // Don't leave a worktree behind for a session the UI doesn't know about
+      await invoke("delete_session", { sessionId: id }).catch((deleteErr) => {
+        debug.warn("SessionStore", "Failed to roll back session", { id, error: errorMessage(deleteErr) });
+      });
       throw err;
     }
 
@@ -681,25 +699,37 @@ export const useSessionStore = create<SessionState>((set, get) => ({
       const { closeAllSessionChatWindows } = await getWindowManager();
       await closeAllSessionChatWindows(id);
     } catch (err) {
-      debug.warn("SessionStore", "Failed to close chat windows for session", { id, error: String(err) });
+      debug.warn("SessionStore", "Failed to close chat windows for session", { id, error: errorMessage(err) });
     }
 
-    // Remove worktree if it exists and is different from project path
-    const project = get().projects[session.projectId];
-    if (project && session.cwd !== project.path && session.cwd.includes(".mindgrid/worktrees")) {
-       try {
-         debug.info("SessionStore", "Removing worktree", { worktreePath: session.cwd });
-         await invoke("remove_workspace_worktree", {
-           projectPath: project.path,
-           worktreePath: session.cwd
-         });
-         debug.info("SessionStore", "Worktree removed successfully");
-       } catch (err) {
-         console.error("Failed to remove worktree:", err);
-         debug.error("SessionStore", "Failed to remove worktree", err);
-         // Don't delete session if worktree removal failed - prevents orphaned worktrees
-         throw new Error(`Cannot delete session: failed to remove worktree. ${err}`);
-       }
+    // Stop the agent, remove the worktree and delete the session row; the row
+    // is kept if the worktree can't be removed
+    try {
+      await invoke("delete_session", { sessionId: id });
+      debug.info("SessionStore", "Session deleted by backend", { id });
+    } catch (err) {
+      if (!hasErrorCode(err, "validation.session_not_found")) {
+        debug.error("SessionStore", "Failed to delete session", err);
+        throw new Error(`Cannot delete session: ${errorMessage(err)}`);
+      }
+
+      // Sessions created before the backend owned them only have a worktree
+      const project = get().projects[session.projectId];
+      if (project && session.cwd !== project.path && session.cwd.includes(".mindgrid/worktrees")) {
+        try {
+          debug.info("SessionStore", "Removing worktree", { worktreePath: session.cwd });
+          await invoke("remove_workspace_worktree", {
+            projectPath: project.path,
+            worktreePath: session.cwd
+          });
+          debug.info("SessionStore", "Worktree removed successfully");
+        } catch (removeErr) {
+          console.error("Failed to remove worktree:", removeErr);
+          debug.error("SessionStore", "Failed to remove worktree", removeErr);
+          // Don't delete session if worktree removal failed - prevents orphaned worktrees
+          throw new Error(`Cannot delete session: failed to remove worktree. ${errorMessage(removeErr)}`);
+        }
+      }
     }
 
     // Delete from DB (only if worktree removal succeeded or wasn't needed)
@@ -729,6 +759,16 @@ export const useSessionStore = create<SessionState>((set, get) => ({
     }
   },
 
+  pauseSession: async (id) => {
+    const record = await invoke<SessionRecord>("pause_session", { sessionId: id });
+    await get().updateSession(id, { status: record.status, ptyId: null, isRunning: false });
+  },
+
+  resumeSession: async (id) => {
+    const record = await invoke<SessionRecord>("resume_session", { sessionId: id, agent: null });
+    await get().updateSession(id, { status: record.status });
+  },
+
   setActiveSession: (id) => {
     set({ activeSessionId: id });
     if (id) {
@@ -1545,9 +1585,16 @@ export const useSessionStore = create<SessionState>((set, get) => ({
 
       // Create checkpoint commit
       const commitMessage = message || `checkpoint: auto-commit at ${new Date().toLocaleTimeString()}`;
+      const lastPrompt = [...session.messages].reverse().find((m) => m.role === "user")?.content;
       const result = await invoke<{ success: boolean; hash?: string; message?: string }>("git_checkpoint_commit", {
         workingDirectory: session.cwd,
         message: commitMessage,
+        provenance: {
+          session_id: session.id,
+          agent: agentForModel(session.model),
+          model: session.model,
+          prompt: lastPrompt ?? session.initialPrompt ?? null,
+        },
       });
 
       if (result.success) {
@@ -1575,6 +1622,7 @@ export const useSessionStore = create<SessionState>((set, get) => ({
       const result = await invoke<{ success: boolean; error?: string }>("git_push", {
         workingDirectory: session.cwd,
         setUpstream: true,
+        operationId: sessionOperationId("git_push", sessionId),
       });
 
       if (result.success) {
@@ -1587,9 +1635,9 @@ export const useSessionStore = create<SessionState>((set, get) => ({
         return { success: false, error: result.error };
       }
     } catch (err) {
-      const errorMessage = err instanceof Error ? err.message : "Failed to push to remote";
+      const message = errorMessage(err, "Failed to push to remote");
       debug.error("SessionStore", "Push error", err);
-      return { success: false, error: errorMessage };
+      return { success: false, error: message };
     }
   },
 
@@ -1619,6 +1667,7 @@ export const useSessionStore = create<SessionState>((set, get) => ({
         workingDirectory: session.cwd,
         title,
         body,
+        operationId: sessionOperationId("git_create_pr", sessionId),
       });
 
       if (result.success) {
@@ -1628,9 +1677,9 @@ export const useSessionStore = create<SessionState>((set, get) => ({
       }
       return result;
     } catch (err) {
-      const errorMessage = err instanceof Error ? err.message : "Failed to create PR";
+      const message = errorMessage(err, "Failed to create PR");
       debug.error("SessionStore", "PR creation error", err);
-      return { success: false, error: errorMessage };
+      return { success: false, error: message };
     }
   },
 
@@ -1688,6 +1737,7 @@ export const useSessionStore = create<SessionState>((set, get) => ({
       const result = await invoke<{ success: boolean; message?: string; error?: string }>("git_merge_pr", {
         workingDirectory: session.cwd,
         squash,
+        operationId: sessionOperationId("git_merge_pr", sessionId),
       });
 
       if (result.success) {
@@ -1716,9 +1766,9 @@ export const useSessionStore = create<SessionState>((set, get) => ({
       }
       return result;
     } catch (err) {
-      const errorMessage = err instanceof Error ? err.message : "Failed to merge PR";
+      const message = errorMessage(err, "Failed to merge PR");
       debug.error("SessionStore", "PR merge error", err);
-      return { success: false, error: errorMessage };
+      return { success: false, error: message };
     }
   },
 
@@ -1,6 +1,7 @@
 import { create } from "zustand";
 import { invoke } from "@tauri-apps/api/core";
 import { parseUsageOutput, getCriticalUsage, type UsageData } from "../lib/usageParser";
+import { errorMessage } from "../lib/errors";
 
 // Codex types
 export interface CodexUsageData {
@@ -85,11 +86,11 @@ export const useUsageStore = create<UsageStore>((set, get) => ({
   fetchClaudeUsage: async () => {
     set({ claudeLoading: true, claudeError: null });
     try {
-      const rawOutput = await invoke<string>("get_claude_usage");
+      const rawOutput = await invoke<string>("get_claude_usage", { operationId: "usage:claude" });
       const parsed = parseUsageOutput(rawOutput);
       set({ claudeUsageData: parsed, claudeLoading: false });
     } catch (err) {
-      const errorMsg = err instanceof Error ? err.message : String(err);
+      const errorMsg = errorMessage(err);
       set({ claudeError: errorMsg, claudeLoading: false });
     }
   },
@@ -97,11 +98,11 @@ export const useUsageStore = create<UsageStore>((set, get) => ({
   fetchCodexUsage: async () => {
     set({ codexLoading: true, codexError: null });
     try {
-      const rawOutput = await invoke<string>("get_codex_usage");
+      const rawOutput = await invoke<string>("get_codex_usage", { operationId: "usage:codex" });
       const parsed = parseCodexOutput(rawOutput);
       set({ codexUsageData: parsed, codexLoading: false });
     } catch (err) {
-      const errorMsg = err instanceof Error ? err.message : String(err);
+      const errorMsg = errorMessage(err);
       set({ codexError: errorMsg, codexLoading: false });
     }
   },